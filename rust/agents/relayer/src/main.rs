@@ -20,6 +20,7 @@ mod prover;
 mod relayer;
 mod server;
 mod settings;
+mod watchtower;
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<()> {
@@ -0,0 +1,828 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use derive_new::new;
+use eyre::{eyre, Context, Result};
+use futures::{
+    future::{join_all, BoxFuture},
+    FutureExt,
+};
+use hyperlane_base::{db::HyperlaneRocksDB, MultisigCheckpointSyncer};
+use hyperlane_core::{
+    AggregationIsm, CheckpointWithMessageId, HyperlaneMessage, InterchainSecurityModule,
+    ModuleType, MultisigIsm, RoutingIsm, TxCostEstimate, H160, H256, U256,
+};
+use serde::Serialize;
+
+use super::{
+    dry_run::DryRunResult,
+    gas_payment::GasPaymentDiagnosis,
+    metadata::{BaseMetadataBuilder, IsmWithMetadataAndType, MessageMetadataBuilder},
+    op_queue::QueueOperationSummary,
+    pending_message::MessageContext,
+};
+use crate::server::OperationQueues;
+
+/// How long a diagnosis is served again before the chains are queried again
+const DIAGNOSIS_CACHE_DURATION: Duration = Duration::from_secs(60);
+
+/// Why a message is or isn't delivered, as far as it could be diagnosed
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageDiagnosis {
+    pub message_id: H256,
+    pub origin_domain: u32,
+    pub destination_domain: u32,
+    pub nonce: u32,
+    pub sender: H256,
+    pub recipient: H256,
+    pub delivered: Option<bool>,
+    /// The index of the message in the origin's merkle tree, which validators
+    /// sign checkpoints of
+    pub merkle_leaf_index: Option<u32>,
+    pub recipient_ism: Option<IsmDiagnosis>,
+    pub whitelisted: Option<bool>,
+    pub blacklisted: Option<bool>,
+    pub metadata: Option<MetadataDiagnosis>,
+    pub gas_payment: Option<GasPaymentDiagnosis>,
+    pub estimated_gas_limit: Option<U256>,
+    /// The most gas the relayer submits transactions to the destination with
+    pub transaction_gas_limit: Option<U256>,
+    pub queue_position: Option<QueuePosition>,
+    /// The latest decision for the message, if the relayer runs in dry run
+    /// mode
+    pub last_dry_run: Option<DryRunResult>,
+    /// The steps that couldn't be diagnosed, and why
+    pub errors: Vec<String>,
+}
+
+/// A module of the recipient's ISM, with the modules it uses to verify the
+/// message
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IsmDiagnosis {
+    pub address: H256,
+    pub module_type: Option<ModuleType>,
+    /// How many of the submodules or validators must verify the message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<u8>,
+    /// The validators of a multisig ISM
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub validators: Vec<ValidatorDiagnosis>,
+    /// The module a routing ISM routes the message to, or the submodules of
+    /// an aggregation ISM
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub modules: Vec<IsmDiagnosis>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The checkpoints a validator of a multisig ISM signed
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorDiagnosis {
+    pub address: H160,
+    /// Whether the validator announced a storage location the relayer can
+    /// read its checkpoints from
+    pub has_checkpoint_syncer: bool,
+    pub latest_index: Option<u32>,
+    /// The validator's checkpoint the message would be delivered with
+    pub checkpoint: Option<CheckpointWithMessageId>,
+    /// Whether the checkpoint covers the message
+    pub signed: bool,
+}
+
+/// Whether metadata could be built for the recipient's ISM, and whether the
+/// ISM accepts it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataDiagnosis {
+    /// None if metadata couldn't be built yet, e.g. because not enough
+    /// validators signed the message
+    pub length: Option<usize>,
+    /// Whether the recipient ISM's `verify` succeeds with the metadata, dry
+    /// run while diagnosing rather than the result of the relayer's last
+    /// attempt to deliver the message
+    pub current_verify_succeeds: Option<bool>,
+    pub current_verify_gas_estimate: Option<U256>,
+}
+
+/// Where a message is in the submitter queues of its destination
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuePosition {
+    /// The number of operations that will be attempted before the message
+    pub position: usize,
+    pub queue_length: usize,
+    pub operation: QueueOperationSummary,
+}
+
+/// Explains why messages aren't delivered, by going through the same steps
+/// as preparing them for submission and reporting the outcome of each. A
+/// diagnosis queries the chains again, including dry running the recipient
+/// ISM's `verify`, so it reflects the current state rather than the relayer's
+/// last attempt.
+///
+/// As diagnosing is expensive, diagnoses are made one at a time and served
+/// again for `DIAGNOSIS_CACHE_DURATION`.
+#[derive(new, Clone)]
+pub struct MessageDiagnoser {
+    /// The databases of the origin chains, to look messages up in
+    origin_dbs: Arc<Vec<HyperlaneRocksDB>>,
+    /// The message context of each (origin, destination) domain id pair
+    msg_ctxs: Arc<HashMap<(u32, u32), Arc<MessageContext>>>,
+    op_queues: Arc<OperationQueues>,
+    /// Recent diagnoses by message id, with when they were made
+    #[new(default)]
+    recent: Arc<Mutex<HashMap<H256, (Instant, MessageDiagnosis)>>>,
+    /// Held while diagnosing
+    #[new(default)]
+    diagnosing: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl MessageDiagnoser {
+    /// Diagnoses the message with `message_id`, or returns None if none of
+    /// the origin chains dispatched it
+    pub async fn diagnose(&self, message_id: H256) -> Result<Option<MessageDiagnosis>> {
+        let mut message = None;
+        for db in self.origin_dbs.iter() {
+            message = db.retrieve_message_by_id(&message_id)?;
+            if message.is_some() {
+                break;
+            }
+        }
+        let Some(message) = message else {
+            return Ok(None);
+        };
+
+        let mut diagnosis = match self.recent_diagnosis(message_id) {
+            Some(diagnosis) => diagnosis,
+            None => {
+                let _diagnosing = self.diagnosing.lock().await;
+                // The message may have been diagnosed while waiting
+                match self.recent_diagnosis(message_id) {
+                    Some(diagnosis) => diagnosis,
+                    None => {
+                        let diagnosis = self.diagnose_message(&message).await;
+                        self.recent
+                            .lock()
+                            .unwrap()
+                            .insert(message_id, (Instant::now(), diagnosis.clone()));
+                        diagnosis
+                    }
+                }
+            }
+        };
+        // The queues are local, so the position is always current
+        diagnosis.queue_position = self.queue_position(&message).await;
+        Ok(Some(diagnosis))
+    }
+
+    async fn diagnose_message(&self, message: &HyperlaneMessage) -> MessageDiagnosis {
+        let mut diagnosis = MessageDiagnosis::new(message);
+        match self.msg_ctxs.get(&(message.origin, message.destination)) {
+            Some(ctx) => diagnosis.diagnose_with_context(ctx, message).await,
+            None => diagnosis.errors.push(format!(
+                "Not relaying messages from domain {} to domain {}",
+                message.origin, message.destination
+            )),
+        }
+        diagnosis
+    }
+
+    /// The diagnosis of the message made within `DIAGNOSIS_CACHE_DURATION`,
+    /// if any. Older diagnoses are forgotten.
+    fn recent_diagnosis(&self, message_id: H256) -> Option<MessageDiagnosis> {
+        let mut recent = self.recent.lock().unwrap();
+        recent.retain(|_, (diagnosed_at, _)| diagnosed_at.elapsed() < DIAGNOSIS_CACHE_DURATION);
+        recent
+            .get(&message_id)
+            .map(|(_, diagnosis)| diagnosis.clone())
+    }
+
+    async fn queue_position(&self, message: &HyperlaneMessage) -> Option<QueuePosition> {
+        let message_id = message.id();
+        for queue in self.op_queues.get(&message.destination)? {
+            let operations = queue.list_operations().await;
+            if let Some(position) = operations.iter().position(|op| op.id == message_id) {
+                return Some(QueuePosition {
+                    position,
+                    queue_length: operations.len(),
+                    operation: operations[position].clone(),
+                });
+            }
+        }
+        None
+    }
+}
+
+impl MessageDiagnosis {
+    fn new(message: &HyperlaneMessage) -> Self {
+        Self {
+            message_id: message.id(),
+            origin_domain: message.origin,
+            destination_domain: message.destination,
+            nonce: message.nonce,
+            sender: message.sender,
+            recipient: message.recipient,
+            delivered: None,
+            merkle_leaf_index: None,
+            recipient_ism: None,
+            whitelisted: None,
+            blacklisted: None,
+            metadata: None,
+            gas_payment: None,
+            estimated_gas_limit: None,
+            transaction_gas_limit: None,
+            queue_position: None,
+            last_dry_run: None,
+            errors: vec![],
+        }
+    }
+
+    /// Records the error of a step, so that the steps that don't depend on it
+    /// are still diagnosed
+    fn note<T, E: Into<eyre::Report>>(&mut self, result: Result<T, E>, step: &str) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.errors.push(format!("When {step}: {:#}", err.into()));
+                None
+            }
+        }
+    }
+
+    async fn diagnose_with_context(&mut self, ctx: &MessageContext, message: &HyperlaneMessage) {
+        let message_id = message.id();
+        self.delivered = self.note(
+            ctx.destination_mailbox.delivered(message_id).await,
+            "checking message delivery status",
+        );
+        self.merkle_leaf_index = self
+            .note(
+                ctx.metadata_builder
+                    .get_merkle_leaf_id_by_message_id(message_id)
+                    .await,
+                "fetching merkle leaf index",
+            )
+            .flatten();
+        self.transaction_gas_limit = ctx.transaction_gas_limit;
+        self.last_dry_run = ctx
+            .dry_run
+            .as_ref()
+            .and_then(|dry_run| dry_run.list(Some(message_id), None).into_iter().next());
+
+        let mut ism_module_type = None;
+        let mut tx_cost_estimate = None;
+        if let Some(ism_address) = self.note(
+            ctx.destination_mailbox
+                .recipient_ism(message.recipient)
+                .await,
+            "fetching recipient ISM address",
+        ) {
+            let recipient_ism = diagnose_ism(
+                ctx.metadata_builder.as_ref(),
+                ism_address,
+                message,
+                self.merkle_leaf_index,
+                0,
+            )
+            .await;
+            ism_module_type = recipient_ism.module_type;
+            self.recipient_ism = Some(recipient_ism);
+            tx_cost_estimate = self
+                .diagnose_metadata(ctx, message, ism_address, ism_module_type)
+                .await;
+        }
+
+        self.whitelisted = Some(
+            ctx.whitelist
+                .msg_matches_with_ism(message, ism_module_type, true),
+        );
+        self.blacklisted = Some(ctx.blacklist.msg_matches_with_ism(
+            message,
+            ism_module_type,
+            false,
+        ));
+        self.estimated_gas_limit = tx_cost_estimate
+            .as_ref()
+            .map(|tx_cost_estimate| tx_cost_estimate.gas_limit);
+        self.gas_payment = self.note(
+            ctx.origin_gas_payment_enforcer
+                .diagnose(message, tx_cost_estimate.as_ref(), ism_module_type)
+                .await,
+            "checking gas payment",
+        );
+    }
+
+    /// Builds metadata for the recipient's ISM and dry runs verifying and
+    /// delivering the message with it, returning the estimated cost of
+    /// delivering it
+    async fn diagnose_metadata(
+        &mut self,
+        ctx: &MessageContext,
+        message: &HyperlaneMessage,
+        ism_address: H256,
+        ism_module_type: Option<ModuleType>,
+    ) -> Option<TxCostEstimate> {
+        let ism_with_metadata = async {
+            MessageMetadataBuilder::new(
+                ism_address,
+                ism_module_type,
+                message,
+                ctx.metadata_builder.clone(),
+            )
+            .await?
+            .build_ism_and_metadata(ism_address, message)
+            .await
+        }
+        .await;
+        let IsmWithMetadataAndType { ism, metadata, .. } =
+            self.note(ism_with_metadata, "building metadata")?;
+
+        let mut diagnosis = MetadataDiagnosis {
+            length: metadata.as_ref().map(Vec::len),
+            current_verify_succeeds: None,
+            current_verify_gas_estimate: None,
+        };
+        let mut tx_cost_estimate = None;
+        if let Some(metadata) = &metadata {
+            if let Some(verify_gas_estimate) = self.note(
+                ism.dry_run_verify(message, metadata).await,
+                "dry running ISM verification",
+            ) {
+                diagnosis.current_verify_succeeds = Some(verify_gas_estimate.is_some());
+                diagnosis.current_verify_gas_estimate = verify_gas_estimate;
+            }
+            tx_cost_estimate = self.note(
+                ctx.destination_mailbox
+                    .process_estimate_costs(message, metadata)
+                    .await,
+                "estimating costs for process call",
+            );
+        }
+        self.metadata = Some(diagnosis);
+        tx_cost_estimate
+    }
+}
+
+/// Builds the ISMs of the destination and the checkpoint syncers of their
+/// validators, which the recipient ISM tree is resolved with
+#[async_trait]
+trait IsmTreeBuilder: Send + Sync {
+    /// How deep ISMs can be nested
+    fn max_depth(&self) -> u32;
+
+    async fn build_ism(&self, address: H256) -> Result<Box<dyn InterchainSecurityModule>>;
+
+    async fn build_routing_ism(&self, address: H256) -> Result<Box<dyn RoutingIsm>>;
+
+    async fn build_aggregation_ism(&self, address: H256) -> Result<Box<dyn AggregationIsm>>;
+
+    async fn build_multisig_ism(&self, address: H256) -> Result<Box<dyn MultisigIsm>>;
+
+    async fn build_checkpoint_syncer(
+        &self,
+        validators: &[H256],
+    ) -> Result<MultisigCheckpointSyncer>;
+
+    async fn highest_known_leaf_index(&self) -> Option<u32>;
+}
+
+#[async_trait]
+impl IsmTreeBuilder for BaseMetadataBuilder {
+    fn max_depth(&self) -> u32 {
+        BaseMetadataBuilder::max_depth(self)
+    }
+
+    async fn build_ism(&self, address: H256) -> Result<Box<dyn InterchainSecurityModule>> {
+        BaseMetadataBuilder::build_ism(self, address).await
+    }
+
+    async fn build_routing_ism(&self, address: H256) -> Result<Box<dyn RoutingIsm>> {
+        BaseMetadataBuilder::build_routing_ism(self, address).await
+    }
+
+    async fn build_aggregation_ism(&self, address: H256) -> Result<Box<dyn AggregationIsm>> {
+        BaseMetadataBuilder::build_aggregation_ism(self, address).await
+    }
+
+    async fn build_multisig_ism(&self, address: H256) -> Result<Box<dyn MultisigIsm>> {
+        BaseMetadataBuilder::build_multisig_ism(self, address).await
+    }
+
+    async fn build_checkpoint_syncer(
+        &self,
+        validators: &[H256],
+    ) -> Result<MultisigCheckpointSyncer> {
+        BaseMetadataBuilder::build_checkpoint_syncer(self, validators, None).await
+    }
+
+    async fn highest_known_leaf_index(&self) -> Option<u32> {
+        BaseMetadataBuilder::highest_known_leaf_index(self).await
+    }
+}
+
+/// Resolves the ISM at `address` and the modules it uses to verify the
+/// message, recording where resolving them fails
+fn diagnose_ism<'a>(
+    builder: &'a dyn IsmTreeBuilder,
+    address: H256,
+    message: &'a HyperlaneMessage,
+    merkle_leaf_index: Option<u32>,
+    depth: u32,
+) -> BoxFuture<'a, IsmDiagnosis> {
+    async move {
+        let mut diagnosis = IsmDiagnosis {
+            address,
+            module_type: None,
+            threshold: None,
+            validators: vec![],
+            modules: vec![],
+            error: None,
+        };
+        if let Err(err) = diagnosis
+            .resolve(builder, message, merkle_leaf_index, depth)
+            .await
+        {
+            diagnosis.error = Some(format!("{err:#}"));
+        }
+        diagnosis
+    }
+    .boxed()
+}
+
+impl IsmDiagnosis {
+    async fn resolve(
+        &mut self,
+        builder: &dyn IsmTreeBuilder,
+        message: &HyperlaneMessage,
+        merkle_leaf_index: Option<u32>,
+        depth: u32,
+    ) -> Result<()> {
+        if depth > builder.max_depth() {
+            return Err(eyre!("Exceeded max depth when resolving ISM ({depth})"));
+        }
+        let ism = builder
+            .build_ism(self.address)
+            .await
+            .context("When building ISM")?;
+        let module_type = ism
+            .module_type()
+            .await
+            .context("When fetching module type")?;
+        self.module_type = Some(module_type);
+
+        match module_type {
+            ModuleType::Routing => {
+                let ism = builder.build_routing_ism(self.address).await?;
+                let module = ism
+                    .route(message)
+                    .await
+                    .context("When routing the message")?;
+                self.modules = vec![
+                    diagnose_ism(builder, module, message, merkle_leaf_index, depth + 1).await,
+                ];
+            }
+            ModuleType::Aggregation => {
+                let ism = builder.build_aggregation_ism(self.address).await?;
+                let (modules, threshold) = ism
+                    .modules_and_threshold(message)
+                    .await
+                    .context("When fetching modules and threshold")?;
+                self.threshold = Some(threshold);
+                self.modules = join_all(modules.into_iter().map(|module| {
+                    diagnose_ism(builder, module, message, merkle_leaf_index, depth + 1)
+                }))
+                .await;
+            }
+            ModuleType::MerkleRootMultisig | ModuleType::MessageIdMultisig => {
+                let ism = builder.build_multisig_ism(self.address).await?;
+                let (validators, threshold) = ism
+                    .validators_and_threshold(message)
+                    .await
+                    .context("When fetching validators and threshold")?;
+                self.threshold = Some(threshold);
+                self.validators = diagnose_validators(
+                    builder,
+                    &validators,
+                    module_type,
+                    message,
+                    merkle_leaf_index,
+                )
+                .await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Finds the checkpoints of a multisig ISM's validators that the message
+/// would be delivered with. Message id multisig ISMs need the checkpoint of
+/// the message's leaf, merkle root multisig ISMs any later one the message can
+/// be proven against.
+async fn diagnose_validators(
+    builder: &dyn IsmTreeBuilder,
+    validators: &[H256],
+    module_type: ModuleType,
+    message: &HyperlaneMessage,
+    merkle_leaf_index: Option<u32>,
+) -> Result<Vec<ValidatorDiagnosis>> {
+    let multisig_syncer = builder
+        .build_checkpoint_syncer(validators)
+        .await
+        .context("When building checkpoint syncers")?;
+    let highest_known_leaf_index = builder.highest_known_leaf_index().await;
+
+    let mut diagnoses = Vec::with_capacity(validators.len());
+    for &validator in validators {
+        let address = H160::from(validator);
+        let checkpoint_syncer = multisig_syncer.checkpoint_syncers().get(&address);
+        let latest_index = match checkpoint_syncer {
+            Some(checkpoint_syncer) => checkpoint_syncer.latest_index().await.ok().flatten(),
+            None => None,
+        };
+        let index = merkle_leaf_index.and_then(|leaf_index| match module_type {
+            ModuleType::MessageIdMultisig => Some(leaf_index),
+            _ => latest_index
+                .zip(highest_known_leaf_index)
+                .map(|(latest_index, highest_index)| latest_index.min(highest_index))
+                .filter(|&index| index >= leaf_index),
+        });
+        let checkpoint = match index {
+            Some(index) => multisig_syncer
+                .fetch_validator_checkpoint(validator, index)
+                .await
+                .map(|signed_checkpoint| signed_checkpoint.value),
+            None => None,
+        };
+        let signed = checkpoint.map_or(false, |checkpoint| {
+            module_type != ModuleType::MessageIdMultisig || checkpoint.message_id == message.id()
+        });
+        diagnoses.push(ValidatorDiagnosis {
+            address,
+            has_checkpoint_syncer: checkpoint_syncer.is_some(),
+            latest_index,
+            checkpoint,
+            signed,
+        });
+    }
+    Ok(diagnoses)
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_base::{
+        db::test_utils, test_utils::test_signer, CheckpointSyncer, CoreMetrics, LocalStorage,
+        VerifiedCheckpointCache,
+    };
+    use hyperlane_core::{
+        ChainCommunicationError, Checkpoint, HyperlaneDomain, HyperlaneSigner, HyperlaneSignerExt,
+    };
+    use hyperlane_test::mocks::{
+        MockAggregationIsm, MockInterchainSecurityModule, MockMailboxContract, MockMultisigIsm,
+        MockRoutingIsm,
+    };
+    use prometheus::Registry;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::msg::test_utils::dummy_message_context;
+
+    /// The ISMs of a destination by address, and the checkpoint syncers of
+    /// their validators
+    struct TestIsmTree {
+        module_types: HashMap<H256, ModuleType>,
+        /// The module each routing ISM routes to
+        routes: HashMap<H256, H256>,
+        /// The modules of each aggregation ISM or validators of each multisig
+        /// ISM, with its threshold
+        sets: HashMap<H256, (Vec<H256>, u8)>,
+        checkpoint_syncer: MultisigCheckpointSyncer,
+        highest_known_leaf_index: u32,
+    }
+
+    impl TestIsmTree {
+        fn set(&self, address: H256) -> (Vec<H256>, u8) {
+            self.sets[&address].clone()
+        }
+    }
+
+    #[async_trait]
+    impl IsmTreeBuilder for TestIsmTree {
+        fn max_depth(&self) -> u32 {
+            4
+        }
+
+        async fn build_ism(&self, address: H256) -> Result<Box<dyn InterchainSecurityModule>> {
+            let module_type = self.module_types[&address];
+            let mut ism = MockInterchainSecurityModule::new();
+            ism.expect__module_type().returning(move || Ok(module_type));
+            Ok(Box::new(ism))
+        }
+
+        async fn build_routing_ism(&self, address: H256) -> Result<Box<dyn RoutingIsm>> {
+            let module = self.routes[&address];
+            let mut ism = MockRoutingIsm::new();
+            ism.expect__route().returning(move |_| Ok(module));
+            Ok(Box::new(ism))
+        }
+
+        async fn build_aggregation_ism(&self, address: H256) -> Result<Box<dyn AggregationIsm>> {
+            let modules_and_threshold = self.set(address);
+            let mut ism = MockAggregationIsm::new();
+            ism.expect__modules_and_threshold()
+                .returning(move |_| Ok(modules_and_threshold.clone()));
+            Ok(Box::new(ism))
+        }
+
+        async fn build_multisig_ism(&self, address: H256) -> Result<Box<dyn MultisigIsm>> {
+            let validators_and_threshold = self.set(address);
+            let mut ism = MockMultisigIsm::new();
+            ism.expect__validators_and_threshold()
+                .returning(move |_| Ok(validators_and_threshold.clone()));
+            Ok(Box::new(ism))
+        }
+
+        async fn build_checkpoint_syncer(
+            &self,
+            _validators: &[H256],
+        ) -> Result<MultisigCheckpointSyncer> {
+            Ok(self.checkpoint_syncer.clone())
+        }
+
+        async fn highest_known_leaf_index(&self) -> Option<u32> {
+            Some(self.highest_known_leaf_index)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_diagnoses_ism_tree_and_validators() {
+        let domain = HyperlaneDomain::new_test_domain("test_diagnoses_ism_tree_and_validators");
+        let dir = TempDir::new().unwrap();
+        let metrics = Arc::new(CoreMetrics::new("test", 9090, Registry::new()).unwrap());
+        let message = HyperlaneMessage::default();
+        let leaf_index = 5;
+
+        // The first validator signed the message, the second has a
+        // checkpoint syncer but didn't sign it yet and the third didn't
+        // announce a storage location
+        let signing_validator = test_signer(0);
+        let lagging_validator = test_signer(1);
+        let unannounced_validator = H160::from_low_u64_be(3);
+        let signing_storage = LocalStorage::new(dir.path().join("signing"), None).unwrap();
+        let lagging_storage = LocalStorage::new(dir.path().join("lagging"), None).unwrap();
+        let checkpoint = CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                merkle_tree_hook_address: H256::from_low_u64_be(1),
+                mailbox_domain: domain.id(),
+                root: H256::from_low_u64_be(2),
+                index: leaf_index,
+            },
+            message_id: message.id(),
+        };
+        signing_storage
+            .write_checkpoint(&signing_validator.sign(checkpoint).await.unwrap())
+            .await
+            .unwrap();
+        signing_storage
+            .write_latest_index(leaf_index)
+            .await
+            .unwrap();
+
+        // A routing ISM routes the message to an aggregation of a message
+        // id multisig ISM and a null ISM
+        let [routing, aggregation, multisig, null] = [1, 2, 3, 4].map(H256::from_low_u64_be);
+        let validators = [
+            signing_validator.eth_address(),
+            lagging_validator.eth_address(),
+            unannounced_validator,
+        ];
+        let tree = TestIsmTree {
+            module_types: HashMap::from([
+                (routing, ModuleType::Routing),
+                (aggregation, ModuleType::Aggregation),
+                (multisig, ModuleType::MessageIdMultisig),
+                (null, ModuleType::Null),
+            ]),
+            routes: HashMap::from([(routing, aggregation)]),
+            sets: HashMap::from([
+                (aggregation, (vec![multisig, null], 2)),
+                (multisig, (validators.map(H256::from).to_vec(), 1)),
+            ]),
+            checkpoint_syncer: MultisigCheckpointSyncer::new(
+                HashMap::from([
+                    (
+                        validators[0],
+                        Arc::new(signing_storage) as Arc<dyn CheckpointSyncer>,
+                    ),
+                    (
+                        validators[1],
+                        Arc::new(lagging_storage) as Arc<dyn CheckpointSyncer>,
+                    ),
+                ]),
+                metrics,
+                None,
+                domain.clone(),
+                H256::from_low_u64_be(1),
+                VerifiedCheckpointCache::default(),
+            ),
+            highest_known_leaf_index: 10,
+        };
+
+        let diagnosis = diagnose_ism(&tree, routing, &message, Some(leaf_index), 0).await;
+
+        assert_eq!(diagnosis.module_type, Some(ModuleType::Routing));
+        assert!(diagnosis.error.is_none());
+        assert_eq!(diagnosis.modules.len(), 1);
+
+        let aggregation_diagnosis = &diagnosis.modules[0];
+        assert_eq!(aggregation_diagnosis.address, aggregation);
+        assert_eq!(
+            aggregation_diagnosis.module_type,
+            Some(ModuleType::Aggregation)
+        );
+        assert_eq!(aggregation_diagnosis.threshold, Some(2));
+        let module_addresses = aggregation_diagnosis
+            .modules
+            .iter()
+            .map(|module| module.address)
+            .collect::<Vec<_>>();
+        assert_eq!(module_addresses, [multisig, null]);
+
+        let null_diagnosis = &aggregation_diagnosis.modules[1];
+        assert_eq!(null_diagnosis.module_type, Some(ModuleType::Null));
+        assert!(null_diagnosis.error.is_none());
+
+        let multisig_diagnosis = &aggregation_diagnosis.modules[0];
+        assert_eq!(
+            multisig_diagnosis.module_type,
+            Some(ModuleType::MessageIdMultisig)
+        );
+        assert!(multisig_diagnosis.error.is_none());
+        assert_eq!(multisig_diagnosis.threshold, Some(1));
+        let [signing, lagging, unannounced] = &multisig_diagnosis.validators[..] else {
+            panic!("Expected a diagnosis per validator");
+        };
+        assert_eq!(signing.address, validators[0]);
+        assert!(signing.has_checkpoint_syncer);
+        assert_eq!(signing.latest_index, Some(leaf_index));
+        assert_eq!(signing.checkpoint, Some(checkpoint));
+        assert!(signing.signed);
+        assert_eq!(lagging.address, validators[1]);
+        assert!(lagging.has_checkpoint_syncer);
+        assert_eq!(lagging.latest_index, None);
+        assert!(!lagging.signed);
+        assert_eq!(unannounced.address, validators[2]);
+        assert!(!unannounced.has_checkpoint_syncer);
+        assert!(unannounced.checkpoint.is_none());
+        assert!(!unannounced.signed);
+    }
+
+    #[tokio::test]
+    async fn test_diagnoses_are_served_again() {
+        test_utils::run_test_db(|db| async move {
+            let origin = HyperlaneDomain::new_test_domain("origin");
+            let destination = HyperlaneDomain::new_test_domain("destination");
+            let db = HyperlaneRocksDB::new(&origin, db);
+            let message = HyperlaneMessage {
+                origin: origin.id(),
+                destination: destination.id(),
+                ..Default::default()
+            };
+            db.store_message(&message, 1).unwrap();
+
+            // The chains are only queried for the first diagnosis
+            let mut mailbox = MockMailboxContract::new();
+            mailbox
+                .expect__delivered()
+                .times(1)
+                .returning(|_| Ok(false));
+            mailbox
+                .expect__recipient_ism()
+                .times(1)
+                .returning(|_| Err(ChainCommunicationError::from_other_str("rpc error")));
+            let ctx = dummy_message_context(&origin, &destination, &db, Arc::new(mailbox));
+            let diagnoser = MessageDiagnoser::new(
+                Arc::new(vec![db]),
+                Arc::new(HashMap::from([(
+                    (origin.id(), destination.id()),
+                    Arc::new(ctx),
+                )])),
+                Default::default(),
+            );
+
+            let first = diagnoser.diagnose(message.id()).await.unwrap().unwrap();
+            let second = diagnoser.diagnose(message.id()).await.unwrap().unwrap();
+            assert_eq!(first.delivered, Some(false));
+            assert_eq!(second.delivered, Some(false));
+            assert_eq!(second.errors, first.errors);
+        })
+        .await;
+    }
+}
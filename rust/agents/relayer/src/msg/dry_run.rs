@@ -0,0 +1,249 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, H256, U256};
+use prometheus::IntCounterVec;
+use serde::Serialize;
+
+/// The max number of dry run results kept, the oldest are forgotten first
+const MAX_RESULTS: usize = 10_000;
+
+/// What a dry run relayer decided for a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DryRunDecision {
+    /// The message would have been submitted
+    WouldDeliver,
+    /// The message was already delivered, e.g. by another relayer
+    AlreadyDelivered,
+    /// The recipient's ISM would reject the metadata
+    VerifyFailed,
+    /// The gas payment policy doesn't allow delivering the message yet
+    GasPaymentNotMet,
+    /// Delivering the message would exceed the transaction gas limit
+    GasLimitExceeded,
+    /// Delivering the message would exceed a rate limit
+    RateLimited,
+}
+
+impl DryRunDecision {
+    /// The label of the decision in metrics
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DryRunDecision::WouldDeliver => "would_deliver",
+            DryRunDecision::AlreadyDelivered => "already_delivered",
+            DryRunDecision::VerifyFailed => "verify_failed",
+            DryRunDecision::GasPaymentNotMet => "gas_payment_not_met",
+            DryRunDecision::GasLimitExceeded => "gas_limit_exceeded",
+            DryRunDecision::RateLimited => "rate_limited",
+        }
+    }
+}
+
+/// The latest decision of a dry run relayer for a message, with the estimated
+/// cost of delivering it if it got that far
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunResult {
+    pub message_id: H256,
+    pub origin_domain: u32,
+    pub destination_domain: u32,
+    pub nonce: u32,
+    pub decision: DryRunDecision,
+    pub gas_limit: Option<U256>,
+    /// In the destination's native token, in the lowest denomination
+    pub estimated_native_spend: Option<U256>,
+    /// Unix timestamp of the decision, in seconds
+    pub decided_at: u64,
+}
+
+/// Records what a relayer running in dry run mode would have done instead of
+/// submitting transactions, so that a new configuration can be validated
+/// alongside the relayer in production.
+#[derive(Debug)]
+pub struct DryRunResults {
+    results: Mutex<Results>,
+    decisions: IntCounterVec,
+    estimated_gas: IntCounterVec,
+}
+
+#[derive(Debug, Default)]
+struct Results {
+    by_id: HashMap<H256, DryRunResult>,
+    /// Message ids from the oldest result to the newest
+    order: VecDeque<H256>,
+}
+
+impl DryRunResults {
+    pub fn new(decisions: IntCounterVec, estimated_gas: IntCounterVec) -> Self {
+        Self {
+            results: Default::default(),
+            decisions,
+            estimated_gas,
+        }
+    }
+
+    /// Records the latest decision for a message. Metrics are only updated
+    /// when the decision changes, so that retries aren't counted again.
+    pub fn record(
+        &self,
+        message: &HyperlaneMessage,
+        origin: &HyperlaneDomain,
+        destination: &HyperlaneDomain,
+        decision: DryRunDecision,
+        gas_limit: Option<U256>,
+        estimated_native_spend: Option<U256>,
+    ) {
+        let id = message.id();
+        let result = DryRunResult {
+            message_id: id,
+            origin_domain: message.origin,
+            destination_domain: message.destination,
+            nonce: message.nonce,
+            decision,
+            gas_limit,
+            estimated_native_spend,
+            decided_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since_epoch| since_epoch.as_secs()),
+        };
+
+        let mut results = self.results.lock().unwrap();
+        let previous = results.by_id.insert(id, result);
+        if previous.as_ref().map(|previous| previous.decision) == Some(decision) {
+            return;
+        }
+        if previous.is_none() {
+            results.order.push_back(id);
+            if results.order.len() > MAX_RESULTS {
+                if let Some(oldest) = results.order.pop_front() {
+                    results.by_id.remove(&oldest);
+                }
+            }
+        }
+        drop(results);
+
+        self.decisions
+            .with_label_values(&[origin.name(), destination.name(), decision.as_str()])
+            .inc();
+        if decision == DryRunDecision::WouldDeliver {
+            if let Some(gas_limit) = gas_limit {
+                self.estimated_gas
+                    .with_label_values(&[origin.name(), destination.name()])
+                    .inc_by(gas_limit.low_u64());
+            }
+        }
+    }
+
+    /// The latest results, newest first, optionally only for one message or
+    /// destination
+    pub fn list(
+        &self,
+        message_id: Option<H256>,
+        destination_domain: Option<u32>,
+    ) -> Vec<DryRunResult> {
+        let results = self.results.lock().unwrap();
+        results
+            .order
+            .iter()
+            .rev()
+            .filter_map(|id| results.by_id.get(id))
+            .filter(|result| message_id.map_or(true, |id| id == result.message_id))
+            .filter(|result| destination_domain.map_or(true, |d| d == result.destination_domain))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::KnownHyperlaneDomain;
+
+    use super::*;
+
+    fn dry_run_results() -> DryRunResults {
+        DryRunResults::new(
+            IntCounterVec::new(
+                prometheus::Opts::new("decisions", "help string"),
+                &["origin", "remote", "decision"],
+            )
+            .unwrap(),
+            IntCounterVec::new(
+                prometheus::Opts::new("estimated_gas", "help string"),
+                &["origin", "remote"],
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_records_latest_decision_per_message() {
+        let results = dry_run_results();
+        let origin = HyperlaneDomain::Known(KnownHyperlaneDomain::Test1);
+        let destination = HyperlaneDomain::Known(KnownHyperlaneDomain::Test2);
+        let messages = (0..2)
+            .map(|nonce| HyperlaneMessage {
+                nonce,
+                origin: origin.id(),
+                destination: destination.id(),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let decision_count = |decision: DryRunDecision| {
+            results
+                .decisions
+                .with_label_values(&[origin.name(), destination.name(), decision.as_str()])
+                .get()
+        };
+
+        for _ in 0..2 {
+            results.record(
+                &messages[0],
+                &origin,
+                &destination,
+                DryRunDecision::GasPaymentNotMet,
+                Some(100_000.into()),
+                None,
+            );
+        }
+        assert_eq!(decision_count(DryRunDecision::GasPaymentNotMet), 1);
+
+        results.record(
+            &messages[0],
+            &origin,
+            &destination,
+            DryRunDecision::WouldDeliver,
+            Some(100_000.into()),
+            Some(5.into()),
+        );
+        results.record(
+            &messages[1],
+            &origin,
+            &destination,
+            DryRunDecision::VerifyFailed,
+            None,
+            None,
+        );
+        assert_eq!(decision_count(DryRunDecision::WouldDeliver), 1);
+        assert_eq!(
+            results
+                .estimated_gas
+                .with_label_values(&[origin.name(), destination.name()])
+                .get(),
+            100_000
+        );
+
+        let listed = results.list(None, None);
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].message_id, messages[1].id());
+        assert_eq!(listed[1].decision, DryRunDecision::WouldDeliver);
+        assert_eq!(listed[1].estimated_native_spend, Some(5.into()));
+
+        let listed = results.list(Some(messages[0].id()), None);
+        assert_eq!(listed.len(), 1);
+        assert!(results.list(None, Some(origin.id())).is_empty());
+    }
+}
@@ -5,8 +5,9 @@ use eyre::Result;
 use hyperlane_base::db::HyperlaneRocksDB;
 use hyperlane_core::{
     FixedPointNumber, GasPaymentKey, HyperlaneMessage, InterchainGasExpenditure,
-    InterchainGasPayment, TxCostEstimate, TxOutcome, U256,
+    InterchainGasPayment, ModuleType, TxCostEstimate, TxOutcome, U256,
 };
+use serde::Serialize;
 use tracing::{debug, error, trace};
 
 use self::policies::{GasPaymentPolicyMinimum, GasPaymentPolicyNone};
@@ -30,6 +31,43 @@ pub trait GasPaymentPolicy: Debug + Send + Sync {
         current_expenditure: &InterchainGasExpenditure,
         tx_cost_estimate: &TxCostEstimate,
     ) -> Result<Option<U256>>;
+
+    /// What the policy requires to have been paid for a message, or None if
+    /// it depends on the transaction's cost and that isn't known.
+    fn requirement(
+        &self,
+        tx_cost_estimate: Option<&TxCostEstimate>,
+    ) -> Option<GasPaymentRequirement>;
+}
+
+/// What a gas payment policy requires to have been paid for a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GasPaymentRequirement {
+    /// Nothing has to be paid
+    None,
+    /// A minimum payment, in the origin's native token
+    MinimumPayment(U256),
+    /// A minimum amount of gas, not counting the gas already used delivering
+    /// the message
+    MinimumGasAmount(U256),
+}
+
+/// How the gas paid for a message compares to what the policy that applies
+/// to it requires
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasPaymentDiagnosis {
+    /// The policy that applies to the message, if any does
+    pub policy: Option<String>,
+    pub payment: U256,
+    pub gas_amount: U256,
+    pub gas_used: U256,
+    pub tokens_used: U256,
+    pub requirement: Option<GasPaymentRequirement>,
+    /// The gas limit the message would be delivered with, if the policy
+    /// approves it
+    pub approved_gas_limit: Option<U256>,
 }
 
 #[derive(Debug)]
@@ -72,25 +110,28 @@ impl GasPaymentEnforcer {
 }
 
 impl GasPaymentEnforcer {
+    /// Whether any of the policies' matching lists match on the module type of
+    /// the recipient's ISM.
+    pub fn matches_ism_module_type(&self) -> bool {
+        self.policies
+            .iter()
+            .any(|(_, whitelist)| whitelist.matches_ism_module_type())
+    }
+
     /// Returns Some(gas_limit) if the enforcer has approved the transaction or
-    /// None if the transaction is not approved.
+    /// None if the transaction is not approved. `ism_module_type` is the
+    /// module type of the recipient's ISM, if it was looked up.
     pub async fn message_meets_gas_payment_requirement(
         &self,
         message: &HyperlaneMessage,
         tx_cost_estimate: &TxCostEstimate,
+        ism_module_type: Option<ModuleType>,
     ) -> Result<Option<U256>> {
-        let msg_id = message.id();
-        let gas_payment_key = GasPaymentKey {
-            message_id: msg_id,
-            destination: message.destination,
-        };
-        let current_payment = self
-            .db
-            .retrieve_gas_payment_by_gas_payment_key(gas_payment_key)?;
-        let current_expenditure = self.db.retrieve_gas_expenditure_by_message_id(msg_id)?;
+        let (current_payment, current_expenditure) =
+            self.current_payment_and_expenditure(message)?;
 
         for (policy, whitelist) in &self.policies {
-            if !whitelist.msg_matches(message, true) {
+            if !whitelist.msg_matches_with_ism(message, ism_module_type, true) {
                 trace!(
                     msg=%message,
                     ?policy,
@@ -131,6 +172,63 @@ impl GasPaymentEnforcer {
         Ok(None)
     }
 
+    /// Compares the gas paid for the message to what the policy that applies
+    /// to it requires. If `tx_cost_estimate` isn't known, the policy isn't
+    /// evaluated.
+    pub async fn diagnose(
+        &self,
+        message: &HyperlaneMessage,
+        tx_cost_estimate: Option<&TxCostEstimate>,
+        ism_module_type: Option<ModuleType>,
+    ) -> Result<GasPaymentDiagnosis> {
+        let (current_payment, current_expenditure) =
+            self.current_payment_and_expenditure(message)?;
+        let policy = self
+            .policies
+            .iter()
+            .find(|(_, whitelist)| whitelist.msg_matches_with_ism(message, ism_module_type, true))
+            .map(|(policy, _)| policy);
+
+        let approved_gas_limit = match (policy, tx_cost_estimate) {
+            (Some(policy), Some(tx_cost_estimate)) => {
+                policy
+                    .message_meets_gas_payment_requirement(
+                        message,
+                        &current_payment,
+                        &current_expenditure,
+                        tx_cost_estimate,
+                    )
+                    .await?
+            }
+            _ => None,
+        };
+        Ok(GasPaymentDiagnosis {
+            policy: policy.map(|policy| format!("{policy:?}")),
+            payment: current_payment.payment,
+            gas_amount: current_payment.gas_amount,
+            gas_used: current_expenditure.gas_used,
+            tokens_used: current_expenditure.tokens_used,
+            requirement: policy.and_then(|policy| policy.requirement(tx_cost_estimate)),
+            approved_gas_limit,
+        })
+    }
+
+    fn current_payment_and_expenditure(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> Result<(InterchainGasPayment, InterchainGasExpenditure)> {
+        let msg_id = message.id();
+        let gas_payment_key = GasPaymentKey {
+            message_id: msg_id,
+            destination: message.destination,
+        };
+        let current_payment = self
+            .db
+            .retrieve_gas_payment_by_gas_payment_key(gas_payment_key)?;
+        let current_expenditure = self.db.retrieve_gas_expenditure_by_message_id(msg_id)?;
+        Ok((current_payment, current_expenditure))
+    }
+
     pub fn record_tx_outcome(&self, message: &HyperlaneMessage, outcome: TxOutcome) -> Result<()> {
         self.db.process_gas_expenditure(InterchainGasExpenditure {
             message_id: message.id(),
@@ -152,7 +250,7 @@ mod test {
         H256, U256,
     };
 
-    use super::GasPaymentEnforcer;
+    use super::{GasPaymentEnforcer, GasPaymentRequirement};
     use crate::settings::{
         matching_list::MatchingList, GasPaymentEnforcementConf, GasPaymentEnforcementPolicy,
     };
@@ -183,6 +281,7 @@ mod test {
                     .message_meets_gas_payment_requirement(
                         &HyperlaneMessage::default(),
                         &TxCostEstimate::default(),
+                        None,
                     )
                     .await
                     .unwrap(),
@@ -213,6 +312,7 @@ mod test {
                     .message_meets_gas_payment_requirement(
                         &HyperlaneMessage::default(),
                         &TxCostEstimate::default(),
+                        None,
                     )
                     .await,
                 Ok(None)
@@ -254,7 +354,7 @@ mod test {
             // Ensure if the gas payment was made to the incorrect destination, it does not meet
             // the requirement
             assert!(enforcer
-                .message_meets_gas_payment_requirement(&msg, &TxCostEstimate::default(),)
+                .message_meets_gas_payment_requirement(&msg, &TxCostEstimate::default(), None)
                 .await
                 .unwrap()
                 .is_none());
@@ -269,7 +369,7 @@ mod test {
             // Ensure if the gas payment was made to the correct destination, it meets the
             // requirement
             assert!(enforcer
-                .message_meets_gas_payment_requirement(&msg, &TxCostEstimate::default(),)
+                .message_meets_gas_payment_requirement(&msg, &TxCostEstimate::default(), None)
                 .await
                 .unwrap()
                 .is_some());
@@ -311,7 +411,7 @@ mod test {
 
             // Ensure if only half gas payment was made, it does not meet the requirement
             assert!(enforcer
-                .message_meets_gas_payment_requirement(&msg, &TxCostEstimate::default(),)
+                .message_meets_gas_payment_requirement(&msg, &TxCostEstimate::default(), None)
                 .await
                 .unwrap()
                 .is_none());
@@ -325,7 +425,7 @@ mod test {
             hyperlane_db.process_gas_payment(deficit_payment, &LogMeta::random());
             // Ensure if the full gas payment was made, it meets the requirement
             assert!(enforcer
-                .message_meets_gas_payment_requirement(&msg, &TxCostEstimate::default(),)
+                .message_meets_gas_payment_requirement(&msg, &TxCostEstimate::default(), None)
                 .await
                 .unwrap()
                 .is_some());
@@ -378,6 +478,7 @@ mod test {
                 .message_meets_gas_payment_requirement(
                     &matching_message,
                     &TxCostEstimate::default(),
+                    None,
                 )
                 .await
                 .unwrap()
@@ -396,6 +497,7 @@ mod test {
                 .message_meets_gas_payment_requirement(
                     &not_matching_message,
                     &TxCostEstimate::default(),
+                    None,
                 )
                 .await
                 .unwrap()
@@ -403,4 +505,57 @@ mod test {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn test_diagnose() {
+        #[allow(unused_must_use)]
+        test_utils::run_test_db(|db| async move {
+            let msg = HyperlaneMessage {
+                destination: 123,
+                ..HyperlaneMessage::default()
+            };
+            let hyperlane_db =
+                HyperlaneRocksDB::new(&HyperlaneDomain::new_test_domain("test_diagnose"), db);
+            let enforcer = GasPaymentEnforcer::new(
+                vec![GasPaymentEnforcementConf {
+                    policy: GasPaymentEnforcementPolicy::Minimum {
+                        payment: U256::from(2),
+                    },
+                    matching_list: serde_json::from_str(r#"[{"destinationdomain": 123}]"#).unwrap(),
+                }],
+                hyperlane_db.clone(),
+            );
+            hyperlane_db.process_gas_payment(
+                InterchainGasPayment {
+                    message_id: msg.id(),
+                    destination: msg.destination,
+                    payment: U256::one(),
+                    gas_amount: U256::from(50_000),
+                },
+                &LogMeta::random(),
+            );
+
+            let diagnosis = enforcer
+                .diagnose(&msg, Some(&TxCostEstimate::default()), None)
+                .await
+                .unwrap();
+            assert_eq!(diagnosis.payment, U256::one());
+            assert_eq!(diagnosis.gas_amount, U256::from(50_000));
+            assert_eq!(
+                diagnosis.requirement,
+                Some(GasPaymentRequirement::MinimumPayment(U256::from(2)))
+            );
+            assert_eq!(diagnosis.approved_gas_limit, None);
+
+            // Messages no policy applies to have no requirement
+            let unmatched = HyperlaneMessage {
+                destination: 456,
+                ..HyperlaneMessage::default()
+            };
+            let diagnosis = enforcer.diagnose(&unmatched, None, None).await.unwrap();
+            assert_eq!(diagnosis.policy, None);
+            assert_eq!(diagnosis.requirement, None);
+        })
+        .await;
+    }
 }
@@ -6,7 +6,7 @@ use hyperlane_core::{
     HyperlaneMessage, InterchainGasExpenditure, InterchainGasPayment, TxCostEstimate, U256,
 };
 
-use crate::msg::gas_payment::GasPaymentPolicy;
+use crate::msg::gas_payment::{GasPaymentPolicy, GasPaymentRequirement};
 
 #[derive(Debug, new)]
 pub struct GasPaymentPolicyMinimum {
@@ -28,6 +28,13 @@ impl GasPaymentPolicy for GasPaymentPolicyMinimum {
             Ok(None)
         }
     }
+
+    fn requirement(
+        &self,
+        _tx_cost_estimate: Option<&TxCostEstimate>,
+    ) -> Option<GasPaymentRequirement> {
+        Some(GasPaymentRequirement::MinimumPayment(self.minimum_payment))
+    }
 }
 
 #[tokio::test]
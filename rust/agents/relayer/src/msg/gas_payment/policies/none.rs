@@ -5,7 +5,7 @@ use hyperlane_core::{
     HyperlaneMessage, InterchainGasExpenditure, InterchainGasPayment, TxCostEstimate, U256,
 };
 
-use crate::msg::gas_payment::GasPaymentPolicy;
+use crate::msg::gas_payment::{GasPaymentPolicy, GasPaymentRequirement};
 
 #[derive(Debug)]
 pub struct GasPaymentPolicyNone;
@@ -21,6 +21,13 @@ impl GasPaymentPolicy for GasPaymentPolicyNone {
     ) -> Result<Option<U256>> {
         Ok(Some(tx_cost_estimate.gas_limit))
     }
+
+    fn requirement(
+        &self,
+        _tx_cost_estimate: Option<&TxCostEstimate>,
+    ) -> Option<GasPaymentRequirement> {
+        Some(GasPaymentRequirement::None)
+    }
 }
 
 #[tokio::test]
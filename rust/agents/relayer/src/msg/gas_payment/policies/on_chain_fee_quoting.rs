@@ -5,7 +5,7 @@ use hyperlane_core::{
     HyperlaneMessage, InterchainGasExpenditure, InterchainGasPayment, TxCostEstimate, U256,
 };
 
-use crate::msg::gas_payment::GasPaymentPolicy;
+use crate::msg::gas_payment::{GasPaymentPolicy, GasPaymentRequirement};
 
 #[derive(Debug)]
 pub struct GasPaymentPolicyOnChainFeeQuoting {
@@ -24,6 +24,12 @@ impl GasPaymentPolicyOnChainFeeQuoting {
             fractional_denominator,
         }
     }
+
+    /// The fraction of the estimated gas that must have been paid for
+    fn fractional_gas_estimate(&self, tx_cost_estimate: &TxCostEstimate) -> U256 {
+        (tx_cost_estimate.enforceable_gas_limit() * self.fractional_numerator)
+            / self.fractional_denominator
+    }
 }
 
 impl Default for GasPaymentPolicyOnChainFeeQuoting {
@@ -45,9 +51,7 @@ impl GasPaymentPolicy for GasPaymentPolicyOnChainFeeQuoting {
         current_expenditure: &InterchainGasExpenditure,
         tx_cost_estimate: &TxCostEstimate,
     ) -> Result<Option<U256>> {
-        let fractional_gas_estimate = (tx_cost_estimate.enforceable_gas_limit()
-            * self.fractional_numerator)
-            / self.fractional_denominator;
+        let fractional_gas_estimate = self.fractional_gas_estimate(tx_cost_estimate);
         let gas_amount = current_payment
             .gas_amount
             .saturating_sub(current_expenditure.gas_used);
@@ -59,6 +63,15 @@ impl GasPaymentPolicy for GasPaymentPolicyOnChainFeeQuoting {
             Ok(None)
         }
     }
+
+    fn requirement(
+        &self,
+        tx_cost_estimate: Option<&TxCostEstimate>,
+    ) -> Option<GasPaymentRequirement> {
+        tx_cost_estimate.map(|tx_cost_estimate| {
+            GasPaymentRequirement::MinimumGasAmount(self.fractional_gas_estimate(tx_cost_estimate))
+        })
+    }
 }
 
 #[cfg(test)]
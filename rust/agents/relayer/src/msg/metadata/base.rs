@@ -22,7 +22,8 @@ use eyre::{Context, Result};
 use hyperlane_base::db::HyperlaneRocksDB;
 use hyperlane_base::{
     settings::{ChainConf, CheckpointSyncerConf},
-    CheckpointSyncer, CoreMetrics, MultisigCheckpointSyncer,
+    CheckpointSyncer, CoreMetrics, MultisigCheckpointSyncer, ReplicatedCheckpointSyncer,
+    VerifiedCheckpointCache,
 };
 use hyperlane_core::{
     accumulator::merkle::Proof, AggregationIsm, CcipReadIsm, Checkpoint, HyperlaneDomain,
@@ -124,8 +125,13 @@ impl IsmAwareAppContextClassifier {
         &self,
         message: &HyperlaneMessage,
         root_ism: H256,
+        ism_module_type: Option<ModuleType>,
     ) -> Result<Option<String>> {
-        if let Some(app_context) = self.app_context_classifier.get_app_context(message).await? {
+        if let Some(app_context) = self
+            .app_context_classifier
+            .get_app_context(message, ism_module_type)
+            .await?
+        {
             return Ok(Some(app_context));
         }
 
@@ -151,12 +157,19 @@ impl AppContextClassifier {
     /// An app context is chosen based on:
     /// - the first element in `app_matching_lists` that matches the message
     /// - if the message's ISM is the default ISM, the app context is "default_ism"
-    pub async fn get_app_context(&self, message: &HyperlaneMessage) -> Result<Option<String>> {
+    ///
+    /// Matching lists can only match on the recipient ISM's module type if
+    /// `ism_module_type` is known.
+    pub async fn get_app_context(
+        &self,
+        message: &HyperlaneMessage,
+        ism_module_type: Option<ModuleType>,
+    ) -> Result<Option<String>> {
         // Give priority to the matching list. If the app from the matching list happens
         // to use the default ISM, it's preferable to use the app context from the matching
         // list.
         for (matching_list, app_context) in self.app_matching_lists.iter() {
-            if matching_list.msg_matches(message, false) {
+            if matching_list.msg_matches_with_ism(message, ism_module_type, false) {
                 return Ok(Some(app_context.clone()));
             }
         }
@@ -200,12 +213,13 @@ impl MetadataBuilder for MessageMetadataBuilder {
 impl MessageMetadataBuilder {
     pub async fn new(
         ism_address: H256,
+        ism_module_type: Option<ModuleType>,
         message: &HyperlaneMessage,
         base: Arc<BaseMetadataBuilder>,
     ) -> Result<Self> {
         let app_context = base
             .app_context_classifier
-            .get_app_context(message, ism_address)
+            .get_app_context(message, ism_address, ism_module_type)
             .await?;
         Ok(Self {
             base,
@@ -271,12 +285,14 @@ impl MessageMetadataBuilder {
 #[derive(new)]
 pub struct BaseMetadataBuilder {
     origin_domain: HyperlaneDomain,
+    origin_merkle_tree_hook_address: H256,
     destination_chain_setup: ChainConf,
     origin_prover_sync: Arc<RwLock<MerkleTreeBuilder>>,
     origin_validator_announce: Arc<dyn ValidatorAnnounce>,
     allow_local_checkpoint_syncers: bool,
     metrics: Arc<CoreMetrics>,
     db: HyperlaneRocksDB,
+    verified_checkpoints: VerifiedCheckpointCache,
     max_depth: u32,
     app_context_classifier: IsmAwareAppContextClassifier,
 }
@@ -300,6 +316,24 @@ impl BaseMetadataBuilder {
         &self.destination_chain_setup.domain
     }
 
+    /// How deep ISMs can be nested
+    pub fn max_depth(&self) -> u32 {
+        self.max_depth
+    }
+
+    /// Classifies `message` into an app context from the metric app contexts
+    /// alone, see `AppContextClassifier::get_app_context`.
+    pub async fn get_app_context(
+        &self,
+        message: &HyperlaneMessage,
+        ism_module_type: Option<ModuleType>,
+    ) -> Result<Option<String>> {
+        self.app_context_classifier
+            .app_context_classifier
+            .get_app_context(message, ism_module_type)
+            .await
+    }
+
     pub async fn get_proof(&self, leaf_index: u32, checkpoint: Checkpoint) -> Result<Proof> {
         const CTX: &str = "When fetching message proof";
         let proof = self
@@ -365,68 +399,104 @@ impl BaseMetadataBuilder {
         validators: &[H256],
         app_context: Option<String>,
     ) -> Result<MultisigCheckpointSyncer> {
-        let storage_locations = self
-            .origin_validator_announce
-            .get_announced_storage_locations(validators)
-            .await?;
+        build_checkpoint_syncer(
+            self.origin_validator_announce.as_ref(),
+            validators,
+            self.allow_local_checkpoint_syncers,
+            self.metrics.clone(),
+            app_context,
+            &self.origin_domain,
+            self.origin_merkle_tree_hook_address,
+            self.verified_checkpoints.clone(),
+        )
+        .await
+    }
+}
 
-        // Only use the most recently announced location for now.
-        let mut checkpoint_syncers: HashMap<H160, Arc<dyn CheckpointSyncer>> = HashMap::new();
-        for (&validator, validator_storage_locations) in validators.iter().zip(storage_locations) {
-            for storage_location in validator_storage_locations.iter().rev() {
-                let Ok(config) = CheckpointSyncerConf::from_str(storage_location) else {
-                    debug!(
-                        ?validator,
-                        ?storage_location,
-                        "Could not parse checkpoint syncer config for validator"
-                    );
-                    continue;
-                };
-
-                // If this is a LocalStorage based checkpoint syncer and it's not
-                // allowed, ignore it
-                if !self.allow_local_checkpoint_syncers
-                    && matches!(config, CheckpointSyncerConf::LocalStorage { .. })
-                {
-                    debug!(
-                        ?config,
-                        "Ignoring disallowed LocalStorage based checkpoint syncer"
-                    );
-                    continue;
-                }
+/// Builds a checkpoint syncer for each of the `validators` from the storage
+/// locations they announced on the origin chain. Verified checkpoints of the
+/// origin's merkle tree hook are cached in `verified_checkpoints`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn build_checkpoint_syncer(
+    validator_announce: &dyn ValidatorAnnounce,
+    validators: &[H256],
+    allow_local_checkpoint_syncers: bool,
+    metrics: Arc<CoreMetrics>,
+    app_context: Option<String>,
+    origin: &HyperlaneDomain,
+    merkle_tree_hook_address: H256,
+    verified_checkpoints: VerifiedCheckpointCache,
+) -> Result<MultisigCheckpointSyncer> {
+    let storage_locations = validator_announce
+        .get_announced_storage_locations(validators)
+        .await?;
+
+    // Use every valid announced location, most recently announced first,
+    // so that reads fall back to the others when one is unavailable.
+    let mut checkpoint_syncers: HashMap<H160, Arc<dyn CheckpointSyncer>> = HashMap::new();
+    for (&validator, validator_storage_locations) in validators.iter().zip(storage_locations) {
+        let mut validator_syncers: Vec<Arc<dyn CheckpointSyncer>> = vec![];
+        for storage_location in validator_storage_locations.iter().rev() {
+            let Ok(config) = CheckpointSyncerConf::from_str(storage_location) else {
+                debug!(
+                    ?validator,
+                    ?storage_location,
+                    "Could not parse checkpoint syncer config for validator"
+                );
+                continue;
+            };
+
+            // If this checkpoint syncer reads from the relayer's own machine
+            // or network and that's not allowed, ignore it
+            if !allow_local_checkpoint_syncers && config.is_local() {
+                debug!(?config, "Ignoring disallowed local checkpoint syncer");
+                continue;
+            }
 
-                match config.build(None).await {
-                    Ok(checkpoint_syncer) => {
-                        // found the syncer for this validator
-                        checkpoint_syncers.insert(validator.into(), checkpoint_syncer.into());
-                        break;
-                    }
-                    Err(err) => {
-                        debug!(
-                            error=%err,
-                            ?config,
-                            ?validator,
-                            "Error when loading checkpoint syncer; will attempt to use the next config"
-                        );
-                    }
+            match config.build(None).await {
+                Ok(checkpoint_syncer) => {
+                    validator_syncers.push(checkpoint_syncer.into());
                 }
-            }
-            if checkpoint_syncers.get(&validator.into()).is_none() {
-                if validator_storage_locations.is_empty() {
-                    warn!(?validator, "Validator has not announced any storage locations; see https://docs.hyperlane.xyz/docs/operators/validators/announcing-your-validator");
-                } else {
-                    warn!(
+                Err(err) => {
+                    debug!(
+                        error=%err,
+                        ?config,
                         ?validator,
-                        ?validator_storage_locations,
-                        "No valid checkpoint syncer configs for validator"
+                        "Error when loading checkpoint syncer; will attempt to use the next config"
                     );
                 }
             }
         }
-        Ok(MultisigCheckpointSyncer::new(
-            checkpoint_syncers,
-            self.metrics.clone(),
-            app_context,
-        ))
+        match validator_syncers.len() {
+            0 => {}
+            1 => {
+                checkpoint_syncers.insert(validator.into(), validator_syncers.remove(0));
+            }
+            _ => {
+                checkpoint_syncers.insert(
+                    validator.into(),
+                    Arc::new(ReplicatedCheckpointSyncer::new(validator_syncers, 1)),
+                );
+            }
+        }
+        if checkpoint_syncers.get(&validator.into()).is_none() {
+            if validator_storage_locations.is_empty() {
+                warn!(?validator, "Validator has not announced any storage locations; see https://docs.hyperlane.xyz/docs/operators/validators/announcing-your-validator");
+            } else {
+                warn!(
+                    ?validator,
+                    ?validator_storage_locations,
+                    "No valid checkpoint syncer configs for validator"
+                );
+            }
+        }
     }
+    Ok(MultisigCheckpointSyncer::new(
+        checkpoint_syncers,
+        metrics,
+        app_context,
+        origin.clone(),
+        merkle_tree_hook_address,
+        verified_checkpoints,
+    ))
 }
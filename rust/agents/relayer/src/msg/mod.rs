@@ -25,10 +25,16 @@
 //!   - FallbackProviderSubmitter (Serialized, but if some RPC provider sucks,
 //!   switch everyone to new one)
 
+pub(crate) mod diagnose;
+pub(crate) mod dry_run;
 pub(crate) mod gas_payment;
 pub(crate) mod metadata;
 pub(crate) mod op_queue;
+pub(crate) mod ordered_delivery;
 pub(crate) mod pending_message;
 pub(crate) mod pending_operation;
 pub(crate) mod processor;
+pub(crate) mod rate_limit;
 pub(crate) mod serial_submitter;
+#[cfg(test)]
+pub(crate) mod test_utils;
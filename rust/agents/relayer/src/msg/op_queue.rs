@@ -1,8 +1,10 @@
-use std::{cmp::Reverse, collections::BinaryHeap, sync::Arc};
+use std::{cmp::Reverse, collections::BinaryHeap, sync::Arc, time::Instant};
 
 use derive_new::new;
-use hyperlane_core::MpmcReceiver;
+use hyperlane_base::db::OperationDisposition;
+use hyperlane_core::{MpmcReceiver, H256};
 use prometheus::{IntGauge, IntGaugeVec};
+use serde::Serialize;
 use tokio::sync::Mutex;
 use tracing::info;
 
@@ -12,6 +14,22 @@ use super::pending_operation::PendingOperation;
 
 pub type QueueOperation = Box<dyn PendingOperation>;
 
+/// Point-in-time view of an operation sitting in an `OpQueue`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueOperationSummary {
+    pub id: H256,
+    pub queue: String,
+    pub origin_domain: u32,
+    pub destination_domain: u32,
+    pub app_context: Option<String>,
+    pub retry_count: u32,
+    /// Seconds until the operation will next be attempted, if it is backing off
+    pub next_attempt_after_secs: Option<u64>,
+    pub disposition: OperationDisposition,
+    pub last_error: Option<String>,
+}
+
 /// Queue of generic operations that can be submitted to a destination chain.
 /// Includes logic for maintaining queue metrics by the destination and `app_context` of an operation
 #[derive(Debug, Clone, new)]
@@ -32,10 +50,43 @@ impl OpQueue {
         self.queue.lock().await.push(Reverse(op));
     }
 
-    /// Pop an element from the queue and update metrics
+    /// Pop an element from the queue and update metrics.
+    ///
+    /// The first operation that is ready to be attempted is returned, so that
+    /// operations backing off don't hold back ready ones queued behind them,
+    /// e.g. deprioritized ones. If none is ready, the first operation that
+    /// isn't paused is returned. Paused operations are never returned.
     pub async fn pop(&mut self) -> Option<Reverse<QueueOperation>> {
         self.process_retry_requests().await;
-        let op = self.queue.lock().await.pop();
+        let op = {
+            let mut queue = self.queue.lock().await;
+            let mut skipped = vec![];
+            let mut ready = None;
+            while let Some(op) = queue.pop() {
+                if op.0.disposition() == OperationDisposition::Paused {
+                    skipped.push(op);
+                    continue;
+                }
+                let is_ready =
+                    op.0.next_attempt_after()
+                        .map_or(true, |at| at <= Instant::now());
+                if is_ready {
+                    ready = Some(op);
+                    break;
+                }
+                skipped.push(op);
+            }
+            // Fall back to the first operation that is backing off, which is
+            // the earliest one left in `skipped` that isn't paused
+            let op = ready.or_else(|| {
+                let index = skipped
+                    .iter()
+                    .position(|op| op.0.disposition() != OperationDisposition::Paused)?;
+                Some(skipped.remove(index))
+            });
+            queue.extend(skipped);
+            op
+        };
         op.map(|op| {
             // even if the metric is decremented here, the operation may fail to process and be re-added to the queue.
             // in those cases, the queue length will decrease to zero until the operation is re-added.
@@ -68,7 +119,10 @@ impl OpQueue {
                         id = ?e.id(),
                         destination_domain, "Retrying OpQueue operation"
                     );
-                    e.reset_attempts()
+                    e.reset_attempts();
+                    if e.disposition() != OperationDisposition::Normal {
+                        e.set_disposition(OperationDisposition::Normal);
+                    }
                 }
                 Reverse(e)
             })
@@ -76,6 +130,66 @@ impl OpQueue {
         queue.append(&mut reprioritized_queue);
     }
 
+    /// Summarize the operations in the queue, in priority order
+    pub async fn list_operations(&self) -> Vec<QueueOperationSummary> {
+        let queue = self.queue.lock().await;
+        let mut ops: Vec<_> = queue.iter().map(|Reverse(op)| op).collect();
+        ops.sort();
+
+        let now = Instant::now();
+        ops.into_iter()
+            .map(|op| QueueOperationSummary {
+                id: op.id(),
+                queue: self.queue_metrics_label.clone(),
+                origin_domain: op.origin_domain_id(),
+                destination_domain: op.destination_domain().id(),
+                app_context: op.app_context(),
+                retry_count: op.retry_count(),
+                next_attempt_after_secs: op
+                    .next_attempt_after()
+                    .map(|at| at.saturating_duration_since(now).as_secs()),
+                disposition: op.disposition(),
+                last_error: op.last_error(),
+            })
+            .collect()
+    }
+
+    /// Set the disposition of every queued operation matching `request` and
+    /// return how many were updated. Dropped operations are removed from the
+    /// queue. Operations that are currently being worked on are not affected.
+    pub async fn set_disposition(
+        &self,
+        request: &MessageRetryRequest,
+        disposition: OperationDisposition,
+    ) -> usize {
+        let mut queue = self.queue.lock().await;
+        let mut updated = 0;
+        let mut remaining: BinaryHeap<_> = queue
+            .drain()
+            .filter_map(|Reverse(mut op)| {
+                if request != op {
+                    return Some(Reverse(op));
+                }
+                info!(
+                    id = ?op.id(),
+                    destination_domain = %op.destination_domain(),
+                    ?disposition,
+                    "Updating OpQueue operation disposition"
+                );
+                updated += 1;
+                op.set_disposition(disposition);
+                if disposition == OperationDisposition::Dropped {
+                    self.get_operation_metric(op.as_ref()).dec();
+                    None
+                } else {
+                    Some(Reverse(op))
+                }
+            })
+            .collect();
+        queue.append(&mut remaining);
+        updated
+    }
+
     /// Get the metric associated with this operation
     fn get_operation_metric(&self, operation: &dyn PendingOperation) -> IntGauge {
         let (destination, app_context) = operation.get_operation_labels();
@@ -88,7 +202,9 @@ impl OpQueue {
 mod test {
     use super::*;
     use crate::msg::pending_operation::PendingOperationResult;
-    use hyperlane_core::{HyperlaneDomain, KnownHyperlaneDomain, MpmcChannel, H256};
+    use hyperlane_core::{
+        BatchItem, HyperlaneDomain, KnownHyperlaneDomain, Mailbox, MpmcChannel, TxOutcome, H256,
+    };
     use std::{
         collections::VecDeque,
         time::{Duration, Instant},
@@ -99,6 +215,7 @@ mod test {
         id: H256,
         seconds_to_next_attempt: u64,
         destination_domain: HyperlaneDomain,
+        disposition: OperationDisposition,
     }
 
     impl MockPendingOperation {
@@ -107,6 +224,7 @@ mod test {
                 id: H256::random(),
                 seconds_to_next_attempt,
                 destination_domain,
+                disposition: OperationDisposition::Normal,
             }
         }
     }
@@ -121,6 +239,28 @@ mod test {
             self.seconds_to_next_attempt = 0;
         }
 
+        fn retry_count(&self) -> u32 {
+            0
+        }
+
+        fn last_error(&self) -> Option<String> {
+            None
+        }
+
+        fn disposition(&self) -> OperationDisposition {
+            self.disposition
+        }
+
+        fn set_disposition(&mut self, disposition: OperationDisposition) {
+            self.disposition = disposition;
+        }
+
+        fn submission_lane(&self) -> Option<usize> {
+            None
+        }
+
+        fn set_submission_lane(&mut self, _lane: usize) {}
+
         fn priority(&self) -> u32 {
             todo!()
         }
@@ -130,7 +270,7 @@ mod test {
         }
 
         fn origin_domain_id(&self) -> u32 {
-            todo!()
+            0
         }
 
         fn destination_domain(&self) -> &HyperlaneDomain {
@@ -138,7 +278,7 @@ mod test {
         }
 
         fn app_context(&self) -> Option<String> {
-            todo!()
+            None
         }
 
         async fn prepare(&mut self) -> PendingOperationResult {
@@ -147,7 +287,15 @@ mod test {
 
         /// Submit this operation to the blockchain and report if it was successful
         /// or not.
-        async fn submit(&mut self) -> PendingOperationResult {
+        async fn submit(&mut self, _mailbox: &dyn Mailbox) -> PendingOperationResult {
+            todo!()
+        }
+
+        fn batch_item(&self) -> Option<BatchItem> {
+            None
+        }
+
+        async fn on_batch_submitted(&mut self, _tx_outcome: TxOutcome) -> PendingOperationResult {
             todo!()
         }
 
@@ -300,4 +448,112 @@ mod test {
         assert_eq!(popped[3], op_ids[0]);
         assert_eq!(popped[4], op_ids[1]);
     }
+
+    #[tokio::test]
+    async fn test_set_disposition() {
+        let (metrics, queue_metrics_label) = dummy_metrics_and_label();
+        let mpmc_channel = MpmcChannel::new(100);
+        let mut op_queue = OpQueue::new(metrics, queue_metrics_label, mpmc_channel.receiver());
+
+        let destination_domain: HyperlaneDomain = KnownHyperlaneDomain::Injective.into();
+        let ops: Vec<_> = (1..=4)
+            .map(|seconds_to_next_attempt| {
+                Box::new(MockPendingOperation::new(
+                    seconds_to_next_attempt,
+                    destination_domain.clone(),
+                )) as QueueOperation
+            })
+            .collect();
+        let op_ids: Vec<_> = ops.iter().map(|op| op.id()).collect();
+        for op in ops {
+            op_queue.push(op).await;
+        }
+
+        let updated = op_queue
+            .set_disposition(
+                &MessageRetryRequest::MessageId(op_ids[0]),
+                OperationDisposition::Paused,
+            )
+            .await;
+        assert_eq!(updated, 1);
+        op_queue
+            .set_disposition(
+                &MessageRetryRequest::MessageId(op_ids[1]),
+                OperationDisposition::Deprioritized,
+            )
+            .await;
+        op_queue
+            .set_disposition(
+                &MessageRetryRequest::MessageId(op_ids[2]),
+                OperationDisposition::Dropped,
+            )
+            .await;
+
+        let mut popped = vec![];
+        while let Some(op) = op_queue.pop().await {
+            popped.push(op.0);
+        }
+
+        // Dropped operations are gone, paused ones stay queued but are never popped
+        assert_eq!(popped.len(), 2);
+        assert_eq!(popped[0].id(), op_ids[3]);
+        assert_eq!(popped[1].id(), op_ids[1]);
+        assert_eq!(popped[1].disposition(), OperationDisposition::Deprioritized);
+        let queued = op_queue.list_operations().await;
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].id, op_ids[0]);
+        assert_eq!(queued[0].disposition, OperationDisposition::Paused);
+    }
+
+    #[tokio::test]
+    async fn test_pop_skips_operations_backing_off() {
+        let (metrics, queue_metrics_label) = dummy_metrics_and_label();
+        let mpmc_channel = MpmcChannel::new(100);
+        let mut op_queue = OpQueue::new(metrics, queue_metrics_label, mpmc_channel.receiver());
+
+        let destination_domain: HyperlaneDomain = KnownHyperlaneDomain::Injective.into();
+        let backing_off = Box::new(MockPendingOperation::new(60, destination_domain.clone()));
+        let mut deprioritized = Box::new(MockPendingOperation::new(0, destination_domain));
+        deprioritized.disposition = OperationDisposition::Deprioritized;
+        let (backing_off_id, deprioritized_id) = (backing_off.id(), deprioritized.id());
+        op_queue.push(backing_off).await;
+        op_queue.push(deprioritized).await;
+
+        // The deprioritized operation sorts last but is the only one ready
+        assert_eq!(op_queue.list_operations().await[0].id, backing_off_id);
+        assert_eq!(op_queue.pop().await.unwrap().0.id(), deprioritized_id);
+        // Without a ready operation, the one backing off is returned
+        assert_eq!(op_queue.pop().await.unwrap().0.id(), backing_off_id);
+        assert!(op_queue.pop().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_retry_resets_disposition() {
+        let (metrics, queue_metrics_label) = dummy_metrics_and_label();
+        let mpmc_channel = MpmcChannel::new(100);
+        let mut op_queue = OpQueue::new(metrics, queue_metrics_label, mpmc_channel.receiver());
+
+        let destination_domain: HyperlaneDomain = KnownHyperlaneDomain::Injective.into();
+        let paused = Box::new(MockPendingOperation::new(1, destination_domain.clone()));
+        let other = Box::new(MockPendingOperation::new(2, destination_domain));
+        let (paused_id, other_id) = (paused.id(), other.id());
+        op_queue.push(paused).await;
+        op_queue.push(other).await;
+        op_queue
+            .set_disposition(
+                &MessageRetryRequest::MessageId(paused_id),
+                OperationDisposition::Paused,
+            )
+            .await;
+
+        mpmc_channel
+            .sender()
+            .send(MessageRetryRequest::MessageId(paused_id))
+            .unwrap();
+
+        let first = op_queue.pop().await.unwrap().0;
+        assert_eq!(first.id(), paused_id);
+        assert_eq!(first.disposition(), OperationDisposition::Normal);
+        assert_eq!(op_queue.pop().await.unwrap().0.id(), other_id);
+    }
 }
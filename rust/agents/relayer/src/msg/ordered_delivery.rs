@@ -0,0 +1,171 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use hyperlane_core::{HyperlaneMessage, H256};
+use prometheus::IntGauge;
+
+use crate::settings::matching_list::MatchingList;
+
+/// The messages that must be delivered in order relative to each other
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+struct SequenceKey {
+    origin: u32,
+    sender: H256,
+    recipient: H256,
+}
+
+impl From<&HyperlaneMessage> for SequenceKey {
+    fn from(message: &HyperlaneMessage) -> Self {
+        Self {
+            origin: message.origin,
+            sender: message.sender,
+            recipient: message.recipient,
+        }
+    }
+}
+
+/// Holds back messages that must be delivered in nonce order until the
+/// messages before them from the same origin, sender and recipient are
+/// delivered. Which messages must be delivered in order is configured with a
+/// matching list, all other messages are never held back.
+///
+/// Messages are tracked in the order the processor finds them, which is nonce
+/// order, and are released once they are delivered or dropped. Delivery in
+/// order can't be guaranteed if a destination chain reorg drops a delivery
+/// that later ones were submitted after.
+#[derive(Debug)]
+pub struct OrderedDelivery {
+    matching_list: Arc<MatchingList>,
+    /// The nonces of the undelivered messages of each sequence
+    undelivered: Mutex<HashMap<SequenceKey, BTreeSet<u32>>>,
+    /// The number of messages held back by messages before them
+    held_messages: IntGauge,
+}
+
+impl OrderedDelivery {
+    pub fn new(matching_list: Arc<MatchingList>, held_messages: IntGauge) -> Self {
+        Self {
+            matching_list,
+            undelivered: Default::default(),
+            held_messages,
+        }
+    }
+
+    /// Tracks the message until it is released, if it must be delivered in
+    /// order
+    pub fn track(&self, message: &HyperlaneMessage) {
+        if !self.matching_list.msg_matches(message, false) {
+            return;
+        }
+        let mut undelivered = self.undelivered.lock().unwrap();
+        let nonces = undelivered.entry(message.into()).or_default();
+        if nonces.insert(message.nonce) && nonces.len() > 1 {
+            self.held_messages.inc();
+        }
+    }
+
+    /// Whether the message is held back by undelivered messages before it
+    pub fn is_held(&self, message: &HyperlaneMessage) -> bool {
+        let undelivered = self.undelivered.lock().unwrap();
+        undelivered
+            .get(&message.into())
+            .and_then(|nonces| nonces.first())
+            .map_or(false, |&first| first < message.nonce)
+    }
+
+    /// Stops tracking the message because it was delivered or won't be,
+    /// releasing the message after it
+    pub fn release(&self, message: &HyperlaneMessage) {
+        let mut undelivered = self.undelivered.lock().unwrap();
+        let key = message.into();
+        let Some(nonces) = undelivered.get_mut(&key) else {
+            return;
+        };
+        if !nonces.remove(&message.nonce) {
+            return;
+        }
+        if nonces.is_empty() {
+            undelivered.remove(&key);
+        } else {
+            self.held_messages.dec();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn message(sender: u64, nonce: u32) -> HyperlaneMessage {
+        HyperlaneMessage {
+            nonce,
+            origin: 1,
+            sender: H256::from_low_u64_be(sender),
+            destination: 2,
+            recipient: H256::from_low_u64_be(10),
+            ..Default::default()
+        }
+    }
+
+    fn ordered_delivery() -> OrderedDelivery {
+        let matching_list: MatchingList = serde_json::from_str(&format!(
+            r#"[{{"senderaddress": "{:?}"}}]"#,
+            H256::from_low_u64_be(1)
+        ))
+        .unwrap();
+        OrderedDelivery::new(
+            Arc::new(matching_list),
+            IntGauge::new("held_messages", "help string").unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_holds_messages_until_predecessors_are_released() {
+        let ordered_delivery = ordered_delivery();
+        let (first, second, third) = (message(1, 3), message(1, 5), message(1, 8));
+        for message in [&first, &second, &third] {
+            ordered_delivery.track(message);
+        }
+        assert!(!ordered_delivery.is_held(&first));
+        assert!(ordered_delivery.is_held(&second));
+        assert!(ordered_delivery.is_held(&third));
+        assert_eq!(ordered_delivery.held_messages.get(), 2);
+
+        // Tracking a message again doesn't hold it back twice
+        ordered_delivery.track(&second);
+        assert_eq!(ordered_delivery.held_messages.get(), 2);
+
+        ordered_delivery.release(&first);
+        assert!(!ordered_delivery.is_held(&second));
+        assert!(ordered_delivery.is_held(&third));
+        assert_eq!(ordered_delivery.held_messages.get(), 1);
+
+        // Releasing a message twice doesn't release the ones after it
+        ordered_delivery.release(&first);
+        assert!(ordered_delivery.is_held(&third));
+
+        ordered_delivery.release(&second);
+        ordered_delivery.release(&third);
+        assert_eq!(ordered_delivery.held_messages.get(), 0);
+    }
+
+    #[test]
+    fn test_only_holds_matching_messages_of_the_same_sequence() {
+        let ordered_delivery = ordered_delivery();
+        ordered_delivery.track(&message(1, 3));
+
+        // Other senders aren't ordered
+        let unmatched = message(2, 5);
+        ordered_delivery.track(&unmatched);
+        assert!(!ordered_delivery.is_held(&unmatched));
+
+        // Other recipients are another sequence
+        let mut other_recipient = message(1, 6);
+        other_recipient.recipient = H256::from_low_u64_be(11);
+        ordered_delivery.track(&other_recipient);
+        assert!(!ordered_delivery.is_held(&other_recipient));
+        assert_eq!(ordered_delivery.held_messages.get(), 0);
+    }
+}
@@ -1,22 +1,32 @@
 use std::{
     fmt::{Debug, Formatter},
     sync::Arc,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use async_trait::async_trait;
 use derive_new::new;
 use eyre::Result;
-use hyperlane_base::{db::HyperlaneRocksDB, CoreMetrics};
-use hyperlane_core::{HyperlaneChain, HyperlaneDomain, HyperlaneMessage, Mailbox, H256, U256};
+use hyperlane_base::{
+    db::{HyperlaneRocksDB, OperationDisposition, PendingMessageState},
+    CoreMetrics,
+};
+use hyperlane_core::{
+    BatchItem, HyperlaneChain, HyperlaneDomain, HyperlaneMessage, Mailbox, ModuleType, TxOutcome,
+    H256, U256,
+};
 use prometheus::{IntCounter, IntGauge};
 use tracing::{debug, error, info, instrument, trace, warn};
 
 use super::{
+    dry_run::{DryRunDecision, DryRunResults},
     gas_payment::GasPaymentEnforcer,
     metadata::{BaseMetadataBuilder, MessageMetadataBuilder, MetadataBuilder},
+    ordered_delivery::OrderedDelivery,
     pending_operation::*,
+    rate_limit::{estimated_native_spend, RateLimitReservation, RateLimiter},
 };
+use crate::settings::matching_list::MatchingList;
 
 const CONFIRM_DELAY: Duration = if cfg!(any(test, feature = "test-utils")) {
     // Wait 5 seconds after submitting the message before confirming in test mode
@@ -26,6 +36,14 @@ const CONFIRM_DELAY: Duration = if cfg!(any(test, feature = "test-utils")) {
     Duration::from_secs(60 * 10)
 };
 
+/// How long to wait before checking again whether a message that is held back
+/// by the messages before it can be delivered
+const HELD_MESSAGE_DELAY: Duration = Duration::from_secs(5);
+
+/// How long to wait before preparing a message again in dry run mode once it
+/// would have been delivered, to keep its dry run result up to date
+const DRY_RUN_REPREPARE_DELAY: Duration = Duration::from_secs(60 * 10);
+
 /// The message context contains the links needed to submit a message. Each
 /// instance is for a unique origin -> destination pairing.
 pub struct MessageContext {
@@ -42,6 +60,21 @@ pub struct MessageContext {
     /// Hard limit on transaction gas when submitting a transaction to the
     /// destination.
     pub transaction_gas_limit: Option<U256>,
+    /// Holds back messages that must be delivered in order until the messages
+    /// before them are delivered.
+    pub ordered_delivery: Arc<OrderedDelivery>,
+    /// Limits deliveries to the destination, shared by all origins.
+    pub rate_limiter: Arc<RateLimiter>,
+    /// Checked again once the recipient's ISM module type is known, for the
+    /// rules that match on it.
+    pub whitelist: Arc<MatchingList>,
+    pub blacklist: Arc<MatchingList>,
+    /// Whether any matching list matches on the recipient ISM's module type,
+    /// which is only looked up if so.
+    pub match_ism_module_type: bool,
+    /// If set, the relayer runs in dry run mode: messages are prepared, but
+    /// what would have been submitted is recorded here instead.
+    pub dry_run: Option<Arc<DryRunResults>>,
     pub metrics: MessageSubmissionMetrics,
 }
 
@@ -55,12 +88,32 @@ pub struct PendingMessage {
     submitted: bool,
     #[new(default)]
     submission_data: Option<Box<SubmissionData>>,
+    /// Set when a batch transaction including the message reverted, so that
+    /// the message is submitted on its own until it is processed.
+    #[new(default)]
+    submit_individually: bool,
+    /// What the message took from the rate limits when it was last prepared,
+    /// until it's settled with the outcome of its delivery transaction.
+    #[new(default)]
+    rate_limit_reservation: Option<RateLimitReservation>,
+    /// Set once a dry run found the message would be delivered, as its
+    /// delivery is then counted against the rate limits for good.
+    #[new(default)]
+    rate_limit_spent: bool,
+    /// The submitter lane the message is pinned to, see
+    /// `PendingOperation::submission_lane`
+    #[new(default)]
+    submission_lane: Option<usize>,
     #[new(default)]
     num_retries: u32,
     #[new(value = "Instant::now()")]
     last_attempted_at: Instant,
     #[new(default)]
     next_attempt_after: Option<Instant>,
+    #[new(default)]
+    disposition: OperationDisposition,
+    #[new(default)]
+    last_error: Option<String>,
 }
 
 /// State for the next submission attempt generated by a prepare call.
@@ -84,8 +137,8 @@ impl Debug for PendingMessage {
                 }
             })
             .unwrap_or(0);
-        write!(f, "PendingMessage {{ num_retries: {}, since_last_attempt_s: {last_attempt}, next_attempt_after_s: {next_attempt}, message: {:?} }}",
-               self.num_retries, self.message)
+        write!(f, "PendingMessage {{ num_retries: {}, since_last_attempt_s: {last_attempt}, next_attempt_after_s: {next_attempt}, disposition: {:?}, message: {:?} }}",
+               self.num_retries, self.disposition, self.message)
     }
 }
 
@@ -123,7 +176,16 @@ impl PendingOperation for PendingMessage {
 
     #[instrument]
     async fn prepare(&mut self) -> PendingOperationResult {
-        make_op_try!(|| self.on_reprepare());
+        make_op_try!(|reason: String| self.on_reprepare(reason));
+
+        match self.disposition {
+            OperationDisposition::Dropped => return self.on_drop(),
+            OperationDisposition::Paused => {
+                trace!("Message is paused");
+                return PendingOperationResult::NotReady;
+            }
+            OperationDisposition::Normal | OperationDisposition::Deprioritized => {}
+        }
 
         if !self.is_ready() {
             trace!("Message is not ready to be submitted yet");
@@ -142,11 +204,20 @@ impl PendingOperation for PendingMessage {
         );
         if is_already_delivered {
             debug!("Message has already been delivered, marking as submitted.");
+            self.record_dry_run(DryRunDecision::AlreadyDelivered, None, None);
             self.submitted = true;
             self.next_attempt_after = Some(Instant::now() + CONFIRM_DELAY);
+            self.ctx.ordered_delivery.release(&self.message);
             return PendingOperationResult::Success;
         }
 
+        if self.ctx.ordered_delivery.is_held(&self.message) {
+            trace!("Message is held back until the messages before it are delivered");
+            // Don't hold up the messages behind this one in the queue
+            self.next_attempt_after = Some(Instant::now() + HELD_MESSAGE_DELAY);
+            return PendingOperationResult::NotReady;
+        }
+
         let provider = self.ctx.destination_mailbox.provider();
 
         // We cannot deliver to an address that is not a contract so check and drop if it isn't.
@@ -159,7 +230,7 @@ impl PendingOperation for PendingMessage {
                 recipient=?self.message.recipient,
                 "Dropping message because recipient is not a contract"
             );
-            return PendingOperationResult::Drop;
+            return self.on_drop();
         }
 
         let ism_address = op_try!(
@@ -170,9 +241,46 @@ impl PendingOperation for PendingMessage {
             "fetching ISM address. Potentially malformed recipient ISM address."
         );
 
+        let ism_module_type = if self.ctx.match_ism_module_type {
+            Some(op_try!(
+                self.fetch_ism_module_type(ism_address).await,
+                "fetching recipient ISM module type"
+            ))
+        } else {
+            None
+        };
+        // The processor can't check rules on the recipient ISM's module type
+        let whitelisted =
+            self.ctx
+                .whitelist
+                .msg_matches_with_ism(&self.message, ism_module_type, true);
+        let blacklisted =
+            self.ctx
+                .blacklist
+                .msg_matches_with_ism(&self.message, ism_module_type, false);
+        if ism_module_type.is_some() && (!whitelisted || blacklisted) {
+            info!(
+                ?ism_module_type,
+                "Dropping message because it's not whitelisted or is blacklisted for its recipient ISM module type"
+            );
+            return self.on_drop();
+        }
+        // The processor classified the message into a metric app context
+        // before the recipient ISM's module type was known
+        if ism_module_type.is_some() {
+            self.app_context = op_try!(
+                self.ctx
+                    .metadata_builder
+                    .get_app_context(&self.message, ism_module_type)
+                    .await,
+                "classifying the message's app context"
+            );
+        }
+
         let message_metadata_builder = op_try!(
             MessageMetadataBuilder::new(
                 ism_address,
+                ism_module_type,
                 &self.message,
                 self.ctx.metadata_builder.clone()
             )
@@ -187,9 +295,27 @@ impl PendingOperation for PendingMessage {
             "building metadata"
         ) else {
             info!("Could not fetch metadata");
-            return self.on_reprepare();
+            return self.on_reprepare("Could not fetch metadata");
         };
 
+        // Submitting would fail anyway if the ISM rejects the metadata, so this
+        // is only checked to report it in dry run mode
+        if self.ctx.dry_run.is_some() {
+            let ism = op_try!(
+                self.ctx.metadata_builder.build_ism(ism_address).await,
+                "building recipient ISM"
+            );
+            let verify_gas = op_try!(
+                ism.dry_run_verify(&self.message, &metadata).await,
+                "dry running ISM verification"
+            );
+            if verify_gas.is_none() {
+                info!("Recipient ISM would reject the metadata");
+                self.record_dry_run(DryRunDecision::VerifyFailed, None, None);
+                return self.on_reprepare("Recipient ISM would reject the metadata");
+            }
+        }
+
         // Estimate transaction costs for the process call. If there are issues, it's
         // likely that gas estimation has failed because the message is
         // reverting. This is defined behavior, so we just log the error and
@@ -206,12 +332,21 @@ impl PendingOperation for PendingMessage {
         let Some(gas_limit) = op_try!(
             self.ctx
                 .origin_gas_payment_enforcer
-                .message_meets_gas_payment_requirement(&self.message, &tx_cost_estimate)
+                .message_meets_gas_payment_requirement(
+                    &self.message,
+                    &tx_cost_estimate,
+                    ism_module_type
+                )
                 .await,
             "checking if message meets gas payment requirement"
         ) else {
             warn!(?tx_cost_estimate, "Gas payment requirement not met yet");
-            return self.on_reprepare();
+            self.record_dry_run(
+                DryRunDecision::GasPaymentNotMet,
+                Some(tx_cost_estimate.gas_limit),
+                None,
+            );
+            return self.on_reprepare("Gas payment requirement not met yet");
         };
 
         // Go ahead and attempt processing of message to destination chain.
@@ -225,10 +360,44 @@ impl PendingOperation for PendingMessage {
         if let Some(max_limit) = self.ctx.transaction_gas_limit {
             if gas_limit > max_limit {
                 info!("Message delivery estimated gas exceeds max gas limit");
-                return self.on_reprepare();
+                self.record_dry_run(DryRunDecision::GasLimitExceeded, Some(gas_limit), None);
+                return self.on_reprepare("Message delivery estimated gas exceeds max gas limit");
             }
         }
 
+        let native_spend = op_try!(
+            estimated_native_spend(&tx_cost_estimate),
+            "estimating native spend of process call"
+        );
+        if let Err(wait_time) = self.acquire_rate_limits(gas_limit, native_spend) {
+            debug!(?wait_time, "Message delivery is rate limited");
+            self.record_dry_run(
+                DryRunDecision::RateLimited,
+                Some(gas_limit),
+                Some(native_spend),
+            );
+            // Don't count this as a retry, the message is fine
+            self.next_attempt_after = Some(Instant::now() + wait_time);
+            return PendingOperationResult::NotReady;
+        }
+
+        if self.ctx.dry_run.is_some() {
+            info!(?gas_limit, ?native_spend, "Dry run, not submitting message");
+            self.record_dry_run(
+                DryRunDecision::WouldDeliver,
+                Some(gas_limit),
+                Some(native_spend),
+            );
+            self.spend_rate_limit_reservation();
+            // The messages that must be delivered after this one would be
+            // delivered too
+            self.ctx.ordered_delivery.release(&self.message);
+            // Not a retry, the message is kept to check again whether it
+            // would still be delivered
+            self.next_attempt_after = Some(Instant::now() + DRY_RUN_REPREPARE_DELAY);
+            return PendingOperationResult::NotReady;
+        }
+
         self.submission_data = Some(Box::new(SubmissionData {
             metadata,
             gas_limit,
@@ -236,9 +405,9 @@ impl PendingOperation for PendingMessage {
         PendingOperationResult::Success
     }
 
-    #[instrument]
-    async fn submit(&mut self) -> PendingOperationResult {
-        make_op_try!(|| self.on_reprepare());
+    #[instrument(skip(mailbox))]
+    async fn submit(&mut self, mailbox: &dyn Mailbox) -> PendingOperationResult {
+        make_op_try!(|reason: String| self.on_reprepare(reason));
 
         if self.submitted {
             // this message has already been submitted, possibly not by us
@@ -257,36 +426,41 @@ impl PendingOperation for PendingMessage {
         // We use the estimated gas limit from the prior call to
         // `process_estimate_costs` to avoid a second gas estimation.
         let tx_outcome = op_try!(
-            self.ctx
-                .destination_mailbox
+            mailbox
                 .process(&self.message, &state.metadata, Some(state.gas_limit))
                 .await,
             "processing message"
         );
 
-        op_try!(critical: self.ctx.origin_gas_payment_enforcer.record_tx_outcome(&self.message, tx_outcome.clone()), "recording tx outcome");
-        if tx_outcome.executed {
-            info!(
-                txid=?tx_outcome.transaction_id,
-                "Message successfully processed by transaction"
-            );
-            self.submitted = true;
-            self.reset_attempts();
-            self.next_attempt_after = Some(Instant::now() + CONFIRM_DELAY);
-            PendingOperationResult::Success
-        } else {
-            warn!(
-                txid=?tx_outcome.transaction_id,
-                "Transaction attempting to process message reverted"
-            );
-            self.on_reprepare()
+        self.on_tx_outcome(tx_outcome)
+    }
+
+    fn batch_item(&self) -> Option<BatchItem> {
+        if self.submitted || self.submit_individually {
+            return None;
         }
+        let state = self.submission_data.as_ref()?;
+        Some(BatchItem {
+            message: self.message.clone(),
+            metadata: state.metadata.clone(),
+            gas_limit: state.gas_limit,
+        })
+    }
+
+    #[instrument]
+    async fn on_batch_submitted(&mut self, tx_outcome: TxOutcome) -> PendingOperationResult {
+        // the submission data was used by the batch
+        self.submission_data = None;
+        // A revert may have been caused by any of the messages in the batch
+        self.submit_individually |= !tx_outcome.executed;
+        self.on_tx_outcome(tx_outcome)
     }
 
     async fn confirm(&mut self) -> PendingOperationResult {
-        make_op_try!(|| {
+        make_op_try!(|reason: String| {
             // Provider error; just try again later
             // Note: this means that we are using `NotReady` for a retryable error case
+            self.set_last_error(reason);
             self.inc_attempts();
             PendingOperationResult::NotReady
         });
@@ -315,7 +489,7 @@ impl PendingOperation for PendingMessage {
             PendingOperationResult::Success
         } else {
             self.reset_attempts();
-            self.on_reprepare()
+            self.on_reprepare("Message was not delivered after submission")
         }
     }
 
@@ -327,6 +501,31 @@ impl PendingOperation for PendingMessage {
         self.reset_attempts();
     }
 
+    fn retry_count(&self) -> u32 {
+        self.num_retries
+    }
+
+    fn last_error(&self) -> Option<String> {
+        self.last_error.clone()
+    }
+
+    fn disposition(&self) -> OperationDisposition {
+        self.disposition
+    }
+
+    fn set_disposition(&mut self, disposition: OperationDisposition) {
+        self.disposition = disposition;
+        self.persist_state();
+    }
+
+    fn submission_lane(&self) -> Option<usize> {
+        self.submission_lane
+    }
+
+    fn set_submission_lane(&mut self, lane: usize) {
+        self.submission_lane = Some(lane);
+    }
+
     #[cfg(test)]
     fn set_retries(&mut self, retries: u32) {
         self.set_retries(retries);
@@ -334,7 +533,10 @@ impl PendingOperation for PendingMessage {
 }
 
 impl PendingMessage {
-    /// Constructor that tries reading the retry count from the HyperlaneDB in order to recompute the `next_attempt_after`.
+    /// Constructor that tries reading the persisted queue state (retries,
+    /// next attempt, disposition and last error) from the HyperlaneDB. Falls
+    /// back to recomputing the `next_attempt_after` from the retry count for
+    /// messages that only have that persisted.
     /// In case of failure, behaves like `Self::new(...)`.
     pub fn from_persisted_retries(
         message: HyperlaneMessage,
@@ -342,6 +544,22 @@ impl PendingMessage {
         app_context: Option<String>,
     ) -> Self {
         let mut pm = Self::new(message, ctx, app_context);
+        match pm
+            .ctx
+            .origin_db
+            .retrieve_pending_message_state_by_message_id(&pm.message.id())
+        {
+            Ok(Some(state)) => {
+                pm.disposition = state.disposition;
+                pm.last_error = state.last_error;
+                pm.num_retries = state.num_retries;
+                pm.next_attempt_after = state.next_attempt_after.map(instant_from_unix_millis);
+                return pm;
+            }
+            r => {
+                trace!(message_id = ?pm.message.id(), result = ?r, "Failed to read queue state from HyperlaneDB for message.")
+            }
+        }
         match pm
             .ctx
             .origin_db
@@ -360,12 +578,115 @@ impl PendingMessage {
         pm
     }
 
-    fn on_reprepare(&mut self) -> PendingOperationResult {
+    fn on_tx_outcome(&mut self, tx_outcome: TxOutcome) -> PendingOperationResult {
+        make_op_try!(|reason: String| self.on_reprepare(reason));
+
+        if let Some(reservation) = self.rate_limit_reservation.take() {
+            self.ctx
+                .rate_limiter
+                .settle(&self.message, reservation, &tx_outcome);
+        }
+        op_try!(critical: self.ctx.origin_gas_payment_enforcer.record_tx_outcome(&self.message, tx_outcome.clone()), "recording tx outcome");
+        if tx_outcome.executed {
+            info!(
+                txid=?tx_outcome.transaction_id,
+                "Message successfully processed by transaction"
+            );
+            self.submitted = true;
+            self.submit_individually = false;
+            self.reset_attempts();
+            self.next_attempt_after = Some(Instant::now() + CONFIRM_DELAY);
+            self.ctx.ordered_delivery.release(&self.message);
+            PendingOperationResult::Success
+        } else {
+            warn!(
+                txid=?tx_outcome.transaction_id,
+                "Transaction attempting to process message reverted"
+            );
+            self.on_reprepare("Transaction attempting to process message reverted")
+        }
+    }
+
+    /// Records the decision for the message if the relayer runs in dry run
+    /// mode
+    fn record_dry_run(
+        &self,
+        decision: DryRunDecision,
+        gas_limit: Option<U256>,
+        native_spend: Option<U256>,
+    ) {
+        if let Some(dry_run) = &self.ctx.dry_run {
+            dry_run.record(
+                &self.message,
+                self.ctx.metadata_builder.origin_domain(),
+                self.ctx.metadata_builder.destination_domain(),
+                decision,
+                gas_limit,
+                native_spend,
+            );
+        }
+    }
+
+    /// Fetches the module type of the recipient's ISM
+    async fn fetch_ism_module_type(&self, ism_address: H256) -> Result<ModuleType> {
+        let ism = self.ctx.metadata_builder.build_ism(ism_address).await?;
+        Ok(ism.module_type().await?)
+    }
+
+    /// Drops the message, which releases the messages that must be delivered
+    /// after it
+    fn on_drop(&mut self) -> PendingOperationResult {
+        self.release_rate_limit_reservation();
+        self.ctx.ordered_delivery.release(&self.message);
+        PendingOperationResult::Drop
+    }
+
+    fn on_reprepare(&mut self, reason: impl Into<String>) -> PendingOperationResult {
+        self.release_rate_limit_reservation();
+        // Persisted along with the attempts
+        self.last_error = Some(reason.into());
         self.inc_attempts();
         self.submitted = false;
         PendingOperationResult::Reprepare
     }
 
+    /// Takes the delivery of the message from the rate limits, unless a dry
+    /// run already counted it as delivered. If they don't allow it yet, the
+    /// time to wait before trying again is returned.
+    fn acquire_rate_limits(&mut self, gas: U256, native_spend: U256) -> Result<(), Duration> {
+        if self.rate_limit_spent {
+            return Ok(());
+        }
+        let reservation = self
+            .ctx
+            .rate_limiter
+            .try_acquire(&self.message, gas, native_spend)?;
+        self.rate_limit_reservation = Some(reservation);
+        Ok(())
+    }
+
+    /// Keeps what the message took from the rate limits for good, as a dry run
+    /// found it would be delivered, so that it's neither given back nor taken
+    /// again.
+    fn spend_rate_limit_reservation(&mut self) {
+        self.rate_limit_reservation = None;
+        self.rate_limit_spent = true;
+    }
+
+    /// Gives back what the message took from the rate limits if no delivery
+    /// transaction was sent for it, so that it's taken again when the message
+    /// is prepared again.
+    fn release_rate_limit_reservation(&mut self) {
+        if let Some(reservation) = self.rate_limit_reservation.take() {
+            self.ctx.rate_limiter.release(&self.message, reservation);
+        }
+    }
+
+    fn set_last_error(&mut self, reason: impl Into<String>) {
+        self.last_error = Some(reason.into());
+        self.persist_state();
+    }
+
     fn is_ready(&self) -> bool {
         self.next_attempt_after
             .map(|a| Instant::now() >= a)
@@ -389,23 +710,25 @@ impl PendingMessage {
     }
 
     fn reset_attempts(&mut self) {
-        self.set_retries(0);
-        self.next_attempt_after = None;
         self.last_attempted_at = Instant::now();
+        self.set_retries(0);
     }
 
     fn inc_attempts(&mut self) {
-        self.set_retries(self.num_retries + 1);
         self.last_attempted_at = Instant::now();
-        self.next_attempt_after = PendingMessage::calculate_msg_backoff(self.num_retries)
-            .map(|dur| self.last_attempted_at + dur);
+        self.set_retries(self.num_retries + 1);
     }
 
     fn set_retries(&mut self, retries: u32) {
         self.num_retries = retries;
+        self.next_attempt_after =
+            PendingMessage::calculate_msg_backoff(self.num_retries).map(|dur| Instant::now() + dur);
         self.persist_retries();
     }
 
+    /// The retry count is persisted on its own, which the message processor
+    /// uses to order messages on startup, and as part of the queue state
+    /// together with the next attempt.
     fn persist_retries(&self) {
         if let Err(e) = self
             .ctx
@@ -414,6 +737,23 @@ impl PendingMessage {
         {
             warn!(message_id = ?self.message.id(), err = %e, "Persisting the `num_retries` failed for message");
         }
+        self.persist_state();
+    }
+
+    fn persist_state(&self) {
+        let state = PendingMessageState {
+            disposition: self.disposition,
+            last_error: self.last_error.clone(),
+            num_retries: self.num_retries,
+            next_attempt_after: self.next_attempt_after.map(instant_to_unix_millis),
+        };
+        if let Err(e) = self
+            .ctx
+            .origin_db
+            .store_pending_message_state_by_message_id(&self.message.id(), &state)
+        {
+            warn!(message_id = ?self.message.id(), err = %e, "Persisting the queue state failed for message");
+        }
     }
 
     /// Get duration we should wait before re-attempting to deliver a message
@@ -436,6 +776,23 @@ impl PendingMessage {
     }
 }
 
+/// `Instant`s can't outlive the process, so they are persisted as unix
+/// timestamps.
+fn instant_to_unix_millis(instant: Instant) -> u64 {
+    let wall_clock = SystemTime::now() + instant.saturating_duration_since(Instant::now());
+    wall_clock
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn instant_from_unix_millis(millis: u64) -> Instant {
+    let remaining = (UNIX_EPOCH + Duration::from_millis(millis))
+        .duration_since(SystemTime::now())
+        .unwrap_or_default();
+    Instant::now() + remaining
+}
+
 #[derive(Debug)]
 pub struct MessageSubmissionMetrics {
     // Fields are public for testing purposes
@@ -471,3 +828,193 @@ impl MessageSubmissionMetrics {
             .set(std::cmp::max(self.last_known_nonce.get(), msg.nonce as i64));
     }
 }
+
+#[cfg(test)]
+mod test {
+    use hyperlane_base::db::test_utils;
+    use hyperlane_core::{FixedPointNumber, H512};
+    use hyperlane_test::mocks::MockMailboxContract;
+    use prometheus::Registry;
+
+    use super::*;
+    use crate::{msg::test_utils::dummy_message_context, settings::RateLimitConf};
+
+    fn prepare(pm: &mut PendingMessage) {
+        pm.submission_data = Some(Box::new(SubmissionData {
+            metadata: vec![1, 2, 3],
+            gas_limit: 100.into(),
+        }));
+    }
+
+    fn tx_outcome(executed: bool) -> TxOutcome {
+        TxOutcome {
+            transaction_id: H512::zero(),
+            executed,
+            gas_used: 50.into(),
+            gas_price: FixedPointNumber::zero(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reverted_batch_is_submitted_individually() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = HyperlaneDomain::new_test_domain("origin");
+            let destination_domain = HyperlaneDomain::new_test_domain("destination");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+            let mut mailbox = MockMailboxContract::new();
+            mailbox
+                .expect_process()
+                .returning(|_, _, _| Ok(tx_outcome(true)));
+            let mailbox: Arc<dyn Mailbox> = Arc::new(mailbox);
+            let ctx = Arc::new(dummy_message_context(
+                &origin_domain,
+                &destination_domain,
+                &db,
+                mailbox.clone(),
+            ));
+            let mut pm = PendingMessage::new(HyperlaneMessage::default(), ctx, None);
+
+            prepare(&mut pm);
+            assert!(pm.batch_item().is_some());
+            let result = pm.on_batch_submitted(tx_outcome(false)).await;
+            assert!(matches!(result, PendingOperationResult::Reprepare));
+            assert_eq!(pm.retry_count(), 1);
+
+            // The message is not batched again, as it may have caused the revert
+            prepare(&mut pm);
+            assert!(pm.batch_item().is_none());
+            let result = pm.submit(mailbox.as_ref()).await;
+            assert!(matches!(result, PendingOperationResult::Success));
+
+            // Once processed, it may be batched again
+            pm.submitted = false;
+            prepare(&mut pm);
+            assert!(pm.batch_item().is_some());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_executed_batch_records_outcome() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = HyperlaneDomain::new_test_domain("origin");
+            let destination_domain = HyperlaneDomain::new_test_domain("destination");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+            let ctx = Arc::new(dummy_message_context(
+                &origin_domain,
+                &destination_domain,
+                &db,
+                Arc::new(MockMailboxContract::new()),
+            ));
+            let message = HyperlaneMessage::default();
+            let mut pm = PendingMessage::new(message.clone(), ctx, None);
+
+            prepare(&mut pm);
+            let result = pm.on_batch_submitted(tx_outcome(true)).await;
+            assert!(matches!(result, PendingOperationResult::Success));
+            // The submission data was used up by the batch
+            assert!(pm.batch_item().is_none());
+            assert_eq!(
+                db.retrieve_gas_expenditure_by_message_id(message.id())
+                    .unwrap()
+                    .gas_used,
+                50.into()
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_reservation_is_settled() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = HyperlaneDomain::new_test_domain("origin");
+            let destination_domain = HyperlaneDomain::new_test_domain("destination");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+            let mut ctx = dummy_message_context(
+                &origin_domain,
+                &destination_domain,
+                &db,
+                Arc::new(MockMailboxContract::new()),
+            );
+            let rate_limiter = Arc::new(RateLimiter::new(
+                &[RateLimitConf {
+                    name: "test".to_owned(),
+                    matching_list: Default::default(),
+                    messages_per_minute: Some(1),
+                    gas_per_hour: None,
+                    native_spend_per_day: None,
+                }],
+                &destination_domain,
+                &CoreMetrics::new("test", 9090, Registry::new()).unwrap(),
+            ));
+            ctx.rate_limiter = rate_limiter.clone();
+            let message = HyperlaneMessage::default();
+            let mut pm = PendingMessage::new(message.clone(), Arc::new(ctx), None);
+            let acquire = || rate_limiter.try_acquire(&message, 100.into(), 0.into());
+
+            // A reverted delivery doesn't count against the message limit
+            prepare(&mut pm);
+            pm.rate_limit_reservation = Some(acquire().unwrap());
+            assert!(acquire().is_err());
+            pm.on_batch_submitted(tx_outcome(false)).await;
+            assert!(pm.rate_limit_reservation.is_none());
+
+            // Neither does a message that's dropped before it's submitted
+            pm.rate_limit_reservation = Some(acquire().unwrap());
+            pm.on_drop();
+
+            // A delivered message does
+            prepare(&mut pm);
+            pm.rate_limit_reservation = Some(acquire().unwrap());
+            let result = pm.on_batch_submitted(tx_outcome(true)).await;
+            assert!(matches!(result, PendingOperationResult::Success));
+            assert!(acquire().is_err());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_delivery_is_counted_once() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = HyperlaneDomain::new_test_domain("origin");
+            let destination_domain = HyperlaneDomain::new_test_domain("destination");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+            let mut ctx = dummy_message_context(
+                &origin_domain,
+                &destination_domain,
+                &db,
+                Arc::new(MockMailboxContract::new()),
+            );
+            let rate_limiter = Arc::new(RateLimiter::new(
+                &[RateLimitConf {
+                    name: "test".to_owned(),
+                    matching_list: Default::default(),
+                    messages_per_minute: Some(2),
+                    gas_per_hour: None,
+                    native_spend_per_day: None,
+                }],
+                &destination_domain,
+                &CoreMetrics::new("test", 9090, Registry::new()).unwrap(),
+            ));
+            ctx.rate_limiter = rate_limiter.clone();
+            let message = HyperlaneMessage::default();
+            let mut pm = PendingMessage::new(message.clone(), Arc::new(ctx), None);
+
+            // The dry run found the message would be delivered
+            pm.acquire_rate_limits(100.into(), 0.into()).unwrap();
+            pm.spend_rate_limit_reservation();
+
+            // Checking it again doesn't take its delivery again, and failing
+            // to prepare it doesn't give it back
+            pm.acquire_rate_limits(100.into(), 0.into()).unwrap();
+            pm.on_reprepare("Gas payment requirement not met yet");
+            assert!(rate_limiter
+                .try_acquire(&message, 100.into(), 0.into())
+                .is_ok());
+            assert!(rate_limiter
+                .try_acquire(&message, 100.into(), 0.into())
+                .is_err());
+        })
+        .await;
+    }
+}
@@ -1,7 +1,8 @@
 use std::{cmp::Ordering, fmt::Debug, time::Instant};
 
 use async_trait::async_trait;
-use hyperlane_core::{HyperlaneDomain, H256};
+use hyperlane_base::db::OperationDisposition;
+use hyperlane_core::{BatchItem, HyperlaneDomain, Mailbox, TxOutcome, H256};
 
 use super::op_queue::QueueOperation;
 
@@ -57,9 +58,19 @@ pub trait PendingOperation: Send + Sync + Debug {
     /// submit call.
     async fn prepare(&mut self) -> PendingOperationResult;
 
-    /// Submit this operation to the blockchain and report if it was successful
-    /// or not.
-    async fn submit(&mut self) -> PendingOperationResult;
+    /// Submit this operation to the blockchain through `mailbox`, whose
+    /// signer sends the transaction, and report if it was successful or not.
+    async fn submit(&mut self, mailbox: &dyn Mailbox) -> PendingOperationResult;
+
+    /// The data needed to submit this operation as part of a batch, if it has
+    /// been prepared and has not been submitted yet.
+    fn batch_item(&self) -> Option<BatchItem>;
+
+    /// This will be called instead of `submit` when the operation was included
+    /// in a batch transaction, with the share of the transaction outcome
+    /// attributed to this operation. Reports if the submission was successful
+    /// or not, just like `submit`.
+    async fn on_batch_submitted(&mut self, tx_outcome: TxOutcome) -> PendingOperationResult;
 
     /// This will be called after the operation has been submitted and is
     /// responsible for checking if the operation has reached a point at
@@ -76,6 +87,28 @@ pub trait PendingOperation: Send + Sync + Debug {
     /// retried immediately.
     fn reset_attempts(&mut self);
 
+    /// Number of times this operation has been attempted without success.
+    fn retry_count(&self) -> u32;
+
+    /// The error that caused the most recent attempt to fail, if any.
+    fn last_error(&self) -> Option<String>;
+
+    /// How an operator has asked for this operation to be handled.
+    fn disposition(&self) -> OperationDisposition;
+
+    /// Change how this operation is handled. Implementations are expected to
+    /// persist the disposition so that it survives a restart.
+    fn set_disposition(&mut self, disposition: OperationDisposition);
+
+    /// The submission lane this operation was first submitted through, if
+    /// any. It is submitted through the same lane when retried, so that a
+    /// transaction still in flight from an earlier attempt is watched and
+    /// replaced by the same signer rather than sent again by another one.
+    fn submission_lane(&self) -> Option<usize>;
+
+    /// Pin this operation to the submission lane it is submitted through.
+    fn set_submission_lane(&mut self, lane: usize);
+
     #[cfg(test)]
     /// Set the number of times this operation has been retried.
     fn set_retries(&mut self, retries: u32);
@@ -98,6 +131,11 @@ impl Eq for QueueOperation {}
 impl Ord for QueueOperation {
     fn cmp(&self, other: &Self) -> Ordering {
         use Ordering::*;
+        // Operations an operator has deprioritized or paused always go last
+        let by_disposition = self.disposition().cmp(&other.disposition());
+        if by_disposition != Equal {
+            return by_disposition;
+        }
         match (self.next_attempt_after(), other.next_attempt_after()) {
             (Some(a), Some(b)) => a.cmp(&b),
             // No time means it should come before
@@ -127,7 +165,8 @@ pub enum PendingOperationResult {
     Drop,
 }
 
-/// create a `op_try!` macro for the `on_retry` handler.
+/// create a `op_try!` macro for the `on_retry` handler. The handler is passed
+/// a description of the error that caused the retry.
 macro_rules! make_op_try {
     ($on_retry:expr) => {
         /// Handle a result and either return early with retry or a critical failure on
@@ -139,7 +178,7 @@ macro_rules! make_op_try {
                                     Err(e) => {
                                         error!(error=?e, concat!("Critical error when ", $ctx));
                                         #[allow(clippy::redundant_closure_call)]
-                                        return $on_retry();
+                                        return $on_retry(format!(concat!("Critical error when ", $ctx, ": {:?}"), e));
                                     }
                                 }
                             };
@@ -149,7 +188,7 @@ macro_rules! make_op_try {
                                     Err(e) => {
                                         warn!(error=?e, concat!("Error when ", $ctx));
                                         #[allow(clippy::redundant_closure_call)]
-                                        return $on_retry();
+                                        return $on_retry(format!(concat!("Error when ", $ctx, ": {:?}"), e));
                                     }
                                 }
                             };
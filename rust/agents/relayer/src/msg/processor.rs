@@ -8,14 +8,22 @@ use std::{
 use async_trait::async_trait;
 use derive_new::new;
 use eyre::Result;
-use hyperlane_base::{db::HyperlaneRocksDB, CoreMetrics};
-use hyperlane_core::{HyperlaneDomain, HyperlaneMessage};
+use hyperlane_base::{
+    db::{HyperlaneRocksDB, OperationDisposition},
+    CoreMetrics,
+};
+use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, MpmcReceiver};
 use prometheus::IntGauge;
 use tokio::sync::mpsc::UnboundedSender;
-use tracing::{debug, trace};
+use tracing::{debug, info, trace};
 
-use super::{metadata::AppContextClassifier, op_queue::QueueOperation, pending_message::*};
-use crate::{processor::ProcessorExt, settings::matching_list::MatchingList};
+use super::{
+    metadata::AppContextClassifier, op_queue::QueueOperation, pending_message::*,
+    pending_operation::PendingOperation,
+};
+use crate::{
+    processor::ProcessorExt, server::MessageRetryRequest, settings::matching_list::MatchingList,
+};
 
 /// Finds unprocessed messages from an origin and submits then through a channel
 /// for to the appropriate destination.
@@ -32,6 +40,8 @@ pub struct MessageProcessor {
     /// Needed context to send a message for each destination chain
     destination_ctxs: HashMap<u32, Arc<MessageContext>>,
     metric_app_contexts: Vec<(MatchingList, String)>,
+    /// Retry requests, which bring back messages an operator dropped
+    retry_rx: MpmcReceiver<MessageRetryRequest>,
     #[new(default)]
     message_nonce: u32,
 }
@@ -56,6 +66,8 @@ impl ProcessorExt for MessageProcessor {
     /// One round of processing, extracted from infinite work loop for
     /// testing purposes.
     async fn tick(&mut self) -> Result<()> {
+        self.restore_dropped_messages().await?;
+
         // Forever, scan HyperlaneRocksDB looking for new messages to send. When criteria are
         // satisfied or the message is disqualified, push the message onto
         // self.tx_msg and then continue the scan at the next highest
@@ -65,8 +77,9 @@ impl ProcessorExt for MessageProcessor {
             debug!(?msg, "Processor working on message");
             let destination = msg.destination;
 
-            // Skip if not whitelisted.
-            if !self.whitelist.msg_matches(&msg, true) {
+            // Skip if not whitelisted. Rules on the recipient's ISM are checked
+            // once it's known, when the message is prepared.
+            if !self.whitelist.msg_matches_any_ism(&msg, true) {
                 debug!(?msg, whitelist=?self.whitelist, "Message not whitelisted, skipping");
                 self.message_nonce += 1;
                 return Ok(());
@@ -93,19 +106,7 @@ impl ProcessorExt for MessageProcessor {
                 return Ok(());
             }
 
-            debug!(%msg, "Sending message to submitter");
-
-            let app_context_classifier =
-                AppContextClassifier::new(self.metric_app_contexts.clone());
-
-            let app_context = app_context_classifier.get_app_context(&msg).await?;
-            // Finally, build the submit arg and dispatch it to the submitter.
-            let pending_msg = PendingMessage::from_persisted_retries(
-                msg,
-                self.destination_ctxs[&destination].clone(),
-                app_context,
-            );
-            self.send_channels[&destination].send(Box::new(pending_msg) as QueueOperation)?;
+            self.send_to_submitter(msg).await?;
             self.message_nonce += 1;
         } else {
             tokio::time::sleep(Duration::from_secs(1)).await;
@@ -115,6 +116,65 @@ impl ProcessorExt for MessageProcessor {
 }
 
 impl MessageProcessor {
+    /// Build the pending message and dispatch it to the destination's
+    /// submitter, unless an operator dropped it.
+    async fn send_to_submitter(&self, msg: HyperlaneMessage) -> Result<()> {
+        let destination = msg.destination;
+        debug!(%msg, "Sending message to submitter");
+
+        let app_context_classifier = AppContextClassifier::new(self.metric_app_contexts.clone());
+
+        // Rules on the recipient ISM's module type are matched once it's known,
+        // when the message is prepared
+        let app_context = app_context_classifier.get_app_context(&msg, None).await?;
+        // Finally, build the submit arg and dispatch it to the submitter.
+        let pending_msg = PendingMessage::from_persisted_retries(
+            msg,
+            self.destination_ctxs[&destination].clone(),
+            app_context,
+        );
+        // Skip if an operator dropped the message from the queue
+        if pending_msg.disposition() == OperationDisposition::Dropped {
+            debug!(?pending_msg, "Message was dropped from the queue, skipping");
+            return Ok(());
+        }
+        self.destination_ctxs[&destination]
+            .ordered_delivery
+            .track(&pending_msg.message);
+        self.send_channels[&destination].send(Box::new(pending_msg) as QueueOperation)?;
+        Ok(())
+    }
+
+    /// Clear the dropped disposition of messages that are retried by id,
+    /// so that dropping a message from the queue can be undone. Messages
+    /// the processor has already gone past are dispatched again.
+    async fn restore_dropped_messages(&mut self) -> Result<()> {
+        while let Ok(request) = self.retry_rx.receiver.try_recv() {
+            let MessageRetryRequest::MessageId(id) = request else {
+                continue;
+            };
+            let Some(mut state) = self.db.retrieve_pending_message_state_by_message_id(&id)? else {
+                continue;
+            };
+            if state.disposition != OperationDisposition::Dropped {
+                continue;
+            }
+            let Some(msg) = self.db.retrieve_message_by_id(&id)? else {
+                continue;
+            };
+            info!(?msg, "Restoring message dropped from the queue");
+            state.disposition = OperationDisposition::Normal;
+            state.num_retries = 0;
+            state.next_attempt_after = None;
+            self.db
+                .store_pending_message_state_by_message_id(&id, &state)?;
+            if msg.nonce < self.message_nonce && self.send_channels.contains_key(&msg.destination) {
+                self.send_to_submitter(msg).await?;
+            }
+        }
+        Ok(())
+    }
+
     fn try_get_unprocessed_message(&mut self) -> Result<Option<HyperlaneMessage>> {
         loop {
             // First, see if we can find the message so we can update the gauge.
@@ -184,30 +244,21 @@ impl MessageProcessorMetrics {
 
 #[cfg(test)]
 mod test {
-    use std::time::Instant;
+    use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
     use crate::{
-        merkle_tree::builder::MerkleTreeBuilder,
-        msg::{
-            gas_payment::GasPaymentEnforcer,
-            metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier},
-            pending_operation::PendingOperation,
-        },
+        msg::{pending_operation::PendingOperation, test_utils::dummy_message_context},
         processor::Processor,
     };
 
     use super::*;
-    use hyperlane_base::{
-        db::{test_utils, HyperlaneRocksDB},
-        settings::{ChainConf, ChainConnectionConf, Settings},
+    use hyperlane_base::db::{
+        test_utils, HyperlaneRocksDB, OperationDisposition, PendingMessageState,
     };
-    use hyperlane_test::mocks::{MockMailboxContract, MockValidatorAnnounceContract};
-    use prometheus::{IntCounter, Registry};
+    use hyperlane_core::MpmcChannel;
+    use hyperlane_test::mocks::MockMailboxContract;
     use tokio::{
-        sync::{
-            mpsc::{self, UnboundedReceiver},
-            RwLock,
-        },
+        sync::mpsc::{self, UnboundedReceiver},
         time::sleep,
     };
 
@@ -225,70 +276,18 @@ mod test {
         }
     }
 
-    fn dummy_submission_metrics() -> MessageSubmissionMetrics {
-        MessageSubmissionMetrics {
-            last_known_nonce: IntGauge::new("last_known_nonce_gauge", "help string").unwrap(),
-            messages_processed: IntCounter::new("message_processed_gauge", "help string").unwrap(),
-        }
-    }
-
-    fn dummy_chain_conf(domain: &HyperlaneDomain) -> ChainConf {
-        ChainConf {
-            domain: domain.clone(),
-            signer: Default::default(),
-            reorg_period: Default::default(),
-            addresses: Default::default(),
-            connection: ChainConnectionConf::Ethereum(hyperlane_ethereum::ConnectionConf::Http {
-                url: "http://example.com".parse().unwrap(),
-            }),
-            metrics_conf: Default::default(),
-            index: Default::default(),
-        }
-    }
-
-    fn dummy_metadata_builder(
-        origin_domain: &HyperlaneDomain,
-        destination_domain: &HyperlaneDomain,
-        db: &HyperlaneRocksDB,
-    ) -> BaseMetadataBuilder {
-        let mut settings = Settings::default();
-        settings.chains.insert(
-            origin_domain.name().to_owned(),
-            dummy_chain_conf(origin_domain),
-        );
-        settings.chains.insert(
-            destination_domain.name().to_owned(),
-            dummy_chain_conf(destination_domain),
-        );
-        let destination_chain_conf = settings.chain_setup(destination_domain).unwrap();
-        let core_metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
-        BaseMetadataBuilder::new(
-            origin_domain.clone(),
-            destination_chain_conf.clone(),
-            Arc::new(RwLock::new(MerkleTreeBuilder::new())),
-            Arc::new(MockValidatorAnnounceContract::default()),
-            false,
-            Arc::new(core_metrics),
-            db.clone(),
-            5,
-            IsmAwareAppContextClassifier::new(Arc::new(MockMailboxContract::default()), vec![]),
-        )
-    }
-
     fn dummy_message_processor(
         origin_domain: &HyperlaneDomain,
         destination_domain: &HyperlaneDomain,
         db: &HyperlaneRocksDB,
+        retry_rx: MpmcReceiver<MessageRetryRequest>,
     ) -> (MessageProcessor, UnboundedReceiver<QueueOperation>) {
-        let base_metadata_builder = dummy_metadata_builder(origin_domain, destination_domain, db);
-        let message_context = Arc::new(MessageContext {
-            destination_mailbox: Arc::new(MockMailboxContract::default()),
-            origin_db: db.clone(),
-            metadata_builder: Arc::new(base_metadata_builder),
-            origin_gas_payment_enforcer: Arc::new(GasPaymentEnforcer::new([], db.clone())),
-            transaction_gas_limit: Default::default(),
-            metrics: dummy_submission_metrics(),
-        });
+        let message_context = Arc::new(dummy_message_context(
+            origin_domain,
+            destination_domain,
+            db,
+            Arc::new(MockMailboxContract::default()),
+        ));
 
         let (send_channel, receive_channel) = mpsc::unbounded_channel::<QueueOperation>();
         (
@@ -300,6 +299,7 @@ mod test {
                 HashMap::from([(destination_domain.id(), send_channel)]),
                 HashMap::from([(destination_domain.id(), message_context)]),
                 vec![],
+                retry_rx,
             ),
             receive_channel,
         )
@@ -361,8 +361,12 @@ mod test {
         db: &HyperlaneRocksDB,
         num_operations: usize,
     ) -> Vec<QueueOperation> {
-        let (message_processor, mut receive_channel) =
-            dummy_message_processor(origin_domain, destination_domain, db);
+        let (message_processor, mut receive_channel) = dummy_message_processor(
+            origin_domain,
+            destination_domain,
+            db,
+            MpmcChannel::new(1).receiver(),
+        );
 
         let processor = Processor::new(Box::new(message_processor));
         let process_fut = processor.spawn();
@@ -437,4 +441,101 @@ mod test {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn test_pending_message_state_restored_on_restart() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+
+            // A message that was being retried when the relayer stopped, with a
+            // next attempt further out than its retry count alone implies
+            let message = dummy_hyperlane_message(&destination_domain, 0);
+            add_db_entry(&db, &message, 5);
+            let next_attempt_after = SystemTime::now() + Duration::from_secs(1000);
+            let state = PendingMessageState {
+                disposition: OperationDisposition::Deprioritized,
+                last_error: Some("Transaction reverted".to_owned()),
+                num_retries: 5,
+                next_attempt_after: Some(
+                    next_attempt_after
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap()
+                        .as_millis() as u64,
+                ),
+            };
+            db.store_pending_message_state_by_message_id(&message.id(), &state)
+                .unwrap();
+
+            let pending_messages =
+                get_first_n_operations_from_processor(&origin_domain, &destination_domain, &db, 1)
+                    .await;
+
+            let pm = &pending_messages[0];
+            assert_eq!(pm.retry_count(), 5);
+            assert_eq!(pm.disposition(), OperationDisposition::Deprioritized);
+            assert_eq!(pm.last_error(), state.last_error);
+            let remaining = pm
+                .next_attempt_after()
+                .unwrap()
+                .duration_since(Instant::now())
+                .as_secs_f32()
+                .round();
+            assert_eq!(remaining, 1000.0);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_retrying_dropped_message_restores_it() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+
+            let dropped = dummy_hyperlane_message(&destination_domain, 0);
+            let other = dummy_hyperlane_message(&destination_domain, 1);
+            add_db_entry(&db, &dropped, 0);
+            add_db_entry(&db, &other, 0);
+            let state = PendingMessageState {
+                disposition: OperationDisposition::Dropped,
+                num_retries: 3,
+                ..Default::default()
+            };
+            db.store_pending_message_state_by_message_id(&dropped.id(), &state)
+                .unwrap();
+
+            let retry_channel = MpmcChannel::new(10);
+            let (mut processor, mut receive_channel) = dummy_message_processor(
+                &origin_domain,
+                &destination_domain,
+                &db,
+                retry_channel.receiver(),
+            );
+
+            // The dropped message is skipped
+            processor.tick().await.unwrap();
+            processor.tick().await.unwrap();
+            assert_eq!(receive_channel.try_recv().unwrap().id(), other.id());
+            assert!(receive_channel.try_recv().is_err());
+
+            // Retrying it clears the drop and sends it to the submitter again
+            retry_channel
+                .sender()
+                .send(MessageRetryRequest::MessageId(dropped.id()))
+                .unwrap();
+            processor.tick().await.unwrap();
+            let restored = receive_channel.try_recv().unwrap();
+            assert_eq!(restored.id(), dropped.id());
+            assert_eq!(restored.disposition(), OperationDisposition::Normal);
+            assert_eq!(restored.retry_count(), 0);
+            let state = db
+                .retrieve_pending_message_state_by_message_id(&dropped.id())
+                .unwrap()
+                .unwrap();
+            assert_eq!(state.disposition, OperationDisposition::Normal);
+        })
+        .await;
+    }
 }
@@ -0,0 +1,428 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use eyre::Result;
+use hyperlane_base::CoreMetrics;
+use hyperlane_core::{
+    FixedPointNumber, HyperlaneDomain, HyperlaneMessage, TxCostEstimate, TxOutcome, U256,
+};
+use prometheus::Gauge;
+
+use crate::settings::{matching_list::MatchingList, RateLimitConf};
+
+const MINUTE: Duration = Duration::from_secs(60);
+const HOUR: Duration = Duration::from_secs(60 * 60);
+const DAY: Duration = Duration::from_secs(60 * 60 * 24);
+
+/// Holds up to `capacity` tokens and refills at `capacity` tokens per
+/// `period`.
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: U256,
+    period: Duration,
+    available: U256,
+    last_refill: Instant,
+    available_gauge: Gauge,
+}
+
+impl TokenBucket {
+    fn new(
+        capacity: U256,
+        period: Duration,
+        capacity_gauge: Gauge,
+        available_gauge: Gauge,
+    ) -> Self {
+        capacity_gauge.set(capacity.to_f64_lossy());
+        available_gauge.set(capacity.to_f64_lossy());
+        Self {
+            capacity,
+            period,
+            available: capacity,
+            last_refill: Instant::now(),
+            available_gauge,
+        }
+    }
+
+    fn period_millis(&self) -> U256 {
+        U256::from(self.period.as_millis() as u64)
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        if elapsed >= self.period || self.capacity.is_zero() {
+            self.available = self.capacity;
+            self.last_refill = now;
+            self.available_gauge.set(self.available.to_f64_lossy());
+            return;
+        }
+        let refilled = self
+            .capacity
+            .saturating_mul(U256::from(elapsed.as_millis() as u64))
+            / self.period_millis();
+        if refilled.is_zero() {
+            return;
+        }
+        self.available = self.capacity.min(self.available.saturating_add(refilled));
+        // Only count the time it took to refill whole tokens, so that frequent
+        // refills don't lose the fractions
+        let refill_millis = self.period_millis().saturating_mul(refilled) / self.capacity;
+        self.last_refill += Duration::from_millis(refill_millis.as_u64());
+        self.available_gauge.set(self.available.to_f64_lossy());
+    }
+
+    /// How long after `now` until `amount` tokens are available. Amounts
+    /// above the capacity need a full bucket, and an empty bucket allows
+    /// nothing.
+    fn wait_time(&self, amount: U256, now: Instant) -> Option<Duration> {
+        if self.capacity.is_zero() && !amount.is_zero() {
+            return Some(self.period);
+        }
+        let amount = amount.min(self.capacity);
+        if self.available >= amount {
+            return None;
+        }
+        let missing = amount - self.available;
+        let millis = missing
+            .saturating_mul(self.period_millis())
+            .saturating_add(self.capacity - 1)
+            / self.capacity;
+        let refilled_at = self.last_refill + Duration::from_millis(millis.as_u64());
+        Some(refilled_at.saturating_duration_since(now))
+    }
+
+    fn take(&mut self, amount: U256) {
+        self.available = self.available.saturating_sub(amount.min(self.capacity));
+        self.available_gauge.set(self.available.to_f64_lossy());
+    }
+
+    fn give_back(&mut self, amount: U256) {
+        self.available = self
+            .capacity
+            .min(self.available.saturating_add(amount.min(self.capacity)));
+        self.available_gauge.set(self.available.to_f64_lossy());
+    }
+
+    /// Corrects an earlier take of `taken` tokens to `used` tokens
+    fn settle(&mut self, taken: U256, used: U256) {
+        if used > taken {
+            self.take(used - taken);
+        } else {
+            self.give_back(taken - used);
+        }
+    }
+}
+
+/// A rate limit on the messages matching its matching list
+#[derive(Debug)]
+struct RateLimit {
+    matching_list: MatchingList,
+    messages: Option<TokenBucket>,
+    gas: Option<TokenBucket>,
+    native_spend: Option<TokenBucket>,
+}
+
+impl RateLimit {
+    /// The buckets of the limit with the amount a delivery would take from
+    /// each
+    fn buckets(
+        &mut self,
+        gas: U256,
+        native_spend: U256,
+    ) -> impl Iterator<Item = (&mut TokenBucket, U256)> {
+        [
+            (self.messages.as_mut(), U256::one()),
+            (self.gas.as_mut(), gas),
+            (self.native_spend.as_mut(), native_spend),
+        ]
+        .into_iter()
+        .filter_map(|(bucket, amount)| bucket.map(|bucket| (bucket, amount)))
+    }
+}
+
+/// What the delivery of a message took from the rate limits it matches, to be
+/// settled once it's known what the delivery actually used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitReservation {
+    gas: U256,
+    native_spend: U256,
+}
+
+/// Limits the messages delivered to a destination, the gas they use and the
+/// native tokens spent on them, with the rate limits that match each message.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    limits: Mutex<Vec<RateLimit>>,
+}
+
+impl RateLimiter {
+    pub fn new(
+        confs: &[RateLimitConf],
+        destination: &HyperlaneDomain,
+        metrics: &CoreMetrics,
+    ) -> Self {
+        let bucket = |name: &str, resource: &str, capacity: Option<U256>, period: Duration| {
+            capacity.map(|capacity| {
+                let labels = [destination.name(), name, resource];
+                TokenBucket::new(
+                    capacity,
+                    period,
+                    metrics.rate_limit_capacity().with_label_values(&labels),
+                    metrics.rate_limit_available().with_label_values(&labels),
+                )
+            })
+        };
+        let limits = confs
+            .iter()
+            .map(|conf| RateLimit {
+                matching_list: conf.matching_list.clone(),
+                messages: bucket(
+                    &conf.name,
+                    "messages",
+                    conf.messages_per_minute.map(U256::from),
+                    MINUTE,
+                ),
+                gas: bucket(&conf.name, "gas", conf.gas_per_hour, HOUR),
+                native_spend: bucket(&conf.name, "native_spend", conf.native_spend_per_day, DAY),
+            })
+            .collect();
+        Self {
+            limits: Mutex::new(limits),
+        }
+    }
+
+    /// Takes the delivery of `message`, estimated to use `gas` and cost
+    /// `native_spend`, from the rate limits it matches. If any of them don't
+    /// allow it yet, nothing is taken and the time to wait before trying
+    /// again is returned.
+    ///
+    /// What was taken is returned so that it can be given back with
+    /// `release` if the message isn't submitted after all, or corrected with
+    /// `settle` once the delivery transaction's outcome is known.
+    pub fn try_acquire(
+        &self,
+        message: &HyperlaneMessage,
+        gas: U256,
+        native_spend: U256,
+    ) -> Result<RateLimitReservation, Duration> {
+        let mut limits = self.limits.lock().unwrap();
+        let now = Instant::now();
+        let mut matching_limits = Self::matching_limits(&mut limits, message);
+
+        let mut wait_time = None;
+        for limit in matching_limits.iter_mut() {
+            for (bucket, amount) in limit.buckets(gas, native_spend) {
+                bucket.refill(now);
+                wait_time = wait_time.max(bucket.wait_time(amount, now));
+            }
+        }
+        if let Some(wait_time) = wait_time {
+            return Err(wait_time);
+        }
+
+        for limit in matching_limits {
+            for (bucket, amount) in limit.buckets(gas, native_spend) {
+                bucket.take(amount);
+            }
+        }
+        Ok(RateLimitReservation { gas, native_spend })
+    }
+
+    /// Gives what `reservation` took back to the rate limits `message`
+    /// matches, as no delivery transaction was sent for it.
+    pub fn release(&self, message: &HyperlaneMessage, reservation: RateLimitReservation) {
+        let mut limits = self.limits.lock().unwrap();
+        for limit in Self::matching_limits(&mut limits, message) {
+            for (bucket, amount) in limit.buckets(reservation.gas, reservation.native_spend) {
+                bucket.give_back(amount);
+            }
+        }
+    }
+
+    /// Corrects what `reservation` took from the rate limits `message`
+    /// matches to the gas the delivery transaction actually used and what
+    /// it cost. The message itself is only counted if it was delivered.
+    pub fn settle(
+        &self,
+        message: &HyperlaneMessage,
+        reservation: RateLimitReservation,
+        tx_outcome: &TxOutcome,
+    ) {
+        let native_spent = native_spend(tx_outcome.gas_used, &tx_outcome.gas_price)
+            .unwrap_or(reservation.native_spend);
+        let mut limits = self.limits.lock().unwrap();
+        for limit in Self::matching_limits(&mut limits, message) {
+            if let Some(messages) = &mut limit.messages {
+                messages.settle(U256::one(), U256::from(tx_outcome.executed as u8));
+            }
+            if let Some(gas) = &mut limit.gas {
+                gas.settle(reservation.gas, tx_outcome.gas_used);
+            }
+            if let Some(native_spend) = &mut limit.native_spend {
+                native_spend.settle(reservation.native_spend, native_spent);
+            }
+        }
+    }
+
+    fn matching_limits<'a>(
+        limits: &'a mut [RateLimit],
+        message: &HyperlaneMessage,
+    ) -> Vec<&'a mut RateLimit> {
+        limits
+            .iter_mut()
+            .filter(|limit| limit.matching_list.msg_matches(message, true))
+            .collect()
+    }
+}
+
+/// The native tokens a transaction is estimated to cost, in the lowest
+/// denomination
+pub fn estimated_native_spend(tx_cost_estimate: &TxCostEstimate) -> Result<U256> {
+    native_spend(tx_cost_estimate.gas_limit, &tx_cost_estimate.gas_price)
+}
+
+fn native_spend(gas: U256, gas_price: &FixedPointNumber) -> Result<U256> {
+    let native_spend = FixedPointNumber::try_from(gas)? * gas_price.clone();
+    Ok(native_spend.try_into()?)
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::KnownHyperlaneDomain;
+
+    use super::*;
+
+    fn rate_limiter(conf: RateLimitConf) -> RateLimiter {
+        let metrics = CoreMetrics::new("test", 9090, Default::default()).unwrap();
+        RateLimiter::new(
+            &[conf],
+            &HyperlaneDomain::Known(KnownHyperlaneDomain::Test2),
+            &metrics,
+        )
+    }
+
+    #[test]
+    fn test_token_bucket_refills_over_its_period() {
+        let gauge = || Gauge::new("test", "help string").unwrap();
+        let mut bucket = TokenBucket::new(60.into(), MINUTE, gauge(), gauge());
+        let start = bucket.last_refill;
+        bucket.take(60.into());
+        assert_eq!(
+            bucket.wait_time(1.into(), start),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            bucket.wait_time(10.into(), start),
+            Some(Duration::from_secs(10))
+        );
+
+        // Refilling often doesn't lose fractions of tokens
+        for millis in (0..=2500).step_by(100) {
+            bucket.refill(start + Duration::from_millis(millis));
+        }
+        assert_eq!(bucket.available, 2.into());
+        assert_eq!(
+            bucket.wait_time(3.into(), start + Duration::from_millis(2500)),
+            Some(Duration::from_millis(500))
+        );
+
+        bucket.refill(start + MINUTE);
+        assert_eq!(bucket.available, 60.into());
+        // Amounts above the capacity need a full bucket
+        assert_eq!(bucket.wait_time(100.into(), start + MINUTE), None);
+        bucket.take(100.into());
+        assert_eq!(bucket.available, 0.into());
+    }
+
+    #[test]
+    fn test_rate_limiter_limits_matching_messages() {
+        let rate_limiter = rate_limiter(RateLimitConf {
+            name: "test".to_owned(),
+            matching_list: serde_json::from_str(r#"[{"origindomain": 1}]"#).unwrap(),
+            messages_per_minute: Some(2),
+            gas_per_hour: Some(1_000_000.into()),
+            native_spend_per_day: None,
+        });
+        let message = HyperlaneMessage {
+            origin: 1,
+            ..Default::default()
+        };
+        let unmatched = HyperlaneMessage {
+            origin: 2,
+            ..Default::default()
+        };
+
+        assert!(rate_limiter
+            .try_acquire(&message, 600_000.into(), U256::MAX)
+            .is_ok());
+        // Not enough gas is left, and nothing is taken from the message limit
+        assert!(rate_limiter
+            .try_acquire(&message, 600_000.into(), 0.into())
+            .is_err());
+        assert!(rate_limiter
+            .try_acquire(&message, 400_000.into(), 0.into())
+            .is_ok());
+        // Out of messages
+        let wait_time = rate_limiter
+            .try_acquire(&message, 0.into(), 0.into())
+            .unwrap_err();
+        assert!(wait_time <= Duration::from_secs(30));
+
+        for _ in 0..10 {
+            assert!(rate_limiter
+                .try_acquire(&unmatched, 600_000.into(), 0.into())
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_rate_limiter_settles_reservations() {
+        let rate_limiter = rate_limiter(RateLimitConf {
+            name: "test".to_owned(),
+            matching_list: Default::default(),
+            messages_per_minute: Some(1),
+            gas_per_hour: Some(1_000.into()),
+            native_spend_per_day: Some(10_000.into()),
+        });
+        let message = HyperlaneMessage::default();
+        let tx_outcome = |executed: bool, gas_used: u64| TxOutcome {
+            transaction_id: Default::default(),
+            executed,
+            gas_used: gas_used.into(),
+            gas_price: 2.into(),
+        };
+        let available = || {
+            let limits = rate_limiter.limits.lock().unwrap();
+            let limit = &limits[0];
+            [&limit.messages, &limit.gas, &limit.native_spend]
+                .map(|bucket| bucket.as_ref().unwrap().available.as_u64())
+        };
+
+        // Messages that aren't submitted give back what they took
+        let reservation = rate_limiter
+            .try_acquire(&message, 800.into(), 1_600.into())
+            .unwrap();
+        assert_eq!(available(), [0, 200, 8_400]);
+        assert!(rate_limiter
+            .try_acquire(&message, 0.into(), 0.into())
+            .is_err());
+        rate_limiter.release(&message, reservation);
+        assert_eq!(available(), [1, 1_000, 10_000]);
+
+        // A reverted delivery still spends the gas it used, but the message
+        // isn't counted
+        let reservation = rate_limiter
+            .try_acquire(&message, 800.into(), 1_600.into())
+            .unwrap();
+        rate_limiter.settle(&message, reservation, &tx_outcome(false, 300));
+        assert_eq!(available(), [1, 700, 9_400]);
+
+        // Using more than estimated takes the difference
+        let reservation = rate_limiter
+            .try_acquire(&message, 100.into(), 200.into())
+            .unwrap();
+        rate_limiter.settle(&message, reservation, &tx_outcome(true, 400));
+        assert_eq!(available(), [0, 300, 8_600]);
+    }
+}
@@ -1,17 +1,18 @@
 use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::sync::Arc;
 use std::time::Duration;
 
-use derive_new::new;
 use futures_util::future::try_join_all;
-use prometheus::{IntCounter, IntGaugeVec};
+use prometheus::{IntCounter, IntGauge, IntGaugeVec};
 use tokio::spawn;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Mutex};
 use tokio::task::JoinHandle;
 use tokio::time::sleep;
-use tracing::{debug, info_span, instrument, instrument::Instrumented, trace, Instrument};
+use tracing::{debug, info_span, instrument, instrument::Instrumented, trace, warn, Instrument};
 
 use hyperlane_base::CoreMetrics;
-use hyperlane_core::{HyperlaneDomain, MpmcReceiver};
+use hyperlane_core::{HyperlaneDomain, Mailbox, MpmcReceiver, TxOutcome, U256};
 
 use crate::server::MessageRetryRequest;
 
@@ -37,6 +38,18 @@ use super::pending_operation::*;
 /// retained within the SerialSubmitter, and will eventually be retried
 /// according to our prioritization rule.
 ///
+/// When the destination mailbox supports it and `max_batch_size` is greater
+/// than one, operations that are ready for submission at the same time are
+/// submitted together in a single transaction, as long as their summed gas
+/// limit stays within the transaction gas limit. Operations that cannot be
+/// included in a batch fall back to being submitted on their own.
+///
+/// When the destination is configured with a pool of signers, the submitter
+/// has a submission lane per signer instead of the single one. Each lane has
+/// its own nonces and submits the next ready operations independently of the
+/// others, so there is an execution slot per signer. Once an operation has
+/// been submitted through a lane, its retries go through the same lane.
+///
 /// Finally, the SerialSubmitter ensures that message delivery is robust to
 /// destination chain reorgs prior to committing delivery status to
 /// HyperlaneRocksDB.
@@ -65,19 +78,65 @@ use super::pending_operation::*;
 /// eligible for submission, we should be working on it within reason. This
 /// must be balanced with the cost of making RPCs that will almost certainly
 /// fail and potentially block new messages from being sent immediately.
-#[derive(Debug, new)]
+#[derive(Debug)]
 pub struct SerialSubmitter {
     /// Domain this submitter delivers to.
     domain: HyperlaneDomain,
     /// Receiver for new messages to submit.
     rx: mpsc::UnboundedReceiver<QueueOperation>,
-    /// Receiver for retry requests.
-    retry_rx: MpmcReceiver<MessageRetryRequest>,
     /// Metrics for serial submitter.
     metrics: SerialSubmitterMetrics,
+    /// Operations waiting to be prepared and submitted.
+    prepare_queue: OpQueue,
+    /// Operations waiting for their submission to be confirmed.
+    confirm_queue: OpQueue,
+    /// The lanes operations are submitted through, one per signer.
+    lanes: Vec<SubmissionLane>,
+    /// Maximum number of operations to submit in a single transaction.
+    max_batch_size: u32,
+    /// Maximum gas a batch transaction may use, if any.
+    transaction_gas_limit: Option<U256>,
 }
 
 impl SerialSubmitter {
+    pub fn new(
+        domain: HyperlaneDomain,
+        rx: mpsc::UnboundedReceiver<QueueOperation>,
+        retry_rx: MpmcReceiver<MessageRetryRequest>,
+        metrics: SerialSubmitterMetrics,
+        lanes: Vec<SubmissionLane>,
+        max_batch_size: u32,
+        transaction_gas_limit: Option<U256>,
+    ) -> Self {
+        assert!(!lanes.is_empty(), "SerialSubmitter needs a submission lane");
+        let prepare_queue = OpQueue::new(
+            metrics.submitter_queue_length.clone(),
+            "prepare_queue".to_string(),
+            retry_rx.clone(),
+        );
+        let confirm_queue = OpQueue::new(
+            metrics.submitter_queue_length.clone(),
+            "confirm_queue".to_string(),
+            retry_rx,
+        );
+        Self {
+            domain,
+            rx,
+            metrics,
+            prepare_queue,
+            confirm_queue,
+            lanes,
+            max_batch_size,
+            transaction_gas_limit,
+        }
+    }
+
+    /// Handles to the queues of this submitter, so they can be inspected and
+    /// managed while the submitter is running.
+    pub fn queues(&self) -> Vec<OpQueue> {
+        vec![self.prepare_queue.clone(), self.confirm_queue.clone()]
+    }
+
     pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
         let span = info_span!("SerialSubmitter", destination=%self.domain);
         spawn(async move { self.run().await }).instrument(span)
@@ -88,26 +147,34 @@ impl SerialSubmitter {
             domain,
             metrics,
             rx: rx_prepare,
-            retry_rx,
+            prepare_queue,
+            confirm_queue,
+            lanes,
+            max_batch_size,
+            transaction_gas_limit,
         } = self;
-        let prepare_queue = OpQueue::new(
-            metrics.submitter_queue_length.clone(),
-            "prepare_queue".to_string(),
-            retry_rx.clone(),
-        );
-        let confirm_queue = OpQueue::new(
-            metrics.submitter_queue_length.clone(),
-            "confirm_queue".to_string(),
-            retry_rx,
-        );
+
+        // Only batch if the destination can actually process batches.
+        let max_batch_size = if lanes[0].mailbox.supports_batching() {
+            max_batch_size.max(1) as usize
+        } else {
+            1
+        };
 
         // This is a channel because we want to only have a small number of messages
         // sitting ready to go at a time and this acts as a synchronization tool
         // to slow down the preparation of messages when the submitter gets
-        // behind.
-        let (tx_submit, rx_submit) = mpsc::channel(1);
+        // behind. It holds at most one batch worth of messages per lane, and
+        // is shared by the lanes so that each takes the next ready operations
+        // when it's free.
+        let (tx_submit, rx_submit) = mpsc::channel(max_batch_size * lanes.len());
+        let rx_submit = Arc::new(Mutex::new(rx_submit));
+        // Operations that were submitted before are sent straight to the lane
+        // they are pinned to instead.
+        let (pinned_txs, pinned_rxs): (Vec<_>, Vec<_>) =
+            lanes.iter().map(|_| mpsc::unbounded_channel()).unzip();
 
-        let tasks = [
+        let mut tasks = vec![
             spawn(receive_task(
                 domain.clone(),
                 rx_prepare,
@@ -117,22 +184,30 @@ impl SerialSubmitter {
                 domain.clone(),
                 prepare_queue.clone(),
                 tx_submit,
+                pinned_txs,
                 metrics.clone(),
             )),
-            spawn(submit_task(
+            spawn(confirm_task(
                 domain.clone(),
-                rx_submit,
                 prepare_queue.clone(),
                 confirm_queue.clone(),
                 metrics.clone(),
             )),
-            spawn(confirm_task(
-                domain.clone(),
-                prepare_queue,
-                confirm_queue,
-                metrics,
-            )),
         ];
+        for (index, (lane, pinned_rx)) in lanes.into_iter().zip(pinned_rxs).enumerate() {
+            tasks.push(spawn(submit_task(
+                domain.clone(),
+                rx_submit.clone(),
+                pinned_rx,
+                prepare_queue.clone(),
+                confirm_queue.clone(),
+                index,
+                lane,
+                max_batch_size,
+                transaction_gas_limit,
+                metrics.clone(),
+            )));
+        }
 
         if let Err(err) = try_join_all(tasks).await {
             tracing::error!(
@@ -165,6 +240,7 @@ async fn prepare_task(
     domain: HyperlaneDomain,
     mut prepare_queue: OpQueue,
     tx_submit: mpsc::Sender<QueueOperation>,
+    pinned_txs: Vec<mpsc::UnboundedSender<QueueOperation>>,
     metrics: SerialSubmitterMetrics,
 ) {
     loop {
@@ -184,6 +260,12 @@ async fn prepare_task(
             PendingOperationResult::Success => {
                 debug!(?op, "Operation prepared");
                 metrics.ops_prepared.inc();
+                if let Some(lane) = op.submission_lane() {
+                    if let Err(err) = pinned_txs[lane].send(op) {
+                        tracing::error!(error=?err, lane, "Failed to send prepared operation to its submission lane");
+                    }
+                    continue;
+                }
                 // this send will pause this task if the submitter is not ready to accept yet
                 if let Err(err) = tx_submit.send(op).await {
                     tracing::error!(error=?err, "Failed to send prepared operation to submitter");
@@ -205,34 +287,196 @@ async fn prepare_task(
     }
 }
 
-#[instrument(skip_all, fields(%domain))]
+#[instrument(skip_all, fields(%domain, signer = %lane.signer))]
+#[allow(clippy::too_many_arguments)]
 async fn submit_task(
     domain: HyperlaneDomain,
-    mut rx_submit: mpsc::Receiver<QueueOperation>,
+    rx_submit: Arc<Mutex<mpsc::Receiver<QueueOperation>>>,
+    mut pinned_rx: mpsc::UnboundedReceiver<QueueOperation>,
     prepare_queue: OpQueue,
     confirm_queue: OpQueue,
+    lane_index: usize,
+    lane: SubmissionLane,
+    max_batch_size: usize,
+    transaction_gas_limit: Option<U256>,
     metrics: SerialSubmitterMetrics,
 ) {
-    while let Some(mut op) = rx_submit.recv().await {
-        trace!(?op, "Submitting operation");
-        debug_assert_eq!(*op.destination_domain(), domain);
-
-        match op.submit().await {
-            PendingOperationResult::Success => {
-                debug!(?op, "Operation submitted");
-                metrics.ops_submitted.inc();
-                confirm_queue.push(op).await;
-            }
-            PendingOperationResult::NotReady => {
-                panic!("Pending operation was prepared and therefore must be ready")
+    let in_flight = metrics.in_flight_operations(&lane.signer);
+    loop {
+        // Retries of operations pinned to this lane go first, then the next
+        // operations that are ready to go.
+        let ops = tokio::select! {
+            biased;
+            Some(op) = pinned_rx.recv() => {
+                take_batch(op, || pinned_rx.try_recv().ok(), max_batch_size)
             }
-            PendingOperationResult::Reprepare => {
-                metrics.ops_failed.inc();
-                prepare_queue.push(op).await;
+            ops = async {
+                let mut rx_submit = rx_submit.lock().await;
+                let op = rx_submit.recv().await?;
+                let mut ops = take_batch(op, || rx_submit.try_recv().ok(), max_batch_size);
+                for op in ops.iter_mut() {
+                    op.set_submission_lane(lane_index);
+                }
+                Some(ops)
+            } => {
+                let Some(ops) = ops else {
+                    break;
+                };
+                ops
             }
-            PendingOperationResult::Drop => {
-                metrics.ops_dropped.inc();
+        };
+        in_flight.set(ops.len() as i64);
+
+        let ops = if ops.len() > 1 {
+            submit_batch(
+                ops,
+                &lane.mailbox,
+                transaction_gas_limit,
+                &prepare_queue,
+                &confirm_queue,
+                &metrics,
+            )
+            .await
+        } else {
+            ops
+        };
+
+        for mut op in ops {
+            trace!(?op, "Submitting operation");
+            debug_assert_eq!(*op.destination_domain(), domain);
+
+            let result = op.submit(lane.mailbox.as_ref()).await;
+            handle_submit_result(op, result, &prepare_queue, &confirm_queue, &metrics).await;
+        }
+        in_flight.set(0);
+    }
+}
+
+/// Takes any other operations that are ready to go along with `op`, up to the
+/// batch size.
+fn take_batch(
+    op: QueueOperation,
+    mut try_recv: impl FnMut() -> Option<QueueOperation>,
+    max_batch_size: usize,
+) -> Vec<QueueOperation> {
+    let mut ops = vec![op];
+    while ops.len() < max_batch_size {
+        match try_recv() {
+            Some(op) => ops.push(op),
+            None => break,
+        }
+    }
+    ops
+}
+
+/// Submits the operations in a single batch transaction. Returns the
+/// operations which could not be included in the batch and need to be
+/// submitted individually.
+async fn submit_batch(
+    ops: Vec<QueueOperation>,
+    mailbox: &Arc<dyn Mailbox>,
+    transaction_gas_limit: Option<U256>,
+    prepare_queue: &OpQueue,
+    confirm_queue: &OpQueue,
+    metrics: &SerialSubmitterMetrics,
+) -> Vec<QueueOperation> {
+    let mut remaining = vec![];
+    let mut batch = vec![];
+    let mut items = vec![];
+    let mut batch_gas_limit = U256::zero();
+    for op in ops {
+        match op.batch_item() {
+            // Operations that would take the batch over the transaction gas
+            // limit are left out of it
+            Some(item)
+                if transaction_gas_limit.map_or(true, |limit| {
+                    batch_gas_limit.saturating_add(item.gas_limit) <= limit
+                }) =>
+            {
+                batch_gas_limit = batch_gas_limit.saturating_add(item.gas_limit);
+                items.push(item);
+                batch.push(op);
             }
+            _ => remaining.push(op),
+        }
+    }
+    if batch.len() < 2 {
+        remaining.extend(batch);
+        return remaining;
+    }
+
+    trace!(batch_size = batch.len(), "Submitting batch of operations");
+    let result = match mailbox.process_batch(&items).await {
+        Ok(result) => result,
+        Err(err) => {
+            warn!(error=?err, "Failed to submit batch, submitting operations individually");
+            remaining.extend(batch);
+            return remaining;
+        }
+    };
+    let Some(outcome) = result.outcome else {
+        debug!("No operations were included in the batch");
+        remaining.extend(batch);
+        return remaining;
+    };
+
+    let failed: HashSet<usize> = result.failed_indexes.into_iter().collect();
+    let reverted: HashSet<usize> = result.reverted_indexes.into_iter().collect();
+    let batched_gas_limit = items
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !failed.contains(index))
+        .fold(U256::zero(), |total, (_, item)| total + item.gas_limit);
+    for (index, (mut op, item)) in batch.into_iter().zip(items).enumerate() {
+        if failed.contains(&index) {
+            remaining.push(op);
+            continue;
+        }
+        let mut share = batch_share(&outcome, item.gas_limit, batched_gas_limit);
+        // The batch may have been executed without this operation's call
+        share.executed &= !reverted.contains(&index);
+        let result = op.on_batch_submitted(share).await;
+        handle_submit_result(op, result, prepare_queue, confirm_queue, metrics).await;
+    }
+    remaining
+}
+
+/// The share of a batch transaction's outcome attributed to a single
+/// operation, in proportion to the gas it was estimated to use on its own.
+fn batch_share(outcome: &TxOutcome, gas_limit: U256, batched_gas_limit: U256) -> TxOutcome {
+    let gas_used = if batched_gas_limit.is_zero() {
+        U256::zero()
+    } else {
+        outcome.gas_used.saturating_mul(gas_limit) / batched_gas_limit
+    };
+    TxOutcome {
+        gas_used,
+        ..outcome.clone()
+    }
+}
+
+async fn handle_submit_result(
+    op: QueueOperation,
+    result: PendingOperationResult,
+    prepare_queue: &OpQueue,
+    confirm_queue: &OpQueue,
+    metrics: &SerialSubmitterMetrics,
+) {
+    match result {
+        PendingOperationResult::Success => {
+            debug!(?op, "Operation submitted");
+            metrics.ops_submitted.inc();
+            confirm_queue.push(op).await;
+        }
+        PendingOperationResult::NotReady => {
+            panic!("Pending operation was prepared and therefore must be ready")
+        }
+        PendingOperationResult::Reprepare => {
+            metrics.ops_failed.inc();
+            prepare_queue.push(op).await;
+        }
+        PendingOperationResult::Drop => {
+            metrics.ops_dropped.inc();
         }
     }
 }
@@ -275,9 +519,22 @@ async fn confirm_task(
     }
 }
 
+/// A signer operations are submitted with, through a destination mailbox that
+/// sends transactions with it.
+#[derive(Debug, Clone)]
+pub struct SubmissionLane {
+    /// Address of the signer
+    pub signer: String,
+    /// Mailbox on the destination chain, used to submit operations and
+    /// batches with the signer.
+    pub mailbox: Arc<dyn Mailbox>,
+}
+
 #[derive(Debug, Clone)]
 pub struct SerialSubmitterMetrics {
+    destination: String,
     submitter_queue_length: IntGaugeVec,
+    submitter_in_flight_operations: IntGaugeVec,
     ops_prepared: IntCounter,
     ops_submitted: IntCounter,
     ops_confirmed: IntCounter,
@@ -290,7 +547,9 @@ impl SerialSubmitterMetrics {
     pub fn new(metrics: &CoreMetrics, destination: &HyperlaneDomain) -> Self {
         let destination = destination.name();
         Self {
+            destination: destination.to_owned(),
             submitter_queue_length: metrics.submitter_queue_length(),
+            submitter_in_flight_operations: metrics.submitter_in_flight_operations(),
             ops_prepared: metrics
                 .operations_processed_count()
                 .with_label_values(&["prepared", destination]),
@@ -311,4 +570,530 @@ impl SerialSubmitterMetrics {
                 .with_label_values(&["dropped", destination]),
         }
     }
+
+    /// The number of operations the lane of `signer` is submitting
+    fn in_flight_operations(&self, signer: &str) -> IntGauge {
+        self.submitter_in_flight_operations
+            .with_label_values(&[&self.destination, signer])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex as StdMutex;
+    use std::time::Instant;
+
+    use hyperlane_base::db::OperationDisposition;
+    use hyperlane_core::{
+        BatchItem, BatchResult, ChainCommunicationError, FixedPointNumber, HyperlaneMessage,
+        MpmcChannel, H256, H512,
+    };
+    use hyperlane_test::mocks::MockMailboxContract;
+    use prometheus::Registry;
+    use tokio::sync::Notify;
+    use tokio::time::timeout;
+
+    use super::*;
+
+    /// What was submitted, by nonce
+    #[derive(Debug, Default)]
+    struct Submissions {
+        individually: Vec<u32>,
+        /// With the gas attributed to the operation
+        batched: Vec<(u32, U256)>,
+    }
+
+    #[derive(Debug)]
+    struct MockOperation {
+        nonce: u32,
+        destination_domain: HyperlaneDomain,
+        /// `None` if the operation can't be batched
+        gas_limit: Option<U256>,
+        submissions: Arc<StdMutex<Submissions>>,
+        /// If set, submitting the operation waits until this is notified
+        release: Option<Arc<Notify>>,
+        submission_lane: Option<usize>,
+    }
+
+    #[async_trait::async_trait]
+    impl PendingOperation for MockOperation {
+        fn id(&self) -> H256 {
+            H256::from_low_u64_be(self.nonce as u64)
+        }
+
+        fn priority(&self) -> u32 {
+            self.nonce
+        }
+
+        fn origin_domain_id(&self) -> u32 {
+            0
+        }
+
+        fn destination_domain(&self) -> &HyperlaneDomain {
+            &self.destination_domain
+        }
+
+        fn app_context(&self) -> Option<String> {
+            None
+        }
+
+        async fn prepare(&mut self) -> PendingOperationResult {
+            PendingOperationResult::Success
+        }
+
+        async fn submit(&mut self, _mailbox: &dyn Mailbox) -> PendingOperationResult {
+            if let Some(release) = &self.release {
+                release.notified().await;
+            }
+            // Give the other lanes a chance to take operations
+            tokio::task::yield_now().await;
+            self.submissions
+                .lock()
+                .unwrap()
+                .individually
+                .push(self.nonce);
+            PendingOperationResult::Success
+        }
+
+        fn batch_item(&self) -> Option<BatchItem> {
+            self.gas_limit.map(|gas_limit| BatchItem {
+                message: HyperlaneMessage {
+                    nonce: self.nonce,
+                    ..Default::default()
+                },
+                metadata: vec![],
+                gas_limit,
+            })
+        }
+
+        async fn on_batch_submitted(&mut self, tx_outcome: TxOutcome) -> PendingOperationResult {
+            self.submissions
+                .lock()
+                .unwrap()
+                .batched
+                .push((self.nonce, tx_outcome.gas_used));
+            if tx_outcome.executed {
+                PendingOperationResult::Success
+            } else {
+                PendingOperationResult::Reprepare
+            }
+        }
+
+        async fn confirm(&mut self) -> PendingOperationResult {
+            PendingOperationResult::Success
+        }
+
+        fn next_attempt_after(&self) -> Option<Instant> {
+            None
+        }
+
+        fn reset_attempts(&mut self) {}
+
+        fn retry_count(&self) -> u32 {
+            0
+        }
+
+        fn last_error(&self) -> Option<String> {
+            None
+        }
+
+        fn disposition(&self) -> OperationDisposition {
+            OperationDisposition::Normal
+        }
+
+        fn set_disposition(&mut self, _disposition: OperationDisposition) {}
+
+        fn submission_lane(&self) -> Option<usize> {
+            self.submission_lane
+        }
+
+        fn set_submission_lane(&mut self, lane: usize) {
+            self.submission_lane = Some(lane);
+        }
+
+        fn set_retries(&mut self, _retries: u32) {}
+    }
+
+    struct TestSetup {
+        metrics: SerialSubmitterMetrics,
+        prepare_queue: OpQueue,
+        confirm_queue: OpQueue,
+        submissions: Arc<StdMutex<Submissions>>,
+    }
+
+    impl TestSetup {
+        fn new() -> Self {
+            let core_metrics = CoreMetrics::new("test", 9090, Registry::new()).unwrap();
+            let metrics = SerialSubmitterMetrics::new(&core_metrics, &test_domain());
+            let queue = |label: &str| {
+                OpQueue::new(
+                    metrics.submitter_queue_length.clone(),
+                    label.to_owned(),
+                    MpmcChannel::new(10).receiver(),
+                )
+            };
+            Self {
+                prepare_queue: queue("prepare_queue"),
+                confirm_queue: queue("confirm_queue"),
+                metrics,
+                submissions: Default::default(),
+            }
+        }
+
+        /// Operations with the given gas limits, numbered from 1
+        fn ops(&self, gas_limits: &[Option<u64>]) -> Vec<QueueOperation> {
+            gas_limits
+                .iter()
+                .enumerate()
+                .map(|(i, gas_limit)| {
+                    Box::new(MockOperation {
+                        nonce: i as u32 + 1,
+                        destination_domain: test_domain(),
+                        gas_limit: gas_limit.map(U256::from),
+                        submissions: self.submissions.clone(),
+                        release: None,
+                        submission_lane: None,
+                    }) as QueueOperation
+                })
+                .collect()
+        }
+
+        async fn submit_batch(
+            &self,
+            ops: Vec<QueueOperation>,
+            mailbox: MockMailboxContract,
+            transaction_gas_limit: Option<u64>,
+        ) -> Vec<u32> {
+            let mailbox: Arc<dyn Mailbox> = Arc::new(mailbox);
+            submit_batch(
+                ops,
+                &mailbox,
+                transaction_gas_limit.map(U256::from),
+                &self.prepare_queue,
+                &self.confirm_queue,
+                &self.metrics,
+            )
+            .await
+            .iter()
+            .map(|op| op.priority())
+            .collect()
+        }
+
+        /// Spawns a submit task for each signer, all taking operations from
+        /// the same channel without batching them. Operations pinned to a
+        /// lane are taken from the given channel of the lane instead.
+        fn spawn_lanes(
+            &self,
+            signers: &[&str],
+            rx_submit: mpsc::Receiver<QueueOperation>,
+            mut pinned_rxs: Vec<mpsc::UnboundedReceiver<QueueOperation>>,
+        ) -> Vec<JoinHandle<()>> {
+            let rx_submit = Arc::new(Mutex::new(rx_submit));
+            pinned_rxs.resize_with(signers.len(), || mpsc::unbounded_channel().1);
+            signers
+                .iter()
+                .zip(pinned_rxs)
+                .enumerate()
+                .map(|(index, (signer, pinned_rx))| {
+                    spawn(submit_task(
+                        test_domain(),
+                        rx_submit.clone(),
+                        pinned_rx,
+                        self.prepare_queue.clone(),
+                        self.confirm_queue.clone(),
+                        index,
+                        SubmissionLane {
+                            signer: signer.to_string(),
+                            mailbox: Arc::new(MockMailboxContract::new()),
+                        },
+                        1,
+                        None,
+                        self.metrics.clone(),
+                    ))
+                })
+                .collect()
+        }
+    }
+
+    fn test_domain() -> HyperlaneDomain {
+        HyperlaneDomain::new_test_domain("test")
+    }
+
+    fn batch_outcome(executed: bool, gas_used: u64) -> BatchResult {
+        BatchResult {
+            outcome: Some(TxOutcome {
+                transaction_id: H512::zero(),
+                executed,
+                gas_used: gas_used.into(),
+                gas_price: FixedPointNumber::zero(),
+            }),
+            failed_indexes: vec![],
+            reverted_indexes: vec![],
+        }
+    }
+
+    fn batched_nonces(items: &[BatchItem]) -> Vec<u32> {
+        items.iter().map(|item| item.message.nonce).collect()
+    }
+
+    async fn queued_nonces(queue: &OpQueue) -> Vec<u32> {
+        let mut queue = queue.clone();
+        let mut nonces = vec![];
+        while let Some(Reverse(op)) = queue.pop().await {
+            nonces.push(op.priority());
+        }
+        nonces
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch_tracks_outcome_per_operation() {
+        let setup = TestSetup::new();
+        let mut mailbox = MockMailboxContract::new();
+        mailbox
+            .expect__process_batch()
+            .withf(|items| batched_nonces(items) == [1, 2])
+            .times(1)
+            .returning(|_| Ok(batch_outcome(true, 200)));
+
+        let remaining = setup
+            .submit_batch(setup.ops(&[Some(100), Some(300), None]), mailbox, None)
+            .await;
+
+        // The operation that can't be batched is left to be submitted on its own
+        assert_eq!(remaining, [3]);
+        let submissions = setup.submissions.lock().unwrap();
+        assert!(submissions.individually.is_empty());
+        // The gas used is split in proportion to the gas limits
+        assert_eq!(
+            submissions.batched,
+            [(1, U256::from(50)), (2, U256::from(150))]
+        );
+        assert_eq!(queued_nonces(&setup.confirm_queue).await, [1, 2]);
+        assert!(queued_nonces(&setup.prepare_queue).await.is_empty());
+        assert_eq!(setup.metrics.ops_submitted.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch_falls_back_to_individual_submission() {
+        let setup = TestSetup::new();
+
+        // The batch could not be submitted at all
+        let mut mailbox = MockMailboxContract::new();
+        mailbox
+            .expect__process_batch()
+            .returning(|_| Err(ChainCommunicationError::from_other_str("rpc error")));
+        let remaining = setup
+            .submit_batch(setup.ops(&[Some(100), Some(100)]), mailbox, None)
+            .await;
+        assert_eq!(remaining, [1, 2]);
+
+        // None of the operations could be included in the batch
+        let mut mailbox = MockMailboxContract::new();
+        mailbox.expect__process_batch().returning(|_| {
+            Ok(BatchResult {
+                outcome: None,
+                failed_indexes: vec![0, 1],
+                reverted_indexes: vec![],
+            })
+        });
+        let remaining = setup
+            .submit_batch(setup.ops(&[Some(100), Some(100)]), mailbox, None)
+            .await;
+        assert_eq!(remaining, [1, 2]);
+
+        // Only some of the operations were included in the batch
+        let mut mailbox = MockMailboxContract::new();
+        mailbox.expect__process_batch().returning(|_| {
+            Ok(BatchResult {
+                failed_indexes: vec![0],
+                ..batch_outcome(true, 80)
+            })
+        });
+        let remaining = setup
+            .submit_batch(setup.ops(&[Some(100), Some(100)]), mailbox, None)
+            .await;
+        assert_eq!(remaining, [1]);
+
+        // A single batchable operation is not worth a batch
+        let remaining = setup
+            .submit_batch(
+                setup.ops(&[Some(100), None]),
+                MockMailboxContract::new(),
+                None,
+            )
+            .await;
+        assert_eq!(remaining, [1, 2]);
+
+        let submissions = setup.submissions.lock().unwrap();
+        assert!(submissions.individually.is_empty());
+        assert_eq!(submissions.batched, [(2, U256::from(80))]);
+        assert_eq!(queued_nonces(&setup.confirm_queue).await, [2]);
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch_respects_transaction_gas_limit() {
+        let setup = TestSetup::new();
+        let mut mailbox = MockMailboxContract::new();
+        mailbox
+            .expect__process_batch()
+            .withf(|items| batched_nonces(items) == [1, 3])
+            .times(1)
+            .returning(|_| Ok(batch_outcome(true, 300)));
+
+        let remaining = setup
+            .submit_batch(
+                setup.ops(&[Some(100), Some(300), Some(200)]),
+                mailbox,
+                Some(350),
+            )
+            .await;
+
+        // Adding the second operation would exceed the limit
+        assert_eq!(remaining, [2]);
+        assert_eq!(queued_nonces(&setup.confirm_queue).await, [1, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_reverted_batch_is_reprepared() {
+        let setup = TestSetup::new();
+        let mut mailbox = MockMailboxContract::new();
+        mailbox
+            .expect__process_batch()
+            .returning(|_| Ok(batch_outcome(false, 100)));
+
+        let remaining = setup
+            .submit_batch(setup.ops(&[Some(100), Some(100)]), mailbox, None)
+            .await;
+
+        assert!(remaining.is_empty());
+        assert_eq!(queued_nonces(&setup.prepare_queue).await, [1, 2]);
+        assert!(queued_nonces(&setup.confirm_queue).await.is_empty());
+        assert_eq!(setup.metrics.ops_failed.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reverted_call_in_batch_is_reprepared() {
+        let setup = TestSetup::new();
+        let mut mailbox = MockMailboxContract::new();
+        mailbox.expect__process_batch().returning(|_| {
+            Ok(BatchResult {
+                reverted_indexes: vec![1],
+                ..batch_outcome(true, 100)
+            })
+        });
+
+        let remaining = setup
+            .submit_batch(setup.ops(&[Some(100), Some(100)]), mailbox, None)
+            .await;
+
+        // Only the operation whose call reverted is reprepared
+        assert!(remaining.is_empty());
+        assert_eq!(queued_nonces(&setup.confirm_queue).await, [1]);
+        assert_eq!(queued_nonces(&setup.prepare_queue).await, [2]);
+        assert_eq!(setup.metrics.ops_submitted.get(), 1);
+        assert_eq!(setup.metrics.ops_failed.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_lanes_share_submissions() {
+        let setup = TestSetup::new();
+        let ops = setup.ops(&[None; 10]);
+        let (tx_submit, rx_submit) = mpsc::channel(ops.len());
+        for op in ops {
+            tx_submit.send(op).await.unwrap();
+        }
+        // The lanes stop once the channel is closed and drained
+        drop(tx_submit);
+
+        try_join_all(setup.spawn_lanes(&["signer1", "signer2", "signer3"], rx_submit, vec![]))
+            .await
+            .unwrap();
+
+        // Every operation is submitted by exactly one of the lanes
+        let mut submitted = setup.submissions.lock().unwrap().individually.clone();
+        submitted.sort();
+        assert_eq!(submitted, (1..=10).collect::<Vec<_>>());
+        let mut confirming = queued_nonces(&setup.confirm_queue).await;
+        confirming.sort();
+        assert_eq!(confirming, (1..=10).collect::<Vec<_>>());
+        assert_eq!(setup.metrics.ops_submitted.get(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_lane_tracks_in_flight_operations() {
+        let setup = TestSetup::new();
+        let release = Arc::new(Notify::new());
+        let (tx_submit, rx_submit) = mpsc::channel(1);
+        let lanes = setup.spawn_lanes(&["signer1", "signer2"], rx_submit, vec![]);
+        let in_flight = setup.metrics.in_flight_operations("signer1");
+        let other_in_flight = setup.metrics.in_flight_operations("signer2");
+
+        tx_submit
+            .send(Box::new(MockOperation {
+                nonce: 1,
+                destination_domain: test_domain(),
+                gas_limit: None,
+                submissions: setup.submissions.clone(),
+                release: Some(release.clone()),
+                submission_lane: None,
+            }) as QueueOperation)
+            .await
+            .unwrap();
+
+        // The operation is in flight on one of the lanes until it's submitted
+        timeout(Duration::from_secs(5), async {
+            while in_flight.get() + other_in_flight.get() == 0 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(in_flight.get() + other_in_flight.get(), 1);
+
+        release.notify_one();
+        drop(tx_submit);
+        try_join_all(lanes).await.unwrap();
+
+        assert_eq!(in_flight.get(), 0);
+        assert_eq!(other_in_flight.get(), 0);
+        assert_eq!(setup.submissions.lock().unwrap().individually, [1]);
+    }
+
+    #[tokio::test]
+    async fn test_operations_are_pinned_to_their_lane() {
+        let setup = TestSetup::new();
+        let mut ops = setup.ops(&[None, None]);
+        let mut retried = ops.pop().unwrap();
+        let new = ops.pop().unwrap();
+        let (tx_submit, rx_submit) = mpsc::channel(1);
+        tx_submit.send(new).await.unwrap();
+        drop(tx_submit);
+        // A retried operation that was first submitted through the second lane
+        let (pinned_tx, pinned_rx) = mpsc::unbounded_channel();
+        retried.set_submission_lane(1);
+        pinned_tx.send(retried).unwrap();
+
+        let (_, other_pinned_rx) = mpsc::unbounded_channel();
+        try_join_all(setup.spawn_lanes(
+            &["signer1", "signer2"],
+            rx_submit,
+            vec![other_pinned_rx, pinned_rx],
+        ))
+        .await
+        .unwrap();
+
+        // Both are submitted, and the new operation is now pinned to the lane
+        // that submitted it
+        let mut submitted = setup.submissions.lock().unwrap().individually.clone();
+        submitted.sort();
+        assert_eq!(submitted, [1, 2]);
+        let mut confirm_queue = setup.confirm_queue.clone();
+        let mut lanes = vec![];
+        while let Some(Reverse(op)) = confirm_queue.pop().await {
+            lanes.push((op.priority(), op.submission_lane()));
+        }
+        lanes.sort();
+        assert!(matches!(lanes[0], (1, Some(_))));
+        assert_eq!(lanes[1], (2, Some(1)));
+    }
 }
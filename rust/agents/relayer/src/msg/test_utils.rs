@@ -0,0 +1,107 @@
+//! Helpers to build the context of pending messages in tests.
+
+use std::sync::Arc;
+
+use hyperlane_base::{
+    db::HyperlaneRocksDB,
+    settings::{ChainConf, ChainConnectionConf, Settings},
+    CoreMetrics, VerifiedCheckpointCache,
+};
+use hyperlane_core::{HyperlaneDomain, Mailbox, H256};
+use hyperlane_test::mocks::{MockMailboxContract, MockValidatorAnnounceContract};
+use prometheus::{IntCounter, IntGauge, Registry};
+use tokio::sync::RwLock;
+
+use super::{
+    gas_payment::GasPaymentEnforcer,
+    metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier},
+    ordered_delivery::OrderedDelivery,
+    pending_message::{MessageContext, MessageSubmissionMetrics},
+};
+use crate::merkle_tree::builder::MerkleTreeBuilder;
+
+pub(crate) fn dummy_submission_metrics() -> MessageSubmissionMetrics {
+    MessageSubmissionMetrics {
+        last_known_nonce: IntGauge::new("last_known_nonce_gauge", "help string").unwrap(),
+        messages_processed: IntCounter::new("message_processed_gauge", "help string").unwrap(),
+    }
+}
+
+pub(crate) fn dummy_chain_conf(domain: &HyperlaneDomain) -> ChainConf {
+    ChainConf {
+        domain: domain.clone(),
+        signer: Default::default(),
+        signer_pool: Default::default(),
+        reorg_period: Default::default(),
+        addresses: Default::default(),
+        connection: ChainConnectionConf::Ethereum(hyperlane_ethereum::ConnectionConf::Http {
+            url: "http://example.com".parse().unwrap(),
+        }),
+        metrics_conf: Default::default(),
+        index: Default::default(),
+        batch: Default::default(),
+        gas_escalation: None,
+    }
+}
+
+pub(crate) fn dummy_metadata_builder(
+    origin_domain: &HyperlaneDomain,
+    destination_domain: &HyperlaneDomain,
+    db: &HyperlaneRocksDB,
+) -> BaseMetadataBuilder {
+    let mut settings = Settings::default();
+    settings.chains.insert(
+        origin_domain.name().to_owned(),
+        dummy_chain_conf(origin_domain),
+    );
+    settings.chains.insert(
+        destination_domain.name().to_owned(),
+        dummy_chain_conf(destination_domain),
+    );
+    let destination_chain_conf = settings.chain_setup(destination_domain).unwrap();
+    let core_metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+    BaseMetadataBuilder::new(
+        origin_domain.clone(),
+        H256::zero(),
+        destination_chain_conf.clone(),
+        Arc::new(RwLock::new(MerkleTreeBuilder::new())),
+        Arc::new(MockValidatorAnnounceContract::default()),
+        false,
+        Arc::new(core_metrics),
+        db.clone(),
+        VerifiedCheckpointCache::default(),
+        5,
+        IsmAwareAppContextClassifier::new(Arc::new(MockMailboxContract::default()), vec![]),
+    )
+}
+
+/// A context delivering messages through `destination_mailbox`, with no gas
+/// payment enforcement, rate limits, ordering or matching lists.
+pub(crate) fn dummy_message_context(
+    origin_domain: &HyperlaneDomain,
+    destination_domain: &HyperlaneDomain,
+    db: &HyperlaneRocksDB,
+    destination_mailbox: Arc<dyn Mailbox>,
+) -> MessageContext {
+    MessageContext {
+        destination_mailbox,
+        origin_db: db.clone(),
+        metadata_builder: Arc::new(dummy_metadata_builder(
+            origin_domain,
+            destination_domain,
+            db,
+        )),
+        origin_gas_payment_enforcer: Arc::new(GasPaymentEnforcer::new([], db.clone())),
+        transaction_gas_limit: Default::default(),
+        ordered_delivery: Arc::new(OrderedDelivery::new(
+            Default::default(),
+            IntGauge::new("dummy_held_messages", "help string").unwrap(),
+        )),
+        rate_limiter: Default::default(),
+        whitelist: Default::default(),
+        blacklist: Default::default(),
+        match_ism_module_type: false,
+        dry_run: None,
+        metrics: dummy_submission_metrics(),
+    }
+}
@@ -13,15 +13,15 @@ use hyperlane_base::{
     metrics::{AgentMetrics, MetricsUpdater},
     settings::ChainConf,
     BaseAgent, ChainMetrics, ContractSyncMetrics, CoreMetrics, HyperlaneAgentCore,
-    SequencedDataContractSync, WatermarkContractSync,
+    SequencedDataContractSync, VerifiedCheckpointCache, WatermarkContractSync,
 };
 use hyperlane_core::{
-    HyperlaneDomain, HyperlaneMessage, InterchainGasPayment, MerkleTreeInsertion, MpmcChannel,
-    MpmcReceiver, U256,
+    HyperlaneDomain, HyperlaneMessage, InterchainGasPayment, Mailbox, MerkleTreeInsertion,
+    MpmcChannel, MpmcReceiver, ValidatorAnnounce, H256, U256,
 };
 use tokio::{
     sync::{
-        mpsc::{self, UnboundedReceiver, UnboundedSender},
+        mpsc::{self, UnboundedSender},
         RwLock,
     },
     task::JoinHandle,
@@ -31,15 +31,20 @@ use tracing::{info, info_span, instrument::Instrumented, warn, Instrument};
 use crate::{
     merkle_tree::builder::MerkleTreeBuilder,
     msg::{
+        diagnose::MessageDiagnoser,
+        dry_run::DryRunResults,
         gas_payment::GasPaymentEnforcer,
         metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier},
         op_queue::QueueOperation,
+        ordered_delivery::OrderedDelivery,
         pending_message::{MessageContext, MessageSubmissionMetrics},
         processor::{MessageProcessor, MessageProcessorMetrics},
-        serial_submitter::{SerialSubmitter, SerialSubmitterMetrics},
+        rate_limit::RateLimiter,
+        serial_submitter::{SerialSubmitter, SerialSubmitterMetrics, SubmissionLane},
     },
     server::{self as relayer_server, MessageRetryRequest},
     settings::{matching_list::MatchingList, RelayerSettings},
+    watchtower::Watchtower,
 };
 use crate::{
     merkle_tree::processor::{MerkleTreeProcessor, MerkleTreeProcessorMetrics},
@@ -58,6 +63,10 @@ struct ContextKey {
 pub struct Relayer {
     origin_chains: HashSet<HyperlaneDomain>,
     destination_chains: HashMap<HyperlaneDomain, ChainConf>,
+    /// Mailboxes on each destination chain
+    destination_mailboxes: HashMap<HyperlaneDomain, Arc<dyn Mailbox>>,
+    /// The lanes operations are submitted through on each destination chain
+    destination_lanes: HashMap<HyperlaneDomain, Vec<SubmissionLane>>,
     #[as_ref]
     core: HyperlaneAgentCore,
     message_syncs: HashMap<HyperlaneDomain, Arc<SequencedDataContractSync<HyperlaneMessage>>>,
@@ -70,11 +79,20 @@ pub struct Relayer {
     merkle_tree_hook_syncs:
         HashMap<HyperlaneDomain, Arc<SequencedDataContractSync<MerkleTreeInsertion>>>,
     dbs: HashMap<HyperlaneDomain, HyperlaneRocksDB>,
+    validator_announces: HashMap<HyperlaneDomain, Arc<dyn ValidatorAnnounce>>,
+    /// The validators the watchtower checks, by origin chain
+    watchtower_validators: HashMap<HyperlaneDomain, Vec<H256>>,
+    /// Checkpoints fetched from validators of any origin chain, shared by the
+    /// metadata builders and watchtowers
+    verified_checkpoints: VerifiedCheckpointCache,
     whitelist: Arc<MatchingList>,
     blacklist: Arc<MatchingList>,
+    strict_ordering: Arc<MatchingList>,
     transaction_gas_limit: Option<U256>,
     skip_transaction_gas_limit_for: HashSet<u32>,
     allow_local_checkpoint_syncers: bool,
+    /// Records what would have been submitted, if running in dry run mode
+    dry_run: Option<Arc<DryRunResults>>,
     metric_app_contexts: Vec<(MatchingList, String)>,
     core_metrics: Arc<CoreMetrics>,
     // TODO: decide whether to consolidate `agent_metrics` and `chain_metrics` into a single struct
@@ -87,14 +105,16 @@ impl Debug for Relayer {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Relayer {{ origin_chains: {:?}, destination_chains: {:?}, whitelist: {:?}, blacklist: {:?}, transaction_gas_limit: {:?}, skip_transaction_gas_limit_for: {:?}, allow_local_checkpoint_syncers: {:?} }}",
+            "Relayer {{ origin_chains: {:?}, destination_chains: {:?}, whitelist: {:?}, blacklist: {:?}, strict_ordering: {:?}, transaction_gas_limit: {:?}, skip_transaction_gas_limit_for: {:?}, allow_local_checkpoint_syncers: {:?}, dry_run: {:?} }}",
             self.origin_chains,
             self.destination_chains,
             self.whitelist,
             self.blacklist,
+            self.strict_ordering,
             self.transaction_gas_limit,
             self.skip_transaction_gas_limit_for,
-            self.allow_local_checkpoint_syncers
+            self.allow_local_checkpoint_syncers,
+            self.dry_run.is_some()
         )
     }
 }
@@ -123,9 +143,42 @@ impl BaseAgent for Relayer {
             .map(|origin| (origin.clone(), HyperlaneRocksDB::new(origin, db.clone())))
             .collect::<HashMap<_, _>>();
 
-        let mailboxes = settings
-            .build_mailboxes(settings.destination_chains.iter(), &core_metrics)
-            .await?;
+        let mut mailboxes: HashMap<HyperlaneDomain, Arc<dyn Mailbox>> = HashMap::new();
+        let mut destination_lanes = HashMap::new();
+        for destination in &settings.destination_chains {
+            // In-flight `process` transactions are persisted so that they are
+            // watched again rather than sent twice after a restart.
+            let tx_store = Arc::new(HyperlaneRocksDB::new(destination, db.clone()));
+            let chain_setup = settings.chain_setup(destination)?;
+            let mailbox: Arc<dyn Mailbox> = chain_setup
+                .build_mailbox_with_tx_store(&core_metrics, Some(tx_store.clone()))
+                .await?
+                .into();
+            mailboxes.insert(destination.clone(), mailbox.clone());
+
+            // Operations are submitted with the chain's signer, unless there is
+            // a pool of signers to submit them with in parallel
+            let lane_confs = chain_setup.signer_pool_confs();
+            let lanes = if lane_confs.is_empty() {
+                vec![SubmissionLane {
+                    signer: Self::signer_address(chain_setup).await?,
+                    mailbox,
+                }]
+            } else {
+                let mut lanes = vec![];
+                for conf in lane_confs {
+                    lanes.push(SubmissionLane {
+                        signer: Self::signer_address(&conf).await?,
+                        mailbox: conf
+                            .build_mailbox_with_tx_store(&core_metrics, Some(tx_store.clone()))
+                            .await?
+                            .into(),
+                    });
+                }
+                lanes
+            };
+            destination_lanes.insert(destination.clone(), lanes);
+        }
         let validator_announces = settings
             .build_validator_announces(settings.origin_chains.iter(), &core_metrics)
             .await?;
@@ -165,16 +218,29 @@ impl BaseAgent for Relayer {
 
         let whitelist = Arc::new(settings.whitelist);
         let blacklist = Arc::new(settings.blacklist);
+        let strict_ordering = Arc::new(settings.strict_ordering);
         let skip_transaction_gas_limit_for = settings.skip_transaction_gas_limit_for;
         let transaction_gas_limit = settings.transaction_gas_limit;
 
         info!(
             %whitelist,
             %blacklist,
+            %strict_ordering,
             ?transaction_gas_limit,
             ?skip_transaction_gas_limit_for,
             "Whitelist configuration"
         );
+        info!(rate_limits=?settings.rate_limits, "Rate limit configuration");
+
+        let dry_run = settings.dry_run.then(|| {
+            Arc::new(DryRunResults::new(
+                core_metrics.dry_run_decisions(),
+                core_metrics.dry_run_estimated_gas(),
+            ))
+        });
+        if dry_run.is_some() {
+            warn!("Running in dry run mode, messages will be prepared but not submitted");
+        }
 
         // provers by origin chain
         let prover_syncs = settings
@@ -206,6 +272,19 @@ impl BaseAgent for Relayer {
             })
             .collect();
 
+        // The recipient ISM's module type is only looked up when preparing
+        // messages if a matching list matches on it
+        let match_ism_module_type = whitelist.matches_ism_module_type()
+            || blacklist.matches_ism_module_type()
+            || gas_payment_enforcers
+                .values()
+                .any(|enforcer| enforcer.matches_ism_module_type())
+            || settings
+                .metric_app_contexts
+                .iter()
+                .any(|(matching_list, _)| matching_list.matches_ism_module_type());
+
+        let verified_checkpoints = VerifiedCheckpointCache::default();
         let mut msg_ctxs = HashMap::new();
         let mut destination_chains = HashMap::new();
         for destination in &settings.destination_chains {
@@ -217,17 +296,30 @@ impl BaseAgent for Relayer {
                 } else {
                     transaction_gas_limit
                 };
+            // Rate limits apply to all messages to the destination, whatever
+            // their origin
+            let rate_limiter = Arc::new(RateLimiter::new(
+                &settings.rate_limits,
+                destination,
+                &core_metrics,
+            ));
 
             for origin in &settings.origin_chains {
                 let db = dbs.get(origin).unwrap().clone();
                 let metadata_builder = BaseMetadataBuilder::new(
                     origin.clone(),
+                    core.settings
+                        .chain_setup(origin)
+                        .unwrap()
+                        .addresses
+                        .merkle_tree_hook,
                     destination_chain_setup.clone(),
                     prover_syncs[origin].clone(),
                     validator_announces[origin].clone(),
                     settings.allow_local_checkpoint_syncers,
                     core.metrics.clone(),
                     db,
+                    verified_checkpoints.clone(),
                     5,
                     IsmAwareAppContextClassifier::new(
                         mailboxes[destination].clone(),
@@ -246,6 +338,17 @@ impl BaseAgent for Relayer {
                         metadata_builder: Arc::new(metadata_builder),
                         origin_gas_payment_enforcer: gas_payment_enforcers[origin].clone(),
                         transaction_gas_limit,
+                        ordered_delivery: Arc::new(OrderedDelivery::new(
+                            strict_ordering.clone(),
+                            core_metrics
+                                .messages_held_for_ordering()
+                                .with_label_values(&[origin.name(), destination.name()]),
+                        )),
+                        rate_limiter: rate_limiter.clone(),
+                        whitelist: whitelist.clone(),
+                        blacklist: blacklist.clone(),
+                        match_ism_module_type,
+                        dry_run: dry_run.clone(),
                         metrics: MessageSubmissionMetrics::new(&core_metrics, origin, destination),
                     }),
                 );
@@ -256,6 +359,8 @@ impl BaseAgent for Relayer {
             dbs,
             origin_chains: settings.origin_chains,
             destination_chains,
+            destination_mailboxes: mailboxes,
+            destination_lanes,
             msg_ctxs,
             core,
             message_syncs,
@@ -264,10 +369,15 @@ impl BaseAgent for Relayer {
             merkle_tree_hook_syncs,
             whitelist,
             blacklist,
+            strict_ordering,
             transaction_gas_limit,
             skip_transaction_gas_limit_for,
             allow_local_checkpoint_syncers: settings.allow_local_checkpoint_syncers,
+            dry_run,
             metric_app_contexts: settings.metric_app_contexts,
+            validator_announces,
+            watchtower_validators: settings.watchtower_validators,
+            verified_checkpoints,
             core_metrics,
             agent_metrics,
             chain_metrics,
@@ -278,31 +388,30 @@ impl BaseAgent for Relayer {
     async fn run(self) {
         let mut tasks = vec![];
 
-        // run server
         let mpmc_channel = MpmcChannel::<MessageRetryRequest>::new(ENDPOINT_MESSAGES_QUEUE_SIZE);
-        let custom_routes = relayer_server::routes(mpmc_channel.sender());
-
-        let server = self
-            .core
-            .settings
-            .server(self.core_metrics.clone())
-            .expect("Failed to create server");
-        let server_task = server
-            .run_with_custom_routes(custom_routes)
-            .instrument(info_span!("Relayer server"));
-        tasks.push(server_task);
 
         // send channels by destination chain
         let mut send_channels = HashMap::with_capacity(self.destination_chains.len());
+        let mut op_queues = HashMap::with_capacity(self.destination_chains.len());
         for (dest_domain, dest_conf) in &self.destination_chains {
             let (send_channel, receive_channel) = mpsc::unbounded_channel::<QueueOperation>();
             send_channels.insert(dest_domain.id(), send_channel);
 
-            tasks.push(self.run_destination_submitter(
-                dest_domain,
+            let serial_submitter = SerialSubmitter::new(
+                dest_domain.clone(),
                 receive_channel,
                 mpmc_channel.receiver(),
-            ));
+                SerialSubmitterMetrics::new(&self.core.metrics, dest_domain),
+                self.destination_lanes[dest_domain].clone(),
+                dest_conf.batch.max_batch_size,
+                self.transaction_gas_limit.filter(|_| {
+                    !self
+                        .skip_transaction_gas_limit_for
+                        .contains(&dest_domain.id())
+                }),
+            );
+            op_queues.insert(dest_domain.id(), serial_submitter.queues());
+            tasks.push(self.run_destination_submitter(dest_domain, serial_submitter));
 
             let metrics_updater = MetricsUpdater::new(
                 dest_conf,
@@ -316,6 +425,45 @@ impl BaseAgent for Relayer {
             tasks.push(metrics_updater.spawn());
         }
 
+        // run server
+        let watchtower_dbs = self
+            .watchtower_validators
+            .keys()
+            .map(|origin| self.dbs[origin].clone())
+            .collect();
+        let diagnoser = MessageDiagnoser::new(
+            Arc::new(
+                self.origin_chains
+                    .iter()
+                    .map(|origin| self.dbs[origin].clone())
+                    .collect(),
+            ),
+            Arc::new(
+                self.msg_ctxs
+                    .iter()
+                    .map(|(key, ctx)| ((key.origin, key.destination), ctx.clone()))
+                    .collect(),
+            ),
+            Arc::new(op_queues.clone()),
+        );
+        let custom_routes = relayer_server::routes(
+            mpmc_channel.sender(),
+            op_queues,
+            watchtower_dbs,
+            self.dry_run.clone(),
+            diagnoser,
+        );
+
+        let server = self
+            .core
+            .settings
+            .server(self.core_metrics.clone())
+            .expect("Failed to create server");
+        let server_task = server
+            .run_with_custom_routes(custom_routes)
+            .instrument(info_span!("Relayer server"));
+        tasks.push(server_task);
+
         for origin in &self.origin_chains {
             tasks.push(self.run_message_sync(origin).await);
             tasks.push(self.run_interchain_gas_payment_sync(origin).await);
@@ -324,10 +472,18 @@ impl BaseAgent for Relayer {
 
         // each message process attempts to send messages from a chain
         for origin in &self.origin_chains {
-            tasks.push(self.run_message_processor(origin, send_channels.clone()));
+            tasks.push(self.run_message_processor(
+                origin,
+                send_channels.clone(),
+                mpmc_channel.receiver(),
+            ));
             tasks.push(self.run_merkle_tree_processor(origin));
         }
 
+        for (origin, validators) in &self.watchtower_validators {
+            tasks.push(self.run_watchtower(origin, validators.clone()));
+        }
+
         if let Err(err) = try_join_all(tasks).await {
             tracing::error!(
                 error=?err,
@@ -338,6 +494,15 @@ impl BaseAgent for Relayer {
 }
 
 impl Relayer {
+    /// The address of the chain's signer, or an empty string if it has none
+    async fn signer_address(chain_conf: &ChainConf) -> Result<String> {
+        Ok(chain_conf
+            .chain_signer()
+            .await?
+            .map(|signer| signer.address_string())
+            .unwrap_or_default())
+    }
+
     async fn run_message_sync(&self, origin: &HyperlaneDomain) -> Instrumented<JoinHandle<()>> {
         let index_settings = self.as_ref().settings.chains[origin.name()].index_settings();
         let contract_sync = self.message_syncs.get(origin).unwrap().clone();
@@ -385,6 +550,7 @@ impl Relayer {
         &self,
         origin: &HyperlaneDomain,
         send_channels: HashMap<u32, UnboundedSender<QueueOperation>>,
+        retry_rx: MpmcReceiver<MessageRetryRequest>,
     ) -> Instrumented<JoinHandle<()>> {
         let metrics = MessageProcessorMetrics::new(
             &self.core.metrics,
@@ -415,6 +581,7 @@ impl Relayer {
             send_channels,
             destination_ctxs,
             self.metric_app_contexts.clone(),
+            retry_rx,
         );
 
         let span = info_span!("MessageProcessor", origin=%message_processor.domain());
@@ -436,20 +603,36 @@ impl Relayer {
         processor.spawn().instrument(span)
     }
 
-    #[allow(clippy::too_many_arguments)]
-    #[tracing::instrument(skip(self, receiver))]
+    fn run_watchtower(
+        &self,
+        origin: &HyperlaneDomain,
+        validators: Vec<H256>,
+    ) -> Instrumented<JoinHandle<()>> {
+        let merkle_tree_hook_address = self.core.settings.chains[origin.name()]
+            .addresses
+            .merkle_tree_hook;
+        let watchtower = Watchtower::new(
+            self.dbs[origin].clone(),
+            merkle_tree_hook_address,
+            self.prover_syncs[origin].clone(),
+            self.validator_announces[origin].clone(),
+            validators,
+            self.allow_local_checkpoint_syncers,
+            self.core_metrics.clone(),
+            self.verified_checkpoints.clone(),
+        );
+
+        let span = info_span!("Watchtower", origin=%watchtower.domain());
+        let processor = Processor::new(Box::new(watchtower));
+        processor.spawn().instrument(span)
+    }
+
+    #[tracing::instrument(skip(self, serial_submitter))]
     fn run_destination_submitter(
         &self,
         destination: &HyperlaneDomain,
-        receiver: UnboundedReceiver<QueueOperation>,
-        retry_receiver_channel: MpmcReceiver<MessageRetryRequest>,
+        serial_submitter: SerialSubmitter,
     ) -> Instrumented<JoinHandle<()>> {
-        let serial_submitter = SerialSubmitter::new(
-            destination.clone(),
-            receiver,
-            retry_receiver_channel,
-            SerialSubmitterMetrics::new(&self.core.metrics, destination),
-        );
         let span = info_span!("SerialSubmitter", destination=%destination);
         let destination = destination.clone();
         tokio::spawn(async move {
@@ -1,24 +1,59 @@
 use axum::{
-    extract::{Query, State},
-    routing, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing, Json, Router,
 };
 use derive_new::new;
+use hyperlane_base::db::{CheckpointFraudEvidence, HyperlaneRocksDB, OperationDisposition};
 use hyperlane_core::{ChainCommunicationError, H256};
 use serde::Deserialize;
-use std::str::FromStr;
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+    sync::Arc,
+};
 use tokio::sync::broadcast::Sender;
 
-use crate::msg::op_queue::QueueOperation;
+use crate::msg::{
+    diagnose::{MessageDiagnoser, MessageDiagnosis},
+    dry_run::{DryRunResult, DryRunResults},
+    op_queue::{OpQueue, QueueOperation, QueueOperationSummary},
+};
 
 const MESSAGE_RETRY_API_BASE: &str = "/message_retry";
+const OPERATIONS_API_BASE: &str = "/operations";
+const WATCHTOWER_API_BASE: &str = "/watchtower";
+const DRY_RUN_API_BASE: &str = "/dry_run";
+const MESSAGE_API_BASE: &str = "/message";
 pub const ENDPOINT_MESSAGES_QUEUE_SIZE: usize = 1_000;
 
+/// The queues of every destination submitter, by destination domain id.
+pub type OperationQueues = HashMap<u32, Vec<OpQueue>>;
+
 /// Returns a vector of agent-specific endpoint routes to be served.
 /// Can be extended with additional routes and feature flags to enable/disable individually.
-pub fn routes(tx: Sender<MessageRetryRequest>) -> Vec<(&'static str, Router)> {
+pub fn routes(
+    tx: Sender<MessageRetryRequest>,
+    op_queues: OperationQueues,
+    watchtower_dbs: Vec<HyperlaneRocksDB>,
+    dry_run: Option<Arc<DryRunResults>>,
+    diagnoser: MessageDiagnoser,
+) -> Vec<(&'static str, Router)> {
     let message_retry_api = MessageRetryApi::new(tx);
+    let operations_api = OperationsApi::new(Arc::new(op_queues));
+    let watchtower_api = WatchtowerApi::new(Arc::new(watchtower_dbs));
+    let diagnose_api = DiagnoseApi::new(diagnoser);
 
-    vec![message_retry_api.get_route()]
+    let mut routes = vec![
+        message_retry_api.get_route(),
+        operations_api.get_route(),
+        watchtower_api.get_route(),
+        diagnose_api.get_route(),
+    ];
+    if let Some(dry_run) = dry_run {
+        routes.push(DryRunApi::new(dry_run).get_route());
+    }
+    routes
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -104,12 +139,263 @@ impl MessageRetryApi {
     }
 }
 
+/// Lists and manages the operations queued by the destination submitters.
+///
+/// - `GET /operations` lists queued operations by destination domain, and can
+///   be filtered by `message_id` and `destination_domain`.
+/// - `POST /operations/{drop,pause,resume,deprioritize}` applies the action to
+///   the operations matching `message_id` or `destination_domain`.
+///
+/// Dropped operations are no longer queued, so they can't be resumed here.
+/// Retrying a dropped message by id with the message retry API brings it back.
+#[derive(new, Clone)]
+pub struct OperationsApi {
+    op_queues: Arc<OperationQueues>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum OperationAction {
+    Drop,
+    Pause,
+    Resume,
+    Deprioritize,
+}
+
+impl From<OperationAction> for OperationDisposition {
+    fn from(action: OperationAction) -> Self {
+        match action {
+            OperationAction::Drop => OperationDisposition::Dropped,
+            OperationAction::Pause => OperationDisposition::Paused,
+            OperationAction::Resume => OperationDisposition::Normal,
+            OperationAction::Deprioritize => OperationDisposition::Deprioritized,
+        }
+    }
+}
+
+async fn list_operations(
+    State(op_queues): State<Arc<OperationQueues>>,
+    Query(request): Query<RawMessageRetryRequest>,
+) -> Result<Json<BTreeMap<u32, Vec<QueueOperationSummary>>>, (StatusCode, String)> {
+    let message_id = match request.message_id.map(|id| H256::from_str(&id)).transpose() {
+        Ok(message_id) => message_id,
+        Err(err) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Failed to parse message id: {}", err),
+            ))
+        }
+    };
+
+    let mut operations = BTreeMap::new();
+    for (domain, queues) in op_queues.iter() {
+        if matches!(request.destination_domain, Some(d) if d != *domain) {
+            continue;
+        }
+        let mut summaries = vec![];
+        for queue in queues {
+            summaries.extend(
+                queue
+                    .list_operations()
+                    .await
+                    .into_iter()
+                    .filter(|op| message_id.map_or(true, |id| id == op.id)),
+            );
+        }
+        operations.insert(*domain, summaries);
+    }
+    Ok(Json(operations))
+}
+
+async fn update_operations(
+    State(op_queues): State<Arc<OperationQueues>>,
+    Path(action): Path<OperationAction>,
+    Query(request): Query<RawMessageRetryRequest>,
+) -> Result<String, (StatusCode, String)> {
+    let requests: Vec<MessageRetryRequest> = match request.try_into() {
+        Ok(requests) => requests,
+        Err(err) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Failed to parse operation request: {}", err),
+            ))
+        }
+    };
+
+    if requests.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "No operations selected. Please provide either a message_id or destination_domain."
+                .to_string(),
+        ));
+    }
+
+    let disposition: OperationDisposition = action.into();
+    let mut updated = 0;
+    for request in &requests {
+        for (domain, queues) in op_queues.iter() {
+            if matches!(request, MessageRetryRequest::DestinationDomain(d) if d != domain) {
+                continue;
+            }
+            for queue in queues {
+                updated += queue.set_disposition(request, disposition).await;
+            }
+        }
+    }
+
+    Ok(format!(
+        "Updated {updated} queued operation(s) to {disposition:?}"
+    ))
+}
+
+impl OperationsApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(list_operations))
+            .route("/:action", routing::post(update_operations))
+            .with_state(self.op_queues.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (OPERATIONS_API_BASE, self.router())
+    }
+}
+
+/// Serves the evidence of fraudulent checkpoints found by the watchtower.
+///
+/// - `GET /watchtower/evidence` lists the evidence by origin chain name.
+#[derive(new, Clone)]
+pub struct WatchtowerApi {
+    /// The databases of the origin chains the watchtower runs for
+    dbs: Arc<Vec<HyperlaneRocksDB>>,
+}
+
+async fn list_evidence(
+    State(dbs): State<Arc<Vec<HyperlaneRocksDB>>>,
+) -> Result<Json<BTreeMap<String, Vec<CheckpointFraudEvidence>>>, (StatusCode, String)> {
+    let mut evidence = BTreeMap::new();
+    for db in dbs.iter() {
+        let origin_evidence = db
+            .retrieve_checkpoint_fraud_evidence()
+            .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+        evidence.insert(db.domain().name().to_owned(), origin_evidence);
+    }
+    Ok(Json(evidence))
+}
+
+impl WatchtowerApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/evidence", routing::get(list_evidence))
+            .with_state(self.dbs.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (WATCHTOWER_API_BASE, self.router())
+    }
+}
+
+/// Serves what a relayer running in dry run mode would have submitted.
+///
+/// - `GET /dry_run` lists the latest decision for each message, newest first,
+///   and can be filtered by `message_id` and `destination_domain`.
+#[derive(new, Clone)]
+pub struct DryRunApi {
+    results: Arc<DryRunResults>,
+}
+
+#[derive(Deserialize)]
+struct DryRunResultsRequest {
+    message_id: Option<String>,
+    destination_domain: Option<u32>,
+}
+
+async fn list_dry_run_results(
+    State(results): State<Arc<DryRunResults>>,
+    Query(request): Query<DryRunResultsRequest>,
+) -> Result<Json<Vec<DryRunResult>>, (StatusCode, String)> {
+    let message_id = request
+        .message_id
+        .map(|id| H256::from_str(&id))
+        .transpose()
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Failed to parse message id: {}", err),
+            )
+        })?;
+    Ok(Json(results.list(message_id, request.destination_domain)))
+}
+
+impl DryRunApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(list_dry_run_results))
+            .with_state(self.results.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (DRY_RUN_API_BASE, self.router())
+    }
+}
+
+/// Explains why a message isn't delivered.
+///
+/// - `GET /message/{id}/diagnose` returns the recipient ISM tree with the
+///   checkpoints its validators signed, the gas paid for the message compared
+///   to what is required, whether the message is whitelisted or blacklisted,
+///   whether the recipient ISM currently accepts the metadata and the
+///   message's position in the queues.
+///
+/// A diagnosis queries the chains again and dry runs the recipient ISM's
+/// `verify`, so it reports the current state rather than the relayer's last
+/// attempt. Diagnoses are made one at a time, and the same diagnosis is
+/// returned for a message for a minute after it's made, apart from its queue
+/// position. In dry run mode, the relayer's last decision is included too.
+#[derive(new, Clone)]
+pub struct DiagnoseApi {
+    diagnoser: MessageDiagnoser,
+}
+
+async fn diagnose_message(
+    State(diagnoser): State<MessageDiagnoser>,
+    Path(message_id): Path<String>,
+) -> Result<Json<MessageDiagnosis>, (StatusCode, String)> {
+    let message_id = H256::from_str(&message_id).map_err(|err| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Failed to parse message id: {}", err),
+        )
+    })?;
+    match diagnoser.diagnose(message_id).await {
+        Ok(Some(diagnosis)) => Ok(Json(diagnosis)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            format!("Message {:?} not found", message_id),
+        )),
+        Err(err) => Err((StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
+
+impl DiagnoseApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/:id/diagnose", routing::get(diagnose_message))
+            .with_state(self.diagnoser.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (MESSAGE_API_BASE, self.router())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use axum::http::StatusCode;
+    use crate::msg::dry_run::DryRunDecision;
     use ethers::utils::hex::ToHex;
-    use hyperlane_core::{MpmcChannel, MpmcReceiver};
+    use hyperlane_base::db::test_utils;
+    use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, MpmcChannel, MpmcReceiver, U256};
     use std::net::SocketAddr;
 
     fn setup_test_server() -> (SocketAddr, MpmcReceiver<MessageRetryRequest>) {
@@ -176,4 +462,185 @@ mod tests {
             MessageRetryRequest::DestinationDomain(destination_domain)
         );
     }
+
+    fn setup_operations_server() -> SocketAddr {
+        let metrics = prometheus::IntGaugeVec::new(
+            prometheus::Opts::new("op_queue", "OpQueue metrics"),
+            &["destination", "queue_metrics_label", "app_context"],
+        )
+        .unwrap();
+        let mpmc_channel = MpmcChannel::<MessageRetryRequest>::new(ENDPOINT_MESSAGES_QUEUE_SIZE);
+        let queue = OpQueue::new(metrics, "prepare_queue".into(), mpmc_channel.receiver());
+        let operations_api = OperationsApi::new(Arc::new(HashMap::from([(42, vec![queue])])));
+        let (path, router) = operations_api.get_route();
+        let app = Router::new().nest(path, router);
+
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_list_operations() {
+        let addr = setup_operations_server();
+
+        let response = reqwest::get(format!("http://{}{}", addr, OPERATIONS_API_BASE))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let operations: HashMap<u32, Vec<serde_json::Value>> = response.json().await.unwrap();
+        assert_eq!(operations.len(), 1);
+        assert!(operations[&42].is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_update_operations() {
+        let addr = setup_operations_server();
+        let client = reqwest::Client::new();
+
+        let response = client
+            .post(format!(
+                "http://{}{}/pause?destination_domain=42",
+                addr, OPERATIONS_API_BASE
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.text().await.unwrap(),
+            "Updated 0 queued operation(s) to Paused"
+        );
+
+        // unknown actions are rejected
+        let response = client
+            .post(format!(
+                "http://{}{}/explode?destination_domain=42",
+                addr, OPERATIONS_API_BASE
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_client_error());
+
+        // invalid and missing selections are rejected
+        let response = client
+            .post(format!(
+                "http://{}{}/drop?message_id=not_a_message_id",
+                addr, OPERATIONS_API_BASE
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let response = client
+            .post(format!("http://{}{}/drop", addr, OPERATIONS_API_BASE))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_list_dry_run_results() {
+        let results = Arc::new(DryRunResults::new(
+            prometheus::IntCounterVec::new(
+                prometheus::Opts::new("decisions", "help string"),
+                &["origin", "remote", "decision"],
+            )
+            .unwrap(),
+            prometheus::IntCounterVec::new(
+                prometheus::Opts::new("estimated_gas", "help string"),
+                &["origin", "remote"],
+            )
+            .unwrap(),
+        ));
+        let message = HyperlaneMessage {
+            destination: 42,
+            ..Default::default()
+        };
+        let domain = HyperlaneDomain::new_test_domain("test");
+        results.record(
+            &message,
+            &domain,
+            &domain,
+            DryRunDecision::WouldDeliver,
+            Some(U256::from(100_000)),
+            None,
+        );
+
+        let (path, router) = DryRunApi::new(results).get_route();
+        let app = Router::new().nest(path, router);
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let response = reqwest::get(format!(
+            "http://{}{}?destination_domain=42",
+            addr, DRY_RUN_API_BASE
+        ))
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let listed: Vec<serde_json::Value> = response.json().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0]["decision"], "wouldDeliver");
+
+        let response = reqwest::get(format!(
+            "http://{}{}?destination_domain=43",
+            addr, DRY_RUN_API_BASE
+        ))
+        .await
+        .unwrap();
+        let listed: Vec<serde_json::Value> = response.json().await.unwrap();
+        assert!(listed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_diagnose_message() {
+        test_utils::run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&HyperlaneDomain::new_test_domain("test"), db);
+            let message = HyperlaneMessage {
+                destination: 42,
+                ..Default::default()
+            };
+            db.store_message(&message, 1).unwrap();
+            let diagnoser =
+                MessageDiagnoser::new(Arc::new(vec![db]), Default::default(), Default::default());
+
+            let (path, router) = DiagnoseApi::new(diagnoser).get_route();
+            let app = Router::new().nest(path, router);
+            let server =
+                axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+            let addr = server.local_addr();
+            tokio::spawn(server);
+            let diagnose = |message_id: String| {
+                reqwest::get(format!(
+                    "http://{}{}/{}/diagnose",
+                    addr, MESSAGE_API_BASE, message_id
+                ))
+            };
+
+            let response = diagnose(message.id().encode_hex::<String>()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let diagnosis: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(diagnosis["destinationDomain"], 42);
+            // The relayer doesn't relay to the destination
+            assert_eq!(diagnosis["errors"].as_array().unwrap().len(), 1);
+            assert!(diagnosis["queuePosition"].is_null());
+
+            let response = diagnose(H256::random().encode_hex::<String>())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+            let response = diagnose("not_a_message_id".to_owned()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        })
+        .await;
+    }
 }
@@ -8,7 +8,12 @@ use std::{
     marker::PhantomData,
 };
 
-use hyperlane_core::{config::StrOrInt, utils::hex_or_base58_to_h256, HyperlaneMessage, H256};
+use ethers::utils::hex;
+use hyperlane_core::{
+    config::StrOrInt, utils::hex_or_base58_to_h256, HyperlaneMessage, ModuleType, H256, U256,
+};
+use num_traits::FromPrimitive;
+use regex::Regex;
 use serde::{
     de::{Error, SeqAccess, Visitor},
     Deserialize, Deserializer,
@@ -22,6 +27,18 @@ use serde::{
 /// - wildcard "*"
 /// - single value in decimal or hex (must start with `0x`) format
 /// - list of values in decimal or hex format
+///
+/// Rules can also match on the message body, with a hex prefix, a regex over
+/// the body's lowercase hex encoding without a `0x` prefix, or a range of body
+/// lengths; on the amount of warp route token transfers; and on the module
+/// type of the recipient's ISM, by name or number. Ranges are objects with an
+/// optional inclusive `min` and `max`.
+///
+/// Any body can be decoded as a token transfer, so rules on the amount must
+/// list the warp route recipients they apply to in their recipient addresses.
+///
+/// The recipient's ISM is only known once a message is being prepared for
+/// delivery, rules on its module type don't match before then.
 #[derive(Debug, Default, Clone)]
 pub struct MatchingList(Option<Vec<ListElement>>);
 
@@ -105,6 +122,11 @@ impl<'de> Visitor<'de> for MatchingListArrayVisitor {
     {
         let mut rules = seq.size_hint().map(Vec::with_capacity).unwrap_or_default();
         while let Some(rule) = seq.next_element::<ListElement>()? {
+            if !rule.token_amount.is_unbounded() && rule.recipient_address == Filter::Wildcard {
+                return Err(A::Error::custom(
+                    "Token amount rules must list the warp route recipients they apply to",
+                ));
+            }
             rules.push(rule);
         }
         Ok(rules)
@@ -193,6 +215,114 @@ impl<'de> Visitor<'de> for FilterVisitor<H256> {
     }
 }
 
+impl<'de> Visitor<'de> for FilterVisitor<ModuleType> {
+    type Value = Filter<ModuleType>;
+
+    fn expecting(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "Expecting either a wildcard \"*\", module type name or number, or list of module type names or numbers"
+        )
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(Self::Value::Enumerated(vec![parse_module_type(
+            &StrOrInt::Int(v as i64),
+        )?]))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(if v == "*" {
+            Self::Value::Wildcard
+        } else {
+            Self::Value::Enumerated(vec![parse_module_type(&StrOrInt::Str(v.to_owned()))?])
+        })
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = Vec::new();
+        while let Some(i) = seq.next_element::<StrOrInt>()? {
+            values.push(parse_module_type(&i)?)
+        }
+        Ok(Self::Value::Enumerated(values))
+    }
+}
+
+/// An inclusive range of values, unbounded where `min` or `max` is missing
+#[derive(Debug, Clone, PartialEq)]
+struct RangeFilter<T> {
+    min: Option<T>,
+    max: Option<T>,
+}
+
+impl<T> Default for RangeFilter<T> {
+    fn default() -> Self {
+        Self {
+            min: None,
+            max: None,
+        }
+    }
+}
+
+impl<T: PartialOrd> RangeFilter<T> {
+    fn is_unbounded(&self) -> bool {
+        self.min.is_none() && self.max.is_none()
+    }
+
+    fn matches(&self, v: &T) -> bool {
+        self.min.as_ref().map_or(true, |min| v >= min)
+            && self.max.as_ref().map_or(true, |max| v <= max)
+    }
+}
+
+impl<T: Debug> Display for RangeFilter<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (&self.min, &self.max) {
+            (Some(min), Some(max)) => write!(f, "{min:?}..={max:?}"),
+            (Some(min), None) => write!(f, "{min:?}.."),
+            (None, Some(max)) => write!(f, "..={max:?}"),
+            (None, None) => write!(f, "*"),
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for RangeFilter<T>
+where
+    T: TryFrom<StrOrInt>,
+    T::Error: Display,
+{
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct RawRangeFilter {
+            min: Option<StrOrInt>,
+            max: Option<StrOrInt>,
+        }
+
+        let raw = RawRangeFilter::deserialize(d)?;
+        let parse = |v: Option<StrOrInt>| {
+            v.map(T::try_from)
+                .transpose()
+                .map_err(to_serde_err::<_, D::Error>)
+        };
+        Ok(Self {
+            min: parse(raw.min)?,
+            max: parse(raw.max)?,
+        })
+    }
+}
+
 impl<'de> Deserialize<'de> for MatchingList {
     fn deserialize<D>(d: D) -> Result<Self, D::Error>
     where
@@ -220,6 +350,15 @@ impl<'de> Deserialize<'de> for Filter<H256> {
     }
 }
 
+impl<'de> Deserialize<'de> for Filter<ModuleType> {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        d.deserialize_any(FilterVisitor::<ModuleType>(Default::default()))
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 #[serde(tag = "type")]
 struct ListElement {
@@ -231,18 +370,80 @@ struct ListElement {
     destination_domain: Filter<u32>,
     #[serde(default, rename = "recipientaddress")]
     recipient_address: Filter<H256>,
+    #[serde(default, rename = "bodyprefix", deserialize_with = "deserialize_hex")]
+    body_prefix: Option<Vec<u8>>,
+    #[serde(default, rename = "bodyregex", deserialize_with = "deserialize_regex")]
+    body_regex: Option<Regex>,
+    #[serde(default, rename = "bodylength")]
+    body_length: RangeFilter<u64>,
+    #[serde(default, rename = "tokenamount")]
+    token_amount: RangeFilter<U256>,
+    #[serde(default, rename = "recipientismtype")]
+    recipient_ism_type: Filter<ModuleType>,
+}
+
+impl ListElement {
+    fn matches(&self, info: MatchInfo, match_any_ism: bool) -> bool {
+        self.origin_domain.matches(&info.src_domain)
+            && self.sender_address.matches(info.src_addr)
+            && self.destination_domain.matches(&info.dst_domain)
+            && self.recipient_address.matches(info.dst_addr)
+            && self.body_matches(info.body)
+            && (match_any_ism
+                || match info.ism_module_type {
+                    Some(ism_module_type) => self.recipient_ism_type.matches(&ism_module_type),
+                    None => self.recipient_ism_type == Filter::Wildcard,
+                })
+    }
+
+    fn body_matches(&self, body: &[u8]) -> bool {
+        if let Some(prefix) = &self.body_prefix {
+            if !body.starts_with(prefix) {
+                return false;
+            }
+        }
+        if let Some(regex) = &self.body_regex {
+            if !regex.is_match(&hex::encode(body)) {
+                return false;
+            }
+        }
+        if !self.body_length.matches(&(body.len() as u64)) {
+            return false;
+        }
+        if !self.token_amount.is_unbounded() {
+            return token_message_amount(body)
+                .map_or(false, |amount| self.token_amount.matches(&amount));
+        }
+        true
+    }
 }
 
 impl Display for ListElement {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{{originDomain: {}, senderAddress: {}, destinationDomain: {}, recipientAddress: {}}}",
+            "{{originDomain: {}, senderAddress: {}, destinationDomain: {}, recipientAddress: {}",
             self.origin_domain,
             self.sender_address,
             self.destination_domain,
             self.recipient_address
-        )
+        )?;
+        if let Some(prefix) = &self.body_prefix {
+            write!(f, ", bodyPrefix: 0x{}", hex::encode(prefix))?;
+        }
+        if let Some(regex) = &self.body_regex {
+            write!(f, ", bodyRegex: {regex}")?;
+        }
+        if !self.body_length.is_unbounded() {
+            write!(f, ", bodyLength: {}", self.body_length)?;
+        }
+        if !self.token_amount.is_unbounded() {
+            write!(f, ", tokenAmount: {}", self.token_amount)?;
+        }
+        if self.recipient_ism_type != Filter::Wildcard {
+            write!(f, ", recipientIsmType: {}", self.recipient_ism_type)?;
+        }
+        write!(f, "}}")
     }
 }
 
@@ -252,6 +453,9 @@ struct MatchInfo<'a> {
     src_addr: &'a H256,
     dst_domain: u32,
     dst_addr: &'a H256,
+    body: &'a [u8],
+    /// The module type of the recipient's ISM, if known
+    ism_module_type: Option<ModuleType>,
 }
 
 impl<'a> From<&'a HyperlaneMessage> for MatchInfo<'a> {
@@ -261,17 +465,58 @@ impl<'a> From<&'a HyperlaneMessage> for MatchInfo<'a> {
             src_addr: &msg.sender,
             dst_domain: msg.destination,
             dst_addr: &msg.recipient,
+            body: &msg.body,
+            ism_module_type: None,
         }
     }
 }
 
 impl MatchingList {
-    /// Check if a message matches any of the rules.
+    /// Check if a message matches any of the rules. Rules on the recipient's
+    /// ISM module type don't match.
     /// - `default`: What to return if the the matching list is empty.
     pub fn msg_matches(&self, msg: &HyperlaneMessage, default: bool) -> bool {
         self.matches(msg.into(), default)
     }
 
+    /// Check if a message whose recipient's ISM has `ism_module_type` matches
+    /// any of the rules. If the module type isn't known, rules on it don't
+    /// match.
+    /// - `default`: What to return if the the matching list is empty.
+    pub fn msg_matches_with_ism(
+        &self,
+        msg: &HyperlaneMessage,
+        ism_module_type: Option<ModuleType>,
+        default: bool,
+    ) -> bool {
+        let info = MatchInfo {
+            ism_module_type,
+            ..MatchInfo::from(msg)
+        };
+        self.matches(info, default)
+    }
+
+    /// Check if a message matches any of the rules for some module type of
+    /// the recipient's ISM, i.e. if it could match once the module type is
+    /// known.
+    /// - `default`: What to return if the the matching list is empty.
+    pub fn msg_matches_any_ism(&self, msg: &HyperlaneMessage, default: bool) -> bool {
+        if let Some(rules) = &self.0 {
+            rules.iter().any(|rule| rule.matches(msg.into(), true))
+        } else {
+            default
+        }
+    }
+
+    /// Whether any of the rules match on the module type of the recipient's
+    /// ISM, which then has to be looked up to match messages.
+    pub fn matches_ism_module_type(&self) -> bool {
+        self.0
+            .iter()
+            .flatten()
+            .any(|rule| rule.recipient_ism_type != Filter::Wildcard)
+    }
+
     /// Check if a message matches any of the rules.
     /// - `default`: What to return if the the matching list is empty.
     fn matches(&self, info: MatchInfo, default: bool) -> bool {
@@ -284,12 +529,15 @@ impl MatchingList {
 }
 
 fn matches_any_rule<'a>(mut rules: impl Iterator<Item = &'a ListElement>, info: MatchInfo) -> bool {
-    rules.any(|rule| {
-        rule.origin_domain.matches(&info.src_domain)
-            && rule.sender_address.matches(info.src_addr)
-            && rule.destination_domain.matches(&info.dst_domain)
-            && rule.recipient_address.matches(info.dst_addr)
-    })
+    rules.any(|rule| rule.matches(info, false))
+}
+
+/// The amount of a warp route token transfer, if the body is a token message:
+/// the recipient, then the amount as a big endian uint256, then any metadata.
+/// Any long enough body decodes, so this is only meaningful for messages to
+/// warp routes.
+fn token_message_amount(body: &[u8]) -> Option<U256> {
+    body.get(32..64).map(U256::from_big_endian)
 }
 
 impl Display for MatchingList {
@@ -314,9 +562,49 @@ fn parse_addr<E: Error>(addr_str: &str) -> Result<H256, E> {
     hex_or_base58_to_h256(addr_str).map_err(to_serde_err)
 }
 
+/// Parses a module type from its number, or its name in any case with or
+/// without underscores, e.g. `messageIdMultisig` or `MESSAGE_ID_MULTISIG`.
+fn parse_module_type<E: Error>(v: &StrOrInt) -> Result<ModuleType, E> {
+    const MODULE_TYPES: [ModuleType; 8] = [
+        ModuleType::Unused,
+        ModuleType::Routing,
+        ModuleType::Aggregation,
+        ModuleType::LegacyMultisig,
+        ModuleType::MerkleRootMultisig,
+        ModuleType::MessageIdMultisig,
+        ModuleType::Null,
+        ModuleType::CcipRead,
+    ];
+    let module_type = match v {
+        StrOrInt::Int(i) => ModuleType::from_i64(*i),
+        StrOrInt::Str(s) => match s.parse::<u8>() {
+            Ok(i) => ModuleType::from_u8(i),
+            Err(_) => {
+                let name = s.replace('_', "");
+                MODULE_TYPES
+                    .into_iter()
+                    .find(|module_type| format!("{module_type:?}").eq_ignore_ascii_case(&name))
+            }
+        },
+    };
+    module_type.ok_or_else(|| E::custom(format!("Unknown ISM module type {v:?}")))
+}
+
+fn deserialize_hex<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Vec<u8>>, D::Error> {
+    let s = String::deserialize(d)?;
+    hex::decode(s.trim_start_matches("0x"))
+        .map(Some)
+        .map_err(to_serde_err)
+}
+
+fn deserialize_regex<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Regex>, D::Error> {
+    let s = String::deserialize(d)?;
+    Regex::new(&s).map(Some).map_err(to_serde_err)
+}
+
 #[cfg(test)]
 mod test {
-    use hyperlane_core::{H160, H256};
+    use hyperlane_core::{HyperlaneMessage, ModuleType, H160, H256, U256};
 
     use super::{Filter::*, MatchingList};
     use crate::settings::matching_list::MatchInfo;
@@ -343,7 +631,9 @@ mod test {
                 src_domain: 0,
                 src_addr: &H256::default(),
                 dst_domain: 0,
-                dst_addr: &H256::default()
+                dst_addr: &H256::default(),
+                body: &[],
+                ism_module_type: None,
             },
             false
         ));
@@ -356,7 +646,9 @@ mod test {
                     .unwrap()
                     .into(),
                 dst_domain: 5456,
-                dst_addr: &H256::default()
+                dst_addr: &H256::default(),
+                body: &[],
+                ism_module_type: None,
             },
             false
         ))
@@ -396,7 +688,9 @@ mod test {
                 dst_addr: &"9d4454B023096f34B160D6B654540c56A1F81688"
                     .parse::<H160>()
                     .unwrap()
-                    .into()
+                    .into(),
+                body: &[],
+                ism_module_type: None,
             },
             false
         ));
@@ -409,7 +703,9 @@ mod test {
                     .unwrap()
                     .into(),
                 dst_domain: 5456,
-                dst_addr: &H256::default()
+                dst_addr: &H256::default(),
+                body: &[],
+                ism_module_type: None,
             },
             false
         ));
@@ -441,6 +737,8 @@ mod test {
             src_addr: &H256::default(),
             dst_domain: 0,
             dst_addr: &H256::default(),
+            body: &[],
+            ism_module_type: None,
         };
         // whitelist use
         assert!(MatchingList(None).matches(info, true));
@@ -469,4 +767,112 @@ mod test {
             hyperlane_base::settings::parser::ValueParser::new(Default::default(), &val);
         crate::settings::parse_matching_list(value_parser).unwrap();
     }
+
+    /// A transfer of `amount` by the warp route at `0xaaaa...aaaa`
+    fn token_message(amount: u64) -> HyperlaneMessage {
+        let mut body = H256::repeat_byte(1).as_bytes().to_vec();
+        let mut amount_bytes = [0; 32];
+        U256::from(amount).to_big_endian(&mut amount_bytes);
+        body.extend_from_slice(&amount_bytes);
+        HyperlaneMessage {
+            recipient: H256::repeat_byte(0xaa),
+            body,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn config_with_body_filters() {
+        let list: MatchingList = serde_json::from_str(
+            r#"[{"recipientaddress": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", "bodyprefix": "0x0101", "bodylength": {"min": 64, "max": "96"}, "tokenamount": {"min": "1000"}}]"#,
+        )
+        .unwrap();
+        assert!(list.msg_matches(&token_message(1000), false));
+        assert!(!list.msg_matches(&token_message(999), false));
+
+        let mut wrong_prefix = token_message(1000);
+        wrong_prefix.body[0] = 2;
+        assert!(!list.msg_matches(&wrong_prefix, false));
+
+        let mut too_long = token_message(1000);
+        too_long.body.extend_from_slice(&[0; 33]);
+        assert!(!list.msg_matches(&too_long, false));
+
+        // Bodies that aren't token messages don't match amounts
+        let list: MatchingList = serde_json::from_str(
+            r#"[{"recipientaddress": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", "tokenamount": {"max": 10}}]"#,
+        )
+        .unwrap();
+        let not_token = HyperlaneMessage {
+            recipient: H256::repeat_byte(0xaa),
+            body: vec![1; 40],
+            ..Default::default()
+        };
+        assert!(!list.msg_matches(&not_token, false));
+        assert!(list.msg_matches(&token_message(10), false));
+
+        // Nor do messages to other recipients, whatever their body decodes to
+        let other_recipient = HyperlaneMessage {
+            recipient: H256::repeat_byte(0xbb),
+            ..token_message(10)
+        };
+        assert!(!list.msg_matches(&other_recipient, false));
+    }
+
+    #[test]
+    fn config_with_token_amount_requires_recipients() {
+        // The amount of any message body would be matched otherwise
+        assert!(serde_json::from_str::<MatchingList>(r#"[{"tokenamount": {"max": 10}}]"#).is_err());
+        assert!(serde_json::from_str::<MatchingList>(
+            r#"[{"recipientaddress": "*", "tokenamount": {"max": 10}}]"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn config_with_body_regex() {
+        let list: MatchingList =
+            serde_json::from_str(r#"[{"bodyregex": "^(01){32}0{60}"}]"#).unwrap();
+        assert!(list.msg_matches(&token_message(1), false));
+
+        let mut other = token_message(1);
+        other.body[31] = 0;
+        assert!(!list.msg_matches(&other, false));
+
+        assert!(serde_json::from_str::<MatchingList>(r#"[{"bodyregex": "("}]"#).is_err());
+    }
+
+    #[test]
+    fn config_with_ism_types() {
+        let list: MatchingList = serde_json::from_str(
+            r#"[{"recipientismtype": ["messageIdMultisig", "MERKLE_ROOT_MULTISIG", 6]}]"#,
+        )
+        .unwrap();
+        let elem = &list.0.as_ref().unwrap()[0];
+        assert_eq!(
+            elem.recipient_ism_type,
+            Enumerated(vec![
+                ModuleType::MessageIdMultisig,
+                ModuleType::MerkleRootMultisig,
+                ModuleType::Null
+            ])
+        );
+        assert!(list.matches_ism_module_type());
+
+        let msg = HyperlaneMessage::default();
+        assert!(list.msg_matches_with_ism(&msg, Some(ModuleType::Null), false));
+        assert!(!list.msg_matches_with_ism(&msg, Some(ModuleType::Routing), false));
+        // Rules on the ISM type don't match until it's known
+        assert!(!list.msg_matches(&msg, false));
+        assert!(list.msg_matches_any_ism(&msg, false));
+
+        assert!(
+            serde_json::from_str::<MatchingList>(r#"[{"recipientismtype": "unknown"}]"#).is_err()
+        );
+        assert!(serde_json::from_str::<MatchingList>(r#"[{"recipientismtype": 42}]"#).is_err());
+
+        let list: MatchingList = serde_json::from_str(r#"[{"recipientismtype": "*"}]"#).unwrap();
+        assert!(!list.matches_ism_module_type());
+        assert!(list.msg_matches(&msg, false));
+    }
 }
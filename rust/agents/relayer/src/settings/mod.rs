@@ -4,7 +4,10 @@
 //! and validations it defines are not applied here, we should mirror them.
 //! ANY CHANGES HERE NEED TO BE REFLECTED IN THE TYPESCRIPT SDK.
 
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 
 use convert_case::Case;
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
@@ -16,7 +19,9 @@ use hyperlane_base::{
         Settings,
     },
 };
-use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain, U256};
+use hyperlane_core::{
+    cfg_unwrap_all, config::*, utils::hex_or_base58_to_h256, HyperlaneDomain, H256, U256,
+};
 use itertools::Itertools;
 use serde::Deserialize;
 use serde_json::Value;
@@ -46,16 +51,31 @@ pub struct RelayerSettings {
     pub whitelist: MatchingList,
     /// Filter for what messages to block.
     pub blacklist: MatchingList,
+    /// Messages that must be delivered in nonce order per origin, sender and
+    /// recipient.
+    pub strict_ordering: MatchingList,
+    /// Limits on the messages delivered to each destination.
+    pub rate_limits: Vec<RateLimitConf>,
     /// This is optional. If not specified, any amount of gas will be valid, otherwise this
     /// is the max allowed gas in wei to relay a transaction.
     pub transaction_gas_limit: Option<U256>,
     /// List of domain ids to skip transaction gas for.
     pub skip_transaction_gas_limit_for: HashSet<u32>,
-    /// If true, allows local storage based checkpoint syncers.
+    /// If true, allows local storage based checkpoint syncers, and ones
+    /// served over HTTP from non-public hosts.
     /// Not intended for production use.
     pub allow_local_checkpoint_syncers: bool,
+    /// If true, messages are prepared but never submitted, and what would have
+    /// been submitted is recorded instead. Used to validate a configuration
+    /// alongside the relayer in production.
+    pub dry_run: bool,
     /// App contexts used for metrics.
     pub metric_app_contexts: Vec<(MatchingList, String)>,
+    /// Validators whose checkpoints the watchtower checks against the merkle
+    /// tree, by origin chain, in addition to every validator announced on the
+    /// origin chain. The watchtower only runs for the origin chains listed
+    /// here.
+    pub watchtower_validators: HashMap<HyperlaneDomain, Vec<H256>>,
 }
 
 /// Config for gas payment enforcement
@@ -68,6 +88,26 @@ pub struct GasPaymentEnforcementConf {
     pub matching_list: MatchingList,
 }
 
+/// Config for a rate limit on the messages delivered to each destination.
+/// Every limit is a token bucket that refills over its period, so that bursts
+/// of up to the limit are allowed.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitConf {
+    /// Name of the rate limit, used in metrics
+    pub name: String,
+    /// The messages the rate limit applies to. By default all messages will
+    /// match.
+    pub matching_list: MatchingList,
+    /// Max number of messages delivered per minute
+    pub messages_per_minute: Option<u64>,
+    /// Max gas used by deliveries per hour. Deliveries are admitted on their
+    /// estimated gas, which is corrected to the gas used once submitted.
+    pub gas_per_hour: Option<U256>,
+    /// Max native tokens spent on deliveries per day, in the lowest
+    /// denomination. Estimated and corrected like the gas.
+    pub native_spend_per_day: Option<U256>,
+}
+
 /// Config for a GasPaymentEnforcementPolicy
 #[derive(Debug, Clone, Default)]
 pub enum GasPaymentEnforcementPolicy {
@@ -180,6 +220,56 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             gas_payment_enforcement.push(GasPaymentEnforcementConf::default());
         }
 
+        let (raw_rate_limits_path, raw_rate_limits) = p
+            .get_opt_key("rateLimits")
+            .take_config_err_flat(&mut err)
+            .and_then(parse_json_array)
+            .unwrap_or_else(|| (&p.cwp + "rate_limits", Value::Array(vec![])));
+
+        let rate_limits_parser = ValueParser::new(raw_rate_limits_path, &raw_rate_limits);
+        let rate_limits = rate_limits_parser
+            .into_array_iter()
+            .map(|itr| {
+                itr.enumerate()
+                    .map(|(index, limit)| {
+                        let name = limit
+                            .chain(&mut err)
+                            .get_opt_key("name")
+                            .parse_string()
+                            .map(ToOwned::to_owned)
+                            .unwrap_or_else(|| index.to_string());
+                        let matching_list = limit
+                            .chain(&mut err)
+                            .get_opt_key("matchingList")
+                            .and_then(parse_matching_list_without_ism_type)
+                            .unwrap_or_default();
+                        let messages_per_minute = limit
+                            .chain(&mut err)
+                            .get_opt_key("messagesPerMinute")
+                            .parse_u64()
+                            .end();
+                        let gas_per_hour = limit
+                            .chain(&mut err)
+                            .get_opt_key("gasPerHour")
+                            .parse_u256()
+                            .end();
+                        let native_spend_per_day = limit
+                            .chain(&mut err)
+                            .get_opt_key("nativeSpendPerDay")
+                            .parse_u256()
+                            .end();
+                        RateLimitConf {
+                            name,
+                            matching_list,
+                            messages_per_minute,
+                            gas_per_hour,
+                            native_spend_per_day,
+                        }
+                    })
+                    .collect_vec()
+            })
+            .unwrap_or_default();
+
         let whitelist = p
             .chain(&mut err)
             .get_opt_key("whitelist")
@@ -190,6 +280,11 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             .get_opt_key("blacklist")
             .and_then(parse_matching_list)
             .unwrap_or_default();
+        let strict_ordering = p
+            .chain(&mut err)
+            .get_opt_key("strictOrdering")
+            .and_then(parse_matching_list_without_ism_type)
+            .unwrap_or_default();
 
         let transaction_gas_limit = p
             .chain(&mut err)
@@ -210,6 +305,12 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             .parse_bool()
             .unwrap_or(false);
 
+        let dry_run = p
+            .chain(&mut err)
+            .get_opt_key("dryRun")
+            .parse_bool()
+            .unwrap_or(false);
+
         cfg_unwrap_all!(cwp, err: [base]);
 
         let skip_transaction_gas_limit_for = skip_transaction_gas_limit_for_names
@@ -260,6 +361,31 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             })
             .unwrap_or_default();
 
+        let watchtower_validators = p
+            .chain(&mut err)
+            .get_opt_key("watchtowerValidators")
+            .into_obj_iter()
+            .map(|itr| {
+                itr.filter_map(|(chain, validators)| {
+                    let domain = base
+                        .lookup_domain(&chain)
+                        .context("Missing configuration for a chain in `watchtowerValidators`")
+                        .into_config_result(|| validators.cwp.clone())
+                        .take_config_err(&mut err)?;
+                    if !relay_chains.contains(&domain) {
+                        err.push(
+                            validators.cwp.clone(),
+                            eyre!("Watchtower chain `{chain}` is not one of the `relayChains`"),
+                        );
+                        return None;
+                    }
+                    let validators = parse_validators(validators).take_config_err(&mut err)?;
+                    Some((domain, validators))
+                })
+                .collect()
+            })
+            .unwrap_or_default();
+
         err.into_result(RelayerSettings {
             base,
             db,
@@ -268,14 +394,45 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             gas_payment_enforcement,
             whitelist,
             blacklist,
+            strict_ordering,
+            rate_limits,
             transaction_gas_limit,
             skip_transaction_gas_limit_for,
             allow_local_checkpoint_syncers,
+            dry_run,
             metric_app_contexts,
+            watchtower_validators,
         })
     }
 }
 
+/// Parses a list of validator addresses, given either as an array or as a
+/// comma separated string.
+fn parse_validators(p: ValueParser) -> ConfigResult<Vec<H256>> {
+    let mut err = ConfigParsingError::default();
+
+    let validators = match p.val {
+        Value::String(validators) => validators
+            .split(',')
+            .filter_map(|validator| {
+                hex_or_base58_to_h256(validator.trim())
+                    .context("Expected a valid validator address")
+                    .take_err(&mut err, || p.cwp.clone())
+            })
+            .collect(),
+        _ => p
+            .chain(&mut err)
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|validator| validator.parse_address_hash().take_config_err(&mut err))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+
+    err.into_result(validators)
+}
+
 fn parse_json_array(p: ValueParser) -> Option<(ConfigPath, Value)> {
     let mut err = ConfigParsingError::default();
 
@@ -311,3 +468,22 @@ fn parse_matching_list(p: ValueParser) -> ConfigResult<MatchingList> {
 
     err.into_result(ml)
 }
+
+/// Parses a matching list that messages are matched against before the
+/// module type of their recipient's ISM is known, so it can't have rules on it.
+fn parse_matching_list_without_ism_type(p: ValueParser) -> ConfigResult<MatchingList> {
+    let mut err = ConfigParsingError::default();
+
+    let cwp = p.cwp.clone();
+    let ml = parse_matching_list(p)
+        .take_config_err(&mut err)
+        .unwrap_or_default();
+    if ml.matches_ism_module_type() {
+        err.push(
+            cwp,
+            eyre!("Matching on `recipientIsmType` is not supported here"),
+        );
+    }
+
+    err.into_result(ml)
+}
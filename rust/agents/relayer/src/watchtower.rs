@@ -0,0 +1,450 @@
+//! The watchtower checks the checkpoints every announced validator publishes
+//! against the merkle tree the relayer builds from the origin chain's merkle
+//! tree hook insertions. Validators are trusted to only sign the canonical
+//! tree, so any signed checkpoint with a conflicting root or message id is
+//! raised as an alert, counted in the `fraudulent_checkpoints_observed` metric
+//! and persisted as evidence. Checkpoints the validator didn't sign are
+//! counted in the `invalid_checkpoint_signatures` metric instead, as they
+//! can't be held against it.
+
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use derive_new::new;
+use eyre::Result;
+use hyperlane_base::{
+    db::{CheckpointFraudEvidence, CheckpointFraudKind, HyperlaneRocksDB},
+    CheckpointSyncer, CoreMetrics, VerifiedCheckpointCache,
+};
+use hyperlane_core::{
+    Checkpoint, CheckpointWithMessageId, HyperlaneDomain, SignedCheckpointWithMessageId,
+    ValidatorAnnounce, H160, H256,
+};
+use tokio::sync::RwLock;
+use tracing::{debug, error, warn};
+
+use crate::{
+    merkle_tree::builder::MerkleTreeBuilder, msg::metadata::build_checkpoint_syncer,
+    processor::ProcessorExt,
+};
+
+/// How long to wait between rounds of checking the validators' checkpoints
+const WATCHTOWER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The most checkpoints checked per validator in a round, so that a validator
+/// with a long backlog doesn't hold up the others
+const MAX_CHECKPOINTS_PER_VALIDATOR_PER_ROUND: u32 = 100;
+
+/// Checks the checkpoints published by the validators of an origin chain
+#[derive(new)]
+pub struct Watchtower {
+    db: HyperlaneRocksDB,
+    merkle_tree_hook_address: H256,
+    prover_sync: Arc<RwLock<MerkleTreeBuilder>>,
+    validator_announce: Arc<dyn ValidatorAnnounce>,
+    /// Validators to check in addition to the announced ones
+    validators: Vec<H256>,
+    allow_local_checkpoint_syncers: bool,
+    core_metrics: Arc<CoreMetrics>,
+    verified_checkpoints: VerifiedCheckpointCache,
+    /// The next checkpoint index to check for each validator
+    #[new(default)]
+    next_indices: HashMap<H160, u32>,
+}
+
+impl Debug for Watchtower {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Watchtower {{ validators: {:?}, next_indices: {:?} }}",
+            self.validators, self.next_indices
+        )
+    }
+}
+
+#[async_trait]
+impl ProcessorExt for Watchtower {
+    /// The domain this watchtower checks checkpoints of.
+    fn domain(&self) -> &HyperlaneDomain {
+        self.db.domain()
+    }
+
+    /// One round of checking the checkpoints of every validator.
+    async fn tick(&mut self) -> Result<()> {
+        // Validators can be announced, and announce new storage locations, at
+        // any time
+        let validators = self.watched_validators().await;
+        let multisig_checkpoint_syncer = build_checkpoint_syncer(
+            self.validator_announce.as_ref(),
+            &validators,
+            self.allow_local_checkpoint_syncers,
+            self.core_metrics.clone(),
+            None,
+            self.db.domain(),
+            self.merkle_tree_hook_address,
+            self.verified_checkpoints.clone(),
+        )
+        .await?;
+        for (&validator, checkpoint_syncer) in multisig_checkpoint_syncer.checkpoint_syncers() {
+            if let Err(err) = self
+                .check_validator(validator, checkpoint_syncer.as_ref())
+                .await
+            {
+                warn!(?validator, error=?err, "Failed to check validator checkpoints");
+            }
+        }
+        tokio::time::sleep(WATCHTOWER_INTERVAL).await;
+        Ok(())
+    }
+}
+
+impl Watchtower {
+    /// Every validator announced on the origin chain, along with the
+    /// configured ones. Only the configured validators are watched if the
+    /// announced ones can't be listed.
+    async fn watched_validators(&self) -> Vec<H256> {
+        let mut validators = self.validators.clone();
+        match self.validator_announce.get_announced_validators().await {
+            Ok(announced) => validators.extend(announced),
+            Err(err) => warn!(error=?err, "Failed to get announced validators"),
+        }
+        validators.sort_unstable();
+        validators.dedup();
+        validators
+    }
+
+    /// Checks the checkpoints the validator published since the last round,
+    /// up to the highest index of the local merkle tree.
+    async fn check_validator(
+        &mut self,
+        validator: H160,
+        checkpoint_syncer: &dyn CheckpointSyncer,
+    ) -> Result<()> {
+        let Some(latest_index) = checkpoint_syncer.latest_index().await? else {
+            return Ok(());
+        };
+        let Some(highest_known_index) = self.prover_sync.read().await.count().checked_sub(1) else {
+            return Ok(());
+        };
+        // Validators are watched from the checkpoint they had published when
+        // the watchtower started, rather than from the start of their history
+        let next_index = *self.next_indices.entry(validator).or_insert(latest_index);
+        let end_index = latest_index
+            .min(highest_known_index)
+            .min(next_index.saturating_add(MAX_CHECKPOINTS_PER_VALIDATOR_PER_ROUND - 1));
+
+        for index in next_index..=end_index {
+            if let Some(signed_checkpoint) = checkpoint_syncer.fetch_checkpoint(index).await? {
+                self.check_checkpoint(validator, signed_checkpoint).await?;
+            }
+            self.next_indices.insert(validator, index + 1);
+        }
+        Ok(())
+    }
+
+    async fn check_checkpoint(
+        &self,
+        validator: H160,
+        signed_checkpoint: SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        let origin = self.domain();
+        let checkpoint = signed_checkpoint.value;
+
+        // A checkpoint that isn't signed by the validator can't be held
+        // against it, but still means its storage can't be trusted
+        match signed_checkpoint.recover() {
+            Ok(signer) if signer == validator => {}
+            recovered => {
+                error!(
+                    alert = "invalid_checkpoint_signature",
+                    %origin,
+                    ?validator,
+                    ?checkpoint,
+                    ?recovered,
+                    "Validator published a checkpoint it did not sign"
+                );
+                self.core_metrics
+                    .invalid_checkpoint_signatures()
+                    .with_label_values(&[origin.name(), &format!("{validator:#x}")])
+                    .inc();
+                return Ok(());
+            }
+        }
+
+        // A checkpoint of another merkle tree doesn't contradict this one
+        if checkpoint.merkle_tree_hook_address != self.merkle_tree_hook_address
+            || checkpoint.mailbox_domain != origin.id()
+        {
+            warn!(
+                %origin,
+                ?validator,
+                ?checkpoint,
+                "Validator published a checkpoint of another merkle tree hook"
+            );
+            return Ok(());
+        }
+
+        let Some(expected_checkpoint) = self.canonical_checkpoint(checkpoint.index).await? else {
+            debug!(
+                ?checkpoint,
+                "Local merkle tree has not reached the checkpoint index yet"
+            );
+            return Ok(());
+        };
+        let Some(kind) = checkpoint_fraud_kind(&checkpoint, &expected_checkpoint) else {
+            return Ok(());
+        };
+
+        error!(
+            alert = "fraudulent_checkpoint",
+            %origin,
+            ?validator,
+            kind = kind.as_str(),
+            ?signed_checkpoint,
+            ?expected_checkpoint,
+            "Validator signed a checkpoint that contradicts the origin chain's merkle tree"
+        );
+        self.core_metrics
+            .fraudulent_checkpoints_observed()
+            .with_label_values(&[origin.name(), &format!("{validator:#x}"), kind.as_str()])
+            .inc();
+        self.db
+            .store_checkpoint_fraud_evidence(&CheckpointFraudEvidence {
+                validator,
+                kind,
+                signed_checkpoint,
+                expected_checkpoint,
+            })?;
+        Ok(())
+    }
+
+    /// The checkpoint validators should sign at `index`, if the local merkle
+    /// tree has reached it
+    async fn canonical_checkpoint(&self, index: u32) -> Result<Option<CheckpointWithMessageId>> {
+        let prover_sync = self.prover_sync.read().await;
+        if index >= prover_sync.count() {
+            return Ok(None);
+        }
+        let proof = prover_sync.get_proof(index, index)?;
+        Ok(Some(CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                merkle_tree_hook_address: self.merkle_tree_hook_address,
+                mailbox_domain: self.domain().id(),
+                root: proof.root(),
+                index,
+            },
+            message_id: proof.leaf,
+        }))
+    }
+}
+
+/// How `checkpoint` contradicts the canonical checkpoint of the same merkle
+/// tree hook at the same index, if it does
+fn checkpoint_fraud_kind(
+    checkpoint: &CheckpointWithMessageId,
+    expected_checkpoint: &CheckpointWithMessageId,
+) -> Option<CheckpointFraudKind> {
+    if checkpoint.message_id != expected_checkpoint.message_id {
+        Some(CheckpointFraudKind::WrongMessageId)
+    } else if checkpoint.root != expected_checkpoint.root {
+        Some(CheckpointFraudKind::WrongRoot)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_base::{db::test_utils::run_test_db, test_utils::test_signer};
+    use hyperlane_core::{ChainCommunicationError, HyperlaneSigner, HyperlaneSignerExt};
+    use hyperlane_test::mocks::MockValidatorAnnounceContract;
+    use prometheus::Registry;
+
+    use super::*;
+
+    const MERKLE_TREE_HOOK: H256 = H256::repeat_byte(1);
+
+    fn watchtower(
+        db: HyperlaneRocksDB,
+        prover_sync: MerkleTreeBuilder,
+        validator_announce: MockValidatorAnnounceContract,
+        validators: Vec<H256>,
+    ) -> Watchtower {
+        Watchtower::new(
+            db,
+            MERKLE_TREE_HOOK,
+            Arc::new(RwLock::new(prover_sync)),
+            Arc::new(validator_announce),
+            validators,
+            true,
+            Arc::new(CoreMetrics::new("test", 9090, Registry::new()).unwrap()),
+            VerifiedCheckpointCache::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_watches_announced_and_configured_validators() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain(
+                    "test_watches_announced_and_configured_validators",
+                ),
+                db,
+            );
+            let validator = H256::from_low_u64_be;
+
+            let mut validator_announce = MockValidatorAnnounceContract::new();
+            validator_announce
+                .expect__get_announced_validators()
+                .returning(move || Ok(vec![validator(3), validator(1)]));
+            let announced = watchtower(
+                db.clone(),
+                MerkleTreeBuilder::new(),
+                validator_announce,
+                vec![validator(2), validator(1)],
+            );
+            assert_eq!(
+                announced.watched_validators().await,
+                vec![validator(1), validator(2), validator(3)]
+            );
+
+            // Only the configured validators are watched if the announced
+            // ones can't be listed
+            let mut validator_announce = MockValidatorAnnounceContract::new();
+            validator_announce
+                .expect__get_announced_validators()
+                .returning(|| {
+                    Err(ChainCommunicationError::from_other_str(
+                        "Listing announced validators is not supported",
+                    ))
+                });
+            let unlisted = watchtower(
+                db,
+                MerkleTreeBuilder::new(),
+                validator_announce,
+                vec![validator(2)],
+            );
+            assert_eq!(unlisted.watched_validators().await, vec![validator(2)]);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_only_conflicting_checkpoints_are_evidence() {
+        run_test_db(|db| async move {
+            let domain =
+                HyperlaneDomain::new_test_domain("test_only_conflicting_checkpoints_are_evidence");
+            let db = HyperlaneRocksDB::new(&domain, db);
+            let mut prover_sync = MerkleTreeBuilder::new();
+            prover_sync
+                .ingest_message_id(H256::repeat_byte(2))
+                .await
+                .unwrap();
+            let watchtower = watchtower(
+                db.clone(),
+                prover_sync,
+                MockValidatorAnnounceContract::new(),
+                vec![],
+            );
+
+            let validator_signer = test_signer(0);
+            let other_signer = test_signer(1);
+            let validator = validator_signer.eth_address();
+            let fraudulent_checkpoints = |kind: CheckpointFraudKind| {
+                watchtower
+                    .core_metrics
+                    .fraudulent_checkpoints_observed()
+                    .with_label_values(&[domain.name(), &format!("{validator:#x}"), kind.as_str()])
+                    .get()
+            };
+            let canonical = watchtower.canonical_checkpoint(0).await.unwrap().unwrap();
+
+            // The canonical checkpoint is fine
+            let signed = validator_signer.sign(canonical.clone()).await.unwrap();
+            watchtower
+                .check_checkpoint(validator, signed)
+                .await
+                .unwrap();
+
+            // A checkpoint the validator didn't sign is only counted
+            let mut wrong_root = canonical.clone();
+            wrong_root.checkpoint.root = H256::repeat_byte(3);
+            let forged = other_signer.sign(wrong_root.clone()).await.unwrap();
+            watchtower
+                .check_checkpoint(validator, forged)
+                .await
+                .unwrap();
+            assert_eq!(
+                watchtower
+                    .core_metrics
+                    .invalid_checkpoint_signatures()
+                    .with_label_values(&[domain.name(), &format!("{validator:#x}")])
+                    .get(),
+                1
+            );
+
+            // A checkpoint of another merkle tree hook doesn't contradict
+            // this one
+            let mut other_hook = wrong_root.clone();
+            other_hook.checkpoint.merkle_tree_hook_address = H256::repeat_byte(4);
+            let signed = validator_signer.sign(other_hook).await.unwrap();
+            watchtower
+                .check_checkpoint(validator, signed)
+                .await
+                .unwrap();
+
+            assert!(db.retrieve_checkpoint_fraud_evidence().unwrap().is_empty());
+            assert_eq!(fraudulent_checkpoints(CheckpointFraudKind::WrongRoot), 0);
+
+            // A conflicting root is evidence
+            let signed = validator_signer.sign(wrong_root).await.unwrap();
+            watchtower
+                .check_checkpoint(validator, signed.clone())
+                .await
+                .unwrap();
+            assert_eq!(
+                db.retrieve_checkpoint_fraud_evidence().unwrap(),
+                vec![CheckpointFraudEvidence {
+                    validator,
+                    kind: CheckpointFraudKind::WrongRoot,
+                    signed_checkpoint: signed,
+                    expected_checkpoint: canonical,
+                }]
+            );
+            assert_eq!(fraudulent_checkpoints(CheckpointFraudKind::WrongRoot), 1);
+        })
+        .await;
+    }
+
+    fn checkpoint(root: u64, message_id: u64) -> CheckpointWithMessageId {
+        CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                merkle_tree_hook_address: H256::from_low_u64_be(1),
+                mailbox_domain: 10,
+                root: H256::from_low_u64_be(root),
+                index: 5,
+            },
+            message_id: H256::from_low_u64_be(message_id),
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_fraud_kind() {
+        let expected = checkpoint(2, 3);
+        assert_eq!(checkpoint_fraud_kind(&checkpoint(2, 3), &expected), None);
+        assert_eq!(
+            checkpoint_fraud_kind(&checkpoint(4, 3), &expected),
+            Some(CheckpointFraudKind::WrongRoot)
+        );
+        // A different message id implies a different root
+        assert_eq!(
+            checkpoint_fraud_kind(&checkpoint(4, 4), &expected),
+            Some(CheckpointFraudKind::WrongMessageId)
+        );
+    }
+}
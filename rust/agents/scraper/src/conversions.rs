@@ -1,7 +1,7 @@
 use num_bigint::{BigInt, Sign};
 use sea_orm::prelude::BigDecimal;
 
-use hyperlane_core::{H256, U256};
+use hyperlane_core::{H256, H512, U256};
 
 // Creates a big-endian hex representation of the address
 pub fn address_to_bytes(data: &H256) -> Vec<u8> {
@@ -32,6 +32,26 @@ pub fn h256_to_bytes(data: &H256) -> Vec<u8> {
     data.as_fixed_bytes().as_slice().into()
 }
 
+// Creates a big-endian hex representation of a transaction hash. 256-bit hashes
+// are stored as 32 bytes so they match rows written before 512-bit hashes were
+// supported.
+pub fn h512_to_bytes(data: &H512) -> Vec<u8> {
+    if data.as_fixed_bytes()[..32] == [0; 32] {
+        data.as_fixed_bytes()[32..].into()
+    } else {
+        data.as_fixed_bytes().as_slice().into()
+    }
+}
+
+// Parses a transaction hash stored by `h512_to_bytes`
+pub fn bytes_to_h512(data: &[u8]) -> eyre::Result<H512> {
+    match data.len() {
+        32 => Ok(H256::from_slice(data).into()),
+        64 => Ok(H512::from_slice(data)),
+        _ => Err(eyre::eyre!("Invalid transaction hash length")),
+    }
+}
+
 pub fn u256_to_decimal(v: U256) -> BigDecimal {
     let mut buf = [0u8; 32];
     v.to_little_endian(&mut buf);
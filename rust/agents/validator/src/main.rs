@@ -11,6 +11,7 @@ use crate::validator::Validator;
 
 mod server;
 mod settings;
+mod slashing_protection;
 mod submit;
 mod validator;
 
@@ -0,0 +1,114 @@
+//! Serves the signed checkpoints of origin chains that use an HTTP checkpoint
+//! syncer, so that relayers can read them without any cloud storage.
+//!
+//! Base URL /checkpoints
+//! Routes
+//! - /:chain/index.json - The latest signed checkpoint index
+//! - /:chain/:index_with_id.json - The signed checkpoint at an index
+//! - /:chain/announcement.json - The signed announcement
+
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use derive_new::new;
+
+const CHECKPOINTS_API_BASE: &str = "/checkpoints";
+
+#[derive(new, Clone)]
+pub struct CheckpointsApi {
+    /// The directory the checkpoints of each origin chain are stored in, by
+    /// chain name
+    paths: Arc<HashMap<String, PathBuf>>,
+}
+
+/// Whether `file` is one of the files a checkpoint syncer writes, so that
+/// nothing else can be read from its directory
+fn is_checkpoint_syncer_file(file: &str) -> bool {
+    file == "index.json"
+        || file == "announcement.json"
+        || file
+            .strip_suffix("_with_id.json")
+            .map_or(false, |index| index.parse::<u32>().is_ok())
+}
+
+async fn serve_file(
+    State(paths): State<Arc<HashMap<String, PathBuf>>>,
+    Path((chain, file)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let Some(path) = paths.get(&chain) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if !is_checkpoint_syncer_file(&file) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    match tokio::fs::read(path.join(file)).await {
+        Ok(data) => ([(header::CONTENT_TYPE, "application/json")], data).into_response(),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            StatusCode::NOT_FOUND.into_response()
+        }
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
+
+impl CheckpointsApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/:chain/:file", get(serve_file))
+            .with_state(self.paths.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (CHECKPOINTS_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use hyperlane_base::{CheckpointSyncer, HttpStorage, LocalStorage};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    async fn setup_test_server(path: PathBuf) -> SocketAddr {
+        let api = CheckpointsApi::new(Arc::new(HashMap::from([("test".to_owned(), path)])));
+        let server = axum::Server::bind(&"127.0.0.1:0".parse().unwrap())
+            .serve(api.router().into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_http_storage_reads_served_checkpoints() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().to_path_buf();
+        let local = LocalStorage::new(path.clone(), None).unwrap();
+        let addr = setup_test_server(path.clone()).await;
+        let http =
+            HttpStorage::new(format!("http://{}/test", addr).parse().unwrap(), None, None).unwrap();
+
+        assert_eq!(http.latest_index().await.unwrap(), None);
+        local.write_latest_index(42).await.unwrap();
+        assert_eq!(http.latest_index().await.unwrap(), Some(42));
+        assert!(http.fetch_checkpoint(42).await.unwrap().is_none());
+        // The client is read-only
+        assert!(http.write_latest_index(43).await.is_err());
+    }
+
+    #[test]
+    fn test_only_checkpoint_syncer_files_are_served() {
+        assert!(is_checkpoint_syncer_file("index.json"));
+        assert!(is_checkpoint_syncer_file("announcement.json"));
+        assert!(is_checkpoint_syncer_file("12_with_id.json"));
+        assert!(!is_checkpoint_syncer_file("../secret.json"));
+        assert!(!is_checkpoint_syncer_file("x_with_id.json"));
+    }
+}
@@ -5,14 +5,17 @@
 //! Routes
 //! - /node - Node Info
 //!   eg. response {"node_name":"Hyperlane Validator","spec_version":"0.1.0","node_version":"0.1.0"}
-//! - /node/health - Node Health
+//! - /node/health - Node Health, the least healthy of all origin chains
 //!  eg. response 200 - healthy, 206 - partially healthy, 503 - unhealthy
+//! - /node/health/:chain - Health of a single origin chain
+//!  eg. response 200 - healthy, 206 - partially healthy, 503 - unhealthy, 404 - unknown chain
 //! - /node/services - List of Services
 //!  eg. response [{"id":"hyperlane-validator-indexer","name":"indexer","description":"indexes the messages from the origin chain mailbox","status":"up"},{"id":"hyperlane-validator-submitter","name":"submitter","description":"signs messages indexed from the indexer","status":"up"}]
 //! - /node/services/:service_id/health - Service Health
 //! eg. response 200 - healthy, 503 - unhealthy  
 
 use axum::{
+    extract::Path,
     http::StatusCode,
     response::IntoResponse,
     routing::{get, Router},
@@ -50,7 +53,7 @@ struct Service {
 
 #[derive(new)]
 pub struct EigenNodeApi {
-    origin_chain: HyperlaneDomain,
+    origin_chains: Vec<HyperlaneDomain>,
     core_metrics: Arc<CoreMetrics>,
 }
 
@@ -61,12 +64,17 @@ impl EigenNodeApi {
 
     pub fn router(&self) -> Router {
         let core_metrics_clone = self.core_metrics.clone();
-        let origin_chain = self.origin_chain.clone();
+        let origin_chains = self.origin_chains.clone();
 
         tracing::info!("Serving the EigenNodeAPI routes...");
 
         let health_route = get(move || {
-            Self::node_health_handler(origin_chain.clone(), core_metrics_clone.clone())
+            Self::node_health_handler(origin_chains.clone(), core_metrics_clone.clone())
+        });
+        let core_metrics_clone = self.core_metrics.clone();
+        let origin_chains = self.origin_chains.clone();
+        let chain_health_route = get(move |Path(chain): Path<String>| {
+            Self::chain_health_handler(chain, origin_chains.clone(), core_metrics_clone.clone())
         });
         let services_route = Router::new()
             .route("/", get(Self::node_services_handler))
@@ -74,6 +82,7 @@ impl EigenNodeApi {
 
         let node_route = Router::new()
             .route("/health", health_route)
+            .route("/health/:chain", chain_health_route)
             .nest("/services", services_route)
             .route("/", get(Self::node_info_handler));
 
@@ -89,14 +98,45 @@ impl EigenNodeApi {
         Json(node_info)
     }
 
-    /// Method to return the NodeInfo data
+    /// Method to return the health of the node, which is the health of its
+    /// least healthy origin chain
+    pub async fn node_health_handler(
+        origin_chains: Vec<HyperlaneDomain>,
+        core_metrics: Arc<CoreMetrics>,
+    ) -> impl IntoResponse {
+        origin_chains
+            .into_iter()
+            .map(|origin_chain| Self::origin_chain_health(origin_chain, &core_metrics))
+            .max_by_key(|status| match *status {
+                StatusCode::OK => 0,
+                StatusCode::PARTIAL_CONTENT => 1,
+                _ => 2,
+            })
+            .unwrap_or(StatusCode::SERVICE_UNAVAILABLE)
+    }
+
+    /// Method to return the health of a single origin chain
+    pub async fn chain_health_handler(
+        chain: String,
+        origin_chains: Vec<HyperlaneDomain>,
+        core_metrics: Arc<CoreMetrics>,
+    ) -> impl IntoResponse {
+        match origin_chains
+            .into_iter()
+            .find(|origin_chain| origin_chain.name() == chain)
+        {
+            Some(origin_chain) => Self::origin_chain_health(origin_chain, &core_metrics),
+            None => StatusCode::NOT_FOUND,
+        }
+    }
+
     /// if signed_checkpoint - observed_checkpoint <= 1 return 200 - healthy
     /// else if observed_checkpoint - signed_checkpoint <= 10 return 203 - partially healthy
     /// else return 503 - unhealthy
-    pub async fn node_health_handler(
+    fn origin_chain_health(
         origin_chain: HyperlaneDomain,
-        core_metrics: Arc<CoreMetrics>,
-    ) -> impl IntoResponse {
+        core_metrics: &CoreMetrics,
+    ) -> StatusCode {
         let checkpoint_delta = core_metrics.get_latest_checkpoint_validator_delta(origin_chain);
 
         // logic to check if the node is healthy
@@ -160,8 +200,20 @@ mod tests {
             .with_label_values(&["validator_observed", "ethereum"])
             .set(HEALTHY_OBSERVED_CHECKPOINT);
 
+        core_metrics
+            .latest_checkpoint()
+            .with_label_values(&["validator_observed", "polygon"])
+            .set(HEALTHY_OBSERVED_CHECKPOINT);
+        core_metrics
+            .latest_checkpoint()
+            .with_label_values(&["validator_processed", "polygon"])
+            .set(HEALTHY_OBSERVED_CHECKPOINT);
+
         let node_api = EigenNodeApi::new(
-            HyperlaneDomain::new_test_domain("ethereum"),
+            vec![
+                HyperlaneDomain::new_test_domain("ethereum"),
+                HyperlaneDomain::new_test_domain("polygon"),
+            ],
             Arc::clone(&core_metrics),
         );
         let app = node_api.router();
@@ -235,6 +287,31 @@ mod tests {
         assert_eq!(res.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn test_eigen_node_chain_health_api() {
+        let (client, addr, _) = setup_test_server().await;
+        let res = client
+            .get(format!("http://{}/node/health/polygon", addr))
+            .send()
+            .await
+            .expect("Failed to send request");
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = client
+            .get(format!("http://{}/node/health/ethereum", addr))
+            .send()
+            .await
+            .expect("Failed to send request");
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let res = client
+            .get(format!("http://{}/node/health/arbitrum", addr))
+            .send()
+            .await
+            .expect("Failed to send request");
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
     #[tokio::test]
     async fn test_eigen_node_services_handler() {
         let (client, addr, _) = setup_test_server().await;
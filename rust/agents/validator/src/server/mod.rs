@@ -1,19 +1,31 @@
+pub mod checkpoints;
 pub mod eigen_node;
-use std::{sync::Arc, vec};
+pub mod slashing_protection;
+use std::{collections::HashMap, path::PathBuf, sync::Arc, vec};
 
 use axum::Router;
+pub use checkpoints::CheckpointsApi;
 pub use eigen_node::EigenNodeApi;
+pub use slashing_protection::SlashingProtectionApi;
 
-use hyperlane_base::CoreMetrics;
-use hyperlane_core::HyperlaneDomain;
+use hyperlane_base::{db::HyperlaneRocksDB, CoreMetrics};
+use hyperlane_core::{HyperlaneDomain, H256};
 
 /// Returns a vector of validator-specific endpoint routes to be served.
 /// Can be extended with additional routes and feature flags to enable/disable individually.
 pub fn routes(
-    origin_chain: HyperlaneDomain,
+    origin_chains: Vec<HyperlaneDomain>,
+    signed_checkpoint_dbs: Vec<(HyperlaneRocksDB, H256)>,
+    served_checkpoint_paths: HashMap<String, PathBuf>,
     metrics: Arc<CoreMetrics>,
 ) -> Vec<(&'static str, Router)> {
-    let eigen_node_api = EigenNodeApi::new(origin_chain, metrics);
+    let eigen_node_api = EigenNodeApi::new(origin_chains, metrics);
+    let slashing_protection_api = SlashingProtectionApi::new(Arc::new(signed_checkpoint_dbs));
+    let checkpoints_api = CheckpointsApi::new(Arc::new(served_checkpoint_paths));
 
-    vec![eigen_node_api.get_route()]
+    vec![
+        eigen_node_api.get_route(),
+        slashing_protection_api.get_route(),
+        checkpoints_api.get_route(),
+    ]
 }
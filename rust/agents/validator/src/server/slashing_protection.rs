@@ -0,0 +1,44 @@
+//! Serves an export of the slashing protection database
+//!
+//! Base URL /slashing_protection
+//! Routes
+//! - / - The checkpoints signed on each origin chain, keyed by chain name, in
+//!   the same JSON format `slashingProtectionImport` expects
+
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+use derive_new::new;
+use hyperlane_base::db::HyperlaneRocksDB;
+use hyperlane_core::H256;
+
+use crate::slashing_protection::export_signed_checkpoints;
+
+const SLASHING_PROTECTION_API_BASE: &str = "/slashing_protection";
+
+#[derive(new, Clone)]
+pub struct SlashingProtectionApi {
+    /// The database and merkle tree hook address of each origin chain
+    origins: Arc<Vec<(HyperlaneRocksDB, H256)>>,
+}
+
+async fn export(State(origins): State<Arc<Vec<(HyperlaneRocksDB, H256)>>>) -> impl IntoResponse {
+    // Reading every signed checkpoint blocks on the database
+    match tokio::task::spawn_blocking(move || export_signed_checkpoints(&origins)).await {
+        Ok(Ok(signed_checkpoints)) => Json(signed_checkpoints).into_response(),
+        Ok(Err(err)) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
+
+impl SlashingProtectionApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", get(export))
+            .with_state(self.origins.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (SLASHING_PROTECTION_API_BASE, self.router())
+    }
+}
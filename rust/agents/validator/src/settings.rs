@@ -4,7 +4,11 @@
 //! and validations it defines are not applied here, we should mirror them.
 //! ANY CHANGES HERE NEED TO BE REFLECTED IN THE TYPESCRIPT SDK.
 
-use std::{collections::HashSet, path::PathBuf, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    time::Duration,
+};
 
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
 use eyre::{eyre, Context};
@@ -30,10 +34,17 @@ pub struct ValidatorSettings {
 
     /// Database path
     pub db: PathBuf,
-    /// Chain to validate messages on
-    pub origin_chain: HyperlaneDomain,
+    /// Chains to validate messages on, and how to validate each of them
+    pub origin_chains: HashMap<HyperlaneDomain, OriginChainSettings>,
     /// The validator attestation signer
     pub validator: SignerConf,
+    /// A JSON export of a slashing protection database to import on startup
+    pub slashing_protection_import: Option<PathBuf>,
+}
+
+/// Settings for validating messages on a single origin chain
+#[derive(Debug, Clone)]
+pub struct OriginChainSettings {
     /// The checkpoint syncer configuration
     pub checkpoint_syncer: CheckpointSyncerConf,
     /// The reorg_period in blocks
@@ -42,6 +53,22 @@ pub struct ValidatorSettings {
     pub interval: Duration,
 }
 
+impl OriginChainSettings {
+    /// Where the checkpoints are stored if the validator serves them over HTTP
+    pub fn served_checkpoints_path(&self) -> Option<PathBuf> {
+        fn served_path(conf: &CheckpointSyncerConf) -> Option<PathBuf> {
+            match conf {
+                CheckpointSyncerConf::Http { path, .. } => path.clone(),
+                CheckpointSyncerConf::Replicated { syncers, .. } => {
+                    syncers.iter().find_map(served_path)
+                }
+                _ => None,
+            }
+        }
+        served_path(&self.checkpoint_syncer)
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(transparent)]
 struct RawValidatorSettings(Value);
@@ -60,28 +87,50 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
 
         let origin_chain_name = p
             .chain(&mut err)
-            .get_key("originChainName")
+            .get_opt_key("originChainName")
             .parse_string()
             .end();
 
-        let origin_chain_name_set = origin_chain_name.map(|s| HashSet::from([s]));
+        let raw_origin_chains: Option<Vec<(String, ValueParser)>> = p
+            .chain(&mut err)
+            .get_opt_key("originChains")
+            .into_obj_iter()
+            .map(|v| v.collect());
+
+        // Either a single origin chain is configured at the top level, or
+        // any number of them are configured in `originChains`.
+        let origin_chain_names: Vec<String> = match (origin_chain_name, &raw_origin_chains) {
+            (Some(name), None) => vec![name.to_owned()],
+            (None, Some(raw_origin_chains)) if !raw_origin_chains.is_empty() => raw_origin_chains
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect(),
+            (Some(_), Some(_)) => {
+                err.push(
+                    cwp + "origin_chains",
+                    eyre!("Expected only one of `originChainName` and `originChains` to be set"),
+                );
+                vec![]
+            }
+            _ => {
+                err.push(
+                    cwp + "origin_chains",
+                    eyre!("Expected `originChainName` or a non-empty `originChains` to be set"),
+                );
+                vec![]
+            }
+        };
+
+        let origin_chain_name_set: HashSet<&str> =
+            origin_chain_names.iter().map(String::as_str).collect();
 
         let base: Option<Settings> = p
             .parse_from_raw_config::<Settings, RawAgentConf, Option<&HashSet<&str>>>(
-                origin_chain_name_set.as_ref(),
+                Some(&origin_chain_name_set),
                 "Expected valid base agent configuration",
             )
             .take_config_err(&mut err);
 
-        let origin_chain = if let (Some(base), Some(origin_chain_name)) = (&base, origin_chain_name)
-        {
-            base.lookup_domain(origin_chain_name)
-                .context("Missing configuration for the origin chain")
-                .take_err(&mut err, || cwp + "origin_chain_name")
-        } else {
-            None
-        };
-
         let validator = p
             .chain(&mut err)
             .get_key("validator")
@@ -96,15 +145,17 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             .get_opt_key("db")
             .parse_from_str("Expected db file path")
             .unwrap_or_else(|| {
+                let mut origin_chain_names = origin_chain_names.clone();
+                origin_chain_names.sort();
                 std::env::current_dir()
                     .unwrap()
-                    .join(format!("validator_db_{}", origin_chain_name.unwrap_or("")))
+                    .join(format!("validator_db_{}", origin_chain_names.join("_")))
             });
 
-        let checkpoint_syncer = p
+        let slashing_protection_import = p
             .chain(&mut err)
-            .get_key("checkpointSyncer")
-            .and_then(parse_checkpoint_syncer)
+            .get_opt_key("slashingProtectionImport")
+            .parse_from_str("Expected slashing protection import file path")
             .end();
 
         let interval = p
@@ -114,35 +165,82 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             .map(Duration::from_secs)
             .unwrap_or(Duration::from_secs(5));
 
-        cfg_unwrap_all!(cwp, err: [origin_chain_name]);
+        // The settings of each origin chain, parsed either from the top level or
+        // from the chain's entry in `originChains`.
+        let origin_chain_parsers: Vec<(String, ValueParser)> =
+            raw_origin_chains.unwrap_or_else(|| {
+                origin_chain_names
+                    .iter()
+                    .map(|name| (name.clone(), p.clone()))
+                    .collect()
+            });
 
-        let reorg_period = p
-            .chain(&mut err)
-            .get_key("chains")
-            .get_key(origin_chain_name)
-            .get_opt_key("blocks")
-            .get_opt_key("reorgPeriod")
-            .parse_u64()
-            .unwrap_or(1);
+        let mut origin_chains = HashMap::new();
+        for (origin_chain_name, origin_p) in origin_chain_parsers {
+            let origin_chain = base.as_ref().and_then(|base| {
+                base.lookup_domain(&origin_chain_name)
+                    .context("Missing configuration for the origin chain")
+                    .take_err(&mut err, || &origin_p.cwp + "origin_chain_name")
+            });
+
+            let checkpoint_syncer = origin_p
+                .chain(&mut err)
+                .get_key("checkpointSyncer")
+                .and_then(parse_checkpoint_syncer)
+                .end();
+
+            let interval = origin_p
+                .chain(&mut err)
+                .get_opt_key("interval")
+                .parse_u64()
+                .map(Duration::from_secs)
+                .unwrap_or(interval);
 
-        cfg_unwrap_all!(cwp, err: [base, origin_chain, validator, checkpoint_syncer]);
+            let chain_reorg_period = p
+                .chain(&mut err)
+                .get_key("chains")
+                .get_key(&origin_chain_name)
+                .get_opt_key("blocks")
+                .get_opt_key("reorgPeriod")
+                .parse_u64()
+                .unwrap_or(1);
+            let reorg_period = origin_p
+                .chain(&mut err)
+                .get_opt_key("reorgPeriod")
+                .parse_u64()
+                .unwrap_or(chain_reorg_period);
+
+            if let (Some(origin_chain), Some(checkpoint_syncer)) = (origin_chain, checkpoint_syncer)
+            {
+                origin_chains.insert(
+                    origin_chain,
+                    OriginChainSettings {
+                        checkpoint_syncer,
+                        reorg_period,
+                        interval,
+                    },
+                );
+            }
+        }
+
+        cfg_unwrap_all!(cwp, err: [base, validator]);
 
         let mut base: Settings = base;
-        // If the origin chain is an EVM chain, then we can use the validator as the signer if needed.
-        if origin_chain.domain_protocol() == HyperlaneDomainProtocol::Ethereum {
-            if let Some(origin) = base.chains.get_mut(origin_chain.name()) {
-                origin.signer.get_or_insert_with(|| validator.clone());
+        // If an origin chain is an EVM chain, then we can use the validator as the signer if needed.
+        for origin_chain in origin_chains.keys() {
+            if origin_chain.domain_protocol() == HyperlaneDomainProtocol::Ethereum {
+                if let Some(origin) = base.chains.get_mut(origin_chain.name()) {
+                    origin.signer.get_or_insert_with(|| validator.clone());
+                }
             }
         }
 
         err.into_result(Self {
             base,
             db,
-            origin_chain,
+            origin_chains,
             validator,
-            checkpoint_syncer,
-            reorg_period,
-            interval,
+            slashing_protection_import,
         })
     }
 }
@@ -188,9 +286,232 @@ fn parse_checkpoint_syncer(syncer: ValueParser) -> ConfigResult<CheckpointSyncer
                 folder,
             })
         }
+        Some("http") => {
+            let url = syncer
+                .chain(&mut err)
+                .get_key("url")
+                .parse_from_str("Expected checkpoint syncer URL")
+                .end();
+            let path = syncer
+                .chain(&mut err)
+                .get_key("path")
+                .parse_from_str("Expected checkpoint syncer file path")
+                .end();
+            cfg_unwrap_all!(&syncer.cwp, err: [url, path]);
+            err.into_result(CheckpointSyncerConf::Http {
+                url,
+                path: Some(path),
+            })
+        }
+        Some("replicated") => {
+            let replicas: Option<Vec<ValueParser>> = syncer
+                .chain(&mut err)
+                .get_key("syncers")
+                .into_array_iter()
+                .map(|replicas| replicas.collect());
+            let syncers: Option<Vec<CheckpointSyncerConf>> = replicas.map(|replicas| {
+                replicas
+                    .into_iter()
+                    .filter_map(|replica| {
+                        parse_checkpoint_syncer(replica).take_config_err(&mut err)
+                    })
+                    .collect()
+            });
+            let write_quorum = syncer
+                .chain(&mut err)
+                .get_opt_key("writeQuorum")
+                .parse_u64()
+                .end()
+                .map(|write_quorum| write_quorum as usize);
+
+            cfg_unwrap_all!(&syncer.cwp, err: [syncers]);
+            if syncers.is_empty() {
+                err.push(
+                    &syncer.cwp + "syncers",
+                    eyre!("Expected at least one replicated checkpoint syncer"),
+                );
+            }
+            let write_quorum = write_quorum.unwrap_or(syncers.len());
+            if !(1..=syncers.len()).contains(&write_quorum) {
+                err.push(
+                    &syncer.cwp + "write_quorum",
+                    eyre!("Expected a write quorum between 1 and the number of syncers"),
+                );
+            }
+            err.into_result(CheckpointSyncerConf::Replicated {
+                syncers,
+                write_quorum,
+            })
+        }
         Some(_) => {
             Err(eyre!("Unknown checkpoint syncer type")).into_config_result(|| &syncer.cwp + "type")
         }
         None => Err(err),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    /// The raw settings of a validator of `test1` and `test2`, with the given
+    /// validator specific settings. Keys are in flat case, as the settings
+    /// loader leaves them.
+    fn raw_settings(validator_settings: Value) -> RawValidatorSettings {
+        let chain = |name: &str, domain_id: u32| {
+            json!({
+                "name": name,
+                "domainid": domain_id,
+                "protocol": "ethereum",
+                "rpcurls": [{ "http": "http://127.0.0.1:8545" }],
+                "blocks": { "reorgperiod": 20 },
+                "mailbox": "0x0000000000000000000000000000000000000001",
+                "interchaingaspaymaster": "0x0000000000000000000000000000000000000002",
+                "validatorannounce": "0x0000000000000000000000000000000000000003",
+                "merkletreehook": "0x0000000000000000000000000000000000000004",
+            })
+        };
+        let mut raw = json!({
+            "chains": {
+                "test1": chain("test1", 13371),
+                "test2": chain("test2", 13372),
+            },
+            "validator": {
+                "type": "hexKey",
+                "key": "0x0000000000000000000000000000000000000000000000000000000000000001",
+            },
+            "db": "/tmp/validator_db",
+        });
+        raw.as_object_mut()
+            .unwrap()
+            .extend(validator_settings.as_object().unwrap().clone());
+        RawValidatorSettings(raw)
+    }
+
+    fn origin_chain<'a>(settings: &'a ValidatorSettings, name: &str) -> &'a OriginChainSettings {
+        let domain = settings.lookup_domain(name).unwrap();
+        &settings.origin_chains[&domain]
+    }
+
+    #[test]
+    fn test_parses_origin_chains() {
+        let settings = ValidatorSettings::from_config(
+            raw_settings(json!({
+                "interval": 10,
+                "originchains": {
+                    "test1": {
+                        "checkpointsyncer": { "type": "localStorage", "path": "/tmp/test1" },
+                        "reorgperiod": 5,
+                        "interval": 3,
+                    },
+                    "test2": {
+                        "checkpointsyncer": {
+                            "type": "s3",
+                            "bucket": "checkpoints",
+                            "region": "us-east-1",
+                        },
+                    },
+                },
+            })),
+            &ConfigPath::default(),
+        )
+        .unwrap();
+
+        assert_eq!(settings.origin_chains.len(), 2);
+
+        let test1 = origin_chain(&settings, "test1");
+        assert!(matches!(
+            &test1.checkpoint_syncer,
+            CheckpointSyncerConf::LocalStorage { path } if path == &PathBuf::from("/tmp/test1")
+        ));
+        assert_eq!(test1.reorg_period, 5);
+        assert_eq!(test1.interval, Duration::from_secs(3));
+
+        // Unset settings fall back to the chain's reorg period and the
+        // top level interval
+        let test2 = origin_chain(&settings, "test2");
+        assert!(matches!(
+            &test2.checkpoint_syncer,
+            CheckpointSyncerConf::S3 { bucket, folder: None, .. } if bucket == "checkpoints"
+        ));
+        assert_eq!(test2.reorg_period, 20);
+        assert_eq!(test2.interval, Duration::from_secs(10));
+
+        // Both origin chains can be signed for by the validator
+        assert!(settings.chains["test1"].signer.is_some());
+        assert!(settings.chains["test2"].signer.is_some());
+    }
+
+    #[test]
+    fn test_parses_single_origin_chain_name() {
+        let settings = ValidatorSettings::from_config(
+            raw_settings(json!({
+                "originchainname": "test1",
+                "checkpointsyncer": { "type": "localStorage", "path": "/tmp/test1" },
+                "reorgperiod": 7,
+            })),
+            &ConfigPath::default(),
+        )
+        .unwrap();
+
+        assert_eq!(settings.origin_chains.len(), 1);
+        let test1 = origin_chain(&settings, "test1");
+        assert!(matches!(
+            &test1.checkpoint_syncer,
+            CheckpointSyncerConf::LocalStorage { path } if path == &PathBuf::from("/tmp/test1")
+        ));
+        assert_eq!(test1.reorg_period, 7);
+        assert_eq!(test1.interval, Duration::from_secs(5));
+        // Only the origin chain is parsed
+        assert!(!settings.chains.contains_key("test2"));
+    }
+
+    #[test]
+    fn test_rejects_both_origin_chain_settings() {
+        let result = ValidatorSettings::from_config(
+            raw_settings(json!({
+                "originchainname": "test1",
+                "originchains": {
+                    "test2": {
+                        "checkpointsyncer": { "type": "localStorage", "path": "/tmp/test2" },
+                    },
+                },
+            })),
+            &ConfigPath::default(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_rejects_invalid_write_quorum() {
+        let with_write_quorum = |write_quorum: u64| {
+            ValidatorSettings::from_config(
+                raw_settings(json!({
+                    "originchainname": "test1",
+                    "checkpointsyncer": {
+                        "type": "replicated",
+                        "syncers": [
+                            { "type": "localStorage", "path": "/tmp/test1a" },
+                            { "type": "localStorage", "path": "/tmp/test1b" },
+                        ],
+                        "writequorum": write_quorum,
+                    },
+                })),
+                &ConfigPath::default(),
+            )
+        };
+
+        assert!(with_write_quorum(0).is_err());
+        assert!(with_write_quorum(3).is_err());
+        let settings = with_write_quorum(1).unwrap();
+        assert!(matches!(
+            &origin_chain(&settings, "test1").checkpoint_syncer,
+            CheckpointSyncerConf::Replicated {
+                write_quorum: 1,
+                ..
+            }
+        ));
+    }
+}
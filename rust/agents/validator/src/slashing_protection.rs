@@ -0,0 +1,66 @@
+//! The slashing protection database records every checkpoint the validator
+//! signed, so that it never signs a conflicting checkpoint at the same index.
+//! It can be exported to and imported from JSON, e.g. to move a validator to
+//! a new machine without losing its history.
+
+use std::{collections::BTreeMap, fs::File, path::Path};
+
+use eyre::{bail, ensure, Context, Result};
+use hyperlane_base::db::HyperlaneRocksDB;
+use hyperlane_core::{CheckpointWithMessageId, H256};
+
+/// The checkpoints signed on each origin chain, keyed by chain name. This is
+/// the JSON format of exports and imports.
+pub(crate) type SignedCheckpoints = BTreeMap<String, Vec<CheckpointWithMessageId>>;
+
+/// Reads signed checkpoints to import from a JSON file
+pub(crate) fn read_signed_checkpoints(path: &Path) -> Result<SignedCheckpoints> {
+    let file = File::open(path).with_context(|| {
+        format!(
+            "Failed to open slashing protection import {}",
+            path.display()
+        )
+    })?;
+    serde_json::from_reader(file).with_context(|| {
+        format!(
+            "Failed to parse slashing protection import {}",
+            path.display()
+        )
+    })
+}
+
+/// Records the imported `checkpoints` as signed in the origin chain's
+/// database. Fails if any of them conflicts with an already signed checkpoint.
+pub(crate) fn import_signed_checkpoints(
+    db: &HyperlaneRocksDB,
+    checkpoints: &[CheckpointWithMessageId],
+) -> Result<()> {
+    let origin_chain = db.domain();
+    for checkpoint in checkpoints {
+        ensure!(
+            checkpoint.mailbox_domain == origin_chain.id(),
+            "Imported checkpoint {checkpoint:?} is not for origin chain {origin_chain}"
+        );
+        if let Some(signed_checkpoint) = db.record_signed_checkpoint(checkpoint)? {
+            bail!(
+                "Imported checkpoint {checkpoint:?} conflicts with already signed checkpoint {signed_checkpoint:?}"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Exports the checkpoints signed for the merkle tree hook of each origin chain
+pub(crate) fn export_signed_checkpoints(
+    origins: &[(HyperlaneRocksDB, H256)],
+) -> Result<SignedCheckpoints> {
+    origins
+        .iter()
+        .map(|(db, merkle_tree_hook_address)| -> Result<_> {
+            Ok((
+                db.domain().name().to_owned(),
+                db.retrieve_signed_checkpoints(merkle_tree_hook_address)?,
+            ))
+        })
+        .collect()
+}
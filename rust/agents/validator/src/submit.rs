@@ -5,7 +5,7 @@ use std::vec;
 
 use hyperlane_core::rpc_clients::call_and_retry_indefinitely;
 use hyperlane_core::{ChainCommunicationError, ChainResult, MerkleTreeHook};
-use prometheus::IntGauge;
+use prometheus::{IntCounter, IntGauge};
 use tokio::time::sleep;
 use tracing::{debug, error, info};
 
@@ -267,6 +267,26 @@ impl ValidatorSubmitter {
                 );
                 continue;
             }
+
+            // Slashing protection: never sign a checkpoint that conflicts with
+            // one that was already signed, e.g. after a deep reorg or by a
+            // misconfigured second instance.
+            if let Some(signed_checkpoint) = self
+                .message_db
+                .record_signed_checkpoint(&queued_checkpoint)?
+            {
+                self.metrics.conflicting_checkpoints_refused.inc();
+                error!(
+                    ?queued_checkpoint,
+                    ?signed_checkpoint,
+                    "Refusing to sign checkpoint that conflicts with an already signed checkpoint"
+                );
+                return Err(ChainCommunicationError::CustomError(format!(
+                    "Refusing to sign checkpoint at index {} that conflicts with an already signed checkpoint",
+                    queued_checkpoint.index
+                )));
+            }
+
             let signed_checkpoint = self.signer.sign(queued_checkpoint).await?;
             self.checkpoint_syncer
                 .write_checkpoint(&signed_checkpoint)
@@ -300,6 +320,7 @@ fn tree_exceeds_checkpoint(checkpoint: &Checkpoint, tree: &IncrementalMerkle) ->
 pub(crate) struct ValidatorSubmitterMetrics {
     latest_checkpoint_observed: IntGauge,
     latest_checkpoint_processed: IntGauge,
+    conflicting_checkpoints_refused: IntCounter,
 }
 
 impl ValidatorSubmitterMetrics {
@@ -312,6 +333,9 @@ impl ValidatorSubmitterMetrics {
             latest_checkpoint_processed: metrics
                 .latest_checkpoint()
                 .with_label_values(&["validator_processed", chain_name]),
+            conflicting_checkpoints_refused: metrics
+                .conflicting_checkpoints_refused()
+                .with_label_values(&[chain_name]),
         }
     }
 }
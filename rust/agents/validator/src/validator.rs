@@ -1,4 +1,4 @@
-use std::{num::NonZeroU64, sync::Arc, time::Duration};
+use std::{num::NonZeroU64, path::PathBuf, sync::Arc, time::Duration};
 
 use crate::server as validator_server;
 use async_trait::async_trait;
@@ -26,30 +26,45 @@ use hyperlane_ethereum::{SingletonSigner, SingletonSignerHandle};
 
 use crate::{
     settings::ValidatorSettings,
+    slashing_protection::{import_signed_checkpoints, read_signed_checkpoints},
     submit::{ValidatorSubmitter, ValidatorSubmitterMetrics},
 };
 
+/// The longest to wait before retrying a failed call to an origin chain while
+/// starting up, e.g. announcing the validator
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
 /// A validator agent
 #[derive(Debug, AsRef)]
 pub struct Validator {
-    origin_chain: HyperlaneDomain,
-    origin_chain_conf: ChainConf,
     #[as_ref]
     core: HyperlaneAgentCore,
+    origin_chains: Vec<OriginChainValidator>,
+    // temporary holder until `run` is called
+    signer_instance: Option<Box<SingletonSigner>>,
+    core_metrics: Arc<CoreMetrics>,
+    agent_metrics: AgentMetrics,
+    chain_metrics: ChainMetrics,
+}
+
+/// Everything the validator needs to index and sign checkpoints for a single
+/// origin chain.
+#[derive(Debug)]
+struct OriginChainValidator {
+    origin_chain: HyperlaneDomain,
+    origin_chain_conf: ChainConf,
     db: HyperlaneRocksDB,
     merkle_tree_hook_sync: Arc<SequencedDataContractSync<MerkleTreeInsertion>>,
     mailbox: Arc<dyn Mailbox>,
     merkle_tree_hook: Arc<dyn MerkleTreeHook>,
     validator_announce: Arc<dyn ValidatorAnnounce>,
     signer: SingletonSignerHandle,
-    // temporary holder until `run` is called
-    signer_instance: Option<Box<SingletonSigner>>,
     reorg_period: u64,
     interval: Duration,
     checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    // where the checkpoints are stored if this validator serves them over HTTP
+    served_checkpoints_path: Option<PathBuf>,
     core_metrics: Arc<CoreMetrics>,
-    agent_metrics: AgentMetrics,
-    chain_metrics: ChainMetrics,
 }
 
 #[async_trait]
@@ -67,59 +82,84 @@ impl BaseAgent for Validator {
     where
         Self: Sized,
     {
+        // A single database is shared by all origin chains, each of which is
+        // namespaced by its domain.
         let db = DB::from_path(&settings.db)?;
-        let msg_db = HyperlaneRocksDB::new(&settings.origin_chain, db);
 
         // Intentionally using hyperlane_ethereum for the validator's signer
         let (signer_instance, signer) = SingletonSigner::new(settings.validator.build().await?);
 
         let core = settings.build_hyperlane_core(metrics.clone());
-        let checkpoint_syncer = settings.checkpoint_syncer.build(None).await?.into();
+        let contract_sync_metrics = Arc::new(ContractSyncMetrics::new(&metrics));
 
-        let mailbox = settings
-            .build_mailbox(&settings.origin_chain, &metrics)
-            .await?;
+        let imported_signed_checkpoints = settings
+            .slashing_protection_import
+            .as_deref()
+            .map(read_signed_checkpoints)
+            .transpose()?
+            .unwrap_or_default();
+
+        let mut origin_chains = Vec::with_capacity(settings.origin_chains.len());
+        for (origin_chain, origin_chain_settings) in &settings.origin_chains {
+            let msg_db = HyperlaneRocksDB::new(origin_chain, db.clone());
+            if let Some(checkpoints) = imported_signed_checkpoints.get(origin_chain.name()) {
+                import_signed_checkpoints(&msg_db, checkpoints)?;
+                info!(
+                    %origin_chain,
+                    count = checkpoints.len(),
+                    "Imported signed checkpoints into the slashing protection database"
+                );
+            }
 
-        let merkle_tree_hook = settings
-            .build_merkle_tree_hook(&settings.origin_chain, &metrics)
-            .await?;
+            let checkpoint_syncer = origin_chain_settings
+                .checkpoint_syncer
+                .build(None)
+                .await?
+                .into();
 
-        let validator_announce = settings
-            .build_validator_announce(&settings.origin_chain, &metrics)
-            .await?;
+            let mailbox = settings.build_mailbox(origin_chain, &metrics).await?;
 
-        let origin_chain_conf = core
-            .settings
-            .chain_setup(&settings.origin_chain)
-            .unwrap()
-            .clone();
+            let merkle_tree_hook = settings
+                .build_merkle_tree_hook(origin_chain, &metrics)
+                .await?;
 
-        let contract_sync_metrics = Arc::new(ContractSyncMetrics::new(&metrics));
+            let validator_announce = settings
+                .build_validator_announce(origin_chain, &metrics)
+                .await?;
 
-        let merkle_tree_hook_sync = settings
-            .build_merkle_tree_hook_indexer(
-                &settings.origin_chain,
-                &metrics,
-                &contract_sync_metrics,
-                Arc::new(msg_db.clone()),
-            )
-            .await?
-            .into();
+            let origin_chain_conf = core.settings.chain_setup(origin_chain).unwrap().clone();
+
+            let merkle_tree_hook_sync = settings
+                .build_merkle_tree_hook_indexer(
+                    origin_chain,
+                    &metrics,
+                    &contract_sync_metrics,
+                    Arc::new(msg_db.clone()),
+                )
+                .await?
+                .into();
+
+            origin_chains.push(OriginChainValidator {
+                origin_chain: origin_chain.clone(),
+                origin_chain_conf,
+                db: msg_db,
+                mailbox: mailbox.into(),
+                merkle_tree_hook: merkle_tree_hook.into(),
+                merkle_tree_hook_sync,
+                validator_announce: validator_announce.into(),
+                signer: signer.clone(),
+                reorg_period: origin_chain_settings.reorg_period,
+                interval: origin_chain_settings.interval,
+                checkpoint_syncer,
+                served_checkpoints_path: origin_chain_settings.served_checkpoints_path(),
+                core_metrics: metrics.clone(),
+            });
+        }
 
         Ok(Self {
-            origin_chain: settings.origin_chain,
-            origin_chain_conf,
             core,
-            db: msg_db,
-            mailbox: mailbox.into(),
-            merkle_tree_hook: merkle_tree_hook.into(),
-            merkle_tree_hook_sync,
-            validator_announce: validator_announce.into(),
-            signer,
+            origin_chains,
             signer_instance: Some(Box::new(signer_instance)),
-            reorg_period: settings.reorg_period,
-            interval: settings.interval,
-            checkpoint_syncer,
             agent_metrics,
             chain_metrics,
             core_metrics: metrics,
@@ -131,8 +171,24 @@ impl BaseAgent for Validator {
         let mut tasks = vec![];
 
         // run server
-        let custom_routes =
-            validator_server::routes(self.origin_chain.clone(), self.core.metrics.clone());
+        let custom_routes = validator_server::routes(
+            self.origin_chains
+                .iter()
+                .map(|origin| origin.origin_chain.clone())
+                .collect(),
+            self.origin_chains
+                .iter()
+                .map(|origin| (origin.db.clone(), origin.merkle_tree_hook.address()))
+                .collect(),
+            self.origin_chains
+                .iter()
+                .filter_map(|origin| {
+                    let path = origin.served_checkpoints_path.clone()?;
+                    Some((origin.origin_chain.name().to_owned(), path))
+                })
+                .collect(),
+            self.core.metrics.clone(),
+        );
         let server = self
             .core
             .settings
@@ -153,29 +209,52 @@ impl BaseAgent for Validator {
             );
         }
 
-        let metrics_updater = MetricsUpdater::new(
-            &self.origin_chain_conf,
-            self.core_metrics.clone(),
-            self.agent_metrics.clone(),
-            self.chain_metrics.clone(),
-            Self::AGENT_NAME.to_string(),
-        )
-        .await
-        .unwrap();
-        tasks.push(
-            tokio::spawn(async move {
-                metrics_updater.spawn().await.unwrap();
-            })
-            .instrument(info_span!("MetricsUpdater")),
-        );
+        for origin in self.origin_chains.drain(..) {
+            let metrics_updater = MetricsUpdater::new(
+                &origin.origin_chain_conf,
+                self.core_metrics.clone(),
+                self.agent_metrics.clone(),
+                self.chain_metrics.clone(),
+                Self::AGENT_NAME.to_string(),
+            )
+            .await
+            .unwrap();
+            tasks.push(
+                tokio::spawn(async move {
+                    metrics_updater.spawn().await.unwrap();
+                })
+                .instrument(info_span!("MetricsUpdater", origin=%origin.origin_chain)),
+            );
+
+            let span = info_span!("OriginChainValidator", origin=%origin.origin_chain);
+            tasks.push(tokio::spawn(origin.run()).instrument(span));
+        }
+
+        // Note that this only returns an error if one of the tasks panics
+        if let Err(err) = try_join_all(tasks).await {
+            error!(?err, "One of the validator tasks returned an error");
+        }
+    }
+}
 
-        // announce the validator after spawning the signer task
-        self.announce().await.expect("Failed to announce validator");
+impl OriginChainValidator {
+    /// Announces the validator on the origin chain and then indexes and signs
+    /// its checkpoints. Runs independently of the other origin chains so that
+    /// one misbehaving chain does not hold the others back.
+    async fn run(self) {
+        // announce the validator; the signer task has already been spawned
+        let mut backoff = self.interval;
+        while let Err(err) = self.announce().await {
+            warn!(?err, ?backoff, "Failed to announce validator, retrying");
+            sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_RETRY_BACKOFF);
+        }
 
         let reorg_period = NonZeroU64::new(self.reorg_period);
 
         // Ensure that the merkle tree hook has count > 0 before we begin indexing
         // messages or submitting checkpoints.
+        let mut tasks = vec![];
         loop {
             match self.merkle_tree_hook.count(reorg_period).await {
                 Ok(0) => {
@@ -189,24 +268,24 @@ impl BaseAgent for Validator {
                     }
                     break;
                 }
-                _ => {
-                    // Future that immediately resolves
-                    return;
+                Err(err) => {
+                    warn!(?err, "Failed to get merkle tree hook count, retrying");
+                    sleep(self.interval).await;
                 }
             }
         }
 
         // Note that this only returns an error if one of the tasks panics
         if let Err(err) = try_join_all(tasks).await {
-            error!(?err, "One of the validator tasks returned an error");
+            error!(
+                ?err,
+                "One of the origin chain validator tasks returned an error"
+            );
         }
     }
-}
 
-impl Validator {
     async fn run_merkle_tree_hook_sync(&self) -> Instrumented<JoinHandle<()>> {
-        let index_settings =
-            self.as_ref().settings.chains[self.origin_chain.name()].index_settings();
+        let index_settings = self.origin_chain_conf.index_settings();
         let contract_sync = self.merkle_tree_hook_sync.clone();
         let cursor = contract_sync
             .forward_backward_message_sync_cursor(index_settings)
@@ -225,15 +304,21 @@ impl Validator {
             self.signer.clone(),
             self.checkpoint_syncer.clone(),
             self.db.clone(),
-            ValidatorSubmitterMetrics::new(&self.core.metrics, &self.origin_chain),
+            ValidatorSubmitterMetrics::new(&self.core_metrics, &self.origin_chain),
         );
 
         let reorg_period = NonZeroU64::new(self.reorg_period);
-        let tip_tree = self
-            .merkle_tree_hook
-            .tree(reorg_period)
-            .await
-            .expect("failed to get merkle tree");
+        let mut backoff = self.interval;
+        let tip_tree = loop {
+            match self.merkle_tree_hook.tree(reorg_period).await {
+                Ok(tree) => break tree,
+                Err(err) => {
+                    warn!(?err, ?backoff, "Failed to get merkle tree, retrying");
+                    sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_RETRY_BACKOFF);
+                }
+            }
+        };
         // This function is only called after we have already checked that the
         // merkle tree hook has count > 0, but we assert to be extra sure this is
         // the case.
@@ -291,19 +376,23 @@ impl Validator {
 
     async fn announce(&self) -> Result<()> {
         let address = self.signer.eth_address();
-        let announcement_location = self.checkpoint_syncer.announcement_location();
-
-        // Sign and post the validator announcement
-        let announcement = Announcement {
-            validator: address,
-            mailbox_address: self.mailbox.address(),
-            mailbox_domain: self.mailbox.domain().id(),
-            storage_location: announcement_location.clone(),
-        };
-        let signed_announcement = self.signer.sign(announcement.clone()).await?;
-        self.checkpoint_syncer
-            .write_announcement(&signed_announcement)
-            .await?;
+
+        // Sign and post an announcement for every storage location, e.g. each
+        // replica of a replicated checkpoint syncer
+        let mut signed_announcements = vec![];
+        for announcement_location in self.checkpoint_syncer.announcement_locations() {
+            let announcement = Announcement {
+                validator: address,
+                mailbox_address: self.mailbox.address(),
+                mailbox_domain: self.mailbox.domain().id(),
+                storage_location: announcement_location,
+            };
+            let signed_announcement = self.signer.sign(announcement).await?;
+            self.checkpoint_syncer
+                .write_announcement(&signed_announcement)
+                .await?;
+            signed_announcements.push(signed_announcement);
+        }
 
         // Ensure that the validator has announced themselves before we enter
         // the main validator submit loop. This is to avoid a situation in
@@ -318,43 +407,46 @@ impl Validator {
                 .await?
                 .first()
             {
-                if locations.contains(&announcement_location) {
+                let unannounced: Vec<_> = signed_announcements
+                    .iter()
+                    .filter(|signed| !locations.contains(&signed.value.storage_location))
+                    .collect();
+                if unannounced.is_empty() {
                     info!(
                         ?locations,
-                        ?announcement_location,
-                        "Validator has announced signature storage location"
+                        "Validator has announced all signature storage locations"
                     );
                     break;
                 }
                 info!(
                     announced_locations=?locations,
-                    "Validator has not announced signature storage location"
+                    "Validator has not announced all signature storage locations"
                 );
 
-                if let Some(chain_signer) = self.core.settings.chains[self.origin_chain.name()]
-                    .chain_signer()
-                    .await?
-                {
+                if let Some(chain_signer) = self.origin_chain_conf.chain_signer().await? {
                     let chain_signer = chain_signer.address_string();
-                    info!(eth_validator_address=?announcement.validator, ?chain_signer, "Attempting self announce");
-                    let balance_delta = self
-                        .validator_announce
-                        .announce_tokens_needed(signed_announcement.clone())
-                        .await
-                        .unwrap_or_default();
-                    if balance_delta > U256::zero() {
-                        warn!(
-                            tokens_needed=%balance_delta,
-                            eth_validator_address=?announcement.validator,
-                            ?chain_signer,
-                            "Please send tokens to your chain signer address to announce",
-                        );
-                    } else {
-                        let result = self
+                    for signed_announcement in unannounced {
+                        let announcement_location = &signed_announcement.value.storage_location;
+                        info!(eth_validator_address=?address, ?chain_signer, ?announcement_location, "Attempting self announce");
+                        let balance_delta = self
                             .validator_announce
-                            .announce(signed_announcement.clone(), None)
-                            .await;
-                        Self::log_on_announce_failure(result, &chain_signer);
+                            .announce_tokens_needed(signed_announcement.clone())
+                            .await
+                            .unwrap_or_default();
+                        if balance_delta > U256::zero() {
+                            warn!(
+                                tokens_needed=%balance_delta,
+                                eth_validator_address=?address,
+                                ?chain_signer,
+                                "Please send tokens to your chain signer address to announce",
+                            );
+                        } else {
+                            let result = self
+                                .validator_announce
+                                .announce(signed_announcement.clone(), None)
+                                .await;
+                            Self::log_on_announce_failure(result, &chain_signer);
+                        }
                     }
                 } else {
                     warn!(origin_chain=%self.origin_chain, "Cannot announce validator without a signer; make sure a signer is set for the origin chain");
@@ -252,8 +252,9 @@ impl Indexer<InterchainGasPayment> for CosmosInterchainGasPaymasterIndexer {
 #[async_trait]
 impl SequenceAwareIndexer<InterchainGasPayment> for CosmosInterchainGasPaymasterIndexer {
     async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
-        // TODO: implement when cosmwasm scraper support is implemented
         let tip = self.get_finalized_block_number().await?;
+
+        // The IGP contract doesn't count payments, so they are indexed by block.
         Ok((None, tip))
     }
 }
@@ -6,6 +6,8 @@ use cosmrs::{
 };
 use derive_new::new;
 use hyperlane_core::{ChainCommunicationError, ChainResult, Error::Overflow, H256};
+use k256::ecdsa::VerifyingKey;
+use sha3::{Digest, Keccak256};
 use tendermint::account::Id as TendermintAccountId;
 use tendermint::public_key::PublicKey as TendermintPublicKey;
 
@@ -36,6 +38,18 @@ impl CosmosAddress {
         Ok(CosmosAddress::new(account_id, digest))
     }
 
+    /// Returns an Ethereum style address: the last 20 bytes of KECCAK256(pubkey),
+    /// hashing the uncompressed public key without its SEC1 tag byte. This is
+    /// how Injective derives the addresses of its eth-secp256k1 keys.
+    pub fn from_pubkey_ethereum(pubkey: PublicKey, prefix: &str) -> ChainResult<Self> {
+        let verifying_key = VerifyingKey::from_sec1_bytes(&pubkey.to_bytes())
+            .map_err(ChainCommunicationError::from_other)?;
+        let hash = Keccak256::digest(&verifying_key.to_encoded_point(false).as_bytes()[1..]);
+        let mut digest = H256::zero();
+        digest.as_bytes_mut()[12..].copy_from_slice(&hash[12..]);
+        Self::from_h256(digest, prefix, 20)
+    }
+
     /// Creates a wrapper around a cosmrs AccountId from a private key byte array
     pub fn from_privkey(priv_key: &[u8], prefix: &str) -> ChainResult<Self> {
         let pubkey = SigningKey::from_slice(priv_key)
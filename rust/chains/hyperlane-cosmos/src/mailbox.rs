@@ -93,8 +93,7 @@ impl HyperlaneChain for CosmosMailbox {
 
 impl Debug for CosmosMailbox {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        // Debug::fmt(&(self as &dyn HyperlaneContract), f)
-        todo!()
+        Debug::fmt(&(self as &dyn HyperlaneContract), f)
     }
 }
 
@@ -396,10 +395,136 @@ impl Indexer<HyperlaneMessage> for CosmosMailboxIndexer {
 }
 
 #[async_trait]
-impl Indexer<H256> for CosmosMailboxIndexer {
+impl SequenceAwareIndexer<HyperlaneMessage> for CosmosMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(&self).await?;
+
+        let sequence = self.mailbox.nonce_at_block(Some(tip.into())).await?;
+
+        Ok((Some(sequence), tip))
+    }
+}
+
+const MESSAGE_ID_ATTRIBUTE_KEY: &str = "message_id";
+static MESSAGE_ID_ATTRIBUTE_KEY_BASE64: Lazy<String> =
+    Lazy::new(|| BASE64.encode(MESSAGE_ID_ATTRIBUTE_KEY));
+
+/// Struct that retrieves delivery event data for a Cosmos Mailbox contract
+#[derive(Debug, Clone)]
+pub struct CosmosMailboxDeliveryIndexer {
+    indexer: Box<CosmosWasmIndexer>,
+}
+
+impl CosmosMailboxDeliveryIndexer {
+    /// The message process event type from the CW contract.
+    const MESSAGE_DELIVERY_EVENT_TYPE: &str = "mailbox_process_id";
+
+    /// Create a reference to a mailbox at a specific Cosmos address on some
+    /// chain
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        reorg_period: u32,
+    ) -> ChainResult<Self> {
+        let indexer = CosmosWasmIndexer::new(
+            conf,
+            locator,
+            Self::MESSAGE_DELIVERY_EVENT_TYPE.into(),
+            reorg_period,
+        )?;
+
+        Ok(Self {
+            indexer: Box::new(indexer),
+        })
+    }
+
+    #[instrument(err)]
+    fn hyperlane_delivery_parser(attrs: &Vec<EventAttribute>) -> ChainResult<ParsedEvent<H256>> {
+        let mut contract_address: Option<String> = None;
+        let mut message_id: Option<H256> = None;
+
+        for attr in attrs {
+            let key = attr.key.as_str();
+            let value = attr.value.as_str();
+
+            match key {
+                CONTRACT_ADDRESS_ATTRIBUTE_KEY => {
+                    contract_address = Some(value.to_string());
+                }
+                v if *CONTRACT_ADDRESS_ATTRIBUTE_KEY_BASE64 == v => {
+                    contract_address = Some(String::from_utf8(
+                        BASE64
+                            .decode(value)
+                            .map_err(Into::<HyperlaneCosmosError>::into)?,
+                    )?);
+                }
+
+                MESSAGE_ID_ATTRIBUTE_KEY => {
+                    message_id = Some(H256::from_slice(hex::decode(value)?.as_slice()));
+                }
+                v if *MESSAGE_ID_ATTRIBUTE_KEY_BASE64 == v => {
+                    message_id = Some(H256::from_slice(
+                        hex::decode(String::from_utf8(
+                            BASE64
+                                .decode(value)
+                                .map_err(Into::<HyperlaneCosmosError>::into)?,
+                        )?)?
+                        .as_slice(),
+                    ));
+                }
+
+                _ => {}
+            }
+        }
+
+        let contract_address = contract_address
+            .ok_or_else(|| ChainCommunicationError::from_other_str("missing contract_address"))?;
+        let message_id = message_id
+            .ok_or_else(|| ChainCommunicationError::from_other_str("missing message_id"))?;
+
+        Ok(ParsedEvent::new(contract_address, message_id))
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for CosmosMailboxDeliveryIndexer {
     async fn fetch_logs(&self, range: RangeInclusive<u32>) -> ChainResult<Vec<(H256, LogMeta)>> {
-        // TODO: implement when implementing Cosmos scraping
-        todo!()
+        let logs_futures: Vec<_> = range
+            .map(|block_number| {
+                let self_clone = self.clone();
+                tokio::spawn(async move {
+                    let logs = self_clone
+                        .indexer
+                        .get_logs_in_block(
+                            block_number,
+                            Self::hyperlane_delivery_parser,
+                            "DeliveryCursor",
+                        )
+                        .await;
+                    (logs, block_number)
+                })
+            })
+            .collect();
+
+        // TODO: this can be refactored when we rework indexing, to be part of the block-by-block indexing
+        let result = future::join_all(logs_futures)
+            .await
+            .into_iter()
+            .flatten()
+            .map(|(logs, block_number)| {
+                if let Err(err) = &logs {
+                    warn!(?err, ?block_number, "Failed to fetch logs for block");
+                }
+                logs
+            })
+            // Propagate errors from any of the queries. This will cause the entire range to be retried,
+            // including successful ones, but we don't have a way to handle partial failures in a range for now.
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        Ok(result)
     }
 
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
@@ -408,7 +533,7 @@ impl Indexer<H256> for CosmosMailboxIndexer {
 }
 
 #[async_trait]
-impl SequenceAwareIndexer<H256> for CosmosMailboxIndexer {
+impl SequenceAwareIndexer<H256> for CosmosMailboxDeliveryIndexer {
     async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
         let tip = Indexer::<H256>::get_finalized_block_number(&self).await?;
 
@@ -417,17 +542,6 @@ impl SequenceAwareIndexer<H256> for CosmosMailboxIndexer {
     }
 }
 
-#[async_trait]
-impl SequenceAwareIndexer<HyperlaneMessage> for CosmosMailboxIndexer {
-    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
-        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(&self).await?;
-
-        let sequence = self.mailbox.nonce_at_block(Some(tip.into())).await?;
-
-        Ok((Some(sequence), tip))
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use hyperlane_core::HyperlaneMessage;
@@ -463,4 +577,32 @@ mod tests {
         );
         assert_parsed_event(&base64_attrs);
     }
+
+    #[test]
+    fn test_hyperlane_delivery_parser() {
+        let expected = ParsedEvent::new(
+            "neutron1sjzzd4gwkggy6hrrs8kxxatexzcuz3jecsxm3wqgregkulzj8r7qlnuef4".into(),
+            H256::from_str("5dcf6120f8adf4f267eb1a122a85c42eae257fbc872671e93929fbf63daed19b")
+                .unwrap(),
+        );
+
+        let assert_parsed_event = |attrs: &Vec<EventAttribute>| {
+            let parsed_event =
+                CosmosMailboxDeliveryIndexer::hyperlane_delivery_parser(attrs).unwrap();
+
+            assert_eq!(parsed_event, expected);
+        };
+
+        // Non-base64 version
+        let non_base64_attrs = event_attributes_from_str(
+            r#"[{"key":"_contract_address","value":"neutron1sjzzd4gwkggy6hrrs8kxxatexzcuz3jecsxm3wqgregkulzj8r7qlnuef4","index":true},{"key":"message_id","value":"5dcf6120f8adf4f267eb1a122a85c42eae257fbc872671e93929fbf63daed19b","index":true}]"#,
+        );
+        assert_parsed_event(&non_base64_attrs);
+
+        // Base64 version
+        let base64_attrs = event_attributes_from_str(
+            r#"[{"key":"X2NvbnRyYWN0X2FkZHJlc3M=","value":"bmV1dHJvbjFzanp6ZDRnd2tnZ3k2aHJyczhreHhhdGV4emN1ejNqZWNzeG0zd3FncmVna3Vsemo4cjdxbG51ZWY0","index":true},{"key":"bWVzc2FnZV9pZA==","value":"NWRjZjYxMjBmOGFkZjRmMjY3ZWIxYTEyMmE4NWM0MmVhZTI1N2ZiYzg3MjY3MWU5MzkyOWZiZjYzZGFlZDE5Yg==","index":true}]"#,
+        );
+        assert_parsed_event(&base64_attrs);
+    }
 }
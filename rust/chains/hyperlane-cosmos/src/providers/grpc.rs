@@ -213,7 +213,8 @@ impl WasmGrpcProvider {
             TryInto::<u32>::try_into(timeout_height)
                 .map_err(ChainCommunicationError::from_other)?,
         );
-        let signer_info = SignerInfo::single_direct(Some(signer.public_key), account_info.sequence);
+        let mut signer_info = SignerInfo::single_direct(None, account_info.sequence);
+        signer_info.public_key = Some(signer.signer_public_key());
 
         let amount: u128 = (FixedPointNumber::from(gas_limit) * self.gas_price())
             .ceil_to_integer()
@@ -258,13 +259,23 @@ impl WasmGrpcProvider {
             .await?;
 
         let signer = self.get_signer()?;
-        let tx_signed = sign_doc
-            .sign(&signer.signing_key()?)
-            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let signature = signer
+            .sign(
+                &sign_doc
+                    .clone()
+                    .into_bytes()
+                    .map_err(Into::<HyperlaneCosmosError>::into)?,
+            )
+            .await?;
+        let tx_signed = TxRaw {
+            body_bytes: sign_doc.body_bytes,
+            auth_info_bytes: sign_doc.auth_info_bytes,
+            signatures: vec![signature],
+        };
         Ok((
             tx_signed
                 .to_bytes()
-                .map_err(Into::<HyperlaneCosmosError>::into)?,
+                .map_err(ChainCommunicationError::from_other)?,
             fee,
         ))
     }
@@ -1,11 +1,23 @@
+use std::str::FromStr;
+
 use async_trait::async_trait;
+use cosmrs::{
+    proto::{cosmwasm::wasm::v1::MsgExecuteContract, traits::Message},
+    tx::Tx,
+};
 use hyperlane_core::{
-    BlockInfo, ChainInfo, ChainResult, ContractLocator, HyperlaneChain, HyperlaneDomain,
-    HyperlaneProvider, TxnInfo, H256, U256,
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneDomain, HyperlaneProvider, HyperlaneProviderError, TxnInfo, TxnReceiptInfo, H256,
+    H512, U256,
 };
-use tendermint_rpc::{client::CompatMode, HttpClient};
+use tendermint::{hash::Algorithm, Hash};
+use tendermint_rpc::{client::CompatMode, Client, HttpClient};
+use tracing::instrument;
 
-use crate::{ConnectionConf, CosmosAmount, HyperlaneCosmosError, Signer};
+use crate::{
+    address::CosmosAddress, AccountAddressType, ConnectionConf, CosmosAmount, HyperlaneCosmosError,
+    Signer,
+};
 
 use self::grpc::WasmGrpcProvider;
 
@@ -14,11 +26,16 @@ pub mod grpc;
 /// cosmos rpc provider
 pub mod rpc;
 
+/// The type url of the message used to execute a CosmWasm contract.
+const MSG_EXECUTE_CONTRACT_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+
 /// Abstraction over a connection to a Cosmos chain
 #[derive(Debug, Clone)]
 pub struct CosmosProvider {
     domain: HyperlaneDomain,
     canonical_asset: String,
+    bech32_prefix: String,
+    account_address_type: AccountAddressType,
     grpc_client: WasmGrpcProvider,
     rpc_client: HttpClient,
 }
@@ -54,6 +71,8 @@ impl CosmosProvider {
             rpc_client,
             grpc_client,
             canonical_asset: conf.get_canonical_asset(),
+            bech32_prefix: conf.get_bech32_prefix(),
+            account_address_type: conf.get_account_address_type(),
         })
     }
 
@@ -66,6 +85,40 @@ impl CosmosProvider {
     pub fn rpc(&self) -> &HttpClient {
         &self.rpc_client
     }
+
+    /// The sender and contract of the first contract execution in `tx`. If
+    /// the transaction doesn't execute a contract, the sender is derived from
+    /// the first signer and there is no recipient.
+    fn sender_and_recipient(&self, tx: &Tx) -> ChainResult<(H256, Option<H256>)> {
+        let execute_msg = tx
+            .body
+            .messages
+            .iter()
+            .find(|msg| msg.type_url == MSG_EXECUTE_CONTRACT_TYPE_URL)
+            .map(|msg| MsgExecuteContract::decode(msg.value.as_slice()))
+            .transpose()
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        if let Some(msg) = execute_msg {
+            let sender = CosmosAddress::from_str(&msg.sender)?.digest();
+            let contract = CosmosAddress::from_str(&msg.contract)?.digest();
+            return Ok((sender, Some(contract)));
+        }
+
+        let public_key = tx
+            .auth_info
+            .signer_infos
+            .first()
+            .and_then(|signer_info| signer_info.public_key.as_ref())
+            .and_then(AccountAddressType::signer_info_public_key)
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str("Transaction has no single-key signer")
+            })?;
+        let sender = self
+            .account_address_type
+            .address(public_key, &self.bech32_prefix)?
+            .digest();
+        Ok((sender, None))
+    }
 }
 
 impl HyperlaneChain for CosmosProvider {
@@ -80,12 +133,96 @@ impl HyperlaneChain for CosmosProvider {
 
 #[async_trait]
 impl HyperlaneProvider for CosmosProvider {
-    async fn get_block_by_hash(&self, _hash: &H256) -> ChainResult<BlockInfo> {
-        todo!() // FIXME
+    #[instrument(err, skip(self))]
+    async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo> {
+        let tendermint_hash = Hash::from_bytes(Algorithm::Sha256, hash.as_bytes())
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let block = self
+            .rpc_client
+            .block_by_hash(tendermint_hash)
+            .await
+            .map_err(Into::<HyperlaneCosmosError>::into)?
+            .block
+            .ok_or(HyperlaneProviderError::CouldNotFindObjectByHash(*hash))?;
+
+        Ok(BlockInfo {
+            hash: *hash,
+            timestamp: block.header.time.unix_timestamp() as u64,
+            number: block.header.height.value(),
+        })
     }
 
-    async fn get_txn_by_hash(&self, _hash: &H256) -> ChainResult<TxnInfo> {
-        todo!() // FIXME
+    #[instrument(err, skip(self))]
+    async fn get_block_by_height(&self, height: u64) -> ChainResult<BlockInfo> {
+        let height: u32 = height
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?;
+        let response = self
+            .rpc_client
+            .block(height)
+            .await
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+
+        Ok(BlockInfo {
+            hash: H256::from_slice(response.block_id.hash.as_bytes()),
+            timestamp: response.block.header.time.unix_timestamp() as u64,
+            number: response.block.header.height.value(),
+        })
+    }
+
+    #[instrument(err, skip(self))]
+    async fn get_txn_by_hash(&self, hash: &H512) -> ChainResult<TxnInfo> {
+        let txn_hash = H256::from(*hash);
+        let tendermint_hash = Hash::from_bytes(Algorithm::Sha256, txn_hash.as_bytes())
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let response = self
+            .rpc_client
+            .tx(tendermint_hash, false)
+            .await
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let tx = Tx::from_bytes(&response.tx).map_err(Into::<HyperlaneCosmosError>::into)?;
+
+        let (sender, recipient) = self.sender_and_recipient(&tx)?;
+        let nonce = tx
+            .auth_info
+            .signer_infos
+            .first()
+            .map(|signer_info| signer_info.sequence)
+            .unwrap_or_default();
+
+        // Cosmos charges the whole fee up front regardless of the gas used, so
+        // the price is the fee spread over the gas limit, rounded up.
+        let fee = &tx.auth_info.fee;
+        let fee_amount = fee
+            .amount
+            .iter()
+            .find(|coin| coin.denom.as_ref() == self.canonical_asset)
+            .or_else(|| fee.amount.first())
+            .map(|coin| U256::from(coin.amount))
+            .unwrap_or_default();
+        let gas_limit = U256::from(fee.gas_limit);
+        let gas_price = if gas_limit.is_zero() {
+            U256::zero()
+        } else {
+            (fee_amount + gas_limit - 1) / gas_limit
+        };
+        let gas_used = U256::from(response.tx_result.gas_used.max(0) as u64);
+
+        Ok(TxnInfo {
+            hash: *hash,
+            gas_limit,
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_price: Some(gas_price),
+            nonce,
+            sender,
+            recipient,
+            receipt: Some(TxnReceiptInfo {
+                gas_used,
+                cumulative_gas_used: gas_used,
+                effective_gas_price: Some(gas_price),
+            }),
+        })
     }
 
     async fn is_contract(&self, _address: &H256) -> ChainResult<bool> {
@@ -1,8 +1,70 @@
-use cosmrs::crypto::{secp256k1::SigningKey, PublicKey};
-use hyperlane_core::ChainResult;
+use std::sync::Arc;
+
+use cosmrs::{
+    crypto::{secp256k1, PublicKey},
+    tx::SignerPublicKey,
+    Any,
+};
+use hyperlane_core::{ChainCommunicationError, ChainResult, KmsKey, H256};
+use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
 
 use crate::{address::CosmosAddress, HyperlaneCosmosError};
 
+/// The type URL of Injective's eth-secp256k1 public keys
+const ETH_SECP256K1_PUBKEY_TYPE_URL: &str = "/injective.crypto.v1beta1.ethsecp256k1.PubKey";
+
+/// How an account's address is derived from its secp256k1 public key, which
+/// also determines the hash function used when signing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AccountAddressType {
+    /// Cosmos SDK secp256k1 keys, with Bitcoin style addresses and SHA256
+    /// signing hashes
+    #[default]
+    Bitcoin,
+    /// eth-secp256k1 keys as used by Injective, with Ethereum style addresses
+    /// and Keccak256 signing hashes
+    Ethereum,
+}
+
+impl AccountAddressType {
+    /// The address of the account with `public_key`
+    pub fn address(&self, public_key: PublicKey, prefix: &str) -> ChainResult<CosmosAddress> {
+        match self {
+            Self::Bitcoin => CosmosAddress::from_pubkey(public_key, prefix),
+            Self::Ethereum => CosmosAddress::from_pubkey_ethereum(public_key, prefix),
+        }
+    }
+
+    /// The secp256k1 public key in a transaction's signer info, which is
+    /// wrapped in an `Any` for eth-secp256k1 keys
+    pub fn signer_info_public_key(signer_public_key: &SignerPublicKey) -> Option<PublicKey> {
+        match signer_public_key {
+            SignerPublicKey::Single(public_key) => Some(*public_key),
+            SignerPublicKey::Any(any) if any.type_url == ETH_SECP256K1_PUBKEY_TYPE_URL => {
+                // The protobuf encoding of a `PubKey { bytes key = 1; }`
+                match any.value.as_slice() {
+                    [0x0a, len, key @ ..] if *len as usize == key.len() => {
+                        PublicKey::from_raw_secp256k1(key)
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The private key of a signer
+#[derive(Clone, Debug)]
+enum SignerKey {
+    /// A private key held in memory
+    Local(Vec<u8>),
+    /// A secp256k1 key in a key management service
+    Kms(Arc<dyn KmsKey>),
+}
+
 #[derive(Clone, Debug)]
 /// Signer for cosmos chain
 pub struct Signer {
@@ -13,7 +75,9 @@ pub struct Signer {
     pub address: String,
     /// address prefix
     pub prefix: String,
-    private_key: Vec<u8>,
+    /// how the address is derived from the public key
+    pub account_address_type: AccountAddressType,
+    key: SignerKey,
 }
 
 impl Signer {
@@ -23,25 +87,201 @@ impl Signer {
     /// * `private_key` - private key for signer
     /// * `prefix` - prefix for signer address
     pub fn new(private_key: Vec<u8>, prefix: String) -> ChainResult<Self> {
-        let address = CosmosAddress::from_privkey(&private_key, &prefix)?.address();
-        let signing_key = Self::build_signing_key(&private_key)?;
-        let public_key = signing_key.public_key();
+        let public_key = secp256k1::SigningKey::from_slice(&private_key)
+            .map_err(Into::<HyperlaneCosmosError>::into)?
+            .public_key();
+        Self::with_key(public_key, prefix, SignerKey::Local(private_key))
+    }
+
+    /// create new signer for a secp256k1 key in a key management service
+    ///
+    /// # Arguments
+    /// * `kms_key` - the key in the key management service
+    /// * `prefix` - prefix for signer address
+    pub async fn new_kms(kms_key: Arc<dyn KmsKey>, prefix: String) -> ChainResult<Self> {
+        let public_key = kms_key
+            .public_key()
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        let public_key = PublicKey::from_raw_secp256k1(&public_key).ok_or_else(|| {
+            ChainCommunicationError::from_other_str("KMS key is not a secp256k1 key")
+        })?;
+        Self::with_key(public_key, prefix, SignerKey::Kms(kms_key))
+    }
+
+    fn with_key(public_key: PublicKey, prefix: String, key: SignerKey) -> ChainResult<Self> {
+        let address = CosmosAddress::from_pubkey(public_key, &prefix)?.address();
         Ok(Self {
             public_key,
-            private_key,
             address,
             prefix,
+            account_address_type: AccountAddressType::Bitcoin,
+            key,
         })
     }
 
-    /// Build a SigningKey from a private key. This cannot be
-    /// precompiled and stored in `Signer`, because `SigningKey` is not `Sync`.
-    pub fn signing_key(&self) -> ChainResult<SigningKey> {
-        Self::build_signing_key(&self.private_key)
+    /// Derives the signer's address with `account_address_type` instead
+    pub fn with_account_address_type(
+        mut self,
+        account_address_type: AccountAddressType,
+    ) -> ChainResult<Self> {
+        self.address = account_address_type
+            .address(self.public_key, &self.prefix)?
+            .address();
+        self.account_address_type = account_address_type;
+        Ok(self)
     }
 
-    fn build_signing_key(private_key: &Vec<u8>) -> ChainResult<SigningKey> {
-        Ok(SigningKey::from_slice(private_key.as_slice())
-            .map_err(Into::<HyperlaneCosmosError>::into)?)
+    /// The public key to include in transactions' signer infos
+    pub fn signer_public_key(&self) -> SignerPublicKey {
+        match self.account_address_type {
+            AccountAddressType::Bitcoin => SignerPublicKey::Single(self.public_key),
+            AccountAddressType::Ethereum => {
+                // The protobuf encoding of a `PubKey { bytes key = 1; }`
+                let key = self.public_key.to_bytes();
+                let mut value = vec![0x0a, key.len() as u8];
+                value.extend_from_slice(&key);
+                SignerPublicKey::Any(Any {
+                    type_url: ETH_SECP256K1_PUBKEY_TYPE_URL.to_owned(),
+                    value,
+                })
+            }
+        }
+    }
+
+    /// Signs `message`, e.g. the bytes of a transaction's sign doc, returning
+    /// the 64 byte signature
+    pub async fn sign(&self, message: &[u8]) -> ChainResult<Vec<u8>> {
+        let digest: [u8; 32] = match self.account_address_type {
+            AccountAddressType::Bitcoin => Sha256::digest(message).into(),
+            AccountAddressType::Ethereum => Keccak256::digest(message).into(),
+        };
+        let signature: Signature = match &self.key {
+            SignerKey::Local(private_key) => SigningKey::from_slice(private_key)
+                .and_then(|signing_key| signing_key.sign_prehash(&digest))
+                .map_err(ChainCommunicationError::from_other)?,
+            SignerKey::Kms(kms_key) => {
+                let signature = kms_key
+                    .sign_digest(&H256::from(digest))
+                    .await
+                    .map_err(ChainCommunicationError::from_other)?;
+                let signature = Signature::from_slice(&signature)
+                    .map_err(ChainCommunicationError::from_other)?;
+                // Cosmos SDK chains only accept signatures with a low `s`
+                signature.normalize_s().unwrap_or(signature)
+            }
+        };
+        Ok(signature.to_bytes().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use hyperlane_core::HyperlaneSignerError;
+    use k256::ecdsa::{signature::hazmat::PrehashVerifier, VerifyingKey};
+
+    use super::*;
+
+    const PRIVATE_KEY: &str = "a011942e70462913d8e2f26a36d487c221dc0b4ca7fc502bd3490c84f98aa0cd";
+
+    /// A local stand-in for a key management service
+    #[derive(Debug)]
+    struct LocalKms(SigningKey);
+
+    #[async_trait]
+    impl KmsKey for LocalKms {
+        async fn public_key(&self) -> Result<Vec<u8>, HyperlaneSignerError> {
+            Ok(self
+                .0
+                .verifying_key()
+                .to_encoded_point(false)
+                .as_bytes()
+                .to_vec())
+        }
+
+        async fn sign_digest(&self, digest: &H256) -> Result<Vec<u8>, HyperlaneSignerError> {
+            let signature: Signature = self
+                .0
+                .sign_prehash(digest.as_bytes())
+                .map_err(|err| HyperlaneSignerError::from(Box::new(err) as Box<_>))?;
+            // KMS doesn't normalize signatures
+            let (r, s) = signature.split_scalars();
+            Ok(Signature::from_scalars(r, -*s).unwrap().to_bytes().to_vec())
+        }
+
+        async fn sign_message(&self, _message: &[u8]) -> Result<Vec<u8>, HyperlaneSignerError> {
+            unimplemented!("secp256k1 keys sign digests")
+        }
+    }
+
+    async fn local_and_kms_signers() -> (Signer, Signer) {
+        let private_key = hex::decode(PRIVATE_KEY).unwrap();
+        let local = Signer::new(private_key.clone(), "neutron".to_owned()).unwrap();
+        let kms = Signer::new_kms(
+            Arc::new(LocalKms(SigningKey::from_slice(&private_key).unwrap())),
+            "neutron".to_owned(),
+        )
+        .await
+        .unwrap();
+        (local, kms)
+    }
+
+    #[tokio::test]
+    async fn test_kms_signer_signs_like_local_signer() {
+        let (local, kms) = local_and_kms_signers().await;
+        assert_eq!(kms.address, local.address);
+        assert_eq!(kms.public_key, local.public_key);
+
+        let message = b"sign doc";
+        let signature = kms.sign(message).await.unwrap();
+        assert_eq!(signature, local.sign(message).await.unwrap());
+        let signature = Signature::from_slice(&signature).unwrap();
+        assert!(signature.normalize_s().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_eth_secp256k1_signer() {
+        let (local, kms) = local_and_kms_signers().await;
+        let local = local
+            .with_account_address_type(AccountAddressType::Ethereum)
+            .unwrap();
+        let kms = kms
+            .with_account_address_type(AccountAddressType::Ethereum)
+            .unwrap();
+        assert_eq!(kms.address, local.address);
+        assert_ne!(
+            local.address,
+            Signer::new(hex::decode(PRIVATE_KEY).unwrap(), "neutron".to_owned())
+                .unwrap()
+                .address
+        );
+        assert!(matches!(
+            kms.signer_public_key(),
+            SignerPublicKey::Any(Any { type_url, .. }) if type_url == ETH_SECP256K1_PUBKEY_TYPE_URL
+        ));
+        // The address can be derived back from the signer info of a transaction
+        let public_key =
+            AccountAddressType::signer_info_public_key(&kms.signer_public_key()).unwrap();
+        assert_eq!(public_key, kms.public_key);
+        assert_eq!(
+            AccountAddressType::Ethereum
+                .address(public_key, "neutron")
+                .unwrap()
+                .address(),
+            kms.address
+        );
+
+        // eth-secp256k1 signatures are over the Keccak256 hash
+        let message = b"sign doc";
+        let signature = kms.sign(message).await.unwrap();
+        assert_eq!(signature, local.sign(message).await.unwrap());
+        let verifying_key = VerifyingKey::from_sec1_bytes(&kms.public_key.to_bytes()).unwrap();
+        verifying_key
+            .verify_prehash(
+                &Keccak256::digest(message),
+                &Signature::from_slice(&signature).unwrap(),
+            )
+            .unwrap();
     }
 }
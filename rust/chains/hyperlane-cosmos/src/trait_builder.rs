@@ -4,6 +4,8 @@ use derive_new::new;
 use hyperlane_core::{ChainCommunicationError, FixedPointNumber};
 use url::Url;
 
+use crate::AccountAddressType;
+
 /// Cosmos connection configuration
 #[derive(Debug, Clone)]
 pub struct ConnectionConf {
@@ -25,6 +27,9 @@ pub struct ConnectionConf {
     /// Cosmos address lengths are sometimes less than 32 bytes, so this helps to serialize it in
     /// bech32 with the appropriate length.
     contract_address_bytes: usize,
+    /// How account addresses are derived from public keys on the chain, e.g.
+    /// Ethereum style for Injective's eth-secp256k1 keys.
+    account_address_type: AccountAddressType,
 }
 
 /// Untyped cosmos amount
@@ -111,6 +116,11 @@ impl ConnectionConf {
         self.contract_address_bytes
     }
 
+    /// Get how account addresses are derived from public keys
+    pub fn get_account_address_type(&self) -> AccountAddressType {
+        self.account_address_type
+    }
+
     /// Create a new connection configuration
     pub fn new(
         grpc_urls: Vec<Url>,
@@ -120,6 +130,7 @@ impl ConnectionConf {
         canonical_asset: String,
         minimum_gas_price: RawCosmosAmount,
         contract_address_bytes: usize,
+        account_address_type: AccountAddressType,
     ) -> Self {
         Self {
             grpc_urls,
@@ -129,6 +140,7 @@ impl ConnectionConf {
             canonical_asset,
             gas_price: minimum_gas_price,
             contract_address_bytes,
+            account_address_type,
         }
     }
 }
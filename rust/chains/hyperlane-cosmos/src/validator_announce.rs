@@ -1,16 +1,23 @@
+use std::str::FromStr;
+
 use async_trait::async_trait;
 
 use cosmrs::proto::cosmos::base::abci::v1beta1::TxResponse;
 use hyperlane_core::{
-    Announcement, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
-    HyperlaneProvider, SignedType, TxOutcome, ValidatorAnnounce, H160, H256, U256,
+    Announcement, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, SignedType, TxOutcome,
+    ValidatorAnnounce, H160, H256, U256,
 };
 
 use crate::{
     grpc::WasmProvider,
-    payloads::validator_announce::{
-        self, AnnouncementRequest, AnnouncementRequestInner, GetAnnounceStorageLocationsRequest,
-        GetAnnounceStorageLocationsRequestInner,
+    payloads::{
+        general,
+        validator_announce::{
+            self, AnnouncementRequest, AnnouncementRequestInner,
+            GetAnnounceStorageLocationsRequest, GetAnnounceStorageLocationsRequestInner,
+            GetAnnouncedValidatorsRequest,
+        },
     },
     signers::Signer,
     types::tx_response_to_outcome,
@@ -92,6 +99,26 @@ impl ValidatorAnnounce for CosmosValidatorAnnounce {
             .collect())
     }
 
+    async fn get_announced_validators(&self) -> ChainResult<Vec<H256>> {
+        let payload = GetAnnouncedValidatorsRequest {
+            get_announced_validators: general::EmptyStruct {},
+        };
+
+        let data: Vec<u8> = self.provider.grpc().wasm_query(payload, None).await?;
+        let response: validator_announce::GetAnnouncedValidatorsResponse =
+            serde_json::from_slice(&data)?;
+
+        response
+            .validators
+            .iter()
+            .map(|validator| {
+                H160::from_str(validator)
+                    .map(Into::into)
+                    .map_err(ChainCommunicationError::from_other)
+            })
+            .collect()
+    }
+
     async fn announce(
         &self,
         announcement: SignedType<Announcement>,
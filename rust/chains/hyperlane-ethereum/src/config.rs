@@ -1,3 +1,6 @@
+use std::time::Duration;
+
+use hyperlane_core::U256;
 use url::Url;
 
 /// Ethereum connection configuration
@@ -24,3 +27,34 @@ pub enum ConnectionConf {
         url: Url,
     },
 }
+
+/// Settings for replacing transactions that aren't included in time with ones
+/// paying higher fees
+#[derive(Debug, Clone)]
+pub struct GasEscalationConf {
+    /// How long to wait for a transaction to be included before replacing it
+    pub escalation_interval: Duration,
+    /// How often to check whether a transaction has been included
+    pub poll_interval: Duration,
+    /// Percentage by which fees are bumped for each replacement. Nodes
+    /// usually only accept replacements that bump fees by at least 10%.
+    pub fee_bump_percent: u32,
+    /// Upper bound on the max fee per gas, or the gas price for legacy
+    /// transactions, that a replacement may pay
+    pub max_fee_per_gas: Option<U256>,
+    /// How many times a transaction is replaced before giving up on waiting
+    /// for it. It is still watched the next time the same call is sent.
+    pub max_replacements: u32,
+}
+
+impl Default for GasEscalationConf {
+    fn default() -> Self {
+        Self {
+            escalation_interval: Duration::from_secs(60),
+            poll_interval: Duration::from_secs(5),
+            fee_bump_percent: 20,
+            max_fee_per_gas: None,
+            max_replacements: 10,
+        }
+    }
+}
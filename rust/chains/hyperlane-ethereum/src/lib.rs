@@ -13,12 +13,18 @@ pub use self::{
     aggregation_ism::*, ccip_read_ism::*, config::*, config::*, interchain_gas::*,
     interchain_gas::*, interchain_security_module::*, interchain_security_module::*, mailbox::*,
     mailbox::*, merkle_tree_hook::*, multisig_ism::*, provider::*, routing_ism::*, rpc_clients::*,
-    signers::*, singleton_signer::*, trait_builder::*, validator_announce::*,
+    remote_signer::*, signers::*, singleton_signer::*, trait_builder::*, validator_announce::*,
 };
 
 #[cfg(not(doctest))]
 mod tx;
 
+#[cfg(not(doctest))]
+mod multicall;
+
+#[cfg(not(doctest))]
+mod tx_manager;
+
 /// Mailbox abi
 #[cfg(not(doctest))]
 mod mailbox;
@@ -69,6 +75,8 @@ mod contracts;
 /// Ethers JSONRPC Client implementations
 mod rpc_clients;
 
+mod remote_signer;
+
 mod signers;
 
 #[cfg(not(doctest))]
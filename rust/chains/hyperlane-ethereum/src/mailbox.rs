@@ -1,29 +1,34 @@
 #![allow(clippy::enum_variant_names)]
 #![allow(missing_docs)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroU64;
 use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use ethers::abi::AbiEncode;
-use ethers::prelude::Middleware;
-use ethers_contract::builders::ContractCall;
+use ethers::abi::{AbiEncode, Detokenize};
+use ethers::prelude::{Address, Middleware, TransactionReceipt};
+use ethers_contract::{builders::ContractCall, parse_log};
 use tracing::instrument;
 
 use hyperlane_core::{
-    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneAbi,
-    HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProtocolError,
-    HyperlaneProvider, Indexer, LogMeta, Mailbox, RawHyperlaneMessage, SequenceAwareIndexer,
-    TxCostEstimate, TxOutcome, H160, H256, U256,
+    utils::bytes_to_hex, BatchItem, BatchResult, ChainCommunicationError, ChainResult,
+    ContractLocator, HyperlaneAbi, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneInFlightTransactionStore, HyperlaneMessage, HyperlaneProtocolError, HyperlaneProvider,
+    Indexer, LogMeta, Mailbox, RawHyperlaneMessage, SequenceAwareIndexer, TxCostEstimate,
+    TxOutcome, H160, H256, U256,
 };
 
 use crate::contracts::arbitrum_node_interface::ArbitrumNodeInterface;
-use crate::contracts::i_mailbox::{IMailbox as EthereumMailboxInternal, ProcessCall, IMAILBOX_ABI};
+use crate::contracts::i_mailbox::{
+    IMailbox as EthereumMailboxInternal, ProcessCall, ProcessIdFilter, IMAILBOX_ABI,
+};
+use crate::multicall;
 use crate::trait_builder::BuildableWithProvider;
 use crate::tx::{call_with_lag, fill_tx_gas_params, report_tx};
-use crate::EthereumProvider;
+use crate::tx_manager::TransactionManager;
+use crate::{EthereumProvider, GasEscalationConf};
 
 impl<M> std::fmt::Display for EthereumMailboxInternal<M>
 where
@@ -198,7 +203,17 @@ where
     }
 }
 
-pub struct MailboxBuilder {}
+pub struct MailboxBuilder {
+    /// Address of the Multicall3 contract used to batch `process` calls.
+    /// Defaults to the canonical deployment.
+    pub batch_contract_address: Option<H256>,
+    /// Replace `process` transactions that aren't included in time with ones
+    /// paying higher fees. Transactions are sent as-is when not set.
+    pub gas_escalation: Option<GasEscalationConf>,
+    /// Where in-flight `process` transactions are persisted when gas
+    /// escalation is enabled.
+    pub tx_store: Option<Arc<dyn HyperlaneInFlightTransactionStore>>,
+}
 
 #[async_trait]
 impl BuildableWithProvider for MailboxBuilder {
@@ -209,7 +224,12 @@ impl BuildableWithProvider for MailboxBuilder {
         provider: M,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumMailbox::new(Arc::new(provider), locator))
+        let mut mailbox = EthereumMailbox::new(Arc::new(provider), locator)
+            .with_batch_contract_address(self.batch_contract_address);
+        if let Some(conf) = &self.gas_escalation {
+            mailbox = mailbox.with_gas_escalation(conf.clone(), self.tx_store.clone());
+        }
+        Box::new(mailbox)
     }
 }
 
@@ -223,6 +243,8 @@ where
     domain: HyperlaneDomain,
     provider: Arc<M>,
     arbitrum_node_interface: Option<Arc<ArbitrumNodeInterface<M>>>,
+    batch_contract_address: Option<H256>,
+    tx_manager: Option<TransactionManager<M>>,
 }
 
 impl<M> EthereumMailbox<M>
@@ -251,6 +273,39 @@ where
             domain: locator.domain.clone(),
             provider,
             arbitrum_node_interface,
+            batch_contract_address: None,
+            tx_manager: None,
+        }
+    }
+
+    /// Use a Multicall3 deployment other than the canonical one to batch
+    /// `process` calls
+    pub fn with_batch_contract_address(mut self, address: Option<H256>) -> Self {
+        self.batch_contract_address = address;
+        self
+    }
+
+    /// Send transactions through a manager that replaces them with escalating
+    /// fees until they are included
+    pub fn with_gas_escalation(
+        mut self,
+        conf: GasEscalationConf,
+        store: Option<Arc<dyn HyperlaneInFlightTransactionStore>>,
+    ) -> Self {
+        self.tx_manager = Some(TransactionManager::new(self.provider.clone(), conf, store));
+        self
+    }
+
+    /// Sends the transaction delivering the messages with the given ids and
+    /// waits for its receipt
+    async fn send_tx<D: Detokenize>(
+        &self,
+        call: ContractCall<M, D>,
+        message_ids: &[H256],
+    ) -> ChainResult<TransactionReceipt> {
+        match &self.tx_manager {
+            Some(tx_manager) => tx_manager.send(call.tx, message_ids).await,
+            None => report_tx(call).await,
         }
     }
 
@@ -337,7 +392,7 @@ where
         let contract_call = self
             .process_contract_call(message, metadata, tx_gas_limit)
             .await?;
-        let receipt = report_tx(contract_call).await?;
+        let receipt = self.send_tx(contract_call, &[message.id()]).await?;
         Ok(receipt.into())
     }
 
@@ -397,6 +452,78 @@ where
 
         AbiEncode::encode(process_call)
     }
+
+    fn supports_batching(&self) -> bool {
+        true
+    }
+
+    #[instrument(skip(self, messages), fields(batch_size = messages.len()))]
+    async fn process_batch(&self, messages: &[BatchItem]) -> ChainResult<BatchResult> {
+        let mut multicall =
+            multicall::build_multicall(self.provider.clone(), self.batch_contract_address).await?;
+        let contract_calls: Vec<_> = messages
+            .iter()
+            .map(|item| {
+                self.contract.process(
+                    item.metadata.to_vec().into(),
+                    RawHyperlaneMessage::from(&item.message).to_vec().into(),
+                )
+            })
+            .collect();
+
+        // Simulate the batch first, so messages that would revert can be left
+        // out of it and submitted on their own.
+        let simulation = multicall::batch(&mut multicall, contract_calls.clone())
+            .call()
+            .await?;
+        let failed_indexes = multicall::failed_indexes(&simulation);
+        let (batched, batched_calls): (Vec<_>, Vec<_>) = messages
+            .iter()
+            .zip(contract_calls)
+            .enumerate()
+            .filter(|(index, _)| !failed_indexes.contains(index))
+            .map(|(index, (item, call))| ((index, item.message.id()), call))
+            .unzip();
+        if batched_calls.is_empty() {
+            return Ok(BatchResult {
+                outcome: None,
+                failed_indexes,
+                reverted_indexes: vec![],
+            });
+        }
+
+        let batched_ids: Vec<_> = batched.iter().map(|(_, id)| *id).collect();
+        let batch_call = multicall::batch(&mut multicall, batched_calls);
+        let batch_call = fill_tx_gas_params(batch_call, None, self.provider.clone()).await?;
+        let receipt = self.send_tx(batch_call, &batched_ids).await?;
+
+        // Calls are allowed to fail individually, so a message was only
+        // delivered if the mailbox emitted its `ProcessId` event. The per-call
+        // results of a mined transaction aren't part of its receipt.
+        let processed_ids = processed_message_ids(&receipt, self.contract.address());
+        let reverted_indexes = batched
+            .into_iter()
+            .filter(|(_, id)| !processed_ids.contains(id))
+            .map(|(index, _)| index)
+            .collect();
+        Ok(BatchResult {
+            outcome: Some(receipt.into()),
+            failed_indexes,
+            reverted_indexes,
+        })
+    }
+}
+
+/// Ids of the messages the mailbox at `mailbox` emitted a `ProcessId` event
+/// for in the transaction with the given receipt.
+fn processed_message_ids(receipt: &TransactionReceipt, mailbox: Address) -> HashSet<H256> {
+    receipt
+        .logs
+        .iter()
+        .filter(|log| log.address == mailbox)
+        .filter_map(|log| parse_log::<ProcessIdFilter>(log.clone()).ok())
+        .map(|event| H256::from(event.message_id))
+        .collect()
 }
 
 pub struct EthereumMailboxAbi;
@@ -411,18 +538,21 @@ impl HyperlaneAbi for EthereumMailboxAbi {
 
 #[cfg(test)]
 mod test {
-    use std::{str::FromStr, sync::Arc};
+    use std::{collections::HashSet, str::FromStr, sync::Arc};
 
     use ethers::{
         providers::{MockProvider, Provider},
-        types::{Block, Transaction, U256 as EthersU256},
+        types::{Address, Block, Log, Transaction, TransactionReceipt, U256 as EthersU256},
     };
+    use ethers_contract::EthEvent;
 
     use hyperlane_core::{
         ContractLocator, HyperlaneDomain, HyperlaneMessage, KnownHyperlaneDomain, Mailbox,
         TxCostEstimate, H160, H256, U256,
     };
 
+    use super::processed_message_ids;
+    use crate::contracts::i_mailbox::ProcessIdFilter;
     use crate::EthereumMailbox;
 
     /// An amount of gas to add to the estimated gas
@@ -492,4 +622,34 @@ mod test {
             },
         );
     }
+
+    #[test]
+    fn test_processed_message_ids_only_counts_mailbox_process_events() {
+        let mailbox = Address::from_low_u64_be(1);
+        let processed = H256::from_low_u64_be(2);
+        let process_log = |address, message_id: H256| Log {
+            address,
+            topics: vec![ProcessIdFilter::signature(), message_id.into()],
+            ..Default::default()
+        };
+        let receipt = TransactionReceipt {
+            logs: vec![
+                process_log(mailbox, processed),
+                // Emitted by a different contract
+                process_log(Address::from_low_u64_be(3), H256::from_low_u64_be(4)),
+                // Not a `ProcessId` event
+                Log {
+                    address: mailbox,
+                    topics: vec![H256::from_low_u64_be(5).into()],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            processed_message_ids(&receipt, mailbox),
+            HashSet::from([processed]),
+        );
+    }
 }
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use ethers::{abi::Detokenize, providers::Middleware, types::Address};
+use ethers_contract::{builders::ContractCall, Multicall, MulticallResult};
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+
+/// Address of the canonical Multicall3 deployment, which is the same on most
+/// EVM chains.
+const MULTICALL3_ADDRESS: &str = "cA11bde05977b3631167028862bE2a173976CA11";
+
+/// Calls in a batch are allowed to fail individually, so that a single
+/// failing call doesn't revert the whole batch.
+const ALLOW_BATCH_FAILURES: bool = true;
+
+/// Builds a Multicall3 instance at the given address, defaulting to the
+/// canonical deployment.
+pub(crate) async fn build_multicall<M>(
+    provider: Arc<M>,
+    address: Option<H256>,
+) -> ChainResult<Multicall<M>>
+where
+    M: Middleware + 'static,
+{
+    let address = match address {
+        Some(address) => Address::from(address),
+        None => MULTICALL3_ADDRESS
+            .parse()
+            .expect("Multicall3 address is valid"),
+    };
+    Multicall::new(provider, Some(address))
+        .await
+        .map_err(ChainCommunicationError::from_other)
+}
+
+/// Aggregates the calls into a single Multicall3 `aggregate3` call.
+pub(crate) fn batch<M, D>(
+    multicall: &mut Multicall<M>,
+    calls: Vec<ContractCall<M, D>>,
+) -> ContractCall<M, Vec<MulticallResult>>
+where
+    M: Middleware + 'static,
+    D: Detokenize,
+{
+    // clear any calls that were in the multicall beforehand
+    multicall.clear_calls();
+    for call in calls {
+        multicall.add_call(call, ALLOW_BATCH_FAILURES);
+    }
+    multicall.as_aggregate_3_value()
+}
+
+/// Indexes of the calls that failed in a batch.
+pub(crate) fn failed_indexes(results: &[MulticallResult]) -> Vec<usize> {
+    results
+        .iter()
+        .enumerate()
+        .filter_map(|(index, result)| (!result.success).then_some(index))
+        .collect()
+}
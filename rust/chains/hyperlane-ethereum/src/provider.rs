@@ -14,6 +14,7 @@ use tracing::instrument;
 use hyperlane_core::{
     BlockInfo, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
     HyperlaneDomain, HyperlaneProvider, HyperlaneProviderError, TxnInfo, TxnReceiptInfo, H256,
+    H512,
 };
 
 use crate::BuildableWithProvider;
@@ -65,11 +66,30 @@ where
     }
 
     #[instrument(err, skip(self))]
-    async fn get_txn_by_hash(&self, hash: &H256) -> ChainResult<TxnInfo> {
-        let txn = get_with_retry_on_none(hash, |h| self.provider.get_transaction(*h)).await?;
+    async fn get_block_by_height(&self, height: u64) -> ChainResult<BlockInfo> {
+        let block = self
+            .provider
+            .get_block(height)
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .ok_or(HyperlaneProviderError::CouldNotFindBlockByHeight(height))?;
+        Ok(BlockInfo {
+            hash: block
+                .hash
+                .ok_or(HyperlaneProviderError::CouldNotFindBlockByHeight(height))?
+                .into(),
+            timestamp: block.timestamp.as_u64(),
+            number: height,
+        })
+    }
+
+    #[instrument(err, skip(self))]
+    async fn get_txn_by_hash(&self, hash: &H512) -> ChainResult<TxnInfo> {
+        let txn_hash = H256::from(*hash);
+        let txn = get_with_retry_on_none(&txn_hash, |h| self.provider.get_transaction(*h)).await?;
         let receipt = self
             .provider
-            .get_transaction_receipt(*hash)
+            .get_transaction_receipt(txn_hash)
             .await
             .map_err(ChainCommunicationError::from_other)?
             .map(|r| -> Result<_, HyperlaneProviderError> {
@@ -0,0 +1,288 @@
+use async_trait::async_trait;
+use ethers::core::k256::ecdsa::VerifyingKey;
+use ethers::prelude::{Address, Signature};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers::types::{RecoveryMessage, SignatureError};
+use ethers::utils::{keccak256, public_key_to_address};
+use ethers_signers::Signer;
+use serde::Serialize;
+use url::Url;
+
+/// The prefix of messages signed according to EIP-191
+const EIP191_PREFIX: &str = "\x19Ethereum Signed Message:\n";
+
+/// A signer using a key held by a remote signing service with a Web3Signer
+/// compatible HTTP API, so that the key is never in process memory.
+///
+/// The service signs the keccak256 hash of the data it's sent, so every kind of
+/// signature is requested by sending the data whose hash is to be signed.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    /// The client for requests to the signing service, including any auth and
+    /// timeouts it requires
+    client: reqwest::Client,
+    /// The URL of the signing service
+    url: Url,
+    /// The public key identifying the key in the signing service, as
+    /// configured
+    public_key: String,
+    address: Address,
+    chain_id: u64,
+}
+
+#[derive(Serialize)]
+struct SignRequest {
+    data: String,
+}
+
+impl RemoteSigner {
+    /// Create a signer for the key with `public_key` in the signing service at
+    /// `url`. The public key can be hex encoded in any SEC1 format, with or
+    /// without the leading tag byte.
+    pub fn new(
+        client: reqwest::Client,
+        url: Url,
+        public_key: String,
+    ) -> Result<Self, RemoteSignerError> {
+        let mut key = hex::decode(public_key.trim_start_matches("0x"))?;
+        // Web3Signer lists uncompressed public keys without the SEC1 tag byte
+        if key.len() == 64 {
+            key.insert(0, 0x04);
+        }
+        let verifying_key = VerifyingKey::from_sec1_bytes(&key)?;
+        Ok(Self {
+            client,
+            url,
+            public_key,
+            address: public_key_to_address(&verifying_key),
+            chain_id: 1,
+        })
+    }
+
+    /// Has the signing service sign the keccak256 hash of `data`, and checks
+    /// that it signed with the expected key. The signature's `v` is 27 or 28.
+    async fn sign_data(&self, data: &[u8]) -> Result<Signature, RemoteSignerError> {
+        let url = Url::parse(&format!(
+            "{}/api/v1/eth1/sign/{}",
+            self.url.as_str().trim_end_matches('/'),
+            self.public_key
+        ))?;
+        let response = self
+            .client
+            .post(url)
+            .json(&SignRequest {
+                data: format!("0x{}", hex::encode(data)),
+            })
+            .send()
+            .await?
+            .error_for_status()?;
+        let signature = hex::decode(response.text().await?.trim().trim_start_matches("0x"))?;
+        let mut signature = Signature::try_from(signature.as_slice())?;
+        if signature.v < 27 {
+            signature.v += 27;
+        }
+        signature.verify(RecoveryMessage::Hash(keccak256(data).into()), self.address)?;
+        Ok(signature)
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    type Error = RemoteSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        let message = message.as_ref();
+        let mut data = format!("{EIP191_PREFIX}{}", message.len()).into_bytes();
+        data.extend_from_slice(message);
+        self.sign_data(&data).await
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        let mut tx = tx.clone();
+        if tx.chain_id().is_none() {
+            tx.set_chain_id(self.chain_id);
+        }
+        let chain_id = tx.chain_id().map_or(self.chain_id, |id| id.as_u64());
+        let mut signature = self.sign_data(&tx.rlp()).await?;
+        // Apply EIP-155 like `LocalWallet` does; ethers normalizes `v` again
+        // when encoding typed transactions
+        signature.v = signature.v - 27 + 35 + chain_id * 2;
+        Ok(signature)
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        let domain_separator = payload
+            .domain_separator()
+            .map_err(|err| RemoteSignerError::Eip712Error(err.to_string()))?;
+        let struct_hash = payload
+            .struct_hash()
+            .map_err(|err| RemoteSignerError::Eip712Error(err.to_string()))?;
+        let mut data = vec![0x19, 0x01];
+        data.extend_from_slice(&domain_separator);
+        data.extend_from_slice(&struct_hash);
+        self.sign_data(&data).await
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+/// Error types for RemoteSigner
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteSignerError {
+    /// Error communicating with the signing service
+    #[error(transparent)]
+    HttpError(#[from] reqwest::Error),
+    /// Invalid signing service URL
+    #[error(transparent)]
+    UrlError(#[from] url::ParseError),
+    /// Invalid hex encoding of the public key or a signature
+    #[error(transparent)]
+    HexError(#[from] hex::FromHexError),
+    /// Invalid public key
+    #[error("Invalid public key: {0}")]
+    PublicKeyError(#[from] ethers::core::k256::ecdsa::Error),
+    /// Invalid signature, or a signature by a different key
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
+    /// Typed data that can't be EIP-712 encoded
+    #[error("Failed to encode typed data: {0}")]
+    Eip712Error(String),
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::SocketAddr;
+
+    use axum::{extract::State, http::HeaderMap, routing::post, Json, Router};
+    use ethers::types::{TransactionRequest, H256};
+    use ethers_signers::LocalWallet;
+    use hyperlane_core::{
+        Checkpoint, CheckpointWithMessageId, HyperlaneSigner, HyperlaneSignerExt,
+    };
+    use serde::Deserialize;
+
+    use crate::Signers;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct MockSignRequest {
+        data: String,
+    }
+
+    /// Signs like Web3Signer, as long as the bearer token is `secret`
+    async fn mock_sign(
+        State(wallet): State<LocalWallet>,
+        headers: HeaderMap,
+        Json(request): Json<MockSignRequest>,
+    ) -> Result<String, axum::http::StatusCode> {
+        if headers.get("authorization").map(|v| v.as_bytes()) != Some(b"Bearer secret") {
+            return Err(axum::http::StatusCode::UNAUTHORIZED);
+        }
+        let data = hex::decode(request.data.trim_start_matches("0x")).unwrap();
+        let signature = wallet.sign_hash(H256::from(keccak256(data))).unwrap();
+        Ok(format!("0x{}", hex::encode(signature.to_vec())))
+    }
+
+    async fn setup_mock_signer(wallet: LocalWallet) -> SocketAddr {
+        let router = Router::new()
+            .route("/api/v1/eth1/sign/:public_key", post(mock_sign))
+            .with_state(wallet);
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(router.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        addr
+    }
+
+    fn remote_signer(addr: SocketAddr, wallet: &LocalWallet, token: &str) -> RemoteSigner {
+        let public_key = wallet
+            .signer()
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()[1..]
+            .to_vec();
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            reqwest::header::AUTHORIZATION,
+            format!("Bearer {token}").parse().unwrap(),
+        );
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap();
+        RemoteSigner::new(
+            client,
+            format!("http://{addr}").parse().unwrap(),
+            format!("0x{}", hex::encode(public_key)),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer_matches_local_wallet() {
+        let wallet: LocalWallet =
+            "1111111111111111111111111111111111111111111111111111111111111111"
+                .parse()
+                .unwrap();
+        let addr = setup_mock_signer(wallet.clone()).await;
+        let signer = Signers::Remote(remote_signer(addr, &wallet, "secret"));
+        assert_eq!(Signer::address(&signer), wallet.address());
+
+        // Checkpoint signing
+        let checkpoint = CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                merkle_tree_hook_address: H256::repeat_byte(2),
+                mailbox_domain: 5,
+                root: H256::repeat_byte(1),
+                index: 123,
+            },
+            message_id: H256::repeat_byte(3),
+        };
+        let signed = signer.sign(checkpoint).await.unwrap();
+        signed.verify(signer.eth_address()).unwrap();
+
+        // Transaction signing
+        let tx: TypedTransaction = TransactionRequest::new()
+            .to(Address::repeat_byte(4))
+            .value(1)
+            .chain_id(5)
+            .into();
+        assert_eq!(
+            signer.sign_transaction(&tx).await.unwrap(),
+            wallet.sign_transaction(&tx).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer_requires_auth() {
+        let wallet: LocalWallet =
+            "1111111111111111111111111111111111111111111111111111111111111111"
+                .parse()
+                .unwrap();
+        let addr = setup_mock_signer(wallet.clone()).await;
+        let signer = remote_signer(addr, &wallet, "wrong");
+        assert!(matches!(
+            signer.sign_message(b"hello").await,
+            Err(RemoteSignerError::HttpError(_))
+        ));
+    }
+}
@@ -0,0 +1,595 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ethers::{
+    prelude::TransactionReceipt,
+    types::{transaction::eip2718::TypedTransaction, Address, BlockNumber, U256 as EthersU256},
+    utils::keccak256,
+};
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, HyperlaneInFlightTransactionStore, InFlightTransaction,
+    H256, U256,
+};
+use tokio::{
+    sync::Mutex,
+    time::{sleep, Instant},
+};
+use tracing::{debug, info, warn};
+
+use crate::{GasEscalationConf, Middleware};
+
+/// Sends transactions and watches them until they are included. The manager
+/// assigns the nonce of every transaction it sends, and replaces transactions
+/// that aren't included in time with ones paying higher fees at the same
+/// nonce.
+///
+/// Transactions are identified by their sender and the ids of the messages
+/// they deliver, rather than by their calldata, which changes whenever the
+/// metadata of a message does. Sending a transaction for a message that is
+/// still in flight, e.g. because waiting for it timed out before, resumes
+/// watching it and replaces it at the same nonce instead of sending another
+/// transaction. In-flight transactions are persisted to the store, if there is
+/// one, so this also holds across restarts.
+#[derive(Debug)]
+pub struct TransactionManager<M> {
+    provider: Arc<M>,
+    conf: GasEscalationConf,
+    store: Option<Arc<dyn HyperlaneInFlightTransactionStore>>,
+    /// In-flight transactions sent by this manager, by the key of each
+    /// message they deliver
+    in_flight: Mutex<HashMap<H256, InFlightTransaction>>,
+    /// The nonce to assign to the next transaction, if known
+    next_nonce: Mutex<Option<EthersU256>>,
+}
+
+impl<M> TransactionManager<M>
+where
+    M: Middleware + 'static,
+{
+    /// Create a new transaction manager that sends transactions with the
+    /// provider's signer.
+    pub fn new(
+        provider: Arc<M>,
+        conf: GasEscalationConf,
+        store: Option<Arc<dyn HyperlaneInFlightTransactionStore>>,
+    ) -> Self {
+        Self {
+            provider,
+            conf,
+            store,
+            in_flight: Default::default(),
+            next_nonce: Default::default(),
+        }
+    }
+
+    /// Send the transaction delivering the messages with the given ids,
+    /// replacing it with escalating fees until it is included, and return its
+    /// receipt.
+    pub async fn send(
+        &self,
+        mut tx: TypedTransaction,
+        message_ids: &[H256],
+    ) -> ChainResult<TransactionReceipt> {
+        let from = tx
+            .from()
+            .copied()
+            .or_else(|| self.provider.default_sender())
+            .ok_or(ChainCommunicationError::SignerUnavailable)?;
+        tx.set_from(from);
+        let keys: Vec<_> = message_ids
+            .iter()
+            .map(|id| transaction_key(from, id))
+            .collect();
+
+        let mut in_flight = match self.retrieve_in_flight(&keys).await {
+            Some(in_flight) => {
+                info!(?keys, ?in_flight, "Resuming in-flight transaction");
+                in_flight
+            }
+            None => {
+                let nonce = self.assign_nonce(from).await?;
+                let (max_fee_per_gas, max_priority_fee_per_gas) = self.fees(&tx).await?;
+                let mut in_flight = InFlightTransaction {
+                    nonce: nonce.into(),
+                    max_fee_per_gas: max_fee_per_gas.into(),
+                    max_priority_fee_per_gas: max_priority_fee_per_gas.map(Into::into),
+                    hashes: vec![],
+                };
+                if let Err(err) = self.send_replacement(&keys, &mut tx, &mut in_flight).await {
+                    // nothing was sent, so the nonce can be handed out again
+                    self.reset_nonce().await;
+                    return Err(err);
+                }
+                in_flight
+            }
+        };
+
+        let mut replacements = 0;
+        loop {
+            if let Some(receipt) = self.wait_for_receipt(&in_flight).await? {
+                info!(?keys, tx_hash = ?receipt.transaction_hash, "Transaction included");
+                self.resolve(&keys).await;
+                return Ok(receipt);
+            }
+
+            if self.nonce_consumed(from, &in_flight).await? {
+                // Check one last time in case the transaction was included in
+                // the meantime.
+                if let Some(receipt) = self.find_receipt(&in_flight).await? {
+                    self.resolve(&keys).await;
+                    return Ok(receipt);
+                }
+                warn!(?keys, ?in_flight, "Nonce was used by another transaction");
+                self.resolve(&keys).await;
+                self.reset_nonce().await;
+                let last_hash = in_flight.hashes.last().copied().unwrap_or_default();
+                return Err(ChainCommunicationError::TransactionDropped(last_hash));
+            }
+
+            if replacements >= self.conf.max_replacements {
+                warn!(
+                    ?keys,
+                    ?in_flight,
+                    "Transaction was not included after the max number of replacements"
+                );
+                return Err(ChainCommunicationError::TransactionTimeout());
+            }
+            replacements += 1;
+
+            if !self.escalate_fees(&mut in_flight) {
+                debug!(?keys, "Fees are already at the max, waiting for inclusion");
+                continue;
+            }
+            if let Err(err) = self.send_replacement(&keys, &mut tx, &mut in_flight).await {
+                // The previous transactions may still be included, so keep
+                // watching them.
+                warn!(?keys, error = ?err, "Failed to send replacement transaction");
+            }
+        }
+    }
+
+    /// Sends the transaction with the nonce and fees of `in_flight`, and
+    /// records its hash.
+    async fn send_replacement(
+        &self,
+        keys: &[H256],
+        tx: &mut TypedTransaction,
+        in_flight: &mut InFlightTransaction,
+    ) -> ChainResult<()> {
+        tx.set_nonce(EthersU256::from(in_flight.nonce));
+        match (&mut *tx, in_flight.max_priority_fee_per_gas) {
+            (TypedTransaction::Eip1559(request), Some(max_priority_fee_per_gas)) => {
+                request.max_fee_per_gas = Some(in_flight.max_fee_per_gas.into());
+                request.max_priority_fee_per_gas = Some(max_priority_fee_per_gas.into());
+            }
+            (tx, _) => {
+                tx.set_gas_price(EthersU256::from(in_flight.max_fee_per_gas));
+            }
+        }
+
+        let pending = self
+            .provider
+            .send_transaction(tx.clone(), None)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        let tx_hash: H256 = (*pending).into();
+        info!(?keys, ?tx_hash, ?in_flight, "Sent transaction");
+
+        in_flight.hashes.push(tx_hash);
+        self.store_in_flight(keys, in_flight).await;
+        Ok(())
+    }
+
+    /// Waits up to the escalation interval for any of the in-flight
+    /// transactions to be included.
+    async fn wait_for_receipt(
+        &self,
+        in_flight: &InFlightTransaction,
+    ) -> ChainResult<Option<TransactionReceipt>> {
+        let deadline = Instant::now() + self.conf.escalation_interval;
+        loop {
+            if let Some(receipt) = self.find_receipt(in_flight).await? {
+                return Ok(Some(receipt));
+            }
+            if Instant::now() >= deadline {
+                return Ok(None);
+            }
+            sleep(self.conf.poll_interval).await;
+        }
+    }
+
+    async fn find_receipt(
+        &self,
+        in_flight: &InFlightTransaction,
+    ) -> ChainResult<Option<TransactionReceipt>> {
+        // the latest replacement is the most likely to be included
+        for tx_hash in in_flight.hashes.iter().rev() {
+            let receipt = self
+                .provider
+                .get_transaction_receipt(*tx_hash)
+                .await
+                .map_err(ChainCommunicationError::from_other)?;
+            if receipt.is_some() {
+                return Ok(receipt);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Whether a transaction with the in-flight nonce has been included.
+    async fn nonce_consumed(
+        &self,
+        from: Address,
+        in_flight: &InFlightTransaction,
+    ) -> ChainResult<bool> {
+        let included_count = self
+            .provider
+            .get_transaction_count(from, Some(BlockNumber::Latest.into()))
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(included_count > in_flight.nonce.into())
+    }
+
+    /// Bumps the in-flight fees, capped at the configured max fee. Returns
+    /// false if the fees could not be bumped any further.
+    fn escalate_fees(&self, in_flight: &mut InFlightTransaction) -> bool {
+        let max_fee_per_gas = escalate_fee(
+            in_flight.max_fee_per_gas,
+            self.conf.fee_bump_percent,
+            self.conf.max_fee_per_gas,
+        );
+        if max_fee_per_gas == in_flight.max_fee_per_gas {
+            return false;
+        }
+        in_flight.max_fee_per_gas = max_fee_per_gas;
+        in_flight.max_priority_fee_per_gas = in_flight.max_priority_fee_per_gas.map(|fee| {
+            // the priority fee can't be higher than the max fee
+            escalate_fee(fee, self.conf.fee_bump_percent, Some(max_fee_per_gas))
+        });
+        true
+    }
+
+    /// The fees the transaction was filled with, filling in the gas price if
+    /// it wasn't.
+    async fn fees(&self, tx: &TypedTransaction) -> ChainResult<(EthersU256, Option<EthersU256>)> {
+        if let TypedTransaction::Eip1559(request) = tx {
+            if let (Some(max_fee), Some(max_priority_fee)) =
+                (request.max_fee_per_gas, request.max_priority_fee_per_gas)
+            {
+                return Ok((max_fee, Some(max_priority_fee)));
+            }
+        }
+        let gas_price = match tx.gas_price() {
+            Some(gas_price) => gas_price,
+            None => self
+                .provider
+                .get_gas_price()
+                .await
+                .map_err(ChainCommunicationError::from_other)?,
+        };
+        Ok((gas_price, None))
+    }
+
+    async fn assign_nonce(&self, from: Address) -> ChainResult<EthersU256> {
+        let mut next_nonce = self.next_nonce.lock().await;
+        let pending_count = self
+            .provider
+            .get_transaction_count(from, Some(BlockNumber::Pending.into()))
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        let nonce = next_nonce.map_or(pending_count, |next| next.max(pending_count));
+        *next_nonce = Some(nonce + 1);
+        Ok(nonce)
+    }
+
+    /// Forget the assigned nonces, so the next one is read from the chain.
+    async fn reset_nonce(&self) {
+        *self.next_nonce.lock().await = None;
+    }
+
+    /// The in-flight transaction of any of the keys. Replacing it rather than
+    /// sending a new transaction means at most one of them is included.
+    async fn retrieve_in_flight(&self, keys: &[H256]) -> Option<InFlightTransaction> {
+        let in_flight_by_key = self.in_flight.lock().await;
+        if let Some(in_flight) = keys.iter().find_map(|key| in_flight_by_key.get(key)) {
+            return Some(in_flight.clone());
+        }
+        let store = self.store.as_ref()?;
+        keys.iter()
+            .find_map(|key| match store.retrieve_in_flight_transaction(key) {
+                Ok(in_flight) => in_flight.filter(InFlightTransaction::is_pending),
+                Err(err) => {
+                    warn!(?key, error = ?err, "Failed to read in-flight transaction from store");
+                    None
+                }
+            })
+    }
+
+    async fn store_in_flight(&self, keys: &[H256], in_flight: &InFlightTransaction) {
+        let mut in_flight_by_key = self.in_flight.lock().await;
+        for key in keys {
+            in_flight_by_key.insert(*key, in_flight.clone());
+            if let Some(store) = &self.store {
+                if let Err(err) = store.store_in_flight_transaction(key, in_flight) {
+                    warn!(?key, error = ?err, "Failed to persist in-flight transaction");
+                }
+            }
+        }
+    }
+
+    /// Stop watching the transaction.
+    async fn resolve(&self, keys: &[H256]) {
+        let mut in_flight_by_key = self.in_flight.lock().await;
+        for key in keys {
+            in_flight_by_key.remove(key);
+            if let Some(store) = &self.store {
+                // an in-flight transaction without hashes is no longer pending
+                if let Err(err) = store.store_in_flight_transaction(key, &Default::default()) {
+                    warn!(?key, error = ?err, "Failed to clear in-flight transaction");
+                }
+            }
+        }
+    }
+}
+
+/// Identifies the transaction of a sender delivering a message, rather than by
+/// its hash or calldata, which change whenever it is replaced or the message's
+/// metadata changes.
+fn transaction_key(from: Address, message_id: &H256) -> H256 {
+    let mut preimage = from.as_bytes().to_vec();
+    preimage.extend_from_slice(message_id.as_bytes());
+    keccak256(preimage).into()
+}
+
+/// Bumps the fee by the given percentage, by at least 1 wei, without exceeding
+/// `max_fee`.
+fn escalate_fee(fee: U256, bump_percent: u32, max_fee: Option<U256>) -> U256 {
+    let bumped = fee.saturating_mul(U256::from(100 + bump_percent)) / U256::from(100);
+    let bumped = bumped.max(fee.saturating_add(U256::one()));
+    match max_fee {
+        Some(max_fee) => bumped.min(max_fee).max(fee),
+        None => bumped,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use ethers::{
+        prelude::{LocalWallet, Provider, SignerMiddleware},
+        providers::{Http, MockProvider},
+        signers::Signer,
+        types::{Eip1559TransactionRequest, TransactionRequest},
+        utils::Anvil,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_escalate_fee() {
+        assert_eq!(escalate_fee(100.into(), 20, None), 120.into());
+        // always bumps by at least 1 wei
+        assert_eq!(escalate_fee(1.into(), 20, None), 2.into());
+        // capped at the max fee
+        assert_eq!(escalate_fee(100.into(), 20, Some(110.into())), 110.into());
+        // never lowered to the max fee
+        assert_eq!(escalate_fee(100.into(), 20, Some(90.into())), 100.into());
+    }
+
+    #[test]
+    fn test_transaction_key_is_per_sender_and_message() {
+        let (from, message_id) = (Address::repeat_byte(1), H256::repeat_byte(2));
+        assert_eq!(
+            transaction_key(from, &message_id),
+            transaction_key(from, &message_id)
+        );
+        assert_ne!(
+            transaction_key(from, &message_id),
+            transaction_key(Address::repeat_byte(3), &message_id)
+        );
+        assert_ne!(
+            transaction_key(from, &message_id),
+            transaction_key(from, &H256::repeat_byte(3))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_transaction_is_shared_by_its_messages() {
+        let provider = Arc::new(Provider::new(MockProvider::new()));
+        let store = Arc::new(MockStore::default());
+        let manager = TransactionManager::new(provider, escalation_conf(), Some(store.clone()));
+        let from = Address::repeat_byte(1);
+        let batch = [H256::repeat_byte(2), H256::repeat_byte(3)];
+        let keys: Vec<_> = batch.iter().map(|id| transaction_key(from, id)).collect();
+        let in_flight = InFlightTransaction {
+            nonce: 7u64.into(),
+            hashes: vec![H256::repeat_byte(4)],
+            ..Default::default()
+        };
+
+        manager.store_in_flight(&keys, &in_flight).await;
+        // A later transaction for one of the messages, e.g. with new
+        // metadata, finds the batch's transaction
+        let retry_keys = [transaction_key(from, &batch[1])];
+        assert_eq!(
+            manager.retrieve_in_flight(&retry_keys).await,
+            Some(in_flight.clone())
+        );
+
+        // Also after a restart
+        let restarted = TransactionManager::new(
+            Arc::new(Provider::new(MockProvider::new())),
+            escalation_conf(),
+            Some(store),
+        );
+        assert_eq!(
+            restarted.retrieve_in_flight(&retry_keys).await,
+            Some(in_flight)
+        );
+
+        restarted.resolve(&keys).await;
+        assert_eq!(restarted.retrieve_in_flight(&retry_keys).await, None);
+    }
+
+    #[derive(Debug, Default)]
+    struct MockStore(std::sync::Mutex<HashMap<H256, InFlightTransaction>>);
+
+    impl HyperlaneInFlightTransactionStore for MockStore {
+        fn retrieve_in_flight_transaction(
+            &self,
+            key: &H256,
+        ) -> eyre::Result<Option<InFlightTransaction>> {
+            Ok(self.0.lock().unwrap().get(key).cloned())
+        }
+
+        fn store_in_flight_transaction(
+            &self,
+            key: &H256,
+            tx: &InFlightTransaction,
+        ) -> eyre::Result<()> {
+            self.0.lock().unwrap().insert(*key, tx.clone());
+            Ok(())
+        }
+    }
+
+    fn escalation_conf() -> GasEscalationConf {
+        GasEscalationConf {
+            escalation_interval: Duration::from_millis(500),
+            poll_interval: Duration::from_millis(100),
+            ..Default::default()
+        }
+    }
+
+    /// Requires `anvil` to be installed.
+    #[tokio::test]
+    #[ignore]
+    async fn test_replaces_stuck_transaction() {
+        let anvil = Anvil::new().spawn();
+        let provider = Provider::<Http>::try_from(anvil.endpoint()).unwrap();
+        let wallet: LocalWallet = anvil.keys()[0].clone().into();
+        let provider = Arc::new(SignerMiddleware::new(
+            provider,
+            wallet.with_chain_id(anvil.chain_id()),
+        ));
+        let store = Arc::new(MockStore::default());
+        let manager = Arc::new(TransactionManager::new(
+            provider.clone(),
+            escalation_conf(),
+            Some(store.clone()),
+        ));
+
+        // pause mining so the transaction gets stuck
+        provider
+            .provider()
+            .request::<_, ()>("evm_setAutomine", [false])
+            .await
+            .unwrap();
+
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(anvil.addresses()[1])
+            .value(1)
+            .gas(21000)
+            .max_fee_per_gas(2_000_000_000u64)
+            .max_priority_fee_per_gas(1_000_000_000u64)
+            .into();
+        let message_id = H256::random();
+        let key = transaction_key(anvil.addresses()[0], &message_id);
+        let send = tokio::spawn({
+            let manager = manager.clone();
+            async move { manager.send(tx, &[message_id]).await }
+        });
+
+        // wait for a couple of replacements before mining
+        sleep(Duration::from_secs(2)).await;
+        let in_flight = store.retrieve_in_flight_transaction(&key).unwrap().unwrap();
+        assert!(in_flight.hashes.len() > 1);
+        assert!(in_flight.max_fee_per_gas > U256::from(2_000_000_000u64));
+        provider
+            .provider()
+            .request::<_, ()>("evm_setAutomine", [true])
+            .await
+            .unwrap();
+        provider
+            .provider()
+            .request::<_, String>("evm_mine", ())
+            .await
+            .unwrap();
+
+        let receipt = send.await.unwrap().unwrap();
+        assert_eq!(receipt.status, Some(1.into()));
+        assert!(in_flight.hashes.contains(&receipt.transaction_hash.into()));
+        // only one of the replacements was included
+        assert_eq!(
+            provider
+                .get_transaction_count(anvil.addresses()[0], None)
+                .await
+                .unwrap(),
+            1.into()
+        );
+    }
+
+    /// Requires `anvil` to be installed.
+    #[tokio::test]
+    #[ignore]
+    async fn test_resumes_persisted_transaction() {
+        let anvil = Anvil::new().spawn();
+        let provider = Provider::<Http>::try_from(anvil.endpoint()).unwrap();
+        let wallet: LocalWallet = anvil.keys()[0].clone().into();
+        let provider = Arc::new(SignerMiddleware::new(
+            provider,
+            wallet.with_chain_id(anvil.chain_id()),
+        ));
+        let store = Arc::new(MockStore::default());
+
+        provider
+            .provider()
+            .request::<_, ()>("evm_setAutomine", [false])
+            .await
+            .unwrap();
+
+        let tx: TypedTransaction = TransactionRequest::new()
+            .to(anvil.addresses()[1])
+            .value(1)
+            .gas(21000)
+            .gas_price(2_000_000_000u64)
+            .into();
+
+        // A manager that gives up right away, like a relayer that was
+        // restarted before its transaction was included.
+        let manager = TransactionManager::new(
+            provider.clone(),
+            GasEscalationConf {
+                max_replacements: 0,
+                ..escalation_conf()
+            },
+            Some(store.clone()),
+        );
+        let message_id = H256::random();
+        assert!(matches!(
+            manager.send(tx.clone(), &[message_id]).await,
+            Err(ChainCommunicationError::TransactionTimeout())
+        ));
+
+        // A new manager resumes watching the persisted transaction rather
+        // than sending it again with a new nonce.
+        let manager = TransactionManager::new(provider.clone(), escalation_conf(), Some(store));
+        let send = tokio::spawn(async move { manager.send(tx, &[message_id]).await });
+        sleep(Duration::from_millis(200)).await;
+        provider
+            .provider()
+            .request::<_, String>("evm_mine", ())
+            .await
+            .unwrap();
+
+        let receipt = send.await.unwrap().unwrap();
+        assert_eq!(receipt.status, Some(1.into()));
+        assert_eq!(
+            provider
+                .get_transaction_count(anvil.addresses()[0], Some(BlockNumber::Pending.into()))
+                .await
+                .unwrap(),
+            1.into()
+        );
+    }
+}
@@ -134,6 +134,14 @@ where
         Ok(storage_locations)
     }
 
+    async fn get_announced_validators(&self) -> ChainResult<Vec<H256>> {
+        let validators = self.contract.get_announced_validators().call().await?;
+        Ok(validators
+            .into_iter()
+            .map(|validator| H160::from(validator.0).into())
+            .collect())
+    }
+
     #[instrument(ret, skip(self))]
     async fn announce_tokens_needed(&self, announcement: SignedType<Announcement>) -> Option<U256> {
         let validator = announcement.value.validator;
@@ -52,3 +52,55 @@ impl_h256!(
     |v| fuels::prelude::ContractId::new(v.0),
     |v| H256::from(<[u8; 32]>::from(v))
 );
+
+/// Conversion between a `HyperlaneMessage` and the `Message` struct generated
+/// for each contract's bindings.
+macro_rules! impl_message {
+    ($type:ty) => {
+        impl From<&hyperlane_core::HyperlaneMessage> for $type {
+            fn from(m: &hyperlane_core::HyperlaneMessage) -> Self {
+                Self {
+                    version: m.version,
+                    nonce: m.nonce,
+                    origin: m.origin,
+                    sender: fuels::types::Bits256(m.sender.0),
+                    destination: m.destination,
+                    recipient: fuels::types::Bits256(m.recipient.0),
+                    body: m.body.clone(),
+                }
+            }
+        }
+    };
+}
+
+impl_message!(crate::contracts::mailbox::Message);
+impl_message!(crate::contracts::interchain_security_module::Message);
+impl_message!(crate::contracts::multisig_ism::Message);
+impl_message!(crate::contracts::routing_ism::Message);
+
+/// Conversion from the `ModuleType` enum generated for each ISM contract's
+/// bindings.
+macro_rules! impl_module_type {
+    ($type:ty) => {
+        impl From<$type> for hyperlane_core::ModuleType {
+            fn from(v: $type) -> Self {
+                use hyperlane_core::ModuleType;
+                type FuelModuleType = $type;
+                match v {
+                    FuelModuleType::UNUSED => ModuleType::Unused,
+                    FuelModuleType::ROUTING => ModuleType::Routing,
+                    FuelModuleType::AGGREGATION => ModuleType::Aggregation,
+                    FuelModuleType::LEGACY_MULTISIG => ModuleType::LegacyMultisig,
+                    FuelModuleType::MERKLE_ROOT_MULTISIG => ModuleType::MerkleRootMultisig,
+                    FuelModuleType::MESSAGE_ID_MULTISIG => ModuleType::MessageIdMultisig,
+                    FuelModuleType::NULL => ModuleType::Null,
+                    FuelModuleType::CCIP_READ => ModuleType::CcipRead,
+                }
+            }
+        }
+    };
+}
+
+impl_module_type!(crate::contracts::interchain_security_module::ModuleType);
+impl_module_type!(crate::contracts::multisig_ism::ModuleType);
+impl_module_type!(crate::contracts::routing_ism::ModuleType);
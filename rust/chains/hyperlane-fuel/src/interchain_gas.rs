@@ -1,29 +1,56 @@
 use std::ops::RangeInclusive;
 
 use async_trait::async_trait;
+use fuels::prelude::{Bech32ContractId, ContractId};
+use tracing::instrument;
 
 use hyperlane_core::{
-    ChainResult, HyperlaneChain, HyperlaneContract, Indexer, InterchainGasPaymaster,
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    Indexer, InterchainGasPaymaster, SequenceAwareIndexer,
 };
 use hyperlane_core::{HyperlaneDomain, HyperlaneProvider, InterchainGasPayment, LogMeta, H256};
 
+use crate::{
+    contracts::interchain_gas_paymaster::{
+        GasPaymentEvent, InterchainGasPaymaster as FuelInterchainGasPaymasterInner,
+    },
+    conversions::*,
+    tx::read_only_wallet,
+    ConnectionConf, FuelProvider,
+};
+
 /// A reference to an IGP contract on some Fuel chain
 #[derive(Debug)]
-pub struct FuelInterchainGasPaymaster {}
+pub struct FuelInterchainGasPaymaster {
+    contract: FuelInterchainGasPaymasterInner,
+    provider: FuelProvider,
+}
+
+impl FuelInterchainGasPaymaster {
+    /// Create a new fuel IGP
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let provider = FuelProvider::new(locator.domain.clone(), conf)?;
+        let address = Bech32ContractId::from_h256(&locator.address);
+        let contract =
+            FuelInterchainGasPaymasterInner::new(address, read_only_wallet(provider.inner()));
+
+        Ok(Self { contract, provider })
+    }
+}
 
 impl HyperlaneContract for FuelInterchainGasPaymaster {
     fn address(&self) -> H256 {
-        todo!()
+        self.contract.contract_id().into_h256()
     }
 }
 
 impl HyperlaneChain for FuelInterchainGasPaymaster {
     fn domain(&self) -> &HyperlaneDomain {
-        todo!()
+        self.provider.domain()
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        Box::new(self.provider.clone())
     }
 }
 
@@ -31,18 +58,55 @@ impl InterchainGasPaymaster for FuelInterchainGasPaymaster {}
 
 /// Struct that retrieves event data for a Fuel IGP contract
 #[derive(Debug)]
-pub struct FuelInterchainGasPaymasterIndexer {}
+pub struct FuelInterchainGasPaymasterIndexer {
+    igp: FuelInterchainGasPaymaster,
+}
+
+impl FuelInterchainGasPaymasterIndexer {
+    /// Create a new fuel IGP indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            igp: FuelInterchainGasPaymaster::new(conf, locator)?,
+        })
+    }
+}
 
 #[async_trait]
 impl Indexer<InterchainGasPayment> for FuelInterchainGasPaymasterIndexer {
+    #[instrument(err, skip(self))]
     async fn fetch_logs(
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(InterchainGasPayment, LogMeta)>> {
-        todo!()
+        let decoder = self.igp.contract.log_decoder();
+        let contract_id = ContractId::from(self.igp.contract.contract_id());
+        self.igp
+            .provider
+            .index_logs_in_range(range, contract_id, |receipt| {
+                let events = decoder
+                    .get_logs_with_type::<GasPaymentEvent>(std::slice::from_ref(receipt))
+                    .map_err(ChainCommunicationError::from_other)?;
+                Ok(events.into_iter().next().map(|event| InterchainGasPayment {
+                    message_id: event.message_id.into_h256(),
+                    destination: event.destination_domain,
+                    payment: event.payment.into(),
+                    gas_amount: event.gas_amount.into(),
+                }))
+            })
+            .await
     }
 
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        todo!()
+        self.igp.provider.get_finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<InterchainGasPayment> for FuelInterchainGasPaymasterIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.get_finalized_block_number().await?;
+
+        // Gas payments are not sequenced.
+        Ok((None, tip))
     }
 }
@@ -0,0 +1,89 @@
+use async_trait::async_trait;
+use fuels::prelude::Bech32ContractId;
+use tracing::{instrument, warn};
+
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, InterchainSecurityModule, ModuleType,
+    H256, U256,
+};
+
+use crate::{
+    contracts::interchain_security_module::InterchainSecurityModule as FuelInterchainSecurityModuleInner,
+    conversions::*, tx::read_only_wallet, ConnectionConf, FuelProvider,
+};
+
+/// A reference to an InterchainSecurityModule contract on some Fuel chain
+#[derive(Debug)]
+pub struct FuelInterchainSecurityModule {
+    contract: FuelInterchainSecurityModuleInner,
+    provider: FuelProvider,
+}
+
+impl FuelInterchainSecurityModule {
+    /// Create a new fuel ISM
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let provider = FuelProvider::new(locator.domain.clone(), conf)?;
+        let address = Bech32ContractId::from_h256(&locator.address);
+        let contract =
+            FuelInterchainSecurityModuleInner::new(address, read_only_wallet(provider.inner()));
+
+        Ok(Self { contract, provider })
+    }
+}
+
+impl HyperlaneContract for FuelInterchainSecurityModule {
+    fn address(&self) -> H256 {
+        self.contract.contract_id().into_h256()
+    }
+}
+
+impl HyperlaneChain for FuelInterchainSecurityModule {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl InterchainSecurityModule for FuelInterchainSecurityModule {
+    #[instrument(err, ret, skip(self))]
+    async fn module_type(&self) -> ChainResult<ModuleType> {
+        self.contract
+            .methods()
+            .module_type()
+            .simulate()
+            .await
+            .map(|r| r.value.into())
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn dry_run_verify(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Option<U256>> {
+        let response = self
+            .contract
+            .methods()
+            .verify(metadata.to_vec(), message.into())
+            .estimate_tx_dependencies(None)
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .simulate()
+            .await;
+
+        match response {
+            Ok(r) if r.value => Ok(Some(r.gas_used.into())),
+            Ok(_) => Ok(None),
+            Err(err) => {
+                warn!(?err, "Fuel ISM verification reverted");
+                Ok(None)
+            }
+        }
+    }
+}
@@ -4,23 +4,48 @@ use std::num::NonZeroU64;
 use std::ops::RangeInclusive;
 
 use async_trait::async_trait;
-use fuels::prelude::{Bech32ContractId, WalletUnlocked};
+use fuels::{
+    core::function_selector::resolve_fn_selector,
+    prelude::{Bech32ContractId, ContractCallHandler, ContractId, WalletUnlocked},
+    tx::Receipt,
+    types::{param_types::ParamType, traits::Parameterize, Bits256, Identity},
+};
 use tracing::instrument;
 
 use hyperlane_core::{
-    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneAbi,
-    HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider,
-    Indexer, LogMeta, Mailbox, TxCostEstimate, TxOutcome, H256, U256,
+    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, Decode,
+    HyperlaneAbi, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
+    HyperlaneProvider, Indexer, LogMeta, Mailbox, SequenceAwareIndexer, TxCostEstimate, TxOutcome,
+    H256, U256,
 };
 
 use crate::{
-    contracts::mailbox::Mailbox as FuelMailboxInner, conversions::*, make_provider, ConnectionConf,
+    contracts::{
+        mailbox::{Mailbox as FuelMailboxInner, Message as FuelMessage},
+        message_recipient::MessageRecipient,
+    },
+    conversions::*,
+    tx::{estimate_call_costs, read_only_wallet, report_call},
+    ConnectionConf, FuelProvider,
 };
 
+/// The log id the mailbox uses when logging the encoded bytes of a dispatched
+/// message. This is the ascii encoding of `mailbox`.
+const DISPATCHED_MESSAGE_LOG_ID: u64 = 0x6d61696c626f78;
+
+/// The log id of the processed message id, as assigned to the `b256` log in the
+/// mailbox ABI.
+const PROCESS_ID_LOG_ID: u64 = 6;
+
+/// The number of attempts fuels gets to resolve the external contracts (ISMs,
+/// recipient) a `process` call depends on.
+const MAX_DEPENDENCY_ESTIMATION_ATTEMPTS: u64 = 10;
+
 /// A reference to a Mailbox contract on some Fuel chain
 pub struct FuelMailbox {
     contract: FuelMailboxInner,
-    domain: HyperlaneDomain,
+    wallet: WalletUnlocked,
+    provider: FuelProvider,
 }
 
 impl FuelMailbox {
@@ -30,15 +55,34 @@ impl FuelMailbox {
         locator: ContractLocator,
         mut wallet: WalletUnlocked,
     ) -> ChainResult<Self> {
-        let provider = make_provider(conf)?;
-        wallet.set_provider(provider);
+        let provider = FuelProvider::new(locator.domain.clone(), conf)?;
+        wallet.set_provider(provider.inner().clone());
         let address = Bech32ContractId::from_h256(&locator.address);
 
         Ok(FuelMailbox {
-            contract: FuelMailboxInner::new(address, wallet),
-            domain: locator.domain.clone(),
+            contract: FuelMailboxInner::new(address, wallet.clone()),
+            wallet,
+            provider,
         })
     }
+
+    fn process_call(&self, message: &HyperlaneMessage, metadata: &[u8]) -> ContractCallHandler<()> {
+        self.contract
+            .methods()
+            .process(metadata.to_vec(), message.into())
+    }
+
+    /// Build the `process` call, resolving the external contracts it touches.
+    async fn resolved_process_call(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<ContractCallHandler<()>> {
+        self.process_call(message, metadata)
+            .estimate_tx_dependencies(Some(MAX_DEPENDENCY_ESTIMATION_ATTEMPTS))
+            .await
+            .map_err(ChainCommunicationError::from_other)
+    }
 }
 
 impl HyperlaneContract for FuelMailbox {
@@ -49,11 +93,11 @@ impl HyperlaneContract for FuelMailbox {
 
 impl HyperlaneChain for FuelMailbox {
     fn domain(&self) -> &HyperlaneDomain {
-        &self.domain
+        self.provider.domain()
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        Box::new(self.provider.clone())
     }
 }
 
@@ -65,12 +109,9 @@ impl Debug for FuelMailbox {
 
 #[async_trait]
 impl Mailbox for FuelMailbox {
+    /// The lag is ignored, see the `MerkleTreeHook` implementation
     #[instrument(level = "debug", err, ret, skip(self))]
-    async fn count(&self, lag: Option<NonZeroU64>) -> ChainResult<u32> {
-        assert!(
-            lag.is_none(),
-            "Fuel does not support querying point-in-time"
-        );
+    async fn count(&self, _lag: Option<NonZeroU64>) -> ChainResult<u32> {
         self.contract
             .methods()
             .count()
@@ -82,17 +123,45 @@ impl Mailbox for FuelMailbox {
 
     #[instrument(level = "debug", err, ret, skip(self))]
     async fn delivered(&self, id: H256) -> ChainResult<bool> {
-        todo!()
+        self.contract
+            .methods()
+            .delivered(Bits256::from_h256(&id))
+            .simulate()
+            .await
+            .map(|r| r.value)
+            .map_err(ChainCommunicationError::from_other)
     }
 
     #[instrument(err, ret, skip(self))]
     async fn default_ism(&self) -> ChainResult<H256> {
-        todo!()
+        self.contract
+            .methods()
+            .get_default_ism()
+            .simulate()
+            .await
+            .map(|r| r.value.into_h256())
+            .map_err(ChainCommunicationError::from_other)
     }
 
     #[instrument(err, ret, skip(self))]
     async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
-        todo!()
+        let recipient_contract =
+            MessageRecipient::new(Bech32ContractId::from_h256(&recipient), self.wallet.clone());
+        // Recipients that do not specify an ISM fall back to the default one,
+        // matching the behaviour of the mailbox itself.
+        let ism = recipient_contract
+            .methods()
+            .interchain_security_module()
+            .simulate()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value
+            .into_h256();
+        if ism.is_zero() {
+            self.default_ism().await
+        } else {
+            Ok(ism)
+        }
     }
 
     #[instrument(err, ret, skip(self))]
@@ -102,7 +171,8 @@ impl Mailbox for FuelMailbox {
         metadata: &[u8],
         tx_gas_limit: Option<U256>,
     ) -> ChainResult<TxOutcome> {
-        todo!()
+        let call = self.resolved_process_call(message, metadata).await?;
+        report_call(call, tx_gas_limit).await
     }
 
     #[instrument(err, ret, skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
@@ -111,40 +181,122 @@ impl Mailbox for FuelMailbox {
         message: &HyperlaneMessage,
         metadata: &[u8],
     ) -> ChainResult<TxCostEstimate> {
-        todo!()
+        let call = self.resolved_process_call(message, metadata).await?;
+        estimate_call_costs(call).await
     }
 
     fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
-        todo!()
+        let call = self.process_call(message, metadata).contract_call;
+        [call.encoded_selector.to_vec(), call.encoded_args.resolve(0)].concat()
     }
 }
 
 /// Struct that retrieves event data for a Fuel Mailbox contract
 #[derive(Debug)]
-pub struct FuelMailboxIndexer {}
+pub struct FuelMailboxIndexer {
+    contract: FuelMailboxInner,
+    provider: FuelProvider,
+}
+
+impl FuelMailboxIndexer {
+    /// Create a new fuel mailbox indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let provider = FuelProvider::new(locator.domain.clone(), conf)?;
+        let address = Bech32ContractId::from_h256(&locator.address);
+        let contract = FuelMailboxInner::new(address, read_only_wallet(provider.inner()));
+
+        Ok(Self { contract, provider })
+    }
+
+    fn contract_id(&self) -> ContractId {
+        ContractId::from(self.contract.contract_id())
+    }
+
+    /// Dispatched messages are logged as their raw encoding rather than as a
+    /// typed log, so they are decoded straight from the `LogData` receipt.
+    fn parse_dispatch(receipt: &Receipt) -> ChainResult<Option<HyperlaneMessage>> {
+        match receipt {
+            Receipt::LogData { rb, data, .. } if *rb == DISPATCHED_MESSAGE_LOG_ID => {
+                Ok(Some(HyperlaneMessage::read_from(&mut data.as_slice())?))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Processed message ids are picked out by their log id, so other `b256`
+    /// logs the mailbox may emit are never mistaken for deliveries.
+    fn parse_process_id(receipt: &Receipt) -> ChainResult<Option<H256>> {
+        match receipt {
+            Receipt::LogData { rb, data, .. } if *rb == PROCESS_ID_LOG_ID => {
+                let id: [u8; 32] = data.as_slice().try_into().map_err(|_| {
+                    ChainCommunicationError::ParseError {
+                        msg: format!(
+                            "Expected a 32 byte processed message id, got {} bytes",
+                            data.len()
+                        ),
+                    }
+                })?;
+                Ok(Some(H256::from(id)))
+            }
+            _ => Ok(None),
+        }
+    }
+}
 
 #[async_trait]
 impl Indexer<HyperlaneMessage> for FuelMailboxIndexer {
+    #[instrument(err, skip(self))]
     async fn fetch_logs(
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(HyperlaneMessage, LogMeta)>> {
-        todo!()
+        self.provider
+            .index_logs_in_range(range, self.contract_id(), Self::parse_dispatch)
+            .await
     }
 
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        todo!()
+        self.provider.get_finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for FuelMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(self).await?;
+        let count = self
+            .contract
+            .methods()
+            .count()
+            .simulate()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value;
+        Ok((Some(count), tip))
     }
 }
 
 #[async_trait]
 impl Indexer<H256> for FuelMailboxIndexer {
+    #[instrument(err, skip(self))]
     async fn fetch_logs(&self, range: RangeInclusive<u32>) -> ChainResult<Vec<(H256, LogMeta)>> {
-        todo!()
+        self.provider
+            .index_logs_in_range(range, self.contract_id(), Self::parse_process_id)
+            .await
     }
 
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
-        todo!()
+        self.provider.get_finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for FuelMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<H256>::get_finalized_block_number(self).await?;
+
+        // No sequence for message deliveries.
+        Ok((None, tip))
     }
 }
 
@@ -154,7 +306,125 @@ impl HyperlaneAbi for FuelMailboxAbi {
     const SELECTOR_SIZE_BYTES: usize = 8;
 
     fn fn_map() -> HashMap<Vec<u8>, &'static str> {
-        // Can't support this without Fuels exporting it in the generated code
-        todo!()
+        let functions: [(&'static str, Vec<ParamType>); 10] = [
+            ("count", vec![]),
+            ("delivered", vec![Bits256::param_type()]),
+            (
+                "dispatch",
+                vec![
+                    u32::param_type(),
+                    Bits256::param_type(),
+                    Vec::<u8>::param_type(),
+                ],
+            ),
+            ("get_default_ism", vec![]),
+            ("latest_checkpoint", vec![]),
+            (
+                "process",
+                vec![Vec::<u8>::param_type(), FuelMessage::param_type()],
+            ),
+            ("root", vec![]),
+            ("set_default_ism", vec![ContractId::param_type()]),
+            ("owner", vec![]),
+            ("transfer_ownership", vec![Option::<Identity>::param_type()]),
+        ];
+        functions
+            .into_iter()
+            .map(|(name, inputs)| (resolve_fn_selector(name, &inputs).to_vec(), name))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use fuels::tx::{Bytes32, Receipt};
+    use hyperlane_core::{Encode, HyperlaneAbi, HyperlaneMessage, H256};
+
+    use super::{FuelMailboxAbi, FuelMailboxIndexer, DISPATCHED_MESSAGE_LOG_ID, PROCESS_ID_LOG_ID};
+
+    fn log_data(rb: u64, data: Vec<u8>) -> Receipt {
+        Receipt::LogData {
+            id: Default::default(),
+            ra: 0,
+            rb,
+            ptr: 0,
+            len: data.len() as u64,
+            digest: Bytes32::default(),
+            data,
+            pc: 0,
+            is: 0,
+        }
+    }
+
+    #[test]
+    fn parses_dispatched_message() {
+        let message = HyperlaneMessage {
+            nonce: 3,
+            origin: 1000,
+            destination: 2000,
+            body: vec![1, 2, 3],
+            ..Default::default()
+        };
+        let receipt = log_data(DISPATCHED_MESSAGE_LOG_ID, message.to_vec());
+        assert_eq!(
+            FuelMailboxIndexer::parse_dispatch(&receipt).unwrap(),
+            Some(message)
+        );
+
+        let other = log_data(PROCESS_ID_LOG_ID, H256::repeat_byte(7).as_bytes().to_vec());
+        assert_eq!(FuelMailboxIndexer::parse_dispatch(&other).unwrap(), None);
+    }
+
+    #[test]
+    fn parses_only_process_id_logs() {
+        let id = H256::repeat_byte(7);
+        let receipt = log_data(PROCESS_ID_LOG_ID, id.as_bytes().to_vec());
+        assert_eq!(
+            FuelMailboxIndexer::parse_process_id(&receipt).unwrap(),
+            Some(id)
+        );
+
+        // Other 32 byte logs, e.g. the dispatched message id, are not deliveries.
+        let other = log_data(PROCESS_ID_LOG_ID + 1, id.as_bytes().to_vec());
+        assert_eq!(FuelMailboxIndexer::parse_process_id(&other).unwrap(), None);
+        let return_receipt = Receipt::Return {
+            id: Default::default(),
+            val: 1,
+            pc: 0,
+            is: 0,
+        };
+        assert_eq!(
+            FuelMailboxIndexer::parse_process_id(&return_receipt).unwrap(),
+            None
+        );
+
+        let truncated = log_data(PROCESS_ID_LOG_ID, vec![0; 31]);
+        assert!(FuelMailboxIndexer::parse_process_id(&truncated).is_err());
+    }
+
+    #[test]
+    fn fn_map_has_every_mailbox_function() {
+        let fn_map = FuelMailboxAbi::fn_map();
+        assert_eq!(fn_map.len(), 10);
+        assert!(fn_map
+            .keys()
+            .all(|selector| selector.len() == FuelMailboxAbi::SELECTOR_SIZE_BYTES));
+        let mut names = fn_map.values().copied().collect::<Vec<_>>();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            [
+                "count",
+                "delivered",
+                "dispatch",
+                "get_default_ism",
+                "latest_checkpoint",
+                "owner",
+                "process",
+                "root",
+                "set_default_ism",
+                "transfer_ownership",
+            ]
+        );
     }
 }
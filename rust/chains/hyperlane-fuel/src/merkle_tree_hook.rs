@@ -0,0 +1,157 @@
+use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use fuels::prelude::{Bech32ContractId, ContractId};
+use tracing::instrument;
+
+use hyperlane_core::{
+    accumulator::incremental::IncrementalMerkle, ChainCommunicationError, ChainResult, Checkpoint,
+    ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneProvider,
+    Indexer, LogMeta, MerkleTreeHook, MerkleTreeInsertion, SequenceAwareIndexer, H256,
+};
+
+use crate::{
+    contracts::merkle_tree_hook::{
+        InsertedIntoTreeEvent, MerkleTreeHook as FuelMerkleTreeHookInner,
+    },
+    conversions::*,
+    tx::read_only_wallet,
+    ConnectionConf, FuelProvider,
+};
+
+/// A reference to a MerkleTreeHook contract on some Fuel chain
+#[derive(Debug)]
+pub struct FuelMerkleTreeHook {
+    contract: FuelMerkleTreeHookInner,
+    provider: FuelProvider,
+}
+
+impl FuelMerkleTreeHook {
+    /// Create a new fuel merkle tree hook
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let provider = FuelProvider::new(locator.domain.clone(), conf)?;
+        let address = Bech32ContractId::from_h256(&locator.address);
+        let contract = FuelMerkleTreeHookInner::new(address, read_only_wallet(provider.inner()));
+
+        Ok(Self { contract, provider })
+    }
+}
+
+impl HyperlaneContract for FuelMerkleTreeHook {
+    fn address(&self) -> H256 {
+        self.contract.contract_id().into_h256()
+    }
+}
+
+impl HyperlaneChain for FuelMerkleTreeHook {
+    fn domain(&self) -> &HyperlaneDomain {
+        self.provider.domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+/// Fuel can't be queried at a past block, but its blocks are final once
+/// produced, so the lag is ignored and the latest state is read.
+#[async_trait]
+impl MerkleTreeHook for FuelMerkleTreeHook {
+    #[instrument(err, ret, skip(self))]
+    async fn tree(&self, lag: Option<NonZeroU64>) -> ChainResult<IncrementalMerkle> {
+        let branch = self
+            .contract
+            .methods()
+            .branch()
+            .simulate()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value
+            .map(FuelIntoH256::into_h256);
+        let count = self.count(lag).await?;
+
+        Ok(IncrementalMerkle::new(branch, count as usize))
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn count(&self, _lag: Option<NonZeroU64>) -> ChainResult<u32> {
+        self.contract
+            .methods()
+            .count()
+            .simulate()
+            .await
+            .map(|r| r.value)
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn latest_checkpoint(&self, _lag: Option<NonZeroU64>) -> ChainResult<Checkpoint> {
+        let (root, index) = self
+            .contract
+            .methods()
+            .latest_checkpoint()
+            .simulate()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value;
+
+        Ok(Checkpoint {
+            merkle_tree_hook_address: self.address(),
+            mailbox_domain: self.domain().id(),
+            root: root.into_h256(),
+            index,
+        })
+    }
+}
+
+/// Struct that retrieves event data for a Fuel MerkleTreeHook contract
+#[derive(Debug)]
+pub struct FuelMerkleTreeHookIndexer {
+    hook: FuelMerkleTreeHook,
+}
+
+impl FuelMerkleTreeHookIndexer {
+    /// Create a new fuel merkle tree hook indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self {
+            hook: FuelMerkleTreeHook::new(conf, locator)?,
+        })
+    }
+}
+
+#[async_trait]
+impl Indexer<MerkleTreeInsertion> for FuelMerkleTreeHookIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(MerkleTreeInsertion, LogMeta)>> {
+        let decoder = self.hook.contract.log_decoder();
+        let contract_id = ContractId::from(self.hook.contract.contract_id());
+        self.hook
+            .provider
+            .index_logs_in_range(range, contract_id, |receipt| {
+                let events = decoder
+                    .get_logs_with_type::<InsertedIntoTreeEvent>(std::slice::from_ref(receipt))
+                    .map_err(ChainCommunicationError::from_other)?;
+                Ok(events.into_iter().next().map(|event| {
+                    MerkleTreeInsertion::new(event.index, event.message_id.into_h256())
+                }))
+            })
+            .await
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.hook.provider.get_finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<MerkleTreeInsertion> for FuelMerkleTreeHookIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.get_finalized_block_number().await?;
+        let count = self.hook.count(None).await?;
+        Ok((Some(count), tip))
+    }
+}
@@ -1,37 +1,79 @@
 use async_trait::async_trait;
+use fuels::prelude::Bech32ContractId;
+use tracing::instrument;
 
 use hyperlane_core::{
-    ChainResult, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
-    HyperlaneProvider, MultisigIsm, H256,
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, MultisigIsm, H256,
+};
+
+use crate::{
+    contracts::multisig_ism::MultisigIsm as FuelMultisigIsmInner, conversions::*,
+    tx::read_only_wallet, ConnectionConf, FuelProvider,
 };
 
 /// A reference to a MultisigIsm contract on some Fuel chain
 #[derive(Debug)]
-pub struct FuelMultisigIsm {}
+pub struct FuelMultisigIsm {
+    contract: FuelMultisigIsmInner,
+    provider: FuelProvider,
+}
+
+impl FuelMultisigIsm {
+    /// Create a new fuel multisig ISM
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let provider = FuelProvider::new(locator.domain.clone(), conf)?;
+        let address = Bech32ContractId::from_h256(&locator.address);
+        let contract = FuelMultisigIsmInner::new(address, read_only_wallet(provider.inner()));
+
+        Ok(Self { contract, provider })
+    }
+}
 
 impl HyperlaneContract for FuelMultisigIsm {
     fn address(&self) -> H256 {
-        todo!()
+        self.contract.contract_id().into_h256()
     }
 }
 
 impl HyperlaneChain for FuelMultisigIsm {
     fn domain(&self) -> &HyperlaneDomain {
-        todo!()
+        self.provider.domain()
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        Box::new(self.provider.clone())
     }
 }
 
 #[async_trait]
 impl MultisigIsm for FuelMultisigIsm {
     /// Returns the validator and threshold needed to verify message
+    #[instrument(err, ret, skip(self))]
     async fn validators_and_threshold(
         &self,
         message: &HyperlaneMessage,
     ) -> ChainResult<(Vec<H256>, u8)> {
-        todo!()
+        let validators = self
+            .contract
+            .methods()
+            .validators(message.into())
+            .simulate()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value
+            .into_iter()
+            .map(FuelIntoH256::into_h256)
+            .collect();
+        let threshold = self
+            .contract
+            .methods()
+            .threshold(message.into())
+            .simulate()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value;
+
+        Ok((validators, threshold))
     }
 }
@@ -1,43 +1,259 @@
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
 use async_trait::async_trait;
+use fuels::{
+    prelude::{Bech32Address, ContractId, Provider, BASE_ASSET_ID},
+    tx::{
+        field::{GasLimit, GasPrice, Inputs},
+        Bytes32, Input, Receipt, Transaction,
+    },
+    types::block::Block,
+};
+use tracing::instrument;
 
 use hyperlane_core::{
-    BlockInfo, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain, HyperlaneProvider, TxnInfo,
-    H256, U256,
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneProvider, HyperlaneProviderError, LogMeta, TxnInfo, TxnReceiptInfo, H256, H512, U256,
 };
 
+use crate::{conversions::*, make_provider, ConnectionConf};
+
 /// A wrapper around a fuel provider to get generic blockchain information.
-#[derive(Debug)]
-pub struct FuelProvider {}
+#[derive(Debug, Clone)]
+pub struct FuelProvider {
+    domain: HyperlaneDomain,
+    provider: Provider,
+}
+
+impl FuelProvider {
+    /// Create a new fuel provider
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf) -> ChainResult<Self> {
+        let provider = make_provider(conf)?;
+        Ok(Self { domain, provider })
+    }
+
+    /// Get the inner fuels provider
+    pub fn inner(&self) -> &Provider {
+        &self.provider
+    }
+
+    /// Get the latest block height. Fuel blocks are produced by a PoA node and
+    /// are final as soon as they are committed.
+    pub async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.provider
+            .latest_block_height()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    async fn fetch_block(&self, height: u32) -> ChainResult<Block> {
+        self.provider
+            .block_by_height(height.into())
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .ok_or_else(|| {
+                ChainCommunicationError::CustomError(format!(
+                    "Could not find fuel block at height {height}"
+                ))
+            })
+    }
+
+    /// Walk every transaction receipt emitted by `contract_id` in the given
+    /// range of blocks, and collect the ones `parser` recognises.
+    ///
+    /// `parser` returns `Ok(None)` for receipts which are not of interest.
+    #[instrument(err, skip(self, parser))]
+    pub async fn index_logs_in_range<T>(
+        &self,
+        range: RangeInclusive<u32>,
+        contract_id: ContractId,
+        mut parser: impl FnMut(&Receipt) -> ChainResult<Option<T>> + Send,
+    ) -> ChainResult<Vec<(T, LogMeta)>> {
+        let address = contract_id.into_h256();
+        let mut logs = vec![];
+        for height in range {
+            let block = self.fetch_block(height).await?;
+            let block_hash = H256::from(<[u8; 32]>::from(block.id));
+            for (transaction_index, tx_id) in block.transactions.iter().enumerate() {
+                let receipts = self
+                    .provider
+                    .get_receipts(tx_id)
+                    .await
+                    .map_err(ChainCommunicationError::from_other)?;
+                for (log_index, receipt) in receipts.iter().enumerate() {
+                    if receipt.id() != Some(&contract_id) {
+                        continue;
+                    }
+                    if let Some(parsed) = parser(receipt)? {
+                        let meta = LogMeta {
+                            address,
+                            block_number: height.into(),
+                            block_hash,
+                            transaction_id: H256::from(<[u8; 32]>::from(*tx_id)).into(),
+                            transaction_index: transaction_index as u64,
+                            log_index: log_index.into(),
+                        };
+                        logs.push((parsed, meta));
+                    }
+                }
+            }
+        }
+        Ok(logs)
+    }
+}
 
 impl HyperlaneChain for FuelProvider {
     fn domain(&self) -> &HyperlaneDomain {
-        todo!()
+        &self.domain
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        Box::new(self.clone())
     }
 }
 
 #[async_trait]
 impl HyperlaneProvider for FuelProvider {
+    #[instrument(err, skip(self))]
     async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo> {
-        todo!()
+        let block = self
+            .provider
+            .block(&Bytes32::new(hash.0))
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .ok_or(HyperlaneProviderError::CouldNotFindObjectByHash(*hash))?;
+
+        Ok(BlockInfo {
+            hash: *hash,
+            timestamp: block
+                .header
+                .time
+                .map(|t| t.timestamp() as u64)
+                .unwrap_or_default(),
+            number: block.header.height,
+        })
     }
 
-    async fn get_txn_by_hash(&self, hash: &H256) -> ChainResult<TxnInfo> {
-        todo!()
+    #[instrument(err, skip(self))]
+    async fn get_block_by_height(&self, height: u64) -> ChainResult<BlockInfo> {
+        let block = self
+            .provider
+            .block_by_height(height)
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .ok_or(HyperlaneProviderError::CouldNotFindBlockByHeight(height))?;
+
+        Ok(BlockInfo {
+            hash: H256::from(<[u8; 32]>::from(block.id)),
+            timestamp: block
+                .header
+                .time
+                .map(|t| t.timestamp() as u64)
+                .unwrap_or_default(),
+            number: block.header.height,
+        })
     }
 
+    #[instrument(err, skip(self))]
+    async fn get_txn_by_hash(&self, hash: &H512) -> ChainResult<TxnInfo> {
+        let txn_hash = H256::from(*hash);
+        let tx_id = Bytes32::new(txn_hash.0);
+        let response = self
+            .provider
+            .get_transaction_by_id(&tx_id)
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .ok_or(HyperlaneProviderError::CouldNotFindObjectByHash(txn_hash))?;
+
+        let (gas_limit, gas_price, sender, recipient) = match &response.transaction {
+            Transaction::Script(tx) => (
+                *tx.gas_limit(),
+                *tx.gas_price(),
+                first_input_owner(tx.inputs()),
+                first_input_contract(tx.inputs()),
+            ),
+            Transaction::Create(tx) => (
+                *tx.gas_limit(),
+                *tx.gas_price(),
+                first_input_owner(tx.inputs()),
+                None,
+            ),
+            Transaction::Mint(_) => (0, 0, H256::zero(), None),
+        };
+
+        let receipts = self
+            .provider
+            .get_receipts(&tx_id)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        let gas_used = receipts.iter().find_map(|r| match r {
+            Receipt::ScriptResult { gas_used, .. } => Some(*gas_used),
+            _ => None,
+        });
+        let receipt = gas_used.map(|gas_used| TxnReceiptInfo {
+            gas_used: gas_used.into(),
+            cumulative_gas_used: gas_used.into(),
+            effective_gas_price: Some(gas_price.into()),
+        });
+
+        Ok(TxnInfo {
+            hash: *hash,
+            gas_limit: gas_limit.into(),
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_price: Some(gas_price.into()),
+            // Fuel is UTXO based and has no account nonces
+            nonce: 0,
+            sender,
+            recipient,
+            receipt,
+        })
+    }
+
+    #[instrument(err, skip(self))]
     async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
-        todo!()
+        let contract = self
+            .provider
+            .client
+            .contract(&ContractId::from_h256(address))
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(contract.is_some())
     }
 
+    #[instrument(err, skip(self))]
     async fn get_balance(&self, address: String) -> ChainResult<U256> {
-        todo!()
+        let address =
+            Bech32Address::from_str(&address).map_err(ChainCommunicationError::from_other)?;
+        let balance = self
+            .provider
+            .get_asset_balance(&address, BASE_ASSET_ID)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(balance.into())
     }
 
     async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
         Ok(None)
     }
 }
+
+/// The owner of the first coin input, which is who paid for the transaction.
+fn first_input_owner(inputs: &[Input]) -> H256 {
+    inputs
+        .iter()
+        .find_map(|input| input.input_owner())
+        .map(|owner| H256::from(<[u8; 32]>::from(*owner)))
+        .unwrap_or_default()
+}
+
+/// The first contract a transaction interacted with.
+fn first_input_contract(inputs: &[Input]) -> Option<H256> {
+    inputs
+        .iter()
+        .find_map(|input| input.contract_id())
+        .map(|id| id.into_h256())
+}
@@ -1,34 +1,62 @@
 use async_trait::async_trait;
+use fuels::prelude::Bech32ContractId;
+use tracing::instrument;
 
 use hyperlane_core::{
-    ChainResult, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
-    HyperlaneProvider, RoutingIsm, H256,
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, RoutingIsm, H256,
+};
+
+use crate::{
+    contracts::routing_ism::RoutingIsm as FuelRoutingIsmInner, conversions::*,
+    tx::read_only_wallet, ConnectionConf, FuelProvider,
 };
 
 /// A reference to a RoutingIsm contract on some Fuel chain
 #[derive(Debug)]
-pub struct FuelRoutingIsm {}
+pub struct FuelRoutingIsm {
+    contract: FuelRoutingIsmInner,
+    provider: FuelProvider,
+}
+
+impl FuelRoutingIsm {
+    /// Create a new fuel routing ISM
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let provider = FuelProvider::new(locator.domain.clone(), conf)?;
+        let address = Bech32ContractId::from_h256(&locator.address);
+        let contract = FuelRoutingIsmInner::new(address, read_only_wallet(provider.inner()));
+
+        Ok(Self { contract, provider })
+    }
+}
 
 impl HyperlaneContract for FuelRoutingIsm {
     fn address(&self) -> H256 {
-        todo!()
+        self.contract.contract_id().into_h256()
     }
 }
 
 impl HyperlaneChain for FuelRoutingIsm {
     fn domain(&self) -> &HyperlaneDomain {
-        todo!()
+        self.provider.domain()
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        Box::new(self.provider.clone())
     }
 }
 
 #[async_trait]
 impl RoutingIsm for FuelRoutingIsm {
     /// Returns the ism needed to verify message
+    #[instrument(err, ret, skip(self))]
     async fn route(&self, message: &HyperlaneMessage) -> ChainResult<H256> {
-        todo!()
+        self.contract
+            .methods()
+            .route(message.into())
+            .simulate()
+            .await
+            .map(|r| r.value.into_h256())
+            .map_err(ChainCommunicationError::from_other)
     }
 }
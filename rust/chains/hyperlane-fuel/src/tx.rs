@@ -0,0 +1,87 @@
+use std::fmt::Debug;
+
+use fuels::{
+    prelude::{ContractCallHandler, Provider, TxParameters, WalletUnlocked},
+    tx::{field::GasPrice, Receipt, ScriptExecutionResult, UniqueIdentifier},
+    types::traits::Tokenizable,
+};
+use tracing::{info, warn};
+
+use hyperlane_core::{ChainCommunicationError, ChainResult, TxCostEstimate, TxOutcome, H256, U256};
+
+/// Tolerance applied on top of the simulated gas usage when estimating costs
+const GAS_ESTIMATE_TOLERANCE: f64 = 0.2;
+
+/// Build a wallet that is only ever used for read-only (simulated) calls.
+/// fuels requires an unlocked wallet to instantiate a contract, even when no
+/// transaction is ever signed.
+pub(crate) fn read_only_wallet(provider: &Provider) -> WalletUnlocked {
+    WalletUnlocked::new_random(Some(provider.clone()))
+}
+
+/// Dispatches a contract call, waits for the receipts, and reports the outcome
+pub(crate) async fn report_call<D>(
+    call: ContractCallHandler<D>,
+    tx_gas_limit: Option<U256>,
+) -> ChainResult<TxOutcome>
+where
+    D: Tokenizable + Debug,
+{
+    let call = match tx_gas_limit {
+        Some(limit) => call.tx_params(TxParameters::default().set_gas_limit(limit.as_u64())),
+        None => call,
+    };
+    let provider = call.provider.clone();
+    let executable = call
+        .get_executable_call()
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    let tx_id = H256::from(<[u8; 32]>::from(executable.tx.id()));
+    let gas_price = *executable.tx.gas_price();
+
+    info!(?tx_id, "Dispatching fuel transaction");
+    let receipts = executable
+        .execute(&provider)
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+
+    let (executed, gas_used) = receipts
+        .iter()
+        .find_map(|r| match r {
+            Receipt::ScriptResult { result, gas_used } => {
+                Some((*result == ScriptExecutionResult::Success, *gas_used))
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| {
+            warn!(?tx_id, "Fuel transaction has no script result receipt");
+            (false, 0)
+        });
+    info!(?tx_id, executed, gas_used, "Fuel transaction included");
+
+    Ok(TxOutcome {
+        transaction_id: tx_id.into(),
+        executed,
+        gas_used: gas_used.into(),
+        gas_price: U256::from(gas_price).try_into()?,
+    })
+}
+
+/// Estimates the gas limit and price of a contract call by simulating it
+pub(crate) async fn estimate_call_costs<D>(
+    call: ContractCallHandler<D>,
+) -> ChainResult<TxCostEstimate>
+where
+    D: Tokenizable + Debug,
+{
+    let cost = call
+        .estimate_transaction_cost(Some(GAS_ESTIMATE_TOLERANCE))
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+
+    Ok(TxCostEstimate {
+        gas_limit: cost.gas_used.into(),
+        gas_price: U256::from(cost.gas_price).try_into()?,
+        l2_gas_limit: None,
+    })
+}
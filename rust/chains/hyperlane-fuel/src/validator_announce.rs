@@ -1,48 +1,185 @@
 use async_trait::async_trait;
+use fuels::{
+    prelude::{Bech32ContractId, WalletUnlocked, BASE_ASSET_ID},
+    types::{Bits256, B512},
+};
+use tracing::{instrument, warn};
 
 use hyperlane_core::{
-    Announcement, ChainResult, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
-    HyperlaneProvider, SignedType, TxOutcome, ValidatorAnnounce, H256, U256,
+    Announcement, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Signature, SignedType, TxOutcome,
+    ValidatorAnnounce, H256, U256,
+};
+
+use crate::{
+    contracts::validator_announce::ValidatorAnnounce as FuelValidatorAnnounceInner,
+    conversions::*,
+    tx::{read_only_wallet, report_call},
+    ConnectionConf, FuelProvider,
 };
 
 /// A reference to a ValidatorAnnounce contract on some Fuel chain
 #[derive(Debug)]
-pub struct FuelValidatorAnnounce {}
+pub struct FuelValidatorAnnounce {
+    contract: FuelValidatorAnnounceInner,
+    wallet: WalletUnlocked,
+    provider: FuelProvider,
+}
+
+impl FuelValidatorAnnounce {
+    /// Create a new fuel validator announce contract. Without a wallet the
+    /// contract can only be read from.
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        wallet: Option<WalletUnlocked>,
+    ) -> ChainResult<Self> {
+        let provider = FuelProvider::new(locator.domain.clone(), conf)?;
+        let wallet = match wallet {
+            Some(mut wallet) => {
+                wallet.set_provider(provider.inner().clone());
+                wallet
+            }
+            None => read_only_wallet(provider.inner()),
+        };
+        let address = Bech32ContractId::from_h256(&locator.address);
+
+        Ok(Self {
+            contract: FuelValidatorAnnounceInner::new(address, wallet.clone()),
+            wallet,
+            provider,
+        })
+    }
+
+    async fn get_announced_storage_locations_for(
+        &self,
+        validator: &H256,
+    ) -> ChainResult<Vec<String>> {
+        let validator = Bits256::from_h256(validator);
+        let count = self
+            .contract
+            .methods()
+            .get_announced_storage_location_count(validator)
+            .simulate()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value;
+
+        let mut locations = Vec::with_capacity(count as usize);
+        for index in 0..count {
+            let location = self
+                .contract
+                .methods()
+                .get_announced_storage_location(validator, index)
+                .simulate()
+                .await
+                .map_err(ChainCommunicationError::from_other)?
+                .value;
+            locations
+                .push(String::from_utf8(location).map_err(ChainCommunicationError::from_other)?);
+        }
+        Ok(locations)
+    }
+}
 
 impl HyperlaneContract for FuelValidatorAnnounce {
     fn address(&self) -> H256 {
-        todo!()
+        self.contract.contract_id().into_h256()
     }
 }
 
 impl HyperlaneChain for FuelValidatorAnnounce {
     fn domain(&self) -> &HyperlaneDomain {
-        todo!()
+        self.provider.domain()
     }
 
     fn provider(&self) -> Box<dyn HyperlaneProvider> {
-        todo!()
+        Box::new(self.provider.clone())
     }
 }
 
 #[async_trait]
 impl ValidatorAnnounce for FuelValidatorAnnounce {
+    #[instrument(err, ret, skip(self))]
     async fn get_announced_storage_locations(
         &self,
         validators: &[H256],
     ) -> ChainResult<Vec<Vec<String>>> {
-        todo!()
+        let mut storage_locations = Vec::with_capacity(validators.len());
+        for validator in validators {
+            storage_locations.push(self.get_announced_storage_locations_for(validator).await?);
+        }
+        Ok(storage_locations)
     }
 
+    #[instrument(err, ret, skip(self))]
+    async fn get_announced_validators(&self) -> ChainResult<Vec<H256>> {
+        let validators = self
+            .contract
+            .methods()
+            .get_announced_validators()
+            .simulate()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value;
+        Ok(validators
+            .into_iter()
+            .map(FuelIntoH256::into_h256)
+            .collect())
+    }
+
+    #[instrument(err, ret, skip(self))]
     async fn announce(
         &self,
         announcement: SignedType<Announcement>,
         tx_gas_limit: Option<U256>,
     ) -> ChainResult<TxOutcome> {
-        todo!()
+        let call = self.contract.methods().announce(
+            Bits256::from_h256(&announcement.value.validator.into()),
+            announcement.value.storage_location.into_bytes(),
+            compact_signature(&announcement.signature),
+        );
+        report_call(call, tx_gas_limit).await
     }
 
+    #[instrument(ret, skip(self))]
     async fn announce_tokens_needed(&self, announcement: SignedType<Announcement>) -> Option<U256> {
-        todo!()
+        let cost = self
+            .contract
+            .methods()
+            .announce(
+                Bits256::from_h256(&announcement.value.validator.into()),
+                announcement.value.storage_location.into_bytes(),
+                compact_signature(&announcement.signature),
+            )
+            .estimate_transaction_cost(None)
+            .await
+            .map_err(|err| warn!(?err, "Failed to estimate announce cost"))
+            .ok()?;
+        let balance = self
+            .wallet
+            .get_asset_balance(&BASE_ASSET_ID)
+            .await
+            .map_err(|err| warn!(?err, "Failed to fetch announcer balance"))
+            .ok()?;
+
+        Some(U256::from(cost.total_fee).saturating_sub(balance.into()))
+    }
+}
+
+/// Fuel verifies secp256k1 signatures in their 64 byte compact form, where the
+/// recovery id is stored in the highest bit of `s`.
+fn compact_signature(signature: &Signature) -> B512 {
+    let bytes: [u8; 65] = signature.into();
+    let mut r = [0u8; 32];
+    let mut s = [0u8; 32];
+    r.copy_from_slice(&bytes[0..32]);
+    s.copy_from_slice(&bytes[32..64]);
+    // `v` is either 27 or 28 for ethereum style signatures.
+    let recovery_id = (signature.v as u8).saturating_sub(27) & 1;
+    s[0] |= recovery_id << 7;
+
+    B512 {
+        bytes: [Bits256(r), Bits256(s)],
     }
 }
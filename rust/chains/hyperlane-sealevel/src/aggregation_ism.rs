@@ -0,0 +1,99 @@
+use async_trait::async_trait;
+
+use hyperlane_core::{
+    AggregationIsm, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, H256,
+};
+use hyperlane_sealevel_aggregation_ism::{
+    accounts::AggregationIsmStorageAccount, aggregation_ism_storage_pda_seeds,
+    instruction::ModulesAndThreshold,
+};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{ConnectionConf, RpcClientWithDebug, SealevelProvider};
+
+/// A reference to an AggregationIsm contract on some Sealevel chain
+#[derive(Debug)]
+pub struct SealevelAggregationIsm {
+    program_id: Pubkey,
+    domain: HyperlaneDomain,
+    provider: SealevelProvider,
+}
+
+impl SealevelAggregationIsm {
+    /// Create a new Sealevel AggregationIsm.
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        let provider = SealevelProvider::new(locator.domain.clone(), conf);
+        let program_id = Pubkey::from(<[u8; 32]>::from(locator.address));
+
+        Self {
+            program_id,
+            domain: locator.domain.clone(),
+            provider,
+        }
+    }
+
+    fn rpc(&self) -> &RpcClientWithDebug {
+        self.provider.rpc()
+    }
+}
+
+impl HyperlaneContract for SealevelAggregationIsm {
+    fn address(&self) -> H256 {
+        self.program_id.to_bytes().into()
+    }
+}
+
+impl HyperlaneChain for SealevelAggregationIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl AggregationIsm for SealevelAggregationIsm {
+    /// Returns the `m` ISMs and `n` threshold needed to n-of-m verify the message
+    async fn modules_and_threshold(
+        &self,
+        _message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        let modules_and_threshold = get_modules_and_threshold(self.rpc(), &self.program_id).await?;
+
+        let modules = modules_and_threshold
+            .modules
+            .into_iter()
+            .map(|module| module.to_bytes().into())
+            .collect();
+
+        Ok((modules, modules_and_threshold.threshold))
+    }
+}
+
+/// Gets the modules and threshold of the aggregation ISM `program_id` from
+/// its storage account.
+pub(crate) async fn get_modules_and_threshold(
+    rpc_client: &RpcClientWithDebug,
+    program_id: &Pubkey,
+) -> ChainResult<ModulesAndThreshold> {
+    let (storage_pda_key, _storage_pda_bump) =
+        Pubkey::try_find_program_address(aggregation_ism_storage_pda_seeds!(), program_id)
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "Could not find program address for aggregation ISM storage",
+                )
+            })?;
+
+    let storage_account = rpc_client
+        .get_account(&storage_pda_key)
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    let storage = AggregationIsmStorageAccount::fetch(&mut &storage_account.data[..])
+        .map_err(ChainCommunicationError::from_other)?
+        .into_inner();
+
+    Ok(storage.modules_and_threshold)
+}
@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use hyperlane_core::{
     config::StrOrIntParseError, ChainCommunicationError, ChainResult, ContractLocator,
     HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Indexer,
-    InterchainGasPaymaster, InterchainGasPayment, LogMeta, SequenceAwareIndexer, H256, H512,
+    InterchainGasPaymaster, InterchainGasPayment, LogMeta, SequenceAwareIndexer, H256,
 };
 use hyperlane_sealevel_igp::{
     accounts::{GasPaymentAccount, ProgramDataAccount},
@@ -17,7 +17,9 @@ use std::ops::RangeInclusive;
 use tracing::{info, instrument};
 
 use crate::{
-    client::RpcClientWithDebug, utils::get_finalized_block_number, ConnectionConf, SealevelProvider,
+    client::RpcClientWithDebug,
+    utils::{get_account_creation_meta, get_finalized_block_number},
+    ConnectionConf, SealevelProvider,
 };
 use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
 
@@ -222,16 +224,21 @@ impl SealevelInterchainGasPaymasterIndexer {
             gas_amount: gas_payment_account.gas_amount.into(),
         };
 
+        let creation_meta = get_account_creation_meta(
+            &self.rpc_client,
+            &valid_payment_pda_pubkey,
+            gas_payment_account.slot,
+        )
+        .await;
+
         Ok(SealevelGasPayment::new(
             igp_payment,
             LogMeta {
                 address: self.igp.program_id.to_bytes().into(),
                 block_number: gas_payment_account.slot,
-                // TODO: get these when building out scraper support.
-                // It's inconvenient to get these :|
-                block_hash: H256::zero(),
-                transaction_id: H512::zero(),
-                transaction_index: 0,
+                block_hash: creation_meta.block_hash,
+                transaction_id: creation_meta.transaction_id,
+                transaction_index: creation_meta.transaction_index,
                 log_index: sequence_number.into(),
             },
             H256::from(gas_payment_account.igp.to_bytes()),
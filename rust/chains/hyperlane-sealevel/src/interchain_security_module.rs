@@ -1,28 +1,49 @@
+use std::{future::Future, pin::Pin};
+
 use async_trait::async_trait;
 use num_traits::cast::FromPrimitive;
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Keypair};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey::Pubkey,
+    transaction::Transaction,
+};
 use tracing::warn;
 
 use hyperlane_core::{
     ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
-    HyperlaneDomain, HyperlaneMessage, InterchainSecurityModule, ModuleType, H256, U256,
+    HyperlaneDomain, HyperlaneMessage, InterchainSecurityModule, ModuleType, RawHyperlaneMessage,
+    H256, U256,
+};
+use hyperlane_sealevel_aggregation_ism::metadata::AggregationIsmMetadata;
+use hyperlane_sealevel_interchain_security_module_interface::{
+    InterchainSecurityModuleInstruction, VerifyInstruction, VERIFY_ACCOUNT_METAS_PDA_SEEDS,
 };
-use hyperlane_sealevel_interchain_security_module_interface::InterchainSecurityModuleInstruction;
 use serializable_account_meta::SimulationReturnData;
 
-use crate::{utils::simulate_instruction, ConnectionConf, RpcClientWithDebug, SealevelProvider};
+use crate::{
+    aggregation_ism::get_modules_and_threshold,
+    routing_ism::get_domain_ism,
+    utils::{get_account_metas, simulate_instruction},
+    ConnectionConf, RpcClientWithDebug, SealevelProvider, SealevelSigner,
+};
 
 /// A reference to an InterchainSecurityModule contract on some Sealevel chain
 #[derive(Debug)]
 pub struct SealevelInterchainSecurityModule {
-    payer: Option<Keypair>,
+    payer: Option<SealevelSigner>,
     program_id: Pubkey,
     provider: SealevelProvider,
 }
 
 impl SealevelInterchainSecurityModule {
     /// Create a new sealevel InterchainSecurityModule
-    pub fn new(conf: &ConnectionConf, locator: ContractLocator, payer: Option<Keypair>) -> Self {
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        payer: Option<SealevelSigner>,
+    ) -> Self {
         let provider = SealevelProvider::new(locator.domain.clone(), conf);
         let program_id = Pubkey::from(<[u8; 32]>::from(locator.address));
         Self {
@@ -56,41 +77,177 @@ impl HyperlaneChain for SealevelInterchainSecurityModule {
 #[async_trait]
 impl InterchainSecurityModule for SealevelInterchainSecurityModule {
     async fn module_type(&self) -> ChainResult<ModuleType> {
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            &InterchainSecurityModuleInstruction::Type
-                .encode()
-                .map_err(ChainCommunicationError::from_other)?[..],
-            vec![],
-        );
-
-        let module = simulate_instruction::<SimulationReturnData<u32>>(
+        get_module_type(
             self.rpc(),
             self.payer
                 .as_ref()
                 .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?,
-            instruction,
+            self.program_id,
+        )
+        .await
+    }
+
+    async fn dry_run_verify(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Option<U256>> {
+        let payer = self
+            .payer
+            .as_ref()
+            .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?;
+        let message = RawHyperlaneMessage::from(message);
+
+        let (metadata, account_metas) = get_ism_verify_account_metas(
+            self.rpc(),
+            payer,
+            self.program_id,
+            metadata.to_vec(),
+            message.clone(),
         )
+        .await?;
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            &InterchainSecurityModuleInstruction::Verify(VerifyInstruction::new(metadata, message))
+                .encode()
+                .map_err(ChainCommunicationError::from_other)?,
+            account_metas,
+        );
+
+        let (recent_blockhash, _) = self
+            .rpc()
+            .get_latest_blockhash_with_commitment(CommitmentConfig::finalized())
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        let simulation = self
+            .rpc()
+            .simulate_transaction(&Transaction::new_unsigned(Message::new_with_blockhash(
+                &[instruction],
+                Some(&payer.pubkey()),
+                &recent_blockhash,
+            )))
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value;
+
+        if simulation.err.is_some() {
+            return Ok(None);
+        }
+        Ok(simulation.units_consumed.map(Into::into))
+    }
+}
+
+/// Gets the type of the ISM `program_id`.
+async fn get_module_type(
+    rpc_client: &RpcClientWithDebug,
+    payer: &SealevelSigner,
+    program_id: Pubkey,
+) -> ChainResult<ModuleType> {
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &InterchainSecurityModuleInstruction::Type
+            .encode()
+            .map_err(ChainCommunicationError::from_other)?[..],
+        vec![],
+    );
+
+    let module = simulate_instruction::<SimulationReturnData<u32>>(rpc_client, payer, instruction)
         .await?
         .ok_or_else(|| {
             ChainCommunicationError::from_other_str("No return data was returned from the ISM")
         })?
         .return_data;
 
-        if let Some(module_type) = ModuleType::from_u32(module) {
-            Ok(module_type)
-        } else {
-            warn!(%module, "Unknown module type");
-            Ok(ModuleType::Unused)
-        }
+    if let Some(module_type) = ModuleType::from_u32(module) {
+        Ok(module_type)
+    } else {
+        warn!(%module, "Unknown module type");
+        Ok(ModuleType::Unused)
     }
+}
 
-    async fn dry_run_verify(
-        &self,
-        _message: &HyperlaneMessage,
-        _metadata: &[u8],
-    ) -> ChainResult<Option<U256>> {
-        // TODO: Implement this once we have aggregation ISM support in Sealevel
-        Ok(Some(U256::zero()))
-    }
+/// Gets the metadata and account metas for the `Verify` instruction of the
+/// ISM `program_id`.
+///
+/// Aggregation and routing ISMs verify the message through a CPI into each
+/// module they use, so the program of each of those modules and the account
+/// metas of its own `Verify` instruction are appended, in order. Aggregation
+/// ISMs also expect the number of account metas of each of those modules
+/// after their metadata.
+pub(crate) fn get_ism_verify_account_metas<'a>(
+    rpc_client: &'a RpcClientWithDebug,
+    payer: &'a SealevelSigner,
+    program_id: Pubkey,
+    metadata: Vec<u8>,
+    message: Vec<u8>,
+) -> Pin<Box<dyn Future<Output = ChainResult<(Vec<u8>, Vec<AccountMeta>)>> + Send + 'a>> {
+    // Boxed because aggregation and routing ISMs may themselves be modules
+    // of another aggregation or routing ISM.
+    Box::pin(async move {
+        let (account_metas_pda_key, _) =
+            Pubkey::find_program_address(VERIFY_ACCOUNT_METAS_PDA_SEEDS, &program_id);
+        let instruction = Instruction::new_with_bytes(
+            program_id,
+            &InterchainSecurityModuleInstruction::VerifyAccountMetas(VerifyInstruction::new(
+                metadata.clone(),
+                message.clone(),
+            ))
+            .encode()
+            .map_err(ChainCommunicationError::from_other)?,
+            vec![AccountMeta::new(account_metas_pda_key, false)],
+        );
+        let mut account_metas = get_account_metas(rpc_client, payer, instruction).await?;
+
+        match get_module_type(rpc_client, payer, program_id).await? {
+            ModuleType::Aggregation => {
+                let modules = get_modules_and_threshold(rpc_client, &program_id)
+                    .await?
+                    .modules;
+                let module_metadatas =
+                    AggregationIsmMetadata::decode_module_metadatas(&metadata, modules.len())
+                        .map_err(ChainCommunicationError::from_other)?;
+                let mut verify_module_metadatas = vec![];
+                let mut account_counts = vec![];
+                for (module, module_metadata) in modules.into_iter().zip(module_metadatas) {
+                    let Some(module_metadata) = module_metadata else {
+                        verify_module_metadatas.push(None);
+                        continue;
+                    };
+                    let (module_metadata, module_account_metas) = get_ism_verify_account_metas(
+                        rpc_client,
+                        payer,
+                        module,
+                        module_metadata,
+                        message.clone(),
+                    )
+                    .await?;
+                    let account_count = u8::try_from(module_account_metas.len()).map_err(|_| {
+                        ChainCommunicationError::from_other_str(
+                            "Too many accounts required by an aggregation ISM module",
+                        )
+                    })?;
+                    verify_module_metadatas.push(Some(module_metadata));
+                    account_counts.push(account_count);
+                    account_metas.push(AccountMeta::new_readonly(module, false));
+                    account_metas.extend(module_account_metas);
+                }
+                let metadata = AggregationIsmMetadata {
+                    module_metadatas: verify_module_metadatas,
+                    account_counts,
+                }
+                .encode();
+                Ok((metadata, account_metas))
+            }
+            ModuleType::Routing => {
+                let origin = HyperlaneMessage::from(&message).origin;
+                let ism = get_domain_ism(rpc_client, &program_id, origin).await?;
+                let (metadata, ism_account_metas) =
+                    get_ism_verify_account_metas(rpc_client, payer, ism, metadata, message).await?;
+                account_metas.push(AccountMeta::new_readonly(ism, false));
+                account_metas.extend(ism_account_metas);
+                Ok((metadata, account_metas))
+            }
+            _ => Ok((metadata, account_metas)),
+        }
+    })
 }
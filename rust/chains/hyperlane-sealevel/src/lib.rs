@@ -5,16 +5,20 @@
 #![deny(warnings)]
 
 pub use crate::multisig_ism::*;
+pub use aggregation_ism::*;
 pub(crate) use client::RpcClientWithDebug;
 pub use interchain_gas::*;
 pub use interchain_security_module::*;
 pub use mailbox::*;
 pub use merkle_tree_hook::*;
 pub use provider::*;
+pub use routing_ism::*;
+pub use signer::*;
 pub use solana_sdk::signer::keypair::Keypair;
 pub use trait_builder::*;
 pub use validator_announce::*;
 
+mod aggregation_ism;
 mod error;
 mod interchain_gas;
 mod interchain_security_module;
@@ -22,6 +26,8 @@ mod mailbox;
 mod merkle_tree_hook;
 mod multisig_ism;
 mod provider;
+mod routing_ism;
+mod signer;
 mod trait_builder;
 mod utils;
 
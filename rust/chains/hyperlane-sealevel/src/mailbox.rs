@@ -1,6 +1,6 @@
 #![allow(warnings)] // FIXME remove
 
-use std::{collections::HashMap, num::NonZeroU64, ops::RangeInclusive, str::FromStr as _};
+use std::{collections::HashMap, num::NonZeroU64, ops::RangeInclusive, slice, str::FromStr as _};
 
 use async_trait::async_trait;
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -8,16 +8,14 @@ use jsonrpc_core::futures_util::TryFutureExt;
 use tracing::{debug, info, instrument, warn};
 
 use hyperlane_core::{
-    accumulator::incremental::IncrementalMerkle, ChainCommunicationError, ChainResult, Checkpoint,
-    ContractLocator, Decode as _, Encode as _, FixedPointNumber, HyperlaneAbi, HyperlaneChain,
-    HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Indexer, LogMeta,
-    Mailbox, MerkleTreeHook, SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, H512, U256,
-};
-use hyperlane_sealevel_interchain_security_module_interface::{
-    InterchainSecurityModuleInstruction, VerifyInstruction,
+    accumulator::incremental::IncrementalMerkle, config::StrOrIntParseError, BatchItem,
+    BatchResult, ChainCommunicationError, ChainResult, Checkpoint, ContractLocator, Decode as _,
+    Encode as _, FixedPointNumber, HyperlaneAbi, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Indexer, LogMeta, Mailbox,
+    MerkleTreeHook, SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, H512, U256,
 };
 use hyperlane_sealevel_mailbox::{
-    accounts::{DispatchedMessageAccount, InboxAccount, OutboxAccount},
+    accounts::{DispatchedMessageAccount, InboxAccount, OutboxAccount, ProcessedMessageAccount},
     instruction::InboxProcess,
     mailbox_dispatched_message_pda_seeds, mailbox_inbox_pda_seeds, mailbox_outbox_pda_seeds,
     mailbox_process_authority_pda_seeds, mailbox_processed_message_pda_seeds,
@@ -29,40 +27,79 @@ use serializable_account_meta::SimulationReturnData;
 use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
 use solana_client::{
     nonblocking::rpc_client::RpcClient,
-    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig},
+    rpc_config::{
+        RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig,
+        RpcTransactionConfig,
+    },
     rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
 };
 use solana_sdk::{
     account::Account,
-    commitment_config::CommitmentConfig,
+    commitment_config::{CommitmentConfig, CommitmentLevel},
     compute_budget::ComputeBudgetInstruction,
     hash::Hash,
     instruction::AccountMeta,
     instruction::Instruction,
     message::Message,
+    packet::PACKET_DATA_SIZE,
     pubkey::Pubkey,
     signature::Signature,
-    signer::{keypair::Keypair, Signer as _},
     transaction::{Transaction, VersionedTransaction},
 };
 use solana_transaction_status::{
     EncodedConfirmedBlock, EncodedTransaction, EncodedTransactionWithStatusMeta,
     UiInnerInstructions, UiInstruction, UiMessage, UiParsedInstruction, UiReturnDataEncoding,
-    UiTransaction, UiTransactionReturnData, UiTransactionStatusMeta,
+    UiTransaction, UiTransactionEncoding, UiTransactionReturnData, UiTransactionStatusMeta,
 };
 
 use crate::RpcClientWithDebug;
 use crate::{
-    utils::{get_account_metas, get_finalized_block_number, simulate_instruction},
-    ConnectionConf, SealevelProvider,
+    interchain_security_module::get_ism_verify_account_metas,
+    provider::MAX_TRANSACTION_COMPUTE_UNITS,
+    utils::{
+        get_account_creation_meta, get_account_metas, get_compute_unit_price,
+        get_finalized_block_number, simulate_instruction,
+    },
+    ConnectionConf, SealevelProvider, SealevelSigner,
 };
 
 const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
 const SPL_NOOP: &str = "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV";
 
-// The max amount of compute units for a transaction.
-// TODO: consider a more sane value and/or use IGP gas payments instead.
-const PROCESS_COMPUTE_UNITS: u32 = 1_400_000;
+// Simulated compute units are padded by this percentage, as the units a
+// transaction consumes may differ slightly between simulation and execution.
+const PROCESS_COMPUTE_UNITS_PADDING_PERCENT: u64 = 10;
+
+// "processed" level commitment does not guarantee finality.
+// roughly 5% of blocks end up on a dropped fork.
+// However we don't want processing to be a bottleneck and there already
+// is retry logic in the agents.
+const PROCESS_COMMITMENT: CommitmentConfig = CommitmentConfig {
+    commitment: CommitmentLevel::Processed,
+};
+
+/// The compute budget requested by a process transaction.
+#[derive(Debug, Clone, Copy)]
+struct ProcessComputeBudget {
+    /// Compute unit limit
+    units: u32,
+    /// Price per compute unit, in micro-lamports
+    unit_price: u64,
+}
+
+impl ProcessComputeBudget {
+    /// The instructions of a transaction made of `inbox_instructions`,
+    /// preceded by the compute budget instructions.
+    fn instructions(&self, inbox_instructions: Vec<Instruction>) -> Vec<Instruction> {
+        let mut instructions = Vec::with_capacity(inbox_instructions.len() + 2);
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(self.units));
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+            self.unit_price,
+        ));
+        instructions.extend(inbox_instructions);
+        instructions
+    }
+}
 
 /// A reference to a Mailbox contract on some Sealevel chain
 pub struct SealevelMailbox {
@@ -70,7 +107,7 @@ pub struct SealevelMailbox {
     inbox: (Pubkey, u8),
     pub(crate) outbox: (Pubkey, u8),
     pub(crate) provider: SealevelProvider,
-    payer: Option<Keypair>,
+    payer: Option<SealevelSigner>,
 }
 
 impl SealevelMailbox {
@@ -78,7 +115,7 @@ impl SealevelMailbox {
     pub fn new(
         conf: &ConnectionConf,
         locator: ContractLocator,
-        payer: Option<Keypair>,
+        payer: Option<SealevelSigner>,
     ) -> ChainResult<Self> {
         let provider = SealevelProvider::new(locator.domain.clone(), conf);
         let program_id = Pubkey::from(<[u8; 32]>::from(locator.address));
@@ -192,24 +229,21 @@ impl SealevelMailbox {
         ).await
     }
 
-    /// Gets the account metas required for the ISM's `Verify` instruction.
+    /// Gets the metadata and account metas for the ISM's `Verify` instruction.
     pub async fn get_ism_verify_account_metas(
         &self,
         ism: Pubkey,
         metadata: Vec<u8>,
         message: Vec<u8>,
-    ) -> ChainResult<Vec<AccountMeta>> {
-        let instruction =
-            InterchainSecurityModuleInstruction::VerifyAccountMetas(VerifyInstruction {
-                metadata,
-                message,
-            });
-        self.get_account_metas_with_instruction_bytes(
+    ) -> ChainResult<(Vec<u8>, Vec<AccountMeta>)> {
+        get_ism_verify_account_metas(
+            &self.rpc(),
+            self.payer
+                .as_ref()
+                .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?,
             ism,
-            &instruction
-                .encode()
-                .map_err(ChainCommunicationError::from_other)?,
-            hyperlane_sealevel_interchain_security_module_interface::VERIFY_ACCOUNT_METAS_PDA_SEEDS,
+            metadata,
+            message,
         )
         .await
     }
@@ -254,6 +288,272 @@ impl SealevelMailbox {
     }
 }
 
+impl SealevelMailbox {
+    /// Builds the inbox instruction that processes `message`, including every
+    /// account required by the recipient and its ISM.
+    async fn get_process_instruction(
+        &self,
+        payer: &Pubkey,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Instruction> {
+        let recipient: Pubkey = message.recipient.0.into();
+        let mut encoded_message = vec![];
+        message.write_to(&mut encoded_message).unwrap();
+
+        let (process_authority_key, _process_authority_bump) = Pubkey::try_find_program_address(
+            mailbox_process_authority_pda_seeds!(&recipient),
+            &self.program_id,
+        )
+        .ok_or_else(|| {
+            ChainCommunicationError::from_other_str(
+                "Could not find program address for process authority",
+            )
+        })?;
+        let (processed_message_account_key, _processed_message_account_bump) =
+            Pubkey::try_find_program_address(
+                mailbox_processed_message_pda_seeds!(message.id()),
+                &self.program_id,
+            )
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "Could not find program address for processed message account",
+                )
+            })?;
+
+        // Get the account metas required for the recipient.InterchainSecurityModule instruction.
+        let ism_getter_account_metas = self.get_ism_getter_account_metas(recipient).await?;
+
+        // Get the recipient ISM.
+        let ism = self
+            .get_recipient_ism(recipient, ism_getter_account_metas.clone())
+            .await?;
+
+        // Get the metadata and account metas for the ISM.Verify instruction.
+        let (ism_metadata, ism_verify_account_metas) = self
+            .get_ism_verify_account_metas(ism, metadata.into(), encoded_message.clone())
+            .await?;
+
+        let ixn =
+            hyperlane_sealevel_mailbox::instruction::Instruction::InboxProcess(InboxProcess {
+                metadata: ism_metadata,
+                message: encoded_message,
+            });
+        let ixn_data = ixn
+            .into_instruction_data()
+            .map_err(ChainCommunicationError::from_other)?;
+
+        // Craft the accounts for the transaction.
+        let mut accounts: Vec<AccountMeta> = vec![
+            AccountMeta::new_readonly(*payer, true),
+            AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM).unwrap(), false),
+            AccountMeta::new(self.inbox.0, false),
+            AccountMeta::new_readonly(process_authority_key, false),
+            AccountMeta::new(processed_message_account_key, false),
+        ];
+        accounts.extend(ism_getter_account_metas);
+        accounts.extend([
+            AccountMeta::new_readonly(Pubkey::from_str(SPL_NOOP).unwrap(), false),
+            AccountMeta::new_readonly(ism, false),
+        ]);
+        accounts.extend(ism_verify_account_metas);
+
+        // The recipient.
+        accounts.extend([AccountMeta::new_readonly(recipient, false)]);
+
+        // Get account metas required for the Handle instruction
+        let handle_account_metas = self.get_handle_account_metas(message).await?;
+        accounts.extend(handle_account_metas);
+
+        Ok(Instruction {
+            program_id: self.program_id,
+            data: ixn_data,
+            accounts,
+        })
+    }
+
+    /// Simulates a transaction made of the given inbox instructions and
+    /// returns the compute units it needs, padded for the variance between
+    /// simulation and execution. Errors if the simulated transaction fails.
+    async fn simulate_process_compute_units(
+        &self,
+        payer: &Pubkey,
+        inbox_instructions: &[Instruction],
+        recent_blockhash: &Hash,
+    ) -> ChainResult<u64> {
+        let compute_budget = ProcessComputeBudget {
+            units: MAX_TRANSACTION_COMPUTE_UNITS as u32,
+            unit_price: 0,
+        };
+        let txn = unsigned_process_transaction(
+            payer,
+            inbox_instructions.to_vec(),
+            compute_budget,
+            recent_blockhash,
+        );
+
+        let simulation = self
+            .rpc()
+            .simulate_transaction(&txn)
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value;
+        if let Some(err) = simulation.err {
+            return Err(ChainCommunicationError::from_other(err));
+        }
+        let units = simulation.units_consumed.ok_or_else(|| {
+            ChainCommunicationError::from_other_str("Simulation did not report compute units")
+        })?;
+        Ok(pad_compute_units(units))
+    }
+
+    /// Gets the compute unit price for a transaction made of the given inbox
+    /// instructions, from the priority fees recently paid for the accounts
+    /// they write to. Falls back to no priority fee if those are unavailable.
+    async fn get_process_compute_unit_price(&self, inbox_instructions: &[Instruction]) -> u64 {
+        let mut writable_accounts: Vec<Pubkey> = inbox_instructions
+            .iter()
+            .flat_map(|instruction| &instruction.accounts)
+            .filter(|account| account.is_writable)
+            .map(|account| account.pubkey)
+            .collect();
+        writable_accounts.sort_unstable();
+        writable_accounts.dedup();
+
+        get_compute_unit_price(self.rpc(), &writable_accounts)
+            .await
+            .unwrap_or_else(|err| {
+                warn!(?err, "Failed to get recent prioritization fees");
+                0
+            })
+    }
+
+    /// Signs a transaction made of the given inbox instructions, preceded by
+    /// the compute budget instructions.
+    async fn create_process_transaction(
+        &self,
+        payer: &SealevelSigner,
+        inbox_instructions: Vec<Instruction>,
+        compute_budget: ProcessComputeBudget,
+        recent_blockhash: &Hash,
+    ) -> ChainResult<Transaction> {
+        let mut transaction = unsigned_process_transaction(
+            &payer.pubkey(),
+            inbox_instructions,
+            compute_budget,
+            recent_blockhash,
+        );
+        payer.sign_transaction(&mut transaction).await?;
+        Ok(transaction)
+    }
+
+    async fn get_recent_blockhash(&self) -> ChainResult<Hash> {
+        let (recent_blockhash, _) = self
+            .rpc()
+            .get_latest_blockhash_with_commitment(PROCESS_COMMITMENT)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(recent_blockhash)
+    }
+
+    /// Gets the price per compute unit, in lamports, and the compute units
+    /// consumed by a sent transaction.
+    async fn get_transaction_gas(
+        &self,
+        signature: &Signature,
+    ) -> ChainResult<(FixedPointNumber, U256)> {
+        let meta = self
+            .rpc()
+            .get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .transaction
+            .meta
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str("Transaction has no status meta")
+            })?;
+        let gas_used: u64 = Option::from(meta.compute_units_consumed).unwrap_or_default();
+        let gas_price = FixedPointNumber::from(meta.fee).checked_div(gas_used.max(1))?;
+        Ok((gas_price, gas_used.into()))
+    }
+
+    async fn send_process_transaction(&self, txn: &Transaction) -> ChainResult<TxOutcome> {
+        let signature = self
+            .rpc()
+            .send_and_confirm_transaction(txn)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+
+        tracing::info!(?txn, ?signature, "Sealevel transaction sent");
+
+        let executed = self
+            .rpc()
+            .confirm_transaction_with_commitment(&signature, PROCESS_COMMITMENT)
+            .await
+            .map_err(|err| warn!("Failed to confirm inbox process transaction: {}", err))
+            .map(|ctx| ctx.value)
+            .unwrap_or(false);
+        let (gas_price, gas_used) = match self.get_transaction_gas(&signature).await {
+            Ok(gas) => gas,
+            Err(err) => {
+                warn!(?err, ?signature, "Failed to get process transaction gas");
+                (FixedPointNumber::zero(), U256::zero())
+            }
+        };
+        let txid = signature.into();
+
+        Ok(TxOutcome {
+            transaction_id: txid,
+            executed,
+            gas_price,
+            gas_used,
+        })
+    }
+}
+
+/// A process transaction paid for by `payer`, yet to be signed.
+fn unsigned_process_transaction(
+    payer: &Pubkey,
+    inbox_instructions: Vec<Instruction>,
+    compute_budget: ProcessComputeBudget,
+    recent_blockhash: &Hash,
+) -> Transaction {
+    Transaction::new_unsigned(Message::new_with_blockhash(
+        &compute_budget.instructions(inbox_instructions),
+        Some(payer),
+        recent_blockhash,
+    ))
+}
+
+/// Pads simulated compute units for the variance between simulation and
+/// execution.
+fn pad_compute_units(units: u64) -> u64 {
+    units.saturating_add(units * PROCESS_COMPUTE_UNITS_PADDING_PERCENT / 100)
+}
+
+/// The cost of a process transaction needing `units` compute units, for which
+/// `fee` lamports are charged in total.
+fn process_cost_estimate(units: u64, fee: u64) -> ChainResult<TxCostEstimate> {
+    Ok(TxCostEstimate {
+        gas_limit: units.into(),
+        gas_price: FixedPointNumber::from(fee).checked_div(units.max(1))?,
+        l2_gas_limit: None,
+    })
+}
+
+/// Size of a transaction once serialized: the signatures (prefixed by their
+/// compact-u16 count) followed by the message.
+fn transaction_size(txn: &Transaction) -> usize {
+    1 + txn.signatures.len() * std::mem::size_of::<Signature>() + txn.message.serialize().len()
+}
+
 impl HyperlaneContract for SealevelMailbox {
     fn address(&self) -> H256 {
         self.program_id.to_bytes().into()
@@ -343,150 +643,199 @@ impl Mailbox for SealevelMailbox {
         metadata: &[u8],
         _tx_gas_limit: Option<U256>,
     ) -> ChainResult<TxOutcome> {
-        let recipient: Pubkey = message.recipient.0.into();
-        let mut encoded_message = vec![];
-        message.write_to(&mut encoded_message).unwrap();
-
         let payer = self
             .payer
             .as_ref()
             .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?;
 
-        let mut instructions = Vec::with_capacity(2);
-        // Set the compute unit limit.
-        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
-            PROCESS_COMPUTE_UNITS,
-        ));
-
-        // "processed" level commitment does not guarantee finality.
-        // roughly 5% of blocks end up on a dropped fork.
-        // However we don't want this function to be a bottleneck and there already
-        // is retry logic in the agents.
-        let commitment = CommitmentConfig::processed();
-
-        let (process_authority_key, _process_authority_bump) = Pubkey::try_find_program_address(
-            mailbox_process_authority_pda_seeds!(&recipient),
-            &self.program_id,
-        )
-        .ok_or_else(|| {
-            ChainCommunicationError::from_other_str(
-                "Could not find program address for process authority",
+        let inbox_instruction = self
+            .get_process_instruction(&payer.pubkey(), message, metadata)
+            .await?;
+        let recent_blockhash = self.get_recent_blockhash().await?;
+        let units = self
+            .simulate_process_compute_units(
+                &payer.pubkey(),
+                slice::from_ref(&inbox_instruction),
+                &recent_blockhash,
             )
-        })?;
-        let (processed_message_account_key, _processed_message_account_bump) =
-            Pubkey::try_find_program_address(
-                mailbox_processed_message_pda_seeds!(message.id()),
-                &self.program_id,
+            .await
+            .unwrap_or_else(|err| {
+                warn!(
+                    ?err,
+                    "Failed to simulate process transaction, requesting the maximum compute units"
+                );
+                MAX_TRANSACTION_COMPUTE_UNITS
+            });
+        let compute_budget = ProcessComputeBudget {
+            units: units.min(MAX_TRANSACTION_COMPUTE_UNITS) as u32,
+            unit_price: self
+                .get_process_compute_unit_price(slice::from_ref(&inbox_instruction))
+                .await,
+        };
+        let txn = self
+            .create_process_transaction(
+                payer,
+                vec![inbox_instruction],
+                compute_budget,
+                &recent_blockhash,
             )
-            .ok_or_else(|| {
-                ChainCommunicationError::from_other_str(
-                    "Could not find program address for processed message account",
-                )
-            })?;
-
-        // Get the account metas required for the recipient.InterchainSecurityModule instruction.
-        let ism_getter_account_metas = self.get_ism_getter_account_metas(recipient).await?;
-
-        // Get the recipient ISM.
-        let ism = self
-            .get_recipient_ism(recipient, ism_getter_account_metas.clone())
             .await?;
 
-        let ixn =
-            hyperlane_sealevel_mailbox::instruction::Instruction::InboxProcess(InboxProcess {
-                metadata: metadata.to_vec(),
-                message: encoded_message.clone(),
-            });
-        let ixn_data = ixn
-            .into_instruction_data()
-            .map_err(ChainCommunicationError::from_other)?;
+        tracing::info!(?txn, "Created sealevel transaction to process message");
 
-        // Craft the accounts for the transaction.
-        let mut accounts: Vec<AccountMeta> = vec![
-            AccountMeta::new_readonly(payer.pubkey(), true),
-            AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM).unwrap(), false),
-            AccountMeta::new(self.inbox.0, false),
-            AccountMeta::new_readonly(process_authority_key, false),
-            AccountMeta::new(processed_message_account_key, false),
-        ];
-        accounts.extend(ism_getter_account_metas);
-        accounts.extend([
-            AccountMeta::new_readonly(Pubkey::from_str(SPL_NOOP).unwrap(), false),
-            AccountMeta::new_readonly(ism, false),
-        ]);
+        self.send_process_transaction(&txn).await
+    }
 
-        // Get the account metas required for the ISM.Verify instruction.
-        let ism_verify_account_metas = self
-            .get_ism_verify_account_metas(ism, metadata.into(), encoded_message)
-            .await?;
-        accounts.extend(ism_verify_account_metas);
+    fn supports_batching(&self) -> bool {
+        true
+    }
 
-        // The recipient.
-        accounts.extend([AccountMeta::new_readonly(recipient, false)]);
+    #[instrument(err, ret, skip(self, messages), fields(batch_size = messages.len()))]
+    async fn process_batch(&self, messages: &[BatchItem]) -> ChainResult<BatchResult> {
+        let payer = self
+            .payer
+            .as_ref()
+            .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?;
 
-        // Get account metas required for the Handle instruction
-        let handle_account_metas = self.get_handle_account_metas(message).await?;
-        accounts.extend(handle_account_metas);
+        // Every transaction built for the batch, including the simulations,
+        // shares a single blockhash.
+        let recent_blockhash = self.get_recent_blockhash().await?;
+
+        // Transactions are atomic, so any message that fails to process on its
+        // own has to be left out of the batch.
+        let mut batched = vec![];
+        let mut batched_units = 0;
+        let mut failed_indexes = vec![];
+        for (index, item) in messages.iter().enumerate() {
+            let instruction = match self
+                .get_process_instruction(&payer.pubkey(), &item.message, &item.metadata)
+                .await
+            {
+                Ok(instruction) => instruction,
+                Err(err) => {
+                    warn!(?err, message_id = ?item.message.id(), "Failed to build process instruction");
+                    failed_indexes.push(index);
+                    continue;
+                }
+            };
+            let units = match self
+                .simulate_process_compute_units(
+                    &payer.pubkey(),
+                    slice::from_ref(&instruction),
+                    &recent_blockhash,
+                )
+                .await
+            {
+                Ok(units) => units,
+                Err(err) => {
+                    debug!(?err, message_id = ?item.message.id(), "Message would fail to process, leaving it out of the batch");
+                    failed_indexes.push(index);
+                    continue;
+                }
+            };
+
+            // Stop adding to the batch once the transaction would be too large
+            // or need more compute units than a transaction may use.
+            if batched_units + units > MAX_TRANSACTION_COMPUTE_UNITS {
+                failed_indexes.push(index);
+                continue;
+            }
+            let mut instructions: Vec<_> = batched.iter().map(|(_, ixn)| ixn).cloned().collect();
+            instructions.push(instruction.clone());
+            let compute_budget = ProcessComputeBudget {
+                units: MAX_TRANSACTION_COMPUTE_UNITS as u32,
+                unit_price: 0,
+            };
+            // Signatures don't change the size of the transaction, so there is
+            // no need to sign it.
+            let txn = unsigned_process_transaction(
+                &payer.pubkey(),
+                instructions,
+                compute_budget,
+                &recent_blockhash,
+            );
+            if transaction_size(&txn) > PACKET_DATA_SIZE {
+                failed_indexes.push(index);
+                continue;
+            }
+            batched.push((index, instruction));
+            batched_units += units;
+        }
 
-        let inbox_instruction = Instruction {
-            program_id: self.program_id,
-            data: ixn_data,
-            accounts,
-        };
-        instructions.push(inbox_instruction);
-        let (recent_blockhash, _) = self
-            .rpc()
-            .get_latest_blockhash_with_commitment(commitment)
-            .await
-            .map_err(ChainCommunicationError::from_other)?;
+        if batched.is_empty() {
+            return Ok(BatchResult {
+                outcome: None,
+                failed_indexes,
+                reverted_indexes: vec![],
+            });
+        }
 
-        let txn = Transaction::new_signed_with_payer(
-            &instructions,
-            Some(&payer.pubkey()),
-            &[payer],
-            recent_blockhash,
+        let instructions: Vec<_> = batched.into_iter().map(|(_, ixn)| ixn).collect();
+        let compute_budget = ProcessComputeBudget {
+            units: batched_units as u32,
+            unit_price: self.get_process_compute_unit_price(&instructions).await,
+        };
+        let txn = self
+            .create_process_transaction(payer, instructions, compute_budget, &recent_blockhash)
+            .await?;
+        tracing::info!(
+            ?txn,
+            "Created sealevel transaction to process message batch"
         );
-
-        tracing::info!(?txn, "Created sealevel transaction to process message");
-
-        let signature = self
-            .rpc()
-            .send_and_confirm_transaction(&txn)
-            .await
-            .map_err(ChainCommunicationError::from_other)?;
-
-        tracing::info!(?txn, ?signature, "Sealevel transaction sent");
-
-        let executed = self
-            .rpc()
-            .confirm_transaction_with_commitment(&signature, commitment)
-            .await
-            .map_err(|err| warn!("Failed to confirm inbox process transaction: {}", err))
-            .map(|ctx| ctx.value)
-            .unwrap_or(false);
-        let txid = signature.into();
-
-        Ok(TxOutcome {
-            transaction_id: txid,
-            executed,
-            // TODO use correct data upon integrating IGP support
-            gas_price: U256::zero().try_into()?,
-            gas_used: U256::zero(),
+        let outcome = self.send_process_transaction(&txn).await?;
+
+        // A transaction's instructions succeed or fail together, so no message
+        // can revert on its own
+        Ok(BatchResult {
+            outcome: Some(outcome),
+            failed_indexes,
+            reverted_indexes: vec![],
         })
     }
 
     #[instrument(err, ret, skip(self))]
     async fn process_estimate_costs(
         &self,
-        _message: &HyperlaneMessage,
-        _metadata: &[u8],
+        message: &HyperlaneMessage,
+        metadata: &[u8],
     ) -> ChainResult<TxCostEstimate> {
-        // TODO use correct data upon integrating IGP support
-        Ok(TxCostEstimate {
-            gas_limit: U256::zero(),
-            gas_price: FixedPointNumber::zero(),
-            l2_gas_limit: None,
-        })
+        let payer = self
+            .payer
+            .as_ref()
+            .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?;
+
+        let inbox_instruction = self
+            .get_process_instruction(&payer.pubkey(), message, metadata)
+            .await?;
+        let recent_blockhash = self.get_recent_blockhash().await?;
+        let units = self
+            .simulate_process_compute_units(
+                &payer.pubkey(),
+                slice::from_ref(&inbox_instruction),
+                &recent_blockhash,
+            )
+            .await?
+            .min(MAX_TRANSACTION_COMPUTE_UNITS);
+        let compute_budget = ProcessComputeBudget {
+            units: units as u32,
+            unit_price: self
+                .get_process_compute_unit_price(slice::from_ref(&inbox_instruction))
+                .await,
+        };
+
+        // Fees are charged per transaction, covering both the signatures and
+        // the priority fee, so they're quoted as a price per compute unit.
+        let fee = self
+            .rpc()
+            .get_fee_for_message(&Message::new_with_blockhash(
+                &compute_budget.instructions(vec![inbox_instruction]),
+                Some(&payer.pubkey()),
+                &recent_blockhash,
+            ))
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+
+        process_cost_estimate(units, fee)
     }
 
     fn process_calldata(&self, _message: &HyperlaneMessage, _metadata: &[u8]) -> Vec<u8> {
@@ -612,21 +961,98 @@ impl SealevelMailboxIndexer {
                 .into_inner();
         let hyperlane_message =
             HyperlaneMessage::read_from(&mut &dispatched_message_account.encoded_message[..])?;
+        let log_meta = self
+            .get_log_meta(
+                &valid_message_storage_pda_pubkey,
+                dispatched_message_account.slot,
+                nonce.into(),
+            )
+            .await?;
+
+        Ok((hyperlane_message, log_meta))
+    }
 
-        Ok((
-            hyperlane_message,
-            LogMeta {
-                address: self.mailbox.program_id.to_bytes().into(),
-                block_number: dispatched_message_account.slot,
-                // TODO: get these when building out scraper support.
-                // It's inconvenient to get these :|
-                block_hash: H256::zero(),
-                transaction_id: H512::zero(),
-                transaction_index: 0,
-                log_index: U256::zero(),
+    async fn get_delivery_with_sequence(&self, sequence: u64) -> ChainResult<(H256, LogMeta)> {
+        let target_delivery_account_bytes = &[
+            &hyperlane_sealevel_mailbox::accounts::PROCESSED_MESSAGE_DISCRIMINATOR[..],
+            &sequence.to_le_bytes()[..],
+        ]
+        .concat();
+        let target_delivery_account_bytes = base64::encode(target_delivery_account_bytes);
+
+        // Processed message accounts are small enough that we request them in
+        // full and validate them as they come back.
+        let memcmp = RpcFilterType::Memcmp(Memcmp {
+            // Ignore the first byte, which is the `initialized` bool flag.
+            offset: 1,
+            bytes: MemcmpEncodedBytes::Base64(target_delivery_account_bytes),
+            encoding: None,
+        });
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![memcmp]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: None,
+                commitment: Some(CommitmentConfig::finalized()),
+                min_context_slot: None,
             },
+            with_context: Some(false),
+        };
+        let accounts = self
+            .rpc()
+            .get_program_accounts_with_config(&self.mailbox.program_id, config)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+
+        // Find the account whose pubkey proves it's an actual processed message PDA.
+        for (pubkey, account) in accounts {
+            let Ok(processed_message_account) =
+                ProcessedMessageAccount::fetch(&mut account.data.as_ref())
+            else {
+                continue;
+            };
+            let processed_message = processed_message_account.into_inner();
+            let (expected_pubkey, _bump) = Pubkey::try_find_program_address(
+                mailbox_processed_message_pda_seeds!(processed_message.message_id),
+                &self.mailbox.program_id,
+            )
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "Could not find program address for message_id",
+                )
+            })?;
+            if expected_pubkey == pubkey {
+                let log_meta = self
+                    .get_log_meta(&pubkey, processed_message.slot, sequence)
+                    .await?;
+                return Ok((processed_message.message_id, log_meta));
+            }
+        }
+
+        Err(ChainCommunicationError::from_other_str(
+            "Could not find valid processed message PDA pubkey",
         ))
     }
+
+    /// Builds the `LogMeta` for an event recorded in a storage PDA. Sealevel
+    /// programs don't emit indexable logs, so the sequence number of the event
+    /// stands in for the log index.
+    async fn get_log_meta(
+        &self,
+        storage_pda_pubkey: &Pubkey,
+        slot: u64,
+        sequence: u64,
+    ) -> ChainResult<LogMeta> {
+        let creation_meta = get_account_creation_meta(self.rpc(), storage_pda_pubkey, slot).await;
+        Ok(LogMeta {
+            address: self.mailbox.program_id.to_bytes().into(),
+            block_number: slot,
+            block_hash: creation_meta.block_hash,
+            transaction_id: creation_meta.transaction_id,
+            transaction_index: creation_meta.transaction_index,
+            log_index: sequence.into(),
+        })
+    }
 }
 
 #[async_trait]
@@ -666,8 +1092,15 @@ impl Indexer<HyperlaneMessage> for SealevelMailboxIndexer {
 
 #[async_trait]
 impl Indexer<H256> for SealevelMailboxIndexer {
-    async fn fetch_logs(&self, _range: RangeInclusive<u32>) -> ChainResult<Vec<(H256, LogMeta)>> {
-        todo!()
+    async fn fetch_logs(&self, range: RangeInclusive<u32>) -> ChainResult<Vec<(H256, LogMeta)>> {
+        info!(?range, "Fetching SealevelMailboxIndexer delivery logs");
+
+        let delivery_capacity = range.end().saturating_sub(*range.start());
+        let mut deliveries = Vec::with_capacity(delivery_capacity as usize);
+        for sequence in range {
+            deliveries.push(self.get_delivery_with_sequence(sequence.into()).await?);
+        }
+        Ok(deliveries)
     }
 
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
@@ -678,10 +1111,24 @@ impl Indexer<H256> for SealevelMailboxIndexer {
 #[async_trait]
 impl SequenceAwareIndexer<H256> for SealevelMailboxIndexer {
     async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
-        // TODO: implement when sealevel scraper support is implemented
-        info!("Message delivery indexing not implemented");
         let tip = Indexer::<H256>::get_finalized_block_number(self).await?;
-        Ok((Some(1), tip))
+        let inbox_account = self
+            .rpc()
+            .get_account_with_commitment(&self.mailbox.inbox.0, CommitmentConfig::finalized())
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .value
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str("Could not find inbox account")
+            })?;
+        let inbox = InboxAccount::fetch(&mut inbox_account.data.as_ref())
+            .map_err(ChainCommunicationError::from_other)?
+            .into_inner();
+        let count = inbox
+            .processed_count
+            .try_into()
+            .map_err(StrOrIntParseError::from)?;
+        Ok((Some(count), tip))
     }
 }
 
@@ -695,3 +1142,40 @@ impl HyperlaneAbi for SealevelMailboxAbi {
         todo!()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pad_compute_units() {
+        assert_eq!(pad_compute_units(0), 0);
+        assert_eq!(pad_compute_units(9), 9);
+        assert_eq!(pad_compute_units(100_000), 110_000);
+        assert_eq!(pad_compute_units(123_456), 135_801);
+        // Padded units above the transaction limit are capped by the callers
+        assert!(pad_compute_units(MAX_TRANSACTION_COMPUTE_UNITS) > MAX_TRANSACTION_COMPUTE_UNITS);
+    }
+
+    #[test]
+    fn test_process_cost_estimate() {
+        let estimate = process_cost_estimate(200_000, 5_000 + 200_000).unwrap();
+        assert_eq!(estimate.gas_limit, U256::from(200_000));
+        assert_eq!(
+            estimate.gas_price,
+            FixedPointNumber::from(205_000)
+                .checked_div(200_000)
+                .unwrap()
+        );
+        assert_eq!(
+            estimate.gas_price * 200_000u64,
+            FixedPointNumber::from(205_000)
+        );
+        assert_eq!(estimate.l2_gas_limit, None);
+
+        // Zero compute units quote the whole fee as the price
+        let estimate = process_cost_estimate(0, 5_000).unwrap();
+        assert_eq!(estimate.gas_limit, U256::zero());
+        assert_eq!(estimate.gas_price, FixedPointNumber::from(5_000));
+    }
+}
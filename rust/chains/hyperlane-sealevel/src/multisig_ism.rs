@@ -8,12 +8,11 @@ use serializable_account_meta::SimulationReturnData;
 use solana_sdk::{
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
-    signature::Keypair,
 };
 
 use crate::{
     utils::{get_account_metas, simulate_instruction},
-    ConnectionConf, RpcClientWithDebug, SealevelProvider,
+    ConnectionConf, RpcClientWithDebug, SealevelProvider, SealevelSigner,
 };
 
 use hyperlane_sealevel_multisig_ism_message_id::instruction::ValidatorsAndThreshold;
@@ -24,7 +23,7 @@ use multisig_ism::interface::{
 /// A reference to a MultisigIsm contract on some Sealevel chain
 #[derive(Debug)]
 pub struct SealevelMultisigIsm {
-    payer: Option<Keypair>,
+    payer: Option<SealevelSigner>,
     program_id: Pubkey,
     domain: HyperlaneDomain,
     provider: SealevelProvider,
@@ -32,7 +31,11 @@ pub struct SealevelMultisigIsm {
 
 impl SealevelMultisigIsm {
     /// Create a new Sealevel MultisigIsm.
-    pub fn new(conf: &ConnectionConf, locator: ContractLocator, payer: Option<Keypair>) -> Self {
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        payer: Option<SealevelSigner>,
+    ) -> Self {
         let provider = SealevelProvider::new(locator.domain.clone(), conf);
         let program_id = Pubkey::from(<[u8; 32]>::from(locator.address));
 
@@ -2,14 +2,27 @@ use std::{str::FromStr, sync::Arc};
 
 use async_trait::async_trait;
 
+use borsh::BorshDeserialize;
 use hyperlane_core::{
-    BlockInfo, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain, HyperlaneProvider, TxnInfo,
-    H256, U256,
+    BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain,
+    HyperlaneProvider, TxnInfo, TxnReceiptInfo, H256, H512, U256,
 };
-use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use solana_client::rpc_config::{RpcBlockConfig, RpcTransactionConfig};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, compute_budget::ComputeBudgetInstruction, hash::Hash,
+    pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction,
+};
+use solana_transaction_status::{TransactionDetails, UiTransactionEncoding};
+use tracing::instrument;
 
 use crate::{client::RpcClientWithDebug, error::HyperlaneSealevelError, ConnectionConf};
 
+/// The compute units a transaction may use per instruction when it doesn't
+/// request a limit itself.
+const DEFAULT_INSTRUCTION_COMPUTE_UNITS: u64 = 200_000;
+/// The most compute units a transaction may use.
+pub(crate) const MAX_TRANSACTION_COMPUTE_UNITS: u64 = 1_400_000;
+
 /// A wrapper around a Sealevel provider to get generic blockchain information.
 #[derive(Debug)]
 pub struct SealevelProvider {
@@ -46,6 +59,45 @@ impl SealevelProvider {
     }
 }
 
+/// The compute unit limit of `txn`: either the limit it requested through the
+/// compute budget program, or the runtime default for its instructions.
+fn compute_unit_limit(txn: &VersionedTransaction) -> u64 {
+    let account_keys = txn.message.static_account_keys();
+    let mut requested_limit = None;
+    let mut instruction_count = 0;
+    for instruction in txn.message.instructions() {
+        let program_id = account_keys.get(instruction.program_id_index as usize);
+        if program_id != Some(&solana_sdk::compute_budget::id()) {
+            instruction_count += 1;
+            continue;
+        }
+        match ComputeBudgetInstruction::try_from_slice(&instruction.data) {
+            Ok(ComputeBudgetInstruction::SetComputeUnitLimit(limit)) => {
+                requested_limit = Some(limit.into())
+            }
+            Ok(ComputeBudgetInstruction::RequestUnitsDeprecated { units, .. }) => {
+                requested_limit = Some(units.into())
+            }
+            _ => {}
+        }
+    }
+    requested_limit
+        .unwrap_or(instruction_count * DEFAULT_INSTRUCTION_COMPUTE_UNITS)
+        .min(MAX_TRANSACTION_COMPUTE_UNITS)
+}
+
+/// The program invoked by the first instruction of `txn` that isn't a compute
+/// budget instruction.
+fn first_invoked_program(txn: &VersionedTransaction) -> Option<H256> {
+    let account_keys = txn.message.static_account_keys();
+    txn.message
+        .instructions()
+        .iter()
+        .filter_map(|instruction| account_keys.get(instruction.program_id_index as usize))
+        .find(|program_id| **program_id != solana_sdk::compute_budget::id())
+        .map(|program_id| H256::from(program_id.to_bytes()))
+}
+
 impl HyperlaneChain for SealevelProvider {
     fn domain(&self) -> &HyperlaneDomain {
         &self.domain
@@ -61,12 +113,95 @@ impl HyperlaneChain for SealevelProvider {
 
 #[async_trait]
 impl HyperlaneProvider for SealevelProvider {
-    async fn get_block_by_hash(&self, _hash: &H256) -> ChainResult<BlockInfo> {
-        todo!() // FIXME
+    async fn get_block_by_hash(&self, hash: &H256) -> ChainResult<BlockInfo> {
+        // Sealevel RPCs index blocks by slot only.
+        Err(ChainCommunicationError::CustomError(format!(
+            "Sealevel blocks can only be looked up by slot, not by hash {hash:?}"
+        )))
     }
 
-    async fn get_txn_by_hash(&self, _hash: &H256) -> ChainResult<TxnInfo> {
-        todo!() // FIXME
+    #[instrument(err, skip(self))]
+    async fn get_block_by_height(&self, height: u64) -> ChainResult<BlockInfo> {
+        let block = self
+            .rpc_client
+            .get_block_with_config(
+                height,
+                RpcBlockConfig {
+                    transaction_details: Some(TransactionDetails::None),
+                    rewards: Some(false),
+                    commitment: Some(CommitmentConfig::finalized()),
+                    max_supported_transaction_version: Some(0),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        let hash = Hash::from_str(&block.blockhash).map_err(ChainCommunicationError::from_other)?;
+
+        Ok(BlockInfo {
+            hash: H256::from(hash.to_bytes()),
+            timestamp: block.block_time.unwrap_or_default() as u64,
+            number: height,
+        })
+    }
+
+    #[instrument(err, skip(self))]
+    async fn get_txn_by_hash(&self, hash: &H512) -> ChainResult<TxnInfo> {
+        let signature = Signature::new(hash.as_bytes());
+        let txn = self
+            .rpc_client
+            .get_transaction_with_config(
+                &signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(CommitmentConfig::finalized()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .transaction;
+        let meta = txn.meta.ok_or_else(|| {
+            ChainCommunicationError::from_other_str("Transaction has no status meta")
+        })?;
+        let decoded = txn.transaction.decode().ok_or_else(|| {
+            ChainCommunicationError::from_other_str("Could not decode transaction")
+        })?;
+
+        // The fee payer is always the first account.
+        let sender = decoded
+            .message
+            .static_account_keys()
+            .first()
+            .map(|payer| H256::from(payer.to_bytes()))
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str("Transaction has no accounts")
+            })?;
+
+        // Fees are paid in lamports for the whole transaction, so the price
+        // is the fee spread over the compute units consumed, rounded up.
+        let compute_units_consumed: u64 =
+            Option::from(meta.compute_units_consumed).unwrap_or_default();
+        let gas_used = U256::from(compute_units_consumed);
+        let priced_units = gas_used.max(U256::one());
+        let gas_price = (U256::from(meta.fee) + priced_units - 1) / priced_units;
+
+        Ok(TxnInfo {
+            hash: *hash,
+            gas_limit: compute_unit_limit(&decoded).into(),
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_price: Some(gas_price),
+            // Sealevel has no account nonces
+            nonce: 0,
+            sender,
+            recipient: first_invoked_program(&decoded),
+            receipt: Some(TxnReceiptInfo {
+                gas_used,
+                cumulative_gas_used: gas_used,
+                effective_gas_price: Some(gas_price),
+            }),
+        })
     }
 
     async fn is_contract(&self, _address: &H256) -> ChainResult<bool> {
@@ -82,3 +217,41 @@ impl HyperlaneProvider for SealevelProvider {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use solana_sdk::{instruction::Instruction, message::Message, transaction::Transaction};
+
+    use super::*;
+
+    fn versioned_transaction(instructions: &[Instruction]) -> VersionedTransaction {
+        let payer = Pubkey::new_unique();
+        Transaction::new_unsigned(Message::new(instructions, Some(&payer))).into()
+    }
+
+    #[test]
+    fn test_compute_unit_limit() {
+        let program_id = Pubkey::new_unique();
+        let instruction = Instruction::new_with_bytes(program_id, &[], vec![]);
+
+        let txn = versioned_transaction(&[instruction.clone(), instruction.clone()]);
+        assert_eq!(
+            compute_unit_limit(&txn),
+            2 * DEFAULT_INSTRUCTION_COMPUTE_UNITS
+        );
+        assert_eq!(
+            first_invoked_program(&txn),
+            Some(H256::from(program_id.to_bytes()))
+        );
+
+        let txn = versioned_transaction(&[
+            ComputeBudgetInstruction::set_compute_unit_limit(300_000),
+            instruction,
+        ]);
+        assert_eq!(compute_unit_limit(&txn), 300_000);
+        assert_eq!(
+            first_invoked_program(&txn),
+            Some(H256::from(program_id.to_bytes()))
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, RoutingIsm, H256,
+};
+use hyperlane_sealevel_domain_routing_ism::{accounts::DomainRouteAccount, domain_route_pda_seeds};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+
+use crate::{ConnectionConf, RpcClientWithDebug, SealevelProvider};
+
+/// A reference to a RoutingIsm contract on some Sealevel chain
+#[derive(Debug)]
+pub struct SealevelRoutingIsm {
+    program_id: Pubkey,
+    domain: HyperlaneDomain,
+    provider: SealevelProvider,
+}
+
+impl SealevelRoutingIsm {
+    /// Create a new Sealevel RoutingIsm.
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> Self {
+        let provider = SealevelProvider::new(locator.domain.clone(), conf);
+        let program_id = Pubkey::from(<[u8; 32]>::from(locator.address));
+
+        Self {
+            program_id,
+            domain: locator.domain.clone(),
+            provider,
+        }
+    }
+
+    fn rpc(&self) -> &RpcClientWithDebug {
+        self.provider.rpc()
+    }
+}
+
+impl HyperlaneContract for SealevelRoutingIsm {
+    fn address(&self) -> H256 {
+        self.program_id.to_bytes().into()
+    }
+}
+
+impl HyperlaneChain for SealevelRoutingIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self.provider.provider()
+    }
+}
+
+#[async_trait]
+impl RoutingIsm for SealevelRoutingIsm {
+    /// Returns the ISM needed to verify message
+    async fn route(&self, message: &HyperlaneMessage) -> ChainResult<H256> {
+        let ism = get_domain_ism(self.rpc(), &self.program_id, message.origin).await?;
+
+        Ok(ism.to_bytes().into())
+    }
+}
+
+/// Gets the ISM the routing ISM `program_id` routes messages from `domain`
+/// to, from the domain's route account.
+pub(crate) async fn get_domain_ism(
+    rpc_client: &RpcClientWithDebug,
+    program_id: &Pubkey,
+    domain: u32,
+) -> ChainResult<Pubkey> {
+    let (domain_route_pda_key, _domain_route_pda_bump) =
+        Pubkey::try_find_program_address(domain_route_pda_seeds!(domain), program_id).ok_or_else(
+            || {
+                ChainCommunicationError::from_other_str(
+                    "Could not find program address for routing ISM domain route",
+                )
+            },
+        )?;
+
+    let domain_route_account = rpc_client
+        .get_account_with_commitment(&domain_route_pda_key, CommitmentConfig::finalized())
+        .await
+        .map_err(ChainCommunicationError::from_other)?
+        .value;
+    let domain_route = domain_route_account
+        .map(|account| DomainRouteAccount::fetch(&mut &account.data[..]))
+        .transpose()
+        .map_err(ChainCommunicationError::from_other)?
+        .map(|domain_route| domain_route.into_inner());
+
+    domain_route
+        .and_then(|domain_route| domain_route.ism)
+        .ok_or_else(|| {
+            ChainCommunicationError::CustomError(format!(
+                "No ISM for origin domain {domain} in routing ISM {program_id}"
+            ))
+        })
+}
@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use hyperlane_core::{ChainCommunicationError, ChainResult, KmsKey};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer as _, SIGNATURE_BYTES},
+    transaction::Transaction,
+};
+
+/// Signs Sealevel transactions, with a keypair held in memory or with an
+/// ed25519 key in a key management service.
+#[derive(Debug)]
+pub enum SealevelSigner {
+    /// A keypair held in memory
+    Keypair(Keypair),
+    /// An ed25519 key in a key management service
+    Kms {
+        /// The public key of the KMS key
+        pubkey: Pubkey,
+        /// The KMS key
+        key: Arc<dyn KmsKey>,
+    },
+}
+
+impl From<Keypair> for SealevelSigner {
+    fn from(keypair: Keypair) -> Self {
+        SealevelSigner::Keypair(keypair)
+    }
+}
+
+impl SealevelSigner {
+    /// Create a signer for an ed25519 key in a key management service
+    pub async fn new_kms(key: Arc<dyn KmsKey>) -> ChainResult<Self> {
+        let pubkey = key
+            .public_key()
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        let pubkey = Pubkey::try_from(pubkey.as_slice()).map_err(|_| {
+            ChainCommunicationError::from_other_str("KMS key is not an ed25519 key")
+        })?;
+        Ok(SealevelSigner::Kms { pubkey, key })
+    }
+
+    /// The signer's public key, which is also its address
+    pub fn pubkey(&self) -> Pubkey {
+        match self {
+            SealevelSigner::Keypair(keypair) => keypair.pubkey(),
+            SealevelSigner::Kms { pubkey, .. } => *pubkey,
+        }
+    }
+
+    /// Signs a serialized transaction message
+    pub async fn sign_message(&self, message: &[u8]) -> ChainResult<Signature> {
+        let (pubkey, key) = match self {
+            SealevelSigner::Keypair(keypair) => return Ok(keypair.sign_message(message)),
+            SealevelSigner::Kms { pubkey, key } => (pubkey, key),
+        };
+        let signature = key
+            .sign_message(message)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        if signature.len() != SIGNATURE_BYTES {
+            return Err(ChainCommunicationError::from_other_str(
+                "KMS returned an invalid ed25519 signature",
+            ));
+        }
+        let signature = Signature::new(&signature);
+        if !signature.verify(pubkey.as_ref(), message) {
+            return Err(ChainCommunicationError::from_other_str(
+                "KMS signature does not match the KMS key",
+            ));
+        }
+        Ok(signature)
+    }
+
+    /// Signs a transaction this signer is the only signer of, i.e. the fee
+    /// payer.
+    pub async fn sign_transaction(&self, transaction: &mut Transaction) -> ChainResult<()> {
+        let signature = self.sign_message(&transaction.message_data()).await?;
+        transaction.signatures = vec![signature];
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use async_trait::async_trait;
+    use hyperlane_core::{HyperlaneSignerError, H256};
+    use solana_sdk::{hash::Hash, message::Message, signature::Signer as _, system_instruction};
+
+    use super::*;
+
+    /// A local stand-in for a key management service
+    #[derive(Debug)]
+    struct LocalKms(Keypair);
+
+    #[async_trait]
+    impl KmsKey for LocalKms {
+        async fn public_key(&self) -> Result<Vec<u8>, HyperlaneSignerError> {
+            Ok(self.0.pubkey().to_bytes().to_vec())
+        }
+
+        async fn sign_digest(&self, _digest: &H256) -> Result<Vec<u8>, HyperlaneSignerError> {
+            unimplemented!("ed25519 keys sign messages")
+        }
+
+        async fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>, HyperlaneSignerError> {
+            Ok(self.0.sign_message(message).as_ref().to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kms_signer_signs_like_keypair() {
+        let keypair = Keypair::new();
+        let kms = SealevelSigner::new_kms(Arc::new(LocalKms(
+            Keypair::from_bytes(&keypair.to_bytes()).unwrap(),
+        )))
+        .await
+        .unwrap();
+        assert_eq!(kms.pubkey(), keypair.pubkey());
+
+        let message = Message::new_with_blockhash(
+            &[system_instruction::transfer(
+                &keypair.pubkey(),
+                &Pubkey::new_unique(),
+                1,
+            )],
+            Some(&keypair.pubkey()),
+            &Hash::new_unique(),
+        );
+        let mut transaction = Transaction::new_unsigned(message);
+        kms.sign_transaction(&mut transaction).await.unwrap();
+        let expected = Transaction::new(
+            &[&keypair],
+            transaction.message.clone(),
+            transaction.message.recent_blockhash,
+        );
+        assert_eq!(transaction, expected);
+        transaction.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_kms_signer_rejects_signatures_by_other_keys() {
+        let pubkey = Keypair::new().pubkey();
+        let kms = SealevelSigner::Kms {
+            pubkey,
+            key: Arc::new(LocalKms(Keypair::new())),
+        };
+        assert!(kms.sign_message(b"message").await.is_err());
+    }
+}
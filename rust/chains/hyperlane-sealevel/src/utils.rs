@@ -1,19 +1,29 @@
 use base64::Engine;
 use borsh::{BorshDeserialize, BorshSerialize};
-use hyperlane_core::{ChainCommunicationError, ChainResult};
+use std::str::FromStr;
 
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256, H512};
+
+use serde::Deserialize;
 use serializable_account_meta::{SerializableAccountMeta, SimulationReturnData};
-use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient, rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_config::RpcBlockConfig, rpc_request::RpcRequest,
+};
 use solana_sdk::{
+    clock::Slot,
     commitment_config::CommitmentConfig,
+    hash::Hash,
     instruction::{AccountMeta, Instruction},
     message::Message,
-    signature::{Keypair, Signer},
+    pubkey::Pubkey,
+    signature::Signature,
     transaction::Transaction,
 };
-use solana_transaction_status::UiReturnDataEncoding;
+use solana_transaction_status::{TransactionDetails, UiReturnDataEncoding};
+use tracing::warn;
 
-use crate::client::RpcClientWithDebug;
+use crate::{client::RpcClientWithDebug, SealevelSigner};
 
 /// Simulates an instruction, and attempts to deserialize it into a T.
 /// If no return data at all was returned, returns Ok(None).
@@ -21,7 +31,7 @@ use crate::client::RpcClientWithDebug;
 /// an Err is returned.
 pub async fn simulate_instruction<T: BorshDeserialize + BorshSerialize>(
     rpc_client: &RpcClient,
-    payer: &Keypair,
+    payer: &SealevelSigner,
     instruction: Instruction,
 ) -> ChainResult<Option<T>> {
     let commitment = CommitmentConfig::finalized();
@@ -59,7 +69,7 @@ pub async fn simulate_instruction<T: BorshDeserialize + BorshSerialize>(
 /// Simulates an Instruction that will return a list of AccountMetas.
 pub async fn get_account_metas(
     rpc_client: &RpcClient,
-    payer: &Keypair,
+    payer: &SealevelSigner,
     instruction: Instruction,
 ) -> ChainResult<Vec<AccountMeta>> {
     // If there's no data at all, default to an empty vec.
@@ -91,3 +101,169 @@ pub async fn get_finalized_block_number(rpc_client: &RpcClientWithDebug) -> Chai
         .expect("sealevel block height exceeds u32::MAX");
     Ok(height)
 }
+
+/// Where the transaction that created an account landed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccountCreationMeta {
+    /// Hash of the block the transaction was included in
+    pub block_hash: H256,
+    /// Signature of the transaction
+    pub transaction_id: H512,
+    /// Index of the transaction within its block
+    pub transaction_index: u64,
+}
+
+/// Finds the block hash, signature and block position of the transaction that
+/// created `account` in `slot`.
+///
+/// This is best-effort: the metadata is only needed to record where events
+/// landed, so rather than holding up indexing when it can't be fetched, it
+/// is left zeroed.
+pub async fn get_account_creation_meta(
+    rpc_client: &RpcClientWithDebug,
+    account: &Pubkey,
+    slot: Slot,
+) -> AccountCreationMeta {
+    fetch_account_creation_meta(rpc_client, account, slot)
+        .await
+        .unwrap_or_else(|err| {
+            warn!(
+                ?account,
+                slot,
+                ?err,
+                "Failed to get account creation metadata"
+            );
+            AccountCreationMeta::default()
+        })
+}
+
+/// Message and gas payment storage PDAs are written exactly once, by the
+/// transaction that dispatched, processed or paid for the message, so the
+/// successful transaction touching the account in its creation slot is the
+/// one we're after.
+async fn fetch_account_creation_meta(
+    rpc_client: &RpcClientWithDebug,
+    account: &Pubkey,
+    slot: Slot,
+) -> ChainResult<AccountCreationMeta> {
+    let signatures = rpc_client
+        .get_signatures_for_address_with_config(
+            account,
+            GetConfirmedSignaturesForAddress2Config {
+                commitment: Some(CommitmentConfig::finalized()),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    // Signatures are returned newest first, so the creating transaction is the
+    // last successful one in the creation slot.
+    let signature = signatures
+        .into_iter()
+        .rev()
+        .find(|status| status.slot == slot && status.err.is_none())
+        .ok_or_else(|| {
+            ChainCommunicationError::from_other_str(
+                "Could not find the transaction that created the account",
+            )
+        })?
+        .signature;
+
+    let block = rpc_client
+        .get_block_with_config(
+            slot,
+            RpcBlockConfig {
+                transaction_details: Some(TransactionDetails::Signatures),
+                rewards: Some(false),
+                commitment: Some(CommitmentConfig::finalized()),
+                max_supported_transaction_version: Some(0),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    let transaction_index = block
+        .signatures
+        .unwrap_or_default()
+        .iter()
+        .position(|block_signature| *block_signature == signature)
+        .ok_or_else(|| {
+            ChainCommunicationError::from_other_str("Transaction is not part of its block")
+        })?;
+
+    let block_hash =
+        Hash::from_str(&block.blockhash).map_err(ChainCommunicationError::from_other)?;
+    let signature = Signature::from_str(&signature).map_err(ChainCommunicationError::from_other)?;
+    Ok(AccountCreationMeta {
+        block_hash: H256::from(block_hash.to_bytes()),
+        transaction_id: signature.into(),
+        transaction_index: transaction_index as u64,
+    })
+}
+
+/// A prioritization fee paid in a recent slot, as returned by the
+/// `getRecentPrioritizationFees` RPC method.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecentPrioritizationFee {
+    /// Price per compute unit, in micro-lamports
+    prioritization_fee: u64,
+}
+
+/// Gets a compute unit price, in micro-lamports, that's competitive for
+/// transactions writing to `writable_accounts`: the median of the
+/// prioritization fees paid for those accounts in recent slots.
+pub async fn get_compute_unit_price(
+    rpc_client: &RpcClientWithDebug,
+    writable_accounts: &[Pubkey],
+) -> ChainResult<u64> {
+    let accounts: Vec<String> = writable_accounts.iter().map(Pubkey::to_string).collect();
+    let fees: Vec<u64> = rpc_client
+        .send::<Vec<RecentPrioritizationFee>>(
+            RpcRequest::Custom {
+                method: "getRecentPrioritizationFees",
+            },
+            serde_json::json!([accounts]),
+        )
+        .await
+        .map_err(ChainCommunicationError::from_other)?
+        .into_iter()
+        .map(|fee| fee.prioritization_fee)
+        .collect();
+    Ok(median_prioritization_fee(fees))
+}
+
+/// The median of the given prioritization fees, rounded down for an even
+/// number of fees, or no fee if there are none.
+fn median_prioritization_fee(mut fees: Vec<u64>) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+    fees.sort_unstable();
+    let mid = fees.len() / 2;
+    if fees.len() % 2 == 0 {
+        // Averaged without summing the fees, which could overflow
+        fees[mid - 1] / 2 + fees[mid] / 2 + (fees[mid - 1] % 2 + fees[mid] % 2) / 2
+    } else {
+        fees[mid]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_median_prioritization_fee() {
+        assert_eq!(median_prioritization_fee(vec![]), 0);
+        assert_eq!(median_prioritization_fee(vec![7]), 7);
+        assert_eq!(median_prioritization_fee(vec![9, 1, 5]), 5);
+        assert_eq!(median_prioritization_fee(vec![10, 0, 4, 2]), 3);
+        assert_eq!(median_prioritization_fee(vec![1, 2]), 1);
+        assert_eq!(median_prioritization_fee(vec![3, 3]), 3);
+        assert_eq!(
+            median_prioritization_fee(vec![u64::MAX, u64::MAX]),
+            u64::MAX
+        );
+    }
+}
@@ -108,6 +108,14 @@ impl ValidatorAnnounce for SealevelValidatorAnnounce {
         Ok(storage_locations)
     }
 
+    async fn get_announced_validators(&self) -> ChainResult<Vec<H256>> {
+        // Storage location accounts are only addressed by a hash of the
+        // validator, so the validators can't be recovered from them
+        Err(ChainCommunicationError::from_other_str(
+            "Listing announced validators is not supported on Sealevel",
+        ))
+    }
+
     async fn announce_tokens_needed(
         &self,
         _announcement: SignedType<Announcement>,
@@ -4,14 +4,17 @@ use paste::paste;
 use tracing::{debug, instrument, trace};
 
 use hyperlane_core::{
-    GasPaymentKey, HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage,
-    HyperlaneSequenceAwareIndexerStoreReader, HyperlaneWatermarkedLogStore,
-    InterchainGasExpenditure, InterchainGasPayment, InterchainGasPaymentMeta, LogMeta,
-    MerkleTreeInsertion, H256,
+    CheckpointWithMessageId, GasPaymentKey, HyperlaneDomain, HyperlaneInFlightTransactionStore,
+    HyperlaneLogStore, HyperlaneMessage, HyperlaneSequenceAwareIndexerStoreReader,
+    HyperlaneWatermarkedLogStore, InFlightTransaction, InterchainGasExpenditure,
+    InterchainGasPayment, InterchainGasPaymentMeta, LogMeta, MerkleTreeInsertion, H160, H256,
 };
 
 use super::{
-    storage_types::{InterchainGasExpenditureData, InterchainGasPaymentData},
+    storage_types::{
+        CheckpointFraudEvidence, InterchainGasExpenditureData, InterchainGasPaymentData,
+        PendingMessageState,
+    },
     DbError, TypedDB, DB,
 };
 
@@ -27,11 +30,21 @@ const GAS_PAYMENT_META_PROCESSED: &str = "gas_payment_meta_processed_v3_";
 const GAS_EXPENDITURE_FOR_MESSAGE_ID: &str = "gas_expenditure_for_message_id_v2_";
 const PENDING_MESSAGE_RETRY_COUNT_FOR_MESSAGE_ID: &str =
     "pending_message_retry_count_for_message_id_";
+const PENDING_MESSAGE_STATE_FOR_MESSAGE_ID: &str = "pending_message_state_for_message_id_";
 const MERKLE_TREE_INSERTION: &str = "merkle_tree_insertion_";
 const MERKLE_LEAF_INDEX_BY_MESSAGE_ID: &str = "merkle_leaf_index_by_message_id_";
 const MERKLE_TREE_INSERTION_BLOCK_NUMBER_BY_LEAF_INDEX: &str =
     "merkle_tree_insertion_block_number_by_leaf_index_";
 const LATEST_INDEXED_GAS_PAYMENT_BLOCK: &str = "latest_indexed_gas_payment_block";
+const IN_FLIGHT_TRANSACTION_BY_KEY: &str = "in_flight_transaction_by_key_";
+const SIGNED_CHECKPOINT_BY_MERKLE_TREE_HOOK_AND_INDEX: &str =
+    "signed_checkpoint_by_merkle_tree_hook_and_index_";
+const HIGHEST_SIGNED_CHECKPOINT_INDEX_BY_MERKLE_TREE_HOOK: &str =
+    "highest_signed_checkpoint_index_by_merkle_tree_hook_";
+const CHECKPOINT_FRAUD_EVIDENCE_BY_SEQUENCE: &str = "checkpoint_fraud_evidence_by_sequence_";
+const CHECKPOINT_FRAUD_EVIDENCE_COUNT: &str = "checkpoint_fraud_evidence_count";
+const CHECKPOINT_FRAUD_EVIDENCE_RECORDED_BY_VALIDATOR_AND_INDEX: &str =
+    "checkpoint_fraud_evidence_recorded_by_validator_and_index_";
 
 type DbResult<T> = std::result::Result<T, DbError>;
 
@@ -165,6 +178,122 @@ impl HyperlaneRocksDB {
         Ok(true)
     }
 
+    /// Records that `checkpoint` is about to be signed, unless a different
+    /// checkpoint was already signed at the same index of the same merkle tree
+    /// hook. This is the validator's slashing protection.
+    ///
+    /// Returns the previously signed checkpoint if it conflicts with
+    /// `checkpoint`, in which case nothing is recorded and `checkpoint` must
+    /// not be signed.
+    pub fn record_signed_checkpoint(
+        &self,
+        checkpoint: &CheckpointWithMessageId,
+    ) -> DbResult<Option<CheckpointWithMessageId>> {
+        if let Some(signed) =
+            self.retrieve_signed_checkpoint(&checkpoint.merkle_tree_hook_address, checkpoint.index)?
+        {
+            if signed == *checkpoint {
+                return Ok(None);
+            }
+            return Ok(Some(signed));
+        }
+
+        self.store_encodable(
+            SIGNED_CHECKPOINT_BY_MERKLE_TREE_HOOK_AND_INDEX,
+            signed_checkpoint_key(&checkpoint.merkle_tree_hook_address, checkpoint.index),
+            checkpoint,
+        )?;
+        let highest_index = self
+            .retrieve_highest_signed_checkpoint_index_by_merkle_tree_hook(
+                &checkpoint.merkle_tree_hook_address,
+            )?
+            .map_or(checkpoint.index, |index| index.max(checkpoint.index));
+        self.store_highest_signed_checkpoint_index_by_merkle_tree_hook(
+            &checkpoint.merkle_tree_hook_address,
+            &highest_index,
+        )?;
+        Ok(None)
+    }
+
+    /// Retrieves the checkpoint signed at `index` of the merkle tree hook
+    pub fn retrieve_signed_checkpoint(
+        &self,
+        merkle_tree_hook_address: &H256,
+        index: u32,
+    ) -> DbResult<Option<CheckpointWithMessageId>> {
+        self.retrieve_decodable(
+            SIGNED_CHECKPOINT_BY_MERKLE_TREE_HOOK_AND_INDEX,
+            signed_checkpoint_key(merkle_tree_hook_address, index),
+        )
+    }
+
+    /// Retrieves all the checkpoints signed for the merkle tree hook, ordered
+    /// by index
+    pub fn retrieve_signed_checkpoints(
+        &self,
+        merkle_tree_hook_address: &H256,
+    ) -> DbResult<Vec<CheckpointWithMessageId>> {
+        // Keys are the merkle tree hook address followed by the big endian
+        // index, so iterating over them yields the checkpoints ordered by index
+        self.prefix_iterator_decodable(
+            SIGNED_CHECKPOINT_BY_MERKLE_TREE_HOOK_AND_INDEX,
+            merkle_tree_hook_address,
+        )
+        .collect()
+    }
+
+    /// Persists evidence of a fraudulent checkpoint, unless evidence against
+    /// the same validator at the same index was already persisted.
+    ///
+    /// Returns whether the evidence was persisted.
+    pub fn store_checkpoint_fraud_evidence(
+        &self,
+        evidence: &CheckpointFraudEvidence,
+    ) -> DbResult<bool> {
+        let recorded_key = checkpoint_fraud_evidence_recorded_key(
+            &evidence.validator,
+            evidence.signed_checkpoint.value.index,
+        );
+        if self
+            .retrieve_decodable::<bool>(
+                CHECKPOINT_FRAUD_EVIDENCE_RECORDED_BY_VALIDATOR_AND_INDEX,
+                &recorded_key,
+            )?
+            .unwrap_or(false)
+        {
+            return Ok(false);
+        }
+
+        let count = self.retrieve_checkpoint_fraud_evidence_count()?;
+        self.store_checkpoint_fraud_evidence_by_sequence(&count, evidence)?;
+        self.store_encodable("", CHECKPOINT_FRAUD_EVIDENCE_COUNT, &(count + 1))?;
+        self.store_encodable(
+            CHECKPOINT_FRAUD_EVIDENCE_RECORDED_BY_VALIDATOR_AND_INDEX,
+            recorded_key,
+            &true,
+        )?;
+        Ok(true)
+    }
+
+    /// Retrieves all the persisted evidence of fraudulent checkpoints, in the
+    /// order it was persisted
+    pub fn retrieve_checkpoint_fraud_evidence(&self) -> DbResult<Vec<CheckpointFraudEvidence>> {
+        let count = self.retrieve_checkpoint_fraud_evidence_count()?;
+        let mut evidence = Vec::with_capacity(count as usize);
+        for sequence in 0..count {
+            if let Some(e) = self.retrieve_checkpoint_fraud_evidence_by_sequence(&sequence)? {
+                evidence.push(e);
+            }
+        }
+        Ok(evidence)
+    }
+
+    fn retrieve_checkpoint_fraud_evidence_count(&self) -> DbResult<u32> {
+        Ok(self
+            .retrieve_decodable("", CHECKPOINT_FRAUD_EVIDENCE_COUNT)?
+            .unwrap_or(0))
+    }
+
     /// Processes the gas expenditure and store the total expenditure for the
     /// message.
     pub fn process_gas_expenditure(&self, expenditure: InterchainGasExpenditure) -> DbResult<()> {
@@ -330,6 +459,34 @@ where
     }
 }
 
+impl HyperlaneInFlightTransactionStore for HyperlaneRocksDB {
+    fn retrieve_in_flight_transaction(&self, key: &H256) -> Result<Option<InFlightTransaction>> {
+        let tx = self.retrieve_in_flight_transaction_by_key(key)?;
+        Ok(tx)
+    }
+
+    fn store_in_flight_transaction(&self, key: &H256, tx: &InFlightTransaction) -> Result<()> {
+        self.store_in_flight_transaction_by_key(key, tx)?;
+        Ok(())
+    }
+}
+
+/// Signed checkpoints are keyed by the merkle tree hook they were signed for
+/// as well as their index, so that a redeployed hook starts from a clean slate.
+fn signed_checkpoint_key(merkle_tree_hook_address: &H256, index: u32) -> Vec<u8> {
+    let mut key = merkle_tree_hook_address.to_vec();
+    key.extend(index.to_vec());
+    key
+}
+
+/// Fraud evidence is recorded at most once per validator and index, no matter
+/// how often the watchtower comes across the checkpoint.
+fn checkpoint_fraud_evidence_recorded_key(validator: &H160, index: u32) -> Vec<u8> {
+    let mut key = validator.to_vec();
+    key.extend(index.to_vec());
+    key
+}
+
 /// Generate a call to ChainSetup for the given builder
 macro_rules! make_store_and_retrieve {
     ($vis:vis, $name_suffix:ident, $key_prefix: ident, $key_ty:ty, $val_ty:ty$(,)?) => {
@@ -357,7 +514,7 @@ macro_rules! make_store_and_retrieve {
 }
 
 make_store_and_retrieve!(pub, message_id_by_nonce, MESSAGE_ID, u32, H256);
-make_store_and_retrieve!(pub(self), message_by_id, MESSAGE, H256, HyperlaneMessage);
+make_store_and_retrieve!(pub, message_by_id, MESSAGE, H256, HyperlaneMessage);
 make_store_and_retrieve!(pub(self), dispatched_block_number_by_nonce, MESSAGE_DISPATCHED_BLOCK_NUMBER, u32, u64);
 make_store_and_retrieve!(pub, processed_by_nonce, NONCE_PROCESSED, u32, bool);
 make_store_and_retrieve!(pub(self), processed_by_gas_payment_meta, GAS_PAYMENT_META_PROCESSED, InterchainGasPaymentMeta, bool);
@@ -370,6 +527,13 @@ make_store_and_retrieve!(
     H256,
     u32
 );
+make_store_and_retrieve!(
+    pub,
+    pending_message_state_by_message_id,
+    PENDING_MESSAGE_STATE_FOR_MESSAGE_ID,
+    H256,
+    PendingMessageState
+);
 make_store_and_retrieve!(
     pub,
     merkle_tree_insertion_by_leaf_index,
@@ -391,3 +555,24 @@ make_store_and_retrieve!(
     u32,
     u64
 );
+make_store_and_retrieve!(
+    pub(self),
+    in_flight_transaction_by_key,
+    IN_FLIGHT_TRANSACTION_BY_KEY,
+    H256,
+    InFlightTransaction
+);
+make_store_and_retrieve!(
+    pub(self),
+    highest_signed_checkpoint_index_by_merkle_tree_hook,
+    HIGHEST_SIGNED_CHECKPOINT_INDEX_BY_MERKLE_TREE_HOOK,
+    H256,
+    u32
+);
+make_store_and_retrieve!(
+    pub(self),
+    checkpoint_fraud_evidence_by_sequence,
+    CHECKPOINT_FRAUD_EVIDENCE_BY_SEQUENCE,
+    u32,
+    CheckpointFraudEvidence
+);
@@ -6,6 +6,9 @@ use rocksdb::{Options, DB as Rocks};
 use tracing::info;
 
 pub use hyperlane_db::*;
+pub use storage_types::{
+    CheckpointFraudEvidence, CheckpointFraudKind, OperationDisposition, PendingMessageState,
+};
 pub use typed_db::*;
 
 /// Shared functionality surrounding use of rocksdb
@@ -16,7 +19,7 @@ mod hyperlane_db;
 /// Type-specific db operations
 mod typed_db;
 
-/// Internal-use storage types.
+/// Storage types, mostly for internal use.
 mod storage_types;
 
 /// Database test utilities.
@@ -109,4 +112,9 @@ impl DB {
     pub fn retrieve(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
         Ok(self.0.get(key)?)
     }
+
+    /// Iterate over the values of the keys starting with `prefix`, in key order
+    pub fn prefix_iterator<V>(&self, prefix: Vec<u8>) -> iterator::PrefixIterator<'_, V> {
+        iterator::PrefixIterator::new(self.0.prefix_iterator(&prefix), prefix)
+    }
 }
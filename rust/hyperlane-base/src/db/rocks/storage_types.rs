@@ -1,9 +1,10 @@
-use std::io::{Read, Write};
+use std::io::{Error, ErrorKind, Read, Write};
 
 use hyperlane_core::{
-    Decode, Encode, HyperlaneProtocolError, InterchainGasExpenditure, InterchainGasPayment, H256,
-    U256,
+    CheckpointWithMessageId, Decode, Encode, HyperlaneProtocolError, InterchainGasExpenditure,
+    InterchainGasPayment, SignedCheckpointWithMessageId, H160, H256, U256,
 };
+use serde::{Deserialize, Serialize};
 
 /// Subset of `InterchainGasPayment` excluding the message id which is stored in
 /// the key.
@@ -121,3 +122,205 @@ impl Decode for InterchainGasExpenditureData {
         })
     }
 }
+
+/// How an operator has asked the relayer to treat a pending message.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OperationDisposition {
+    /// The message is processed as usual.
+    #[default]
+    Normal,
+    /// The message is only attempted once all normal messages have been.
+    Deprioritized,
+    /// The message is kept in the queue but never submitted.
+    Paused,
+    /// The message has been removed and will not be attempted again, even
+    /// after a restart.
+    Dropped,
+}
+
+impl Encode for OperationDisposition {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        writer.write_all(&[*self as u8])?;
+        Ok(1)
+    }
+}
+
+impl Decode for OperationDisposition {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        let mut buf = [0; 1];
+        reader.read_exact(&mut buf)?;
+        match buf[0] {
+            0 => Ok(Self::Normal),
+            1 => Ok(Self::Deprioritized),
+            2 => Ok(Self::Paused),
+            3 => Ok(Self::Dropped),
+            _ => Err(HyperlaneProtocolError::IoError(Error::new(
+                ErrorKind::InvalidData,
+                "decoded operation disposition invalid",
+            ))),
+        }
+    }
+}
+
+/// Snapshot of the queue state of a pending message, so it can be restored
+/// when the relayer restarts.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PendingMessageState {
+    /// Operator requested handling of the message.
+    pub disposition: OperationDisposition,
+    /// The last error encountered when attempting the message.
+    pub last_error: Option<String>,
+    /// The number of times the message has been retried.
+    pub num_retries: u32,
+    /// Unix timestamp in milliseconds before which the message is not attempted
+    /// again.
+    pub next_attempt_after: Option<u64>,
+}
+
+impl Encode for PendingMessageState {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        let mut written = self.disposition.write_to(writer)?;
+        let last_error = self.last_error.as_deref().unwrap_or_default().as_bytes();
+        written += self.last_error.is_some().write_to(writer)?;
+        written += (last_error.len() as u32).write_to(writer)?;
+        writer.write_all(last_error)?;
+        written += last_error.len();
+        written += self.num_retries.write_to(writer)?;
+        written += self.next_attempt_after.is_some().write_to(writer)?;
+        written += self
+            .next_attempt_after
+            .unwrap_or_default()
+            .write_to(writer)?;
+        Ok(written)
+    }
+}
+
+impl Decode for PendingMessageState {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        let disposition = OperationDisposition::read_from(reader)?;
+        let has_error = bool::read_from(reader)?;
+        let mut last_error = vec![0; u32::read_from(reader)? as usize];
+        reader.read_exact(&mut last_error)?;
+        let last_error = has_error
+            .then(|| String::from_utf8(last_error))
+            .transpose()
+            .map_err(|e| HyperlaneProtocolError::IoError(Error::new(ErrorKind::InvalidData, e)))?;
+        let num_retries = u32::read_from(reader)?;
+        let has_next_attempt = bool::read_from(reader)?;
+        let next_attempt_after = u64::read_from(reader)?;
+        Ok(Self {
+            disposition,
+            last_error,
+            num_retries,
+            next_attempt_after: has_next_attempt.then_some(next_attempt_after),
+        })
+    }
+}
+
+/// How a validator's signed checkpoint contradicts the origin chain's merkle
+/// tree.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckpointFraudKind {
+    /// The message id is not the one inserted into the tree at the index.
+    WrongMessageId = 1,
+    /// The root is not the root of the tree at the index.
+    WrongRoot = 2,
+}
+
+impl CheckpointFraudKind {
+    /// The name of the kind, e.g. for metric labels.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::WrongMessageId => "wrong_message_id",
+            Self::WrongRoot => "wrong_root",
+        }
+    }
+}
+
+impl Encode for CheckpointFraudKind {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        writer.write_all(&[*self as u8])?;
+        Ok(1)
+    }
+}
+
+impl Decode for CheckpointFraudKind {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        let mut buf = [0; 1];
+        reader.read_exact(&mut buf)?;
+        match buf[0] {
+            1 => Ok(Self::WrongMessageId),
+            2 => Ok(Self::WrongRoot),
+            _ => Err(HyperlaneProtocolError::IoError(Error::new(
+                ErrorKind::InvalidData,
+                "decoded checkpoint fraud kind invalid",
+            ))),
+        }
+    }
+}
+
+/// Evidence that a validator signed a checkpoint contradicting the origin
+/// chain's merkle tree: the signed checkpoint itself and the canonical
+/// checkpoint at the same index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointFraudEvidence {
+    /// The validator that signed the checkpoint.
+    pub validator: H160,
+    /// How the signed checkpoint contradicts the canonical one.
+    pub kind: CheckpointFraudKind,
+    /// The checkpoint as signed by the validator.
+    pub signed_checkpoint: SignedCheckpointWithMessageId,
+    /// The canonical checkpoint at the same index.
+    pub expected_checkpoint: CheckpointWithMessageId,
+}
+
+impl Encode for CheckpointFraudEvidence {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        Ok(self.validator.write_to(writer)?
+            + self.kind.write_to(writer)?
+            + self.signed_checkpoint.write_to(writer)?
+            + self.expected_checkpoint.write_to(writer)?)
+    }
+}
+
+impl Decode for CheckpointFraudEvidence {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        Ok(Self {
+            validator: H160::read_from(reader)?,
+            kind: CheckpointFraudKind::read_from(reader)?,
+            signed_checkpoint: SignedCheckpointWithMessageId::read_from(reader)?,
+            expected_checkpoint: CheckpointWithMessageId::read_from(reader)?,
+        })
+    }
+}
@@ -31,11 +31,15 @@ where
 #[cfg(test)]
 mod test {
     use hyperlane_core::{
-        HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage, LogMeta, RawHyperlaneMessage, H256,
-        H512, U256,
+        Checkpoint, CheckpointWithMessageId, Decode, Encode, HyperlaneDomain, HyperlaneLogStore,
+        HyperlaneMessage, LogMeta, RawHyperlaneMessage, Signature, SignedCheckpointWithMessageId,
+        H160, H256, H512, U256,
     };
 
-    use crate::db::HyperlaneRocksDB;
+    use crate::db::{
+        CheckpointFraudEvidence, CheckpointFraudKind, HyperlaneRocksDB, OperationDisposition,
+        PendingMessageState,
+    };
 
     use super::*;
 
@@ -75,4 +79,216 @@ mod test {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn db_refuses_conflicting_signed_checkpoints() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("db_refuses_conflicting_signed_checkpoints"),
+                db,
+            );
+
+            let checkpoint = |index: u32, root: u64| CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: H256::from_low_u64_be(1),
+                    mailbox_domain: 10,
+                    root: H256::from_low_u64_be(root),
+                    index,
+                },
+                message_id: H256::from_low_u64_be(index as u64),
+            };
+
+            assert_eq!(
+                db.record_signed_checkpoint(&checkpoint(0, 1)).unwrap(),
+                None
+            );
+            assert_eq!(
+                db.record_signed_checkpoint(&checkpoint(2, 3)).unwrap(),
+                None
+            );
+            // Signing the same checkpoint again is fine
+            assert_eq!(
+                db.record_signed_checkpoint(&checkpoint(0, 1)).unwrap(),
+                None
+            );
+            // Signing a different root at the same index is not
+            assert_eq!(
+                db.record_signed_checkpoint(&checkpoint(0, 2)).unwrap(),
+                Some(checkpoint(0, 1))
+            );
+
+            assert_eq!(
+                db.retrieve_signed_checkpoints(&H256::from_low_u64_be(1))
+                    .unwrap(),
+                vec![checkpoint(0, 1), checkpoint(2, 3)]
+            );
+            assert!(db
+                .retrieve_signed_checkpoints(&H256::from_low_u64_be(2))
+                .unwrap()
+                .is_empty());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn db_retrieves_signed_checkpoints_in_index_order() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("db_retrieves_signed_checkpoints_in_index_order"),
+                db,
+            );
+            let checkpoint = |merkle_tree_hook: u64, index: u32| CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: H256::from_low_u64_be(merkle_tree_hook),
+                    mailbox_domain: 1,
+                    root: H256::from_low_u64_be(index as u64 + 100),
+                    index,
+                },
+                message_id: H256::from_low_u64_be(index as u64),
+            };
+
+            // Checkpoints are stored as they're encoded
+            let stored = checkpoint(1, 70_000);
+            let encoded = stored.to_vec();
+            assert_eq!(encoded.len(), 32 + 4 + 32 + 4 + 32);
+            assert_eq!(
+                CheckpointWithMessageId::read_from(&mut encoded.as_slice()).unwrap(),
+                stored
+            );
+
+            // Record them out of order, with indexes whose little endian
+            // encodings would sort differently, and next to other hooks
+            for (merkle_tree_hook, index) in [(1, 70_000), (1, 256), (2, 0), (1, 1), (0, 5), (1, 0)]
+            {
+                assert_eq!(
+                    db.record_signed_checkpoint(&checkpoint(merkle_tree_hook, index))
+                        .unwrap(),
+                    None
+                );
+            }
+
+            assert_eq!(
+                db.retrieve_signed_checkpoints(&H256::from_low_u64_be(1))
+                    .unwrap(),
+                vec![
+                    checkpoint(1, 0),
+                    checkpoint(1, 1),
+                    checkpoint(1, 256),
+                    checkpoint(1, 70_000)
+                ]
+            );
+            assert_eq!(
+                db.retrieve_signed_checkpoints(&H256::from_low_u64_be(2))
+                    .unwrap(),
+                vec![checkpoint(2, 0)]
+            );
+            assert!(db
+                .retrieve_signed_checkpoints(&H256::from_low_u64_be(3))
+                .unwrap()
+                .is_empty());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn db_stores_checkpoint_fraud_evidence_once() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("db_stores_checkpoint_fraud_evidence_once"),
+                db,
+            );
+
+            let evidence = |validator: u64, index: u32| {
+                let checkpoint = CheckpointWithMessageId {
+                    checkpoint: Checkpoint {
+                        merkle_tree_hook_address: H256::from_low_u64_be(1),
+                        mailbox_domain: 10,
+                        root: H256::from_low_u64_be(2),
+                        index,
+                    },
+                    message_id: H256::from_low_u64_be(3),
+                };
+                CheckpointFraudEvidence {
+                    validator: H160::from_low_u64_be(validator),
+                    kind: CheckpointFraudKind::WrongRoot,
+                    signed_checkpoint: SignedCheckpointWithMessageId {
+                        value: checkpoint,
+                        signature: Signature {
+                            r: U256::from(4),
+                            s: U256::from(5),
+                            v: 27,
+                        },
+                    },
+                    expected_checkpoint: CheckpointWithMessageId {
+                        checkpoint: Checkpoint {
+                            root: H256::from_low_u64_be(6),
+                            ..checkpoint.checkpoint
+                        },
+                        ..checkpoint
+                    },
+                }
+            };
+
+            assert!(db.retrieve_checkpoint_fraud_evidence().unwrap().is_empty());
+            assert!(db.store_checkpoint_fraud_evidence(&evidence(1, 7)).unwrap());
+            assert!(db.store_checkpoint_fraud_evidence(&evidence(2, 7)).unwrap());
+            // Evidence against the same validator at the same index is only stored once
+            assert!(!db.store_checkpoint_fraud_evidence(&evidence(1, 7)).unwrap());
+
+            assert_eq!(
+                db.retrieve_checkpoint_fraud_evidence().unwrap(),
+                vec![evidence(1, 7), evidence(2, 7)]
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn db_stores_and_retrieves_pending_message_state() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("db_stores_and_retrieves_pending_message_state"),
+                db,
+            );
+
+            let states = [
+                PendingMessageState::default(),
+                PendingMessageState {
+                    disposition: OperationDisposition::Paused,
+                    last_error: Some("Transaction reverted".to_owned()),
+                    num_retries: 12,
+                    next_attempt_after: Some(1_700_000_000_000),
+                },
+                PendingMessageState {
+                    disposition: OperationDisposition::Deprioritized,
+                    last_error: Some(String::new()),
+                    num_retries: 3,
+                    next_attempt_after: None,
+                },
+            ];
+            for (i, state) in states.iter().enumerate() {
+                let encoded = state.to_vec();
+                assert_eq!(
+                    &PendingMessageState::read_from(&mut encoded.as_slice()).unwrap(),
+                    state
+                );
+
+                let id = H256::from_low_u64_be(i as u64);
+                assert_eq!(
+                    db.retrieve_pending_message_state_by_message_id(&id)
+                        .unwrap(),
+                    None
+                );
+                db.store_pending_message_state_by_message_id(&id, state)
+                    .unwrap();
+                assert_eq!(
+                    db.retrieve_pending_message_state_by_message_id(&id)
+                        .unwrap()
+                        .as_ref(),
+                    Some(state)
+                );
+            }
+        })
+        .await;
+    }
 }
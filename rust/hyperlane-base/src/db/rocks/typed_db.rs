@@ -1,6 +1,6 @@
 use hyperlane_core::{Decode, Encode, HyperlaneDomain};
 
-use crate::db::{DbError, DB};
+use crate::db::{iterator::PrefixIterator, DbError, DB};
 
 type Result<T> = std::result::Result<T, DbError>;
 
@@ -54,6 +54,17 @@ impl TypedDB {
         )
     }
 
+    /// Iterate over the decodable values whose keys start with `key_prefix`,
+    /// in key order
+    pub fn prefix_iterator_decodable<V: Decode>(
+        &self,
+        prefix: impl AsRef<[u8]>,
+        key_prefix: impl AsRef<[u8]>,
+    ) -> PrefixIterator<'_, V> {
+        self.db
+            .prefix_iterator(self.prefixed_key(prefix.as_ref(), key_prefix.as_ref()))
+    }
+
     /// Retrieve decodable value
     pub fn retrieve_decodable<V: Decode>(
         &self,
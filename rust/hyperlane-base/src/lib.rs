@@ -31,5 +31,9 @@ pub use types::*;
 /// Hyperlane database utils
 pub mod db;
 
+/// Test utilities shared by the agents
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
 #[cfg(feature = "oneline-eyre")]
 pub mod oneline_eyre;
@@ -38,11 +38,20 @@ pub struct CoreMetrics {
     span_events: IntCounterVec,
     last_known_message_nonce: IntGaugeVec,
     submitter_queue_length: IntGaugeVec,
+    submitter_in_flight_operations: IntGaugeVec,
 
     operations_processed_count: IntCounterVec,
     messages_processed_count: IntCounterVec,
+    messages_held_for_ordering: IntGaugeVec,
+    rate_limit_capacity: GaugeVec,
+    rate_limit_available: GaugeVec,
+    dry_run_decisions: IntCounterVec,
+    dry_run_estimated_gas: IntCounterVec,
 
     latest_checkpoint: IntGaugeVec,
+    conflicting_checkpoints_refused: IntCounterVec,
+    fraudulent_checkpoints_observed: IntCounterVec,
+    invalid_checkpoint_signatures: IntCounterVec,
 
     /// Set of metrics that tightly wrap the JsonRpcClient for use with the
     /// quorum provider.
@@ -136,6 +145,16 @@ impl CoreMetrics {
             registry
         )?;
 
+        let submitter_in_flight_operations = register_int_gauge_vec_with_registry!(
+            opts!(
+                namespaced!("submitter_in_flight_operations"),
+                "Number of operations being submitted by each of a submitter's signers",
+                const_labels_ref
+            ),
+            &["remote", "signer"],
+            registry
+        )?;
+
         let latest_checkpoint = register_int_gauge_vec_with_registry!(
             opts!(
                 namespaced!("latest_checkpoint"),
@@ -146,6 +165,36 @@ impl CoreMetrics {
             registry
         )?;
 
+        let conflicting_checkpoints_refused = register_int_counter_vec_with_registry!(
+            opts!(
+                namespaced!("conflicting_checkpoints_refused"),
+                "Number of times the validator refused to sign a checkpoint that conflicts with one it already signed",
+                const_labels_ref
+            ),
+            &["chain"],
+            registry
+        )?;
+
+        let fraudulent_checkpoints_observed = register_int_counter_vec_with_registry!(
+            opts!(
+                namespaced!("fraudulent_checkpoints_observed"),
+                "Number of checkpoints signed by validators that contradict the origin chain's merkle tree",
+                const_labels_ref
+            ),
+            &["origin", "validator", "kind"],
+            registry
+        )?;
+
+        let invalid_checkpoint_signatures = register_int_counter_vec_with_registry!(
+            opts!(
+                namespaced!("invalid_checkpoint_signatures"),
+                "Number of checkpoints fetched from validators that were rejected because they were not signed by the validator",
+                const_labels_ref
+            ),
+            &["origin", "validator"],
+            registry
+        )?;
+
         let operations_processed_count = register_int_counter_vec_with_registry!(
             opts!(
                 namespaced!("operations_processed_count"),
@@ -166,6 +215,56 @@ impl CoreMetrics {
             registry
         )?;
 
+        let messages_held_for_ordering = register_int_gauge_vec_with_registry!(
+            opts!(
+                namespaced!("messages_held_for_ordering"),
+                "Number of messages held back until the messages before them from the same sender to the same recipient are delivered",
+                const_labels_ref
+            ),
+            &["origin", "remote"],
+            registry
+        )?;
+
+        let rate_limit_capacity = register_gauge_vec_with_registry!(
+            opts!(
+                namespaced!("rate_limit_capacity"),
+                "Amount a rate limit allows to be spent per period",
+                const_labels_ref
+            ),
+            &["remote", "rate_limit", "resource"],
+            registry
+        )?;
+
+        let rate_limit_available = register_gauge_vec_with_registry!(
+            opts!(
+                namespaced!("rate_limit_available"),
+                "Amount that can currently be spent under a rate limit",
+                const_labels_ref
+            ),
+            &["remote", "rate_limit", "resource"],
+            registry
+        )?;
+
+        let dry_run_decisions = register_int_counter_vec_with_registry!(
+            opts!(
+                namespaced!("dry_run_decisions"),
+                "Number of messages a dry run relayer made a decision about, by decision",
+                const_labels_ref
+            ),
+            &["origin", "remote", "decision"],
+            registry
+        )?;
+
+        let dry_run_estimated_gas = register_int_counter_vec_with_registry!(
+            opts!(
+                namespaced!("dry_run_estimated_gas"),
+                "Estimated gas of the deliveries a dry run relayer would have submitted",
+                const_labels_ref
+            ),
+            &["origin", "remote"],
+            registry
+        )?;
+
         Ok(Self {
             agent_name: for_agent.into(),
             registry,
@@ -178,11 +277,20 @@ impl CoreMetrics {
             last_known_message_nonce,
 
             submitter_queue_length,
+            submitter_in_flight_operations,
 
             operations_processed_count,
             messages_processed_count,
+            messages_held_for_ordering,
+            rate_limit_capacity,
+            rate_limit_available,
+            dry_run_decisions,
+            dry_run_estimated_gas,
 
             latest_checkpoint,
+            conflicting_checkpoints_refused,
+            fraudulent_checkpoints_observed,
+            invalid_checkpoint_signatures,
 
             json_rpc_client_metrics: OnceLock::new(),
             provider_metrics: OnceLock::new(),
@@ -318,6 +426,38 @@ impl CoreMetrics {
         self.latest_checkpoint.clone()
     }
 
+    /// Number of times the validator refused to sign a checkpoint because a
+    /// different checkpoint was already signed at the same index. Any
+    /// increase means the validator was about to equivocate.
+    ///
+    /// Labels:
+    /// - `chain`: Origin chain of the checkpoint.
+    pub fn conflicting_checkpoints_refused(&self) -> IntCounterVec {
+        self.conflicting_checkpoints_refused.clone()
+    }
+
+    /// The number of checkpoints the relayer's watchtower found to contradict
+    /// the origin chain's merkle tree.
+    ///
+    /// Labels:
+    /// - `origin`: Origin chain of the checkpoint.
+    /// - `validator`: Address of the validator the checkpoint was published by.
+    /// - `kind`: How the checkpoint contradicts the merkle tree.
+    pub fn fraudulent_checkpoints_observed(&self) -> IntCounterVec {
+        self.fraudulent_checkpoints_observed.clone()
+    }
+
+    /// The number of checkpoints fetched from validators, to build metadata or
+    /// by the relayer's watchtower, that were rejected because their signature
+    /// doesn't recover to the validator.
+    ///
+    /// Labels:
+    /// - `origin`: Origin chain of the checkpoint.
+    /// - `validator`: Address of the validator the checkpoint was fetched for.
+    pub fn invalid_checkpoint_signatures(&self) -> IntCounterVec {
+        self.invalid_checkpoint_signatures.clone()
+    }
+
     /// Measure of the queue lengths in Submitter instances
     ///
     /// Labels:
@@ -327,6 +467,16 @@ impl CoreMetrics {
         self.submitter_queue_length.clone()
     }
 
+    /// The number of operations a submitter is currently submitting with each
+    /// of its signers.
+    ///
+    /// Labels:
+    /// - `remote`: Remote chain the operations are submitted to.
+    /// - `signer`: Address of the signer submitting the operations.
+    pub fn submitter_in_flight_operations(&self) -> IntGaugeVec {
+        self.submitter_in_flight_operations.clone()
+    }
+
     /// The number of operations successfully submitted by this process during
     /// its lifetime.
     ///
@@ -371,6 +521,63 @@ impl CoreMetrics {
         self.messages_processed_count.clone()
     }
 
+    /// The number of messages that must be delivered in order and are held
+    /// back until the messages before them from the same sender to the same
+    /// recipient are delivered.
+    ///
+    /// Labels:
+    /// - `origin`: Chain the messages came from.
+    /// - `remote`: Chain the messages are delivered to.
+    pub fn messages_held_for_ordering(&self) -> IntGaugeVec {
+        self.messages_held_for_ordering.clone()
+    }
+
+    /// The amount of a resource a relayer rate limit allows to be spent per
+    /// period.
+    ///
+    /// Labels:
+    /// - `remote`: Chain the rate limited messages are delivered to.
+    /// - `rate_limit`: Name of the rate limit.
+    /// - `resource`: What is limited, one of `messages`, `gas` or
+    ///   `native_spend`. Native spend is in the lowest denomination.
+    pub fn rate_limit_capacity(&self) -> GaugeVec {
+        self.rate_limit_capacity.clone()
+    }
+
+    /// The amount of a resource that can currently be spent under a relayer
+    /// rate limit.
+    ///
+    /// Labels:
+    /// - `remote`: Chain the rate limited messages are delivered to.
+    /// - `rate_limit`: Name of the rate limit.
+    /// - `resource`: What is limited, one of `messages`, `gas` or
+    ///   `native_spend`. Native spend is in the lowest denomination.
+    pub fn rate_limit_available(&self) -> GaugeVec {
+        self.rate_limit_available.clone()
+    }
+
+    /// The number of messages a dry run relayer made a decision about. A
+    /// message is counted again when a later attempt changes the decision.
+    ///
+    /// Labels:
+    /// - `origin`: Chain the message came from.
+    /// - `remote`: Chain the message would have been delivered to.
+    /// - `decision`: What the relayer decided, e.g. `would_deliver` or
+    ///   `gas_payment_not_met`.
+    pub fn dry_run_decisions(&self) -> IntCounterVec {
+        self.dry_run_decisions.clone()
+    }
+
+    /// The estimated gas of the deliveries a dry run relayer would have
+    /// submitted.
+    ///
+    /// Labels:
+    /// - `origin`: Chain the messages came from.
+    /// - `remote`: Chain the messages would have been delivered to.
+    pub fn dry_run_estimated_gas(&self) -> IntCounterVec {
+        self.dry_run_estimated_gas.clone()
+    }
+
     /// Measure of span durations provided by tracing.
     ///
     /// Labels:
@@ -0,0 +1,170 @@
+use async_trait::async_trait;
+use hyperlane_core::{HyperlaneSignerError, KmsKey, H256};
+use k256::ecdsa::Signature;
+use rusoto_core::Region;
+use rusoto_kms::{GetPublicKeyRequest, Kms, KmsClient, SignRequest};
+
+use super::aws_credentials::AwsChainCredentialsProvider;
+use crate::types::utils;
+
+/// The KMS signing algorithm for secp256k1 keys. KMS is sent the digest, so
+/// the hash function doesn't matter.
+const ECDSA_SIGNING_ALGORITHM: &str = "ECDSA_SHA_256";
+/// The KMS signing algorithm for ed25519 keys
+const ED25519_SIGNING_ALGORITHM: &str = "ED25519_SHA_512";
+
+/// Create a KMS client for `region`, using the agents' AWS credentials
+pub(crate) fn kms_client(region: Region) -> KmsClient {
+    KmsClient::new_with_client(
+        rusoto_core::Client::new_with(
+            AwsChainCredentialsProvider::new(),
+            utils::http_client_with_timeout().unwrap(),
+        ),
+        region,
+    )
+}
+
+/// A secp256k1 or ed25519 key in AWS KMS, for chains whose signers aren't
+/// ethers' `AwsSigner`.
+pub(crate) struct AwsKmsKey {
+    client: KmsClient,
+    key_id: String,
+}
+
+impl std::fmt::Debug for AwsKmsKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AwsKmsKey {{ key_id: {} }}", self.key_id)
+    }
+}
+
+impl AwsKmsKey {
+    /// Create a handle to the KMS key `key_id` in `region`
+    pub(crate) fn new(key_id: String, region: Region) -> Self {
+        Self {
+            client: kms_client(region),
+            key_id,
+        }
+    }
+
+    async fn sign(
+        &self,
+        message: &[u8],
+        message_type: &str,
+        signing_algorithm: &str,
+    ) -> Result<Vec<u8>, HyperlaneSignerError> {
+        let response = self
+            .client
+            .sign(SignRequest {
+                key_id: self.key_id.clone(),
+                message: message.to_vec().into(),
+                message_type: Some(message_type.to_owned()),
+                signing_algorithm: signing_algorithm.to_owned(),
+                ..Default::default()
+            })
+            .await
+            .map_err(signer_error)?;
+        response
+            .signature
+            .map(|signature| signature.to_vec())
+            .ok_or_else(|| signer_error("KMS returned no signature"))
+    }
+}
+
+#[async_trait]
+impl KmsKey for AwsKmsKey {
+    async fn public_key(&self) -> Result<Vec<u8>, HyperlaneSignerError> {
+        let response = self
+            .client
+            .get_public_key(GetPublicKeyRequest {
+                key_id: self.key_id.clone(),
+                ..Default::default()
+            })
+            .await
+            .map_err(signer_error)?;
+        let der = response
+            .public_key
+            .ok_or_else(|| signer_error("KMS returned no public key"))?;
+        spki_public_key(&der)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| signer_error("KMS returned an invalid public key"))
+    }
+
+    async fn sign_digest(&self, digest: &H256) -> Result<Vec<u8>, HyperlaneSignerError> {
+        let der = self
+            .sign(digest.as_bytes(), "DIGEST", ECDSA_SIGNING_ALGORITHM)
+            .await?;
+        let signature = Signature::from_der(&der).map_err(signer_error)?;
+        Ok(signature.to_bytes().to_vec())
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>, HyperlaneSignerError> {
+        self.sign(message, "RAW", ED25519_SIGNING_ALGORITHM).await
+    }
+}
+
+fn signer_error(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> HyperlaneSignerError {
+    HyperlaneSignerError::from(err.into())
+}
+
+/// Extracts the public key from a DER encoded SubjectPublicKeyInfo, which KMS
+/// returns public keys as:
+///
+/// SEQUENCE { SEQUENCE { algorithm identifier }, BIT STRING { public key } }
+fn spki_public_key(der: &[u8]) -> Option<&[u8]> {
+    /// Splits off a DER element with `tag`, returning its contents and the
+    /// rest of the input
+    fn element(der: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+        let (&actual_tag, rest) = der.split_first()?;
+        let (&first_len_byte, rest) = rest.split_first()?;
+        if actual_tag != tag {
+            return None;
+        }
+        let (len, rest) = if first_len_byte < 0x80 {
+            (first_len_byte as usize, rest)
+        } else {
+            // Long form, the low bits are the number of length bytes
+            let len_bytes = (first_len_byte & 0x7f) as usize;
+            if len_bytes > std::mem::size_of::<usize>() || rest.len() < len_bytes {
+                return None;
+            }
+            let (len, rest) = rest.split_at(len_bytes);
+            let len = len
+                .iter()
+                .fold(0usize, |len, &byte| (len << 8) | byte as usize);
+            (len, rest)
+        };
+        (rest.len() >= len).then(|| rest.split_at(len))
+    }
+
+    let (spki, _) = element(der, 0x30)?;
+    let (_, spki) = element(spki, 0x30)?;
+    let (bit_string, _) = element(spki, 0x03)?;
+    // The first byte of a bit string is its number of unused bits
+    match bit_string.split_first()? {
+        (0, public_key) => Some(public_key),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::utils::hex;
+
+    use super::*;
+
+    #[test]
+    fn test_spki_public_key() {
+        // An ed25519 key, from RFC 8410
+        let der = hex::decode(
+            "302a300506032b657003210019bf44096984cdfe8541bac167dc3b96c85086aa30b6b6cb0c5c38ad703166e1",
+        )
+        .unwrap();
+        assert_eq!(
+            spki_public_key(&der).unwrap(),
+            hex::decode("19bf44096984cdfe8541bac167dc3b96c85086aa30b6b6cb0c5c38ad703166e1")
+                .unwrap()
+        );
+        assert_eq!(spki_public_key(&der[..der.len() - 1]), None);
+        assert_eq!(spki_public_key(&[]), None);
+    }
+}
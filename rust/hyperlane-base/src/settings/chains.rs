@@ -1,16 +1,17 @@
 use ethers::prelude::Selector;
 use h_cosmos::CosmosProvider;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use eyre::{eyre, Context, Result};
 
 use ethers_prometheus::middleware::{ChainInfo, ContractInfo, PrometheusMiddlewareConf};
 use hyperlane_core::{
     AggregationIsm, CcipReadIsm, ContractLocator, HyperlaneAbi, HyperlaneDomain,
-    HyperlaneDomainProtocol, HyperlaneMessage, HyperlaneProvider, IndexMode,
-    InterchainGasPaymaster, InterchainGasPayment, InterchainSecurityModule, Mailbox,
-    MerkleTreeHook, MerkleTreeInsertion, MultisigIsm, RoutingIsm, SequenceAwareIndexer,
-    ValidatorAnnounce, H256,
+    HyperlaneDomainProtocol, HyperlaneInFlightTransactionStore, HyperlaneMessage,
+    HyperlaneProvider, IndexMode, InterchainGasPaymaster, InterchainGasPayment,
+    InterchainSecurityModule, Mailbox, MerkleTreeHook, MerkleTreeInsertion, MultisigIsm,
+    RoutingIsm, SequenceAwareIndexer, ValidatorAnnounce, H256,
 };
 use hyperlane_cosmos as h_cosmos;
 use hyperlane_ethereum::{
@@ -36,6 +37,10 @@ pub struct ChainConf {
     pub domain: HyperlaneDomain,
     /// Signer configuration for this chain
     pub signer: Option<SignerConf>,
+    /// Signers the relayer submits transactions with in parallel, each with
+    /// its own nonces, instead of `signer`. When empty, transactions are only
+    /// submitted with `signer`.
+    pub signer_pool: Vec<SignerConf>,
     /// The reorg period of the chain, i.e. the number of blocks until finality
     pub reorg_period: u32,
     /// Addresses of contracts on the chain
@@ -48,6 +53,12 @@ pub struct ChainConf {
     pub metrics_conf: PrometheusMiddlewareConf,
     /// Settings for event indexing
     pub index: IndexSettings,
+    /// Settings for batching operations into a single transaction
+    pub batch: OperationBatchConf,
+    /// Settings for replacing transactions that aren't included in time.
+    /// Only supported on EVM chains, where transactions are sent as-is when
+    /// not set.
+    pub gas_escalation: Option<h_eth::GasEscalationConf>,
 }
 
 /// A connection to _some_ blockchain.
@@ -99,12 +110,45 @@ pub struct IndexSettings {
     pub mode: IndexMode,
 }
 
+/// Settings for batching several operations into a single transaction
+#[derive(Debug, Clone)]
+pub struct OperationBatchConf {
+    /// The max number of operations in a batch. Batching is disabled when
+    /// this is 1.
+    pub max_batch_size: u32,
+    /// Address of the contract used to batch calls, e.g. Multicall3 on EVM
+    /// chains. Uses the chain's default when not set.
+    pub batch_contract_address: Option<H256>,
+}
+
+impl Default for OperationBatchConf {
+    fn default() -> Self {
+        Self {
+            max_batch_size: 1,
+            batch_contract_address: None,
+        }
+    }
+}
+
 impl ChainConf {
     /// Fetch the index settings and index mode, since they are often used together.
     pub fn index_settings(&self) -> IndexSettings {
         self.index.clone()
     }
 
+    /// The chain config of each signer in the signer pool, which is this
+    /// config with the pool's signer as its signer
+    pub fn signer_pool_confs(&self) -> Vec<ChainConf> {
+        self.signer_pool
+            .iter()
+            .map(|signer| ChainConf {
+                signer: Some(signer.clone()),
+                signer_pool: vec![],
+                ..self.clone()
+            })
+            .collect()
+    }
+
     /// Try to convert the chain settings into an HyperlaneProvider.
     pub async fn build_provider(
         &self,
@@ -117,7 +161,10 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::HyperlaneProviderBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let provider = h_fuel::FuelProvider::new(locator.domain.clone(), conf)?;
+                Ok(Box::new(provider) as Box<dyn HyperlaneProvider>)
+            }
             ChainConnectionConf::Sealevel(conf) => Ok(Box::new(h_sealevel::SealevelProvider::new(
                 locator.domain.clone(),
                 conf,
@@ -137,13 +184,32 @@ impl ChainConf {
 
     /// Try to convert the chain setting into a Mailbox contract
     pub async fn build_mailbox(&self, metrics: &CoreMetrics) -> Result<Box<dyn Mailbox>> {
+        self.build_mailbox_with_tx_store(metrics, None).await
+    }
+
+    /// Try to convert the chain setting into a Mailbox contract that persists
+    /// its in-flight transactions to `tx_store`
+    pub async fn build_mailbox_with_tx_store(
+        &self,
+        metrics: &CoreMetrics,
+        tx_store: Option<Arc<dyn HyperlaneInFlightTransactionStore>>,
+    ) -> Result<Box<dyn Mailbox>> {
         let ctx = "Building mailbox";
         let locator = self.locator(self.addresses.mailbox);
 
         match &self.connection {
             ChainConnectionConf::Ethereum(conf) => {
-                self.build_ethereum(conf, &locator, metrics, h_eth::MailboxBuilder {})
-                    .await
+                self.build_ethereum(
+                    conf,
+                    &locator,
+                    metrics,
+                    h_eth::MailboxBuilder {
+                        batch_contract_address: self.batch.batch_contract_address,
+                        gas_escalation: self.gas_escalation.clone(),
+                        tx_store,
+                    },
+                )
+                .await
             }
             ChainConnectionConf::Fuel(conf) => {
                 let wallet = self.fuel_signer().await.context(ctx)?;
@@ -152,8 +218,8 @@ impl ChainConf {
                     .map_err(Into::into)
             }
             ChainConnectionConf::Sealevel(conf) => {
-                let keypair = self.sealevel_signer().await.context(ctx)?;
-                h_sealevel::SealevelMailbox::new(conf, locator, keypair)
+                let signer = self.sealevel_signer().await.context(ctx)?;
+                h_sealevel::SealevelMailbox::new(conf, locator, signer)
                     .map(|m| Box::new(m) as Box<dyn Mailbox>)
                     .map_err(Into::into)
             }
@@ -180,9 +246,9 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::MerkleTreeHookBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_conf) => {
-                todo!("Fuel does not support merkle tree hooks yet")
-            }
+            ChainConnectionConf::Fuel(conf) => h_fuel::FuelMerkleTreeHook::new(conf, locator)
+                .map(|m| Box::new(m) as Box<dyn MerkleTreeHook>)
+                .map_err(Into::into),
             ChainConnectionConf::Sealevel(conf) => {
                 h_sealevel::SealevelMailbox::new(conf, locator, None)
                     .map(|m| Box::new(m) as Box<dyn MerkleTreeHook>)
@@ -219,7 +285,10 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let indexer = Box::new(h_fuel::FuelMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let indexer = Box::new(h_sealevel::SealevelMailboxIndexer::new(conf, locator)?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
@@ -258,17 +327,18 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let indexer = Box::new(h_fuel::FuelMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let indexer = Box::new(h_sealevel::SealevelMailboxIndexer::new(conf, locator)?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
             }
             ChainConnectionConf::Cosmos(conf) => {
-                let signer = self.cosmos_signer().await.context(ctx)?;
-                let indexer = Box::new(h_cosmos::CosmosMailboxIndexer::new(
+                let indexer = Box::new(h_cosmos::CosmosMailboxDeliveryIndexer::new(
                     conf.clone(),
                     locator,
-                    signer,
                     self.reorg_period,
                 )?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
@@ -296,7 +366,10 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let paymaster = Box::new(h_fuel::FuelInterchainGasPaymaster::new(conf, locator)?);
+                Ok(paymaster as Box<dyn InterchainGasPaymaster>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let paymaster = Box::new(
                     h_sealevel::SealevelInterchainGasPaymaster::new(conf, &locator).await?,
@@ -337,7 +410,12 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let indexer = Box::new(h_fuel::FuelInterchainGasPaymasterIndexer::new(
+                    conf, locator,
+                )?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<InterchainGasPayment>>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let indexer = Box::new(
                     h_sealevel::SealevelInterchainGasPaymasterIndexer::new(conf, locator).await?,
@@ -376,7 +454,10 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let indexer = Box::new(h_fuel::FuelMerkleTreeHookIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let mailbox_indexer =
                     Box::new(h_sealevel::SealevelMailboxIndexer::new(conf, locator)?);
@@ -412,7 +493,11 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::ValidatorAnnounceBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let wallet = self.signer().await.context(ctx)?;
+                let va = Box::new(h_fuel::FuelValidatorAnnounce::new(conf, locator, wallet)?);
+                Ok(va as Box<dyn ValidatorAnnounce>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let va = Box::new(h_sealevel::SealevelValidatorAnnounce::new(conf, locator));
                 Ok(va as Box<dyn ValidatorAnnounce>)
@@ -451,11 +536,14 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let ism = Box::new(h_fuel::FuelInterchainSecurityModule::new(conf, locator)?);
+                Ok(ism as Box<dyn InterchainSecurityModule>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
-                let keypair = self.sealevel_signer().await.context(ctx)?;
+                let signer = self.sealevel_signer().await.context(ctx)?;
                 let ism = Box::new(h_sealevel::SealevelInterchainSecurityModule::new(
-                    conf, locator, keypair,
+                    conf, locator, signer,
                 ));
                 Ok(ism as Box<dyn InterchainSecurityModule>)
             }
@@ -485,10 +573,13 @@ impl ChainConf {
                     .await
             }
 
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let ism = Box::new(h_fuel::FuelMultisigIsm::new(conf, locator)?);
+                Ok(ism as Box<dyn MultisigIsm>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
-                let keypair = self.sealevel_signer().await.context(ctx)?;
-                let ism = Box::new(h_sealevel::SealevelMultisigIsm::new(conf, locator, keypair));
+                let signer = self.sealevel_signer().await.context(ctx)?;
+                let ism = Box::new(h_sealevel::SealevelMultisigIsm::new(conf, locator, signer));
                 Ok(ism as Box<dyn MultisigIsm>)
             }
             ChainConnectionConf::Cosmos(conf) => {
@@ -521,9 +612,13 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::RoutingIsmBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
-            ChainConnectionConf::Sealevel(_) => {
-                Err(eyre!("Sealevel does not support routing ISM yet")).context(ctx)
+            ChainConnectionConf::Fuel(conf) => {
+                let ism = Box::new(h_fuel::FuelRoutingIsm::new(conf, locator)?);
+                Ok(ism as Box<dyn RoutingIsm>)
+            }
+            ChainConnectionConf::Sealevel(conf) => {
+                let ism = Box::new(h_sealevel::SealevelRoutingIsm::new(conf, locator));
+                Ok(ism as Box<dyn RoutingIsm>)
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
@@ -555,9 +650,12 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::AggregationIsmBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
-            ChainConnectionConf::Sealevel(_) => {
-                Err(eyre!("Sealevel does not support aggregation ISM yet")).context(ctx)
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support aggregation ISM yet")).context(ctx)
+            }
+            ChainConnectionConf::Sealevel(conf) => {
+                let ism = Box::new(h_sealevel::SealevelAggregationIsm::new(conf, locator));
+                Ok(ism as Box<dyn AggregationIsm>)
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
@@ -590,7 +688,9 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::CcipReadIsmBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(_) => {
+                Err(eyre!("Fuel does not support CCIP read ISM yet")).context(ctx)
+            }
             ChainConnectionConf::Sealevel(_) => {
                 Err(eyre!("Sealevel does not support CCIP read ISM yet")).context(ctx)
             }
@@ -618,9 +718,13 @@ impl ChainConf {
                     Box::new(conf.build::<fuels::prelude::WalletUnlocked>().await?)
                 }
                 ChainConnectionConf::Sealevel(_) => {
-                    Box::new(conf.build::<h_sealevel::Keypair>().await?)
+                    Box::new(conf.build::<h_sealevel::SealevelSigner>().await?)
                 }
-                ChainConnectionConf::Cosmos(_) => Box::new(conf.build::<h_cosmos::Signer>().await?),
+                ChainConnectionConf::Cosmos(_) => Box::new(
+                    conf.build::<h_cosmos::Signer>()
+                        .await?
+                        .with_account_address_type(self.cosmos_account_address_type())?,
+                ),
             };
             Ok(Some(chain_signer))
         } else {
@@ -638,19 +742,37 @@ impl ChainConf {
         })
     }
 
-    async fn sealevel_signer(&self) -> Result<Option<h_sealevel::Keypair>> {
+    async fn sealevel_signer(&self) -> Result<Option<h_sealevel::SealevelSigner>> {
         self.signer().await
     }
 
     async fn cosmos_signer(&self) -> Result<Option<h_cosmos::Signer>> {
-        self.signer().await
+        let signer: Option<h_cosmos::Signer> = self.signer().await?;
+        Ok(signer
+            .map(|signer| signer.with_account_address_type(self.cosmos_account_address_type()))
+            .transpose()?)
+    }
+
+    /// How the chain derives account addresses from public keys
+    fn cosmos_account_address_type(&self) -> h_cosmos::AccountAddressType {
+        match &self.connection {
+            ChainConnectionConf::Cosmos(conf) => conf.get_account_address_type(),
+            _ => h_cosmos::AccountAddressType::default(),
+        }
     }
 
     /// Try to build an agent metrics configuration from the chain config
     pub async fn agent_metrics_conf(&self, agent_name: String) -> Result<AgentMetricsConf> {
         let chain_signer_address = self.chain_signer().await?.map(|s| s.address_string());
+        let mut signer_pool_addresses = vec![];
+        for conf in self.signer_pool_confs() {
+            if let Some(signer) = conf.chain_signer().await? {
+                signer_pool_addresses.push(signer.address_string());
+            }
+        }
         Ok(AgentMetricsConf {
             address: chain_signer_address,
+            signer_pool_addresses,
             domain: self.domain.clone(),
             name: agent_name,
         })
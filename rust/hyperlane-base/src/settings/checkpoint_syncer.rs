@@ -1,12 +1,14 @@
 use crate::{
-    CheckpointSyncer, GcsStorageClientBuilder, LocalStorage, S3Storage, GCS_SERVICE_ACCOUNT_KEY,
-    GCS_USER_SECRET,
+    is_public_url, CheckpointSyncer, GcsStorageClientBuilder, HttpStorage, LocalStorage,
+    ReplicatedCheckpointSyncer, S3Storage, GCS_SERVICE_ACCOUNT_KEY, GCS_USER_SECRET,
 };
 use core::str::FromStr;
 use eyre::{eyre, Context, Report, Result};
+use futures_util::future::BoxFuture;
 use prometheus::IntGauge;
 use rusoto_core::Region;
 use std::{env, path::PathBuf};
+use url::Url;
 use ya_gcp::{AuthFlow, ServiceAccountAuth};
 
 /// Checkpoint Syncer types
@@ -38,6 +40,21 @@ pub enum CheckpointSyncerConf {
         /// `gcloud auth application-default login`
         user_secrets: Option<String>,
     },
+    /// A checkpoint syncer served over HTTP by a validator
+    Http {
+        /// The URL the checkpoints are served at
+        url: Url,
+        /// Where the validator serving the checkpoints stores them. Only set
+        /// for that validator.
+        path: Option<PathBuf>,
+    },
+    /// A checkpoint syncer that replicates checkpoints to several others
+    Replicated {
+        /// The replicas, in the order they are read from
+        syncers: Vec<CheckpointSyncerConf>,
+        /// How many replicas a write must succeed on
+        write_quorum: usize,
+    },
 }
 
 impl FromStr for CheckpointSyncerConf {
@@ -65,6 +82,12 @@ impl FromStr for CheckpointSyncerConf {
                         .context("Invalid region when parsing storage location")?,
                 })
             }
+            "http" | "https" => Ok(CheckpointSyncerConf::Http {
+                url: s
+                    .parse()
+                    .context("Invalid URL when parsing storage location")?,
+                path: None,
+            }),
             "file" => Ok(CheckpointSyncerConf::LocalStorage {
                 path: suffix.into(),
             }),
@@ -96,8 +119,26 @@ impl FromStr for CheckpointSyncerConf {
 }
 
 impl CheckpointSyncerConf {
+    /// Whether the checkpoint syncer reads from the local machine or network:
+    /// local storage, or an HTTP server at a non-public host.
+    pub fn is_local(&self) -> bool {
+        match self {
+            CheckpointSyncerConf::LocalStorage { .. } => true,
+            CheckpointSyncerConf::Http { url, .. } => !is_public_url(url),
+            _ => false,
+        }
+    }
+
     /// Turn conf info a Checkpoint Syncer
-    pub async fn build(
+    pub fn build(
+        &self,
+        latest_index_gauge: Option<IntGauge>,
+    ) -> BoxFuture<'_, Result<Box<dyn CheckpointSyncer>, Report>> {
+        // Boxed since replicated syncers build their replicas recursively
+        Box::pin(self.build_inner(latest_index_gauge))
+    }
+
+    async fn build_inner(
         &self,
         latest_index_gauge: Option<IntGauge>,
     ) -> Result<Box<dyn CheckpointSyncer>, Report> {
@@ -136,6 +177,23 @@ impl CheckpointSyncerConf {
                         .await?,
                 )
             }
+            CheckpointSyncerConf::Http { url, path } => {
+                let local = path
+                    .as_ref()
+                    .map(|path| LocalStorage::new(path.clone(), latest_index_gauge.clone()))
+                    .transpose()?;
+                Box::new(HttpStorage::new(url.clone(), local, latest_index_gauge)?)
+            }
+            CheckpointSyncerConf::Replicated {
+                syncers,
+                write_quorum,
+            } => {
+                let mut replicas = Vec::with_capacity(syncers.len());
+                for syncer in syncers {
+                    replicas.push(syncer.build(latest_index_gauge.clone()).await?.into());
+                }
+                Box::new(ReplicatedCheckpointSyncer::new(replicas, *write_quorum))
+            }
         })
     }
 }
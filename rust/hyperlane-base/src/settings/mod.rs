@@ -80,6 +80,8 @@ mod envs {
 
 /// AWS Credentials provider.
 pub(crate) mod aws_credentials;
+/// AWS KMS keys for chain signers.
+pub(crate) mod aws_kms;
 mod base;
 /// Chain configuration
 mod chains;
@@ -98,6 +98,23 @@ pub fn build_cosmos_connection_conf(
         .parse_u64()
         .end();
 
+    let account_address_type = match chain
+        .chain(err)
+        .get_opt_key("accountAddressType")
+        .parse_string()
+        .end()
+    {
+        None | Some("bitcoin") => Some(h_cosmos::AccountAddressType::Bitcoin),
+        Some("ethereum") => Some(h_cosmos::AccountAddressType::Ethereum),
+        Some(ty) => {
+            local_err.push(
+                &chain.cwp + "account_address_type",
+                eyre!("unknown account address type `{ty}`"),
+            );
+            None
+        }
+    };
+
     if !local_err.is_ok() {
         err.merge(local_err);
         None
@@ -110,6 +127,7 @@ pub fn build_cosmos_connection_conf(
             canonical_asset.unwrap(),
             gas_price.unwrap(),
             contract_address_bytes.unwrap().try_into().unwrap(),
+            account_address_type.unwrap(),
         )))
     }
 }
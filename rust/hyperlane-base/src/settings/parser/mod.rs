@@ -7,6 +7,7 @@
 use std::{
     collections::{HashMap, HashSet},
     default::Default,
+    time::Duration,
 };
 
 use convert_case::{Case, Casing};
@@ -24,7 +25,9 @@ use url::Url;
 pub use self::json_value_parser::ValueParser;
 pub use super::envs::*;
 use crate::settings::{
-    chains::IndexSettings, parser::connection_parser::build_connection_conf, trace::TracingConfig,
+    chains::{IndexSettings, OperationBatchConf},
+    parser::connection_parser::build_connection_conf,
+    trace::TracingConfig,
     ChainConf, CoreContractAddresses, Settings, SignerConf,
 };
 
@@ -128,6 +131,16 @@ fn parse_chain(
         .get_opt_key("signer")
         .and_then(parse_signer)
         .end();
+    let signer_pool = chain
+        .chain(&mut err)
+        .get_opt_key("signerPool")
+        .into_array_iter()
+        .map(|signers| {
+            signers
+                .filter_map(|signer| parse_signer(signer).take_config_err(&mut err))
+                .collect_vec()
+        })
+        .unwrap_or_default();
 
     let reorg_period = chain
         .chain(&mut err)
@@ -166,6 +179,25 @@ fn parse_chain(
                 .unwrap_or_default()
         });
 
+    let max_batch_size = chain
+        .chain(&mut err)
+        .get_opt_key("batch")
+        .get_opt_key("maxBatchSize")
+        .parse_u32()
+        .unwrap_or(1);
+    let batch_contract_address = chain
+        .chain(&mut err)
+        .get_opt_key("batch")
+        .get_opt_key("batchContractAddress")
+        .parse_address_hash()
+        .end();
+
+    let gas_escalation = chain
+        .chain(&mut err)
+        .get_opt_key("gasEscalation")
+        .end()
+        .map(|escalation| parse_gas_escalation(escalation, &mut err));
+
     let mailbox = chain
         .chain(&mut err)
         .get_key("mailbox")
@@ -200,6 +232,7 @@ fn parse_chain(
     err.into_result(ChainConf {
         domain,
         signer,
+        signer_pool,
         reorg_period,
         addresses: CoreContractAddresses {
             mailbox,
@@ -214,9 +247,69 @@ fn parse_chain(
             chunk_size,
             mode,
         },
+        batch: OperationBatchConf {
+            max_batch_size,
+            batch_contract_address,
+        },
+        gas_escalation,
     })
 }
 
+/// Nodes usually reject replacement transactions that bump fees by less than
+/// this percentage.
+const MIN_FEE_BUMP_PERCENT: u32 = 10;
+
+/// Expects GasEscalationConfig, with every field optional
+fn parse_gas_escalation(
+    escalation: ValueParser,
+    err: &mut ConfigParsingError,
+) -> h_eth::GasEscalationConf {
+    let default = h_eth::GasEscalationConf::default();
+    let escalation_interval = escalation
+        .chain(err)
+        .get_opt_key("escalationIntervalSecs")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(default.escalation_interval);
+    let poll_interval = escalation
+        .chain(err)
+        .get_opt_key("pollIntervalSecs")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(default.poll_interval);
+    let fee_bump_percent = escalation
+        .chain(err)
+        .get_opt_key("feeBumpPercent")
+        .parse_u32()
+        .unwrap_or(default.fee_bump_percent);
+    if fee_bump_percent < MIN_FEE_BUMP_PERCENT {
+        err.push(
+            &escalation.cwp + "fee_bump_percent",
+            eyre!(
+                "Fee bump must be at least {MIN_FEE_BUMP_PERCENT}% for replacements to be accepted"
+            ),
+        );
+    }
+    let max_fee_per_gas = escalation
+        .chain(err)
+        .get_opt_key("maxFeePerGas")
+        .parse_u256()
+        .end();
+    let max_replacements = escalation
+        .chain(err)
+        .get_opt_key("maxReplacements")
+        .parse_u32()
+        .unwrap_or(default.max_replacements);
+
+    h_eth::GasEscalationConf {
+        escalation_interval,
+        poll_interval,
+        fee_bump_percent,
+        max_fee_per_gas,
+        max_replacements,
+    }
+}
+
 /// Expects ChainMetadata
 fn parse_domain(chain: ValueParser, name: &str) -> ConfigResult<HyperlaneDomain> {
     let mut err = ConfigParsingError::default();
@@ -265,6 +358,10 @@ fn parse_domain(chain: ValueParser, name: &str) -> ConfigResult<HyperlaneDomain>
     err.into_result(domain)
 }
 
+/// How long to wait for a remote signing service to sign before giving up,
+/// unless configured otherwise.
+const DEFAULT_REMOTE_SIGNER_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Expects AgentSigner.
 fn parse_signer(signer: ValueParser) -> ConfigResult<SignerConf> {
     let mut err = ConfigParsingError::default();
@@ -300,7 +397,13 @@ fn parse_signer(signer: ValueParser) -> ConfigResult<SignerConf> {
                 .get_key("region")
                 .parse_from_str("Expected AWS region")
                 .unwrap_or_default();
-            err.into_result(SignerConf::Aws { id, region })
+            let prefix = signer
+                .chain(&mut err)
+                .get_opt_key("prefix")
+                .parse_string()
+                .map(ToOwned::to_owned)
+                .end();
+            err.into_result(SignerConf::Aws { id, region, prefix })
         }};
         (cosmosKey) => {{
             let key = signer
@@ -318,12 +421,87 @@ fn parse_signer(signer: ValueParser) -> ConfigResult<SignerConf> {
                 prefix: prefix.to_string(),
             })
         }};
+        (remote) => {{
+            let url = signer
+                .chain(&mut err)
+                .get_key("url")
+                .parse_from_str("Expected signing service url")
+                .end();
+            let public_key = signer
+                .chain(&mut err)
+                .get_key("publicKey")
+                .parse_string()
+                .end()
+                .map(ToOwned::to_owned);
+            if public_key.as_deref() == Some("") {
+                err.push(
+                    &signer.cwp + "public_key",
+                    eyre!("Expected a non-empty signing service public key"),
+                );
+            }
+            let bearer_token = signer
+                .chain(&mut err)
+                .get_opt_key("bearerToken")
+                .parse_string()
+                .map(ToOwned::to_owned)
+                .end();
+            let client_identity = signer
+                .chain(&mut err)
+                .get_opt_key("clientIdentity")
+                .parse_from_str("Expected client identity path")
+                .end();
+            let ca_certificate = signer
+                .chain(&mut err)
+                .get_opt_key("caCertificate")
+                .parse_from_str("Expected CA certificate path")
+                .end();
+            let timeout = signer
+                .chain(&mut err)
+                .get_opt_key("timeoutSecs")
+                .parse_u64()
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_REMOTE_SIGNER_TIMEOUT);
+            cfg_unwrap_all!(&signer.cwp, err: [url, public_key]);
+            err.into_result(SignerConf::Remote {
+                url,
+                public_key,
+                bearer_token,
+                client_identity,
+                ca_certificate,
+                timeout,
+            })
+        }};
+        (keystore) => {{
+            let path = signer
+                .chain(&mut err)
+                .get_key("path")
+                .parse_from_str("Expected keystore path")
+                .unwrap_or_default();
+            let password_file = signer
+                .chain(&mut err)
+                .get_opt_key("passwordFile")
+                .parse_from_str("Expected keystore password file path")
+                .end();
+            let prefix = signer
+                .chain(&mut err)
+                .get_opt_key("prefix")
+                .parse_string()
+                .map(ToOwned::to_owned)
+                .end();
+            err.into_result(SignerConf::Keystore {
+                path,
+                password_file,
+                prefix,
+            })
+        }};
     }
 
     match signer_type {
         Some("hexKey") => parse_signer!(hexKey),
         Some("aws") => parse_signer!(aws),
         Some("cosmosKey") => parse_signer!(cosmosKey),
+        Some("remote") => parse_signer!(remote),
+        Some("keystore") => parse_signer!(keystore),
         Some(t) => {
             Err(eyre!("Unknown signer type `{t}`")).into_config_result(|| &signer.cwp + "type")
         }
@@ -450,3 +628,109 @@ fn parse_base_and_override_urls(
     }
     combined
 }
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::H256;
+    use serde_json::json;
+
+    use super::*;
+
+    /// The raw settings of a single `test1` chain with the given signer
+    /// pool. Keys are in flat case, as the settings loader leaves them.
+    fn raw_settings(signer_pool: Option<Value>) -> RawAgentConf {
+        let mut chain = json!({
+            "name": "test1",
+            "domainid": 13371,
+            "protocol": "ethereum",
+            "rpcurls": [{ "http": "http://127.0.0.1:8545" }],
+            "mailbox": "0x0000000000000000000000000000000000000001",
+            "interchaingaspaymaster": "0x0000000000000000000000000000000000000002",
+            "validatorannounce": "0x0000000000000000000000000000000000000003",
+            "merkletreehook": "0x0000000000000000000000000000000000000004",
+            "signer": { "type": "hexKey", "key": hex_key(1) },
+        });
+        if let Some(signer_pool) = signer_pool {
+            chain["signerpool"] = signer_pool;
+        }
+        RawAgentConf(json!({ "chains": { "test1": chain } }))
+    }
+
+    fn hex_key(key: u64) -> String {
+        format!("{:?}", H256::from_low_u64_be(key))
+    }
+
+    /// The hex keys of the signers of each chain config
+    fn signer_keys(confs: &[ChainConf]) -> Vec<H256> {
+        confs
+            .iter()
+            .map(|conf| match &conf.signer {
+                Some(SignerConf::HexKey { key }) => *key,
+                signer => panic!("Unexpected signer {signer:?}"),
+            })
+            .collect()
+    }
+
+    fn parse_chain_conf(raw: RawAgentConf) -> ChainConf {
+        let mut settings = Settings::from_config(raw, &ConfigPath::default()).unwrap();
+        settings.chains.remove("test1").unwrap()
+    }
+
+    #[test]
+    fn test_parses_signer_pool() {
+        let conf = parse_chain_conf(raw_settings(Some(json!([
+            { "type": "hexKey", "key": hex_key(2) },
+            { "type": "hexKey", "key": hex_key(3) },
+        ]))));
+
+        assert_eq!(conf.signer_pool.len(), 2);
+        let pool_confs = conf.signer_pool_confs();
+        assert_eq!(
+            signer_keys(&pool_confs),
+            [H256::from_low_u64_be(2), H256::from_low_u64_be(3)]
+        );
+        // Each config of the pool only signs with its own signer
+        for pool_conf in &pool_confs {
+            assert!(pool_conf.signer_pool.is_empty());
+            assert_eq!(pool_conf.domain, conf.domain);
+            assert_eq!(pool_conf.addresses.mailbox, conf.addresses.mailbox);
+        }
+    }
+
+    #[test]
+    fn test_parses_single_signer_pool() {
+        let conf = parse_chain_conf(raw_settings(Some(json!([
+            { "type": "hexKey", "key": hex_key(2) },
+        ]))));
+
+        assert_eq!(
+            signer_keys(&conf.signer_pool_confs()),
+            [H256::from_low_u64_be(2)]
+        );
+        // The chain's own signer is left as configured
+        assert_eq!(signer_keys(&[conf]), [H256::from_low_u64_be(1)]);
+    }
+
+    #[test]
+    fn test_parses_empty_signer_pool() {
+        let conf = parse_chain_conf(raw_settings(None));
+        assert!(conf.signer_pool.is_empty());
+        assert!(conf.signer_pool_confs().is_empty());
+
+        let conf = parse_chain_conf(raw_settings(Some(json!([]))));
+        assert!(conf.signer_pool.is_empty());
+        assert!(conf.signer_pool_confs().is_empty());
+    }
+
+    #[test]
+    fn test_rejects_invalid_signer_pool_signer() {
+        let result = Settings::from_config(
+            raw_settings(Some(json!([
+                { "type": "hexKey", "key": hex_key(2) },
+                { "type": "hexKey" },
+            ]))),
+            &ConfigPath::default(),
+        );
+        assert!(result.is_err());
+    }
+}
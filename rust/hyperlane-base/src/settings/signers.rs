@@ -1,16 +1,23 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
 use async_trait::async_trait;
 use ed25519_dalek::SecretKey;
 use ethers::prelude::{AwsSigner, LocalWallet};
 use ethers::utils::hex::ToHex;
 use eyre::{bail, Context, Report};
 use hyperlane_core::H256;
-use hyperlane_sealevel::Keypair;
+use hyperlane_ethereum::RemoteSigner;
+use hyperlane_sealevel::{Keypair, SealevelSigner};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
 use rusoto_core::Region;
-use rusoto_kms::KmsClient;
 use tracing::instrument;
+use url::Url;
 
-use super::aws_credentials::AwsChainCredentialsProvider;
-use crate::types::utils;
+use super::aws_kms::{kms_client, AwsKmsKey};
 
 /// Signer types
 #[derive(Default, Debug, Clone)]
@@ -27,6 +34,8 @@ pub enum SignerConf {
         id: String,
         /// The AWS region
         region: Region,
+        /// Prefix for cosmos address, only needed for Cosmos
+        prefix: Option<String>,
     },
     /// Cosmos Specific key
     CosmosKey {
@@ -35,6 +44,36 @@ pub enum SignerConf {
         /// Prefix for cosmos address
         prefix: String,
     },
+    /// A key held by a remote signing service with a Web3Signer compatible
+    /// HTTP API
+    Remote {
+        /// The URL of the signing service
+        url: Url,
+        /// The hex encoded public key identifying the key in the signing
+        /// service
+        public_key: String,
+        /// A token to authenticate with the signing service as a bearer
+        bearer_token: Option<String>,
+        /// A PEM file with the client certificate and private key to
+        /// authenticate with the signing service over mTLS
+        client_identity: Option<PathBuf>,
+        /// A PEM file with a CA certificate to trust for the signing service,
+        /// in addition to the system's
+        ca_certificate: Option<PathBuf>,
+        /// How long to wait for a signature
+        timeout: Duration,
+    },
+    /// A key in an encrypted Ethereum JSON keystore file, or for Sealevel
+    /// also a Solana keypair file
+    Keystore {
+        /// The path of the keystore file
+        path: PathBuf,
+        /// The path of a file with the keystore password, not needed for
+        /// Solana keypair files
+        password_file: Option<PathBuf>,
+        /// Prefix for cosmos address, only needed for Cosmos
+        prefix: Option<String>,
+    },
     /// Assume node will sign on RPC calls
     #[default]
     Node,
@@ -65,20 +104,9 @@ pub trait BuildableWithSignerConf: Sized + ChainSigner {
 impl BuildableWithSignerConf for hyperlane_ethereum::Signers {
     async fn build(conf: &SignerConf) -> Result<Self, Report> {
         Ok(match conf {
-            SignerConf::HexKey { key } => hyperlane_ethereum::Signers::Local(LocalWallet::from(
-                ethers::core::k256::ecdsa::SigningKey::from(
-                    ethers::core::k256::SecretKey::from_be_bytes(key.as_bytes())
-                        .context("Invalid ethereum signer key")?,
-                ),
-            )),
-            SignerConf::Aws { id, region } => {
-                let client = KmsClient::new_with_client(
-                    rusoto_core::Client::new_with(
-                        AwsChainCredentialsProvider::new(),
-                        utils::http_client_with_timeout().unwrap(),
-                    ),
-                    region.clone(),
-                );
+            SignerConf::HexKey { key } => hyperlane_ethereum::Signers::Local(local_wallet(key)?),
+            SignerConf::Aws { id, region, .. } => {
+                let client = kms_client(region.clone());
 
                 let signer = AwsSigner::new(client, id, 0).await?;
                 hyperlane_ethereum::Signers::Aws(signer)
@@ -86,11 +114,72 @@ impl BuildableWithSignerConf for hyperlane_ethereum::Signers {
             SignerConf::CosmosKey { .. } => {
                 bail!("cosmosKey signer is not supported by Ethereum")
             }
+            SignerConf::Remote {
+                url, public_key, ..
+            } => hyperlane_ethereum::Signers::Remote(
+                RemoteSigner::new(remote_signer_client(conf)?, url.clone(), public_key.clone())
+                    .context("Invalid remote signer")?,
+            ),
+            SignerConf::Keystore {
+                path,
+                password_file,
+                ..
+            } => hyperlane_ethereum::Signers::Local(local_wallet(&keystore_secret_key(
+                path,
+                password_file.as_deref(),
+                "ethereum",
+            )?)?),
             SignerConf::Node => bail!("Node signer"),
         })
     }
 }
 
+fn local_wallet(key: &H256) -> Result<LocalWallet, Report> {
+    Ok(LocalWallet::from(
+        ethers::core::k256::ecdsa::SigningKey::from(
+            ethers::core::k256::SecretKey::from_be_bytes(key.as_bytes())
+                .context("Invalid ethereum signer key")?,
+        ),
+    ))
+}
+
+/// Builds the HTTP client for a remote signing service, with the auth and
+/// timeout configured for it
+fn remote_signer_client(conf: &SignerConf) -> Result<reqwest::Client, Report> {
+    let SignerConf::Remote {
+        bearer_token,
+        client_identity,
+        ca_certificate,
+        timeout,
+        ..
+    } = conf
+    else {
+        bail!("Not a remote signer")
+    };
+    let mut builder = reqwest::Client::builder()
+        .use_rustls_tls()
+        .timeout(*timeout);
+    if let Some(token) = bearer_token {
+        let mut value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .context("Invalid remote signer bearer token")?;
+        value.set_sensitive(true);
+        builder = builder.default_headers(HeaderMap::from_iter([(AUTHORIZATION, value)]));
+    }
+    if let Some(path) = client_identity {
+        let pem = std::fs::read(path).context("Failed to read remote signer client identity")?;
+        builder = builder.identity(
+            reqwest::Identity::from_pem(&pem).context("Invalid remote signer client identity")?,
+        );
+    }
+    if let Some(path) = ca_certificate {
+        let pem = std::fs::read(path).context("Failed to read remote signer CA certificate")?;
+        builder = builder.add_root_certificate(
+            reqwest::Certificate::from_pem(&pem).context("Invalid remote signer CA certificate")?,
+        );
+    }
+    Ok(builder.build()?)
+}
+
 impl ChainSigner for hyperlane_ethereum::Signers {
     fn address_string(&self) -> String {
         ethers::signers::Signer::address(self).encode_hex()
@@ -100,15 +189,20 @@ impl ChainSigner for hyperlane_ethereum::Signers {
 #[async_trait]
 impl BuildableWithSignerConf for fuels::prelude::WalletUnlocked {
     async fn build(conf: &SignerConf) -> Result<Self, Report> {
-        if let SignerConf::HexKey { key } = conf {
-            let key = fuels::signers::fuel_crypto::SecretKey::try_from(key.as_bytes())
-                .context("Invalid fuel signer key")?;
-            Ok(fuels::prelude::WalletUnlocked::new_from_private_key(
-                key, None,
-            ))
-        } else {
-            bail!(format!("{conf:?} key is not supported by fuel"));
-        }
+        let key = match conf {
+            SignerConf::HexKey { key } => *key,
+            SignerConf::Keystore {
+                path,
+                password_file,
+                ..
+            } => keystore_secret_key(path, password_file.as_deref(), "fuel")?,
+            _ => bail!(format!("{conf:?} key is not supported by fuel")),
+        };
+        let key = fuels::signers::fuel_crypto::SecretKey::try_from(key.as_bytes())
+            .context("Invalid fuel signer key")?;
+        Ok(fuels::prelude::WalletUnlocked::new_from_private_key(
+            key, None,
+        ))
     }
 }
 
@@ -119,38 +213,77 @@ impl ChainSigner for fuels::prelude::WalletUnlocked {
 }
 
 #[async_trait]
-impl BuildableWithSignerConf for Keypair {
+impl BuildableWithSignerConf for SealevelSigner {
     async fn build(conf: &SignerConf) -> Result<Self, Report> {
-        if let SignerConf::HexKey { key } = conf {
-            let secret = SecretKey::from_bytes(key.as_bytes())
-                .context("Invalid sealevel ed25519 secret key")?;
-            Ok(
-                Keypair::from_bytes(&ed25519_dalek::Keypair::from(secret).to_bytes())
-                    .context("Unable to create Keypair")?,
-            )
-        } else {
-            bail!(format!("{conf:?} key is not supported by sealevel"));
-        }
+        let key = match conf {
+            SignerConf::HexKey { key } => *key,
+            SignerConf::Keystore {
+                path,
+                password_file,
+                ..
+            } => match read_keystore(path, password_file.as_deref())? {
+                KeystoreKey::Secret(key) => key,
+                KeystoreKey::SolanaKeypair(keypair) => {
+                    return Ok(Keypair::from_bytes(&keypair)
+                        .context("Invalid Solana keypair file")?
+                        .into());
+                }
+            },
+            SignerConf::Aws { id, region, .. } => {
+                let key = AwsKmsKey::new(id.clone(), region.clone());
+                return Ok(SealevelSigner::new_kms(Arc::new(key)).await?);
+            }
+            _ => bail!(format!("{conf:?} key is not supported by sealevel")),
+        };
+        let secret =
+            SecretKey::from_bytes(key.as_bytes()).context("Invalid sealevel ed25519 secret key")?;
+        Ok(
+            Keypair::from_bytes(&ed25519_dalek::Keypair::from(secret).to_bytes())
+                .context("Unable to create Keypair")?
+                .into(),
+        )
     }
 }
 
-impl ChainSigner for Keypair {
+impl ChainSigner for SealevelSigner {
     fn address_string(&self) -> String {
-        solana_sdk::signer::Signer::pubkey(self).to_string()
+        self.pubkey().to_string()
     }
 }
 
 #[async_trait]
 impl BuildableWithSignerConf for hyperlane_cosmos::Signer {
     async fn build(conf: &SignerConf) -> Result<Self, Report> {
-        if let SignerConf::CosmosKey { key, prefix } = conf {
-            Ok(hyperlane_cosmos::Signer::new(
-                key.as_bytes().to_vec(),
-                prefix.clone(),
-            )?)
-        } else {
-            bail!(format!("{conf:?} key is not supported by cosmos"));
-        }
+        let (key, prefix) = match conf {
+            SignerConf::CosmosKey { key, prefix } => (*key, prefix),
+            SignerConf::Aws {
+                id,
+                region,
+                prefix: Some(prefix),
+            } => {
+                let key = AwsKmsKey::new(id.clone(), region.clone());
+                return Ok(hyperlane_cosmos::Signer::new_kms(Arc::new(key), prefix.clone()).await?);
+            }
+            SignerConf::Aws { prefix: None, .. } => {
+                bail!("aws signer needs a prefix for cosmos")
+            }
+            SignerConf::Keystore {
+                path,
+                password_file,
+                prefix: Some(prefix),
+            } => (
+                keystore_secret_key(path, password_file.as_deref(), "cosmos")?,
+                prefix,
+            ),
+            SignerConf::Keystore { prefix: None, .. } => {
+                bail!("keystore signer needs a prefix for cosmos")
+            }
+            _ => bail!(format!("{conf:?} key is not supported by cosmos")),
+        };
+        Ok(hyperlane_cosmos::Signer::new(
+            key.as_bytes().to_vec(),
+            prefix.clone(),
+        )?)
     }
 }
 
@@ -159,3 +292,150 @@ impl ChainSigner for hyperlane_cosmos::Signer {
         self.address.clone()
     }
 }
+
+/// A private key read from a keystore file
+enum KeystoreKey {
+    /// The secret key of an Ethereum JSON keystore
+    Secret(H256),
+    /// The keypair of a Solana keypair file, i.e. the ed25519 secret key
+    /// followed by the public key
+    SolanaKeypair(Vec<u8>),
+}
+
+/// Reads a keystore file, which is a Solana keypair file if it's a JSON array
+/// and an Ethereum JSON keystore (scrypt or pbkdf2) otherwise
+fn read_keystore(path: &Path, password_file: Option<&Path>) -> Result<KeystoreKey, Report> {
+    let contents = std::fs::read(path)
+        .with_context(|| format!("Failed to read keystore {}", path.display()))?;
+    let json: serde_json::Value =
+        serde_json::from_slice(&contents).context("Keystore is not valid JSON")?;
+    if json.is_array() {
+        let keypair: Vec<u8> =
+            serde_json::from_value(json).context("Invalid Solana keypair file")?;
+        return Ok(KeystoreKey::SolanaKeypair(keypair));
+    }
+
+    let Some(password_file) = password_file else {
+        bail!(
+            "keystore signer needs a password file to decrypt {}",
+            path.display()
+        )
+    };
+    let password = std::fs::read_to_string(password_file).with_context(|| {
+        format!(
+            "Failed to read keystore password file {}",
+            password_file.display()
+        )
+    })?;
+    let key = eth_keystore::decrypt_key(path, password.trim_end_matches(['\r', '\n']))
+        .with_context(|| format!("Failed to decrypt keystore {}", path.display()))?;
+    if key.len() != H256::len_bytes() {
+        bail!("Keystore {} does not hold a 32 byte key", path.display());
+    }
+    Ok(KeystoreKey::Secret(H256::from_slice(&key)))
+}
+
+/// Reads the secret key of an Ethereum JSON keystore, for protocols that
+/// don't support Solana keypair files
+fn keystore_secret_key(
+    path: &Path,
+    password_file: Option<&Path>,
+    protocol: &str,
+) -> Result<H256, Report> {
+    match read_keystore(path, password_file)? {
+        KeystoreKey::Secret(key) => Ok(key),
+        KeystoreKey::SolanaKeypair(_) => {
+            bail!("Solana keypair files are not supported by {protocol}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::core::rand::thread_rng;
+    use solana_sdk::signer::keypair::write_keypair_file;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const KEY: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+
+    /// Writes `KEY` to an Ethereum keystore encrypted with `password`
+    fn keystore_conf(dir: &TempDir, prefix: Option<&str>) -> SignerConf {
+        eth_keystore::encrypt_key(
+            dir.path(),
+            &mut thread_rng(),
+            KEY.parse::<H256>().unwrap(),
+            "password",
+            Some("keystore.json"),
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("password"), "password\n").unwrap();
+        SignerConf::Keystore {
+            path: dir.path().join("keystore.json"),
+            password_file: Some(dir.path().join("password")),
+            prefix: prefix.map(ToOwned::to_owned),
+        }
+    }
+
+    #[tokio::test]
+    async fn builds_signers_from_ethereum_keystore() {
+        let dir = TempDir::new().unwrap();
+        let hex_key = SignerConf::HexKey {
+            key: KEY.parse().unwrap(),
+        };
+
+        let conf = keystore_conf(&dir, None);
+        let signer: hyperlane_ethereum::Signers = conf.build().await.unwrap();
+        let expected: hyperlane_ethereum::Signers = hex_key.build().await.unwrap();
+        assert_eq!(signer.address_string(), expected.address_string());
+
+        let signer: SealevelSigner = conf.build().await.unwrap();
+        let expected: SealevelSigner = hex_key.build().await.unwrap();
+        assert_eq!(signer.address_string(), expected.address_string());
+
+        let wallet: fuels::prelude::WalletUnlocked = conf.build().await.unwrap();
+        let expected: fuels::prelude::WalletUnlocked = hex_key.build().await.unwrap();
+        assert_eq!(wallet.address_string(), expected.address_string());
+
+        assert!(conf.build::<hyperlane_cosmos::Signer>().await.is_err());
+        let signer: hyperlane_cosmos::Signer =
+            keystore_conf(&dir, Some("neutron")).build().await.unwrap();
+        let expected: hyperlane_cosmos::Signer = SignerConf::CosmosKey {
+            key: KEY.parse().unwrap(),
+            prefix: "neutron".to_owned(),
+        }
+        .build()
+        .await
+        .unwrap();
+        assert_eq!(signer.address_string(), expected.address_string());
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_keystore_password() {
+        let dir = TempDir::new().unwrap();
+        let conf = keystore_conf(&dir, None);
+        std::fs::write(dir.path().join("password"), "wrong").unwrap();
+        assert!(conf.build::<hyperlane_ethereum::Signers>().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn builds_sealevel_keypair_from_solana_keypair_file() {
+        let dir = TempDir::new().unwrap();
+        let expected = Keypair::new();
+        let path = dir.path().join("keypair.json");
+        write_keypair_file(&expected, &path).unwrap();
+        let conf = SignerConf::Keystore {
+            path,
+            password_file: None,
+            prefix: None,
+        };
+
+        let signer: SealevelSigner = conf.build().await.unwrap();
+        assert_eq!(
+            signer.pubkey(),
+            solana_sdk::signer::Signer::pubkey(&expected)
+        );
+        assert!(conf.build::<hyperlane_ethereum::Signers>().await.is_err());
+    }
+}
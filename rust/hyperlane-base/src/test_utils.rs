@@ -0,0 +1,14 @@
+use ethers::signers::LocalWallet;
+use hyperlane_ethereum::Signers;
+
+/// Private keys of the first accounts of the well-known test mnemonic that
+/// anvil and hardhat fund
+const TEST_SIGNER_KEYS: [&str; 2] = [
+    "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+    "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d",
+];
+
+/// A local signer with the key of the test account at `index`
+pub fn test_signer(index: usize) -> Signers {
+    Signers::Local(TEST_SIGNER_KEYS[index].parse::<LocalWallet>().unwrap())
+}
@@ -31,4 +31,9 @@ pub trait CheckpointSyncer: Debug + Send + Sync {
     async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()>;
     /// Return the announcement storage location for this syncer
     fn announcement_location(&self) -> String;
+    /// Return every storage location this syncer writes to, each of which
+    /// should be announced
+    fn announcement_locations(&self) -> Vec<String> {
+        vec![self.announcement_location()]
+    }
 }
@@ -0,0 +1,242 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use eyre::{bail, eyre, Result};
+use hyperlane_core::{SignedAnnouncement, SignedCheckpointWithMessageId};
+use prometheus::IntGauge;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::{redirect, StatusCode};
+use url::{Host, Url};
+
+use crate::{CheckpointSyncer, LocalStorage};
+
+/// The timeout for requests to a validator's checkpoint server
+const HTTP_REQUEST_TIMEOUT_SECONDS: u64 = 30;
+
+/// The max size of a served file. Signed checkpoints and indexes are well
+/// under a kilobyte.
+const MAX_RESPONSE_BYTES: usize = 64 * 1024;
+
+/// Type for reading checkpoints a validator serves over HTTP.
+///
+/// The served files are laid out like a `LocalStorage`. The validator serving
+/// them also holds that `LocalStorage`, which it writes to and reads from
+/// directly. Everyone else can only read.
+///
+/// Anyone can announce a URL, so requests don't follow redirects, and a URL
+/// with a public host is only ever connected to at public addresses.
+#[derive(Debug, Clone)]
+pub struct HttpStorage {
+    /// The URL the checkpoints are served at
+    url: Url,
+    /// The storage backing the served checkpoints, only set for the validator
+    /// serving them
+    local: Option<LocalStorage>,
+    client: reqwest::Client,
+    /// The latest seen signed checkpoint index.
+    latest_index: Option<IntGauge>,
+}
+
+impl HttpStorage {
+    /// Create a new HttpStorage checkpoint syncer instance.
+    pub fn new(
+        url: Url,
+        local: Option<LocalStorage>,
+        latest_index: Option<IntGauge>,
+    ) -> Result<Self> {
+        let mut client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(HTTP_REQUEST_TIMEOUT_SECONDS))
+            .redirect(redirect::Policy::none());
+        if is_public_url(&url) {
+            client = client.dns_resolver(Arc::new(PublicAddressResolver));
+        }
+        let client = client.build()?;
+        Ok(Self {
+            url,
+            local,
+            client,
+            latest_index,
+        })
+    }
+
+    /// Fetches a served file, returning `None` if it doesn't exist
+    async fn fetch(&self, file: &str) -> Result<Option<Vec<u8>>> {
+        // Joining a relative path onto a URL replaces its last segment unless
+        // it ends with a slash
+        let mut url = self.url.clone();
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+        let response = self.client.get(url.join(file)?).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let mut response = response.error_for_status()?;
+        let mut data = vec![];
+        while let Some(chunk) = response.chunk().await? {
+            if data.len() + chunk.len() > MAX_RESPONSE_BYTES {
+                bail!(
+                    "{file} served at {} exceeds {MAX_RESPONSE_BYTES} bytes",
+                    self.url
+                );
+            }
+            data.extend_from_slice(&chunk);
+        }
+        Ok(Some(data))
+    }
+
+    fn local(&self) -> Result<&LocalStorage> {
+        match &self.local {
+            Some(local) => Ok(local),
+            None => bail!("Checkpoints served at {} are read-only", self.url),
+        }
+    }
+}
+
+#[async_trait]
+impl CheckpointSyncer for HttpStorage {
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        if let Some(local) = &self.local {
+            return local.latest_index().await;
+        }
+        let Some(data) = self.fetch("index.json").await? else {
+            return Ok(None);
+        };
+        let index = String::from_utf8(data)?.trim().parse()?;
+        if let Some(gauge) = &self.latest_index {
+            gauge.set(index as i64);
+        }
+        Ok(Some(index))
+    }
+
+    async fn write_latest_index(&self, index: u32) -> Result<()> {
+        self.local()?.write_latest_index(index).await
+    }
+
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        if let Some(local) = &self.local {
+            return local.fetch_checkpoint(index).await;
+        }
+        self.fetch(&format!("{}_with_id.json", index))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn write_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        self.local()?.write_checkpoint(signed_checkpoint).await
+    }
+
+    async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
+        self.local()?.write_announcement(signed_announcement).await
+    }
+
+    fn announcement_location(&self) -> String {
+        self.url.to_string()
+    }
+}
+
+/// Resolves hosts to their public addresses only, so that a public URL can't
+/// be pointed at the reader's own network through DNS.
+struct PublicAddressResolver;
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            // The port is set by the connector
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(eyre!("{} has no public address", name.as_str()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Whether the URL's host is neither a non-public address nor a name for the
+/// local machine or network. Names may still resolve to non-public addresses.
+pub(crate) fn is_public_url(url: &Url) -> bool {
+    match url.host() {
+        Some(Host::Ipv4(ip)) => is_public_ip(ip.into()),
+        Some(Host::Ipv6(ip)) => is_public_ip(ip.into()),
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            !["localhost", "local", "internal"]
+                .iter()
+                .any(|local| domain == *local || domain.ends_with(&format!(".{local}")))
+        }
+        None => false,
+    }
+}
+
+/// Whether the address is reachable on the public internet
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_unspecified()
+        // "this network"
+        || a == 0
+        // shared address space
+        || (a == 100 && (64..128).contains(&b))
+        // reserved
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first_segment = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        // unique local
+        || (first_segment & 0xfe00) == 0xfc00
+        // link local
+        || (first_segment & 0xffc0) == 0xfe80)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_public_url() {
+        let is_public = |url: &str| is_public_url(&url.parse().unwrap());
+
+        assert!(is_public("https://checkpoints.example.com/validator"));
+        assert!(is_public("http://8.8.8.8"));
+        assert!(is_public("http://[2001:4860:4860::8888]"));
+
+        assert!(!is_public("http://localhost:3000"));
+        assert!(!is_public("http://validator.localhost"));
+        assert!(!is_public("http://metadata.google.internal"));
+        assert!(!is_public("http://127.0.0.1"));
+        assert!(!is_public("http://10.0.0.1"));
+        assert!(!is_public("http://169.254.169.254/latest/meta-data"));
+        assert!(!is_public("http://100.64.0.1"));
+        assert!(!is_public("http://0.0.0.0"));
+        assert!(!is_public("http://[::1]"));
+        assert!(!is_public("http://[fd00::1]"));
+        assert!(!is_public("http://[::ffff:127.0.0.1]"));
+    }
+}
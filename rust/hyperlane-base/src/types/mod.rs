@@ -1,12 +1,16 @@
 mod gcs_storage;
+mod http_storage;
 mod local_storage;
 mod multisig;
+mod replicated_storage;
 mod s3_storage;
 
 /// Reusable logic for working with storage backends.
 pub mod utils;
 
 pub use gcs_storage::*;
+pub use http_storage::*;
 pub use local_storage::*;
 pub use multisig::*;
+pub use replicated_storage::*;
 pub use s3_storage::*;
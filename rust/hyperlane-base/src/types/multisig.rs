@@ -1,9 +1,10 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use derive_new::new;
 use eyre::Result;
-use tracing::{debug, instrument};
+use lru::LruCache;
+use tracing::{debug, instrument, warn};
 
 use hyperlane_core::{
     HyperlaneDomain, MultisigSignedCheckpoint, SignedCheckpointWithMessageId, H160, H256,
@@ -11,6 +12,41 @@ use hyperlane_core::{
 
 use crate::{CheckpointSyncer, CoreMetrics};
 
+/// The most verified checkpoints kept in a `VerifiedCheckpointCache`
+const VERIFIED_CHECKPOINT_CACHE_SIZE: usize = 10_000;
+
+/// The origin domain, merkle tree hook, validator and index of a checkpoint
+type VerifiedCheckpointKey = (u32, H256, H160, u32);
+
+/// Checkpoints fetched from validators whose signature was verified, so that
+/// retries never download them again. Only the most recently used ones are
+/// kept.
+#[derive(Clone, Debug)]
+pub struct VerifiedCheckpointCache(
+    Arc<Mutex<LruCache<VerifiedCheckpointKey, SignedCheckpointWithMessageId>>>,
+);
+
+impl Default for VerifiedCheckpointCache {
+    fn default() -> Self {
+        Self::new(VERIFIED_CHECKPOINT_CACHE_SIZE)
+    }
+}
+
+impl VerifiedCheckpointCache {
+    /// A cache holding at most `capacity` checkpoints
+    pub fn new(capacity: usize) -> Self {
+        Self(Arc::new(Mutex::new(LruCache::new(capacity))))
+    }
+
+    fn get(&self, key: &VerifiedCheckpointKey) -> Option<SignedCheckpointWithMessageId> {
+        self.0.lock().unwrap().get(key).cloned()
+    }
+
+    fn put(&self, key: VerifiedCheckpointKey, signed_checkpoint: SignedCheckpointWithMessageId) {
+        self.0.lock().unwrap().put(key, signed_checkpoint);
+    }
+}
+
 /// For a particular validator set, fetches signed checkpoints from multiple
 /// validators to create MultisigSignedCheckpoints.
 #[derive(Clone, Debug, new)]
@@ -19,9 +55,20 @@ pub struct MultisigCheckpointSyncer {
     checkpoint_syncers: HashMap<H160, Arc<dyn CheckpointSyncer>>,
     metrics: Arc<CoreMetrics>,
     app_context: Option<String>,
+    /// The origin chain the checkpoints are of
+    origin: HyperlaneDomain,
+    /// The origin chain's merkle tree hook the checkpoints are of
+    merkle_tree_hook_address: H256,
+    verified_checkpoints: VerifiedCheckpointCache,
 }
 
 impl MultisigCheckpointSyncer {
+    /// The checkpoint syncer of each validator with a valid announced storage
+    /// location.
+    pub fn checkpoint_syncers(&self) -> &HashMap<H160, Arc<dyn CheckpointSyncer>> {
+        &self.checkpoint_syncers
+    }
+
     /// Gets the latest checkpoint index from each validator's checkpoint syncer.
     /// Returns a vector of the latest indices, in an unspecified order, and does
     /// not contain indices for validators that did not provide a latest index.
@@ -154,34 +201,10 @@ impl MultisigCheckpointSyncer {
         for validator in validators.iter() {
             let addr = H160::from(*validator);
             if let Some(checkpoint_syncer) = self.checkpoint_syncers.get(&addr) {
-                // Gracefully ignore an error fetching the checkpoint from a validator's
-                // checkpoint syncer, which can happen if the validator has not
-                // signed the checkpoint at `index`.
-                if let Ok(Some(signed_checkpoint)) = checkpoint_syncer.fetch_checkpoint(index).await
+                if let Some(signed_checkpoint) = self
+                    .fetch_verified_checkpoint(addr, checkpoint_syncer.as_ref(), index)
+                    .await
                 {
-                    // If the signed checkpoint is for a different index, ignore it
-                    if signed_checkpoint.value.index != index {
-                        debug!(
-                            validator = format!("{:#x}", validator),
-                            index = index,
-                            checkpoint_index = signed_checkpoint.value.index,
-                            "Checkpoint index mismatch"
-                        );
-                        continue;
-                    }
-
-                    // Ensure that the signature is actually by the validator
-                    let signer = signed_checkpoint.recover()?;
-
-                    if H256::from(signer) != *validator {
-                        debug!(
-                            validator = format!("{:#x}", validator),
-                            index = index,
-                            "Checkpoint signature mismatch"
-                        );
-                        continue;
-                    }
-
                     // Push the signed checkpoint into the hashmap
                     let root = signed_checkpoint.value.root;
                     let signed_checkpoints = signed_checkpoints_per_root.entry(root).or_default();
@@ -218,4 +241,188 @@ impl MultisigCheckpointSyncer {
         debug!("No quorum checkpoint found for message");
         Ok(None)
     }
+
+    /// Fetches `validator`'s signed checkpoint at `index`, if the validator
+    /// has a checkpoint syncer and the checkpoint's signature recovers to it.
+    pub async fn fetch_validator_checkpoint(
+        &self,
+        validator: H256,
+        index: u32,
+    ) -> Option<SignedCheckpointWithMessageId> {
+        let address = H160::from(validator);
+        let checkpoint_syncer = self.checkpoint_syncers.get(&address)?;
+        self.fetch_verified_checkpoint(address, checkpoint_syncer.as_ref(), index)
+            .await
+    }
+
+    /// Fetches a validator's signed checkpoint at `index`, as long as its
+    /// signature recovers to the validator. Checkpoints with an invalid
+    /// signature are rejected and counted per validator. Verified checkpoints
+    /// of the origin's merkle tree hook are cached.
+    async fn fetch_verified_checkpoint(
+        &self,
+        validator: H160,
+        checkpoint_syncer: &dyn CheckpointSyncer,
+        index: u32,
+    ) -> Option<SignedCheckpointWithMessageId> {
+        let key = (
+            self.origin.id(),
+            self.merkle_tree_hook_address,
+            validator,
+            index,
+        );
+        if let Some(signed_checkpoint) = self.verified_checkpoints.get(&key) {
+            return Some(signed_checkpoint);
+        }
+
+        // Gracefully ignore an error fetching the checkpoint from a validator's
+        // checkpoint syncer, which can happen if the validator has not
+        // signed the checkpoint at `index`.
+        let Ok(Some(signed_checkpoint)) = checkpoint_syncer.fetch_checkpoint(index).await else {
+            return None;
+        };
+
+        // If the signed checkpoint is for a different index, ignore it
+        if signed_checkpoint.value.index != index {
+            debug!(
+                validator = format!("{:#x}", validator),
+                index = index,
+                checkpoint_index = signed_checkpoint.value.index,
+                "Checkpoint index mismatch"
+            );
+            return None;
+        }
+
+        // Ensure that the signature is actually by the validator
+        match signed_checkpoint.recover() {
+            Ok(signer) if signer == validator => {}
+            recovered => {
+                warn!(
+                    validator = format!("{:#x}", validator),
+                    index = index,
+                    ?recovered,
+                    "Rejected checkpoint not signed by the validator"
+                );
+                self.metrics
+                    .invalid_checkpoint_signatures()
+                    .with_label_values(&[self.origin.name(), &format!("{:#x}", validator)])
+                    .inc();
+                return None;
+            }
+        }
+
+        // Checkpoints of another merkle tree hook are returned as they are,
+        // but not cached under the origin's one
+        let checkpoint = &signed_checkpoint.value.checkpoint;
+        if checkpoint.mailbox_domain == self.origin.id()
+            && checkpoint.merkle_tree_hook_address == self.merkle_tree_hook_address
+        {
+            self.verified_checkpoints
+                .put(key, signed_checkpoint.clone());
+        }
+        Some(signed_checkpoint)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{
+        Checkpoint, CheckpointWithMessageId, HyperlaneSigner, HyperlaneSignerExt,
+    };
+    use prometheus::Registry;
+    use tempfile::TempDir;
+
+    use crate::{test_utils::test_signer, LocalStorage};
+
+    use super::*;
+
+    const MERKLE_TREE_HOOK_ADDRESS: H256 = H256::repeat_byte(1);
+
+    fn checkpoint(domain: &HyperlaneDomain, index: u32) -> CheckpointWithMessageId {
+        CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                merkle_tree_hook_address: MERKLE_TREE_HOOK_ADDRESS,
+                mailbox_domain: domain.id(),
+                root: H256::from_low_u64_be(2),
+                index,
+            },
+            message_id: H256::from_low_u64_be(3),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_and_caches_verified_checkpoints() {
+        let domain =
+            HyperlaneDomain::new_test_domain("rejects_invalid_and_caches_verified_checkpoints");
+        let dir = TempDir::new().unwrap();
+        let storage = LocalStorage::new(dir.path().join("validator"), None).unwrap();
+        let metrics = Arc::new(CoreMetrics::new("test", 9090, Registry::new()).unwrap());
+        let verified_checkpoints = VerifiedCheckpointCache::new(2);
+
+        let validator_signer = test_signer(0);
+        let other_signer = test_signer(1);
+        let validator = validator_signer.eth_address();
+        let syncer = MultisigCheckpointSyncer::new(
+            HashMap::from([(
+                validator,
+                Arc::new(storage.clone()) as Arc<dyn CheckpointSyncer>,
+            )]),
+            metrics.clone(),
+            None,
+            domain.clone(),
+            MERKLE_TREE_HOOK_ADDRESS,
+            verified_checkpoints.clone(),
+        );
+        let key = |index| (domain.id(), MERKLE_TREE_HOOK_ADDRESS, validator, index);
+
+        // A checkpoint signed by someone else is rejected and counted
+        let forged = other_signer.sign(checkpoint(&domain, 0)).await.unwrap();
+        storage.write_checkpoint(&forged).await.unwrap();
+        assert_eq!(
+            syncer
+                .fetch_verified_checkpoint(validator, &storage, 0)
+                .await,
+            None
+        );
+        assert_eq!(
+            metrics
+                .invalid_checkpoint_signatures()
+                .with_label_values(&[domain.name(), &format!("{:#x}", validator)])
+                .get(),
+            1
+        );
+        assert_eq!(verified_checkpoints.get(&key(0)), None);
+
+        // A checkpoint signed by the validator is cached, so it's no longer
+        // downloaded once fetched
+        let signed = validator_signer.sign(checkpoint(&domain, 1)).await.unwrap();
+        storage.write_checkpoint(&signed).await.unwrap();
+        assert_eq!(
+            syncer
+                .fetch_verified_checkpoint(validator, &storage, 1)
+                .await,
+            Some(signed.clone())
+        );
+        std::fs::remove_dir_all(dir.path().join("validator")).unwrap();
+        assert_eq!(
+            syncer
+                .fetch_verified_checkpoint(validator, &storage, 1)
+                .await,
+            Some(signed.clone())
+        );
+
+        // The same checkpoint is cached separately for another merkle tree
+        // hook and the least recently used checkpoints are evicted
+        let other_hook_key = (domain.id(), H256::repeat_byte(2), validator, 1);
+        assert_eq!(verified_checkpoints.get(&other_hook_key), None);
+        verified_checkpoints.put(other_hook_key, signed.clone());
+        verified_checkpoints.put(key(2), signed);
+        assert_eq!(verified_checkpoints.get(&key(1)), None);
+        assert_eq!(
+            syncer
+                .fetch_verified_checkpoint(validator, &storage, 1)
+                .await,
+            None
+        );
+    }
 }
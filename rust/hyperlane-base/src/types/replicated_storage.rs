@@ -0,0 +1,289 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use eyre::{bail, eyre, Result};
+use futures_util::future::join_all;
+use hyperlane_core::{SignedAnnouncement, SignedCheckpointWithMessageId};
+use tracing::warn;
+
+use crate::traits::CheckpointSyncer;
+
+/// A checkpoint syncer made of several replicas, e.g. buckets with different
+/// storage providers, so that checkpoints remain available when one of them
+/// is not.
+///
+/// Writes go to every replica and succeed if at least `write_quorum` of them
+/// do. Checkpoints are read from every replica, and written back to the
+/// replicas that are missing them, so that a checkpoint one replica missed
+/// isn't considered submitted until all of them have it.
+#[derive(Debug, Clone)]
+pub struct ReplicatedCheckpointSyncer {
+    replicas: Vec<Arc<dyn CheckpointSyncer>>,
+    write_quorum: usize,
+}
+
+impl ReplicatedCheckpointSyncer {
+    /// Create a new replicated checkpoint syncer. The write quorum must be
+    /// between 1 and the number of replicas.
+    pub fn new(replicas: Vec<Arc<dyn CheckpointSyncer>>, write_quorum: usize) -> Self {
+        Self {
+            replicas,
+            write_quorum,
+        }
+    }
+
+    /// Checks that at least `write_quorum` of the replicas' writes succeeded
+    fn ensure_write_quorum(&self, results: Vec<Result<()>>, what: &str) -> Result<()> {
+        let mut successes = 0;
+        for (replica, result) in self.replicas.iter().zip(results) {
+            match result {
+                Ok(()) => successes += 1,
+                Err(err) => warn!(
+                    ?err,
+                    location = replica.announcement_location(),
+                    "Failed to write {what} to checkpoint syncer replica"
+                ),
+            }
+        }
+        if successes < self.write_quorum {
+            bail!(
+                "Wrote {what} to {successes} of {} checkpoint syncer replicas, below the write quorum of {}",
+                self.replicas.len(),
+                self.write_quorum
+            );
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CheckpointSyncer for ReplicatedCheckpointSyncer {
+    /// The highest latest index of any replica that could be read
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        let results = join_all(self.replicas.iter().map(|replica| replica.latest_index())).await;
+        let mut latest_index = None;
+        let mut last_err = None;
+        let mut any_ok = false;
+        for (replica, result) in self.replicas.iter().zip(results) {
+            match result {
+                Ok(index) => {
+                    any_ok = true;
+                    latest_index = latest_index.max(index);
+                }
+                Err(err) => {
+                    warn!(
+                        ?err,
+                        location = replica.announcement_location(),
+                        "Failed to read latest index from checkpoint syncer replica"
+                    );
+                    last_err = Some(err);
+                }
+            }
+        }
+        match (any_ok, last_err) {
+            (false, Some(err)) => Err(err),
+            _ => Ok(latest_index),
+        }
+    }
+
+    async fn write_latest_index(&self, index: u32) -> Result<()> {
+        let results = join_all(
+            self.replicas
+                .iter()
+                .map(|replica| replica.write_latest_index(index)),
+        )
+        .await;
+        self.ensure_write_quorum(results, "latest index")
+    }
+
+    /// Updates each replica on its own, so that a replica that missed an
+    /// update catches up even if the others are already ahead.
+    async fn update_latest_index(&self, index: u32) -> Result<()> {
+        let results = join_all(
+            self.replicas
+                .iter()
+                .map(|replica| replica.update_latest_index(index)),
+        )
+        .await;
+        self.ensure_write_quorum(results, "latest index")
+    }
+
+    /// The checkpoint of the first replica that has it. Replicas known to be
+    /// missing it get a copy, and the checkpoint is only returned once the
+    /// write quorum of replicas has it.
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        let results = join_all(
+            self.replicas
+                .iter()
+                .map(|replica| replica.fetch_checkpoint(index)),
+        )
+        .await;
+        let mut checkpoint = None;
+        let mut missing = vec![];
+        let mut present = 0;
+        let mut last_err = None;
+        for (replica, result) in self.replicas.iter().zip(results) {
+            match result {
+                Ok(Some(fetched)) => {
+                    present += 1;
+                    checkpoint.get_or_insert(fetched);
+                }
+                Ok(None) => missing.push(replica),
+                Err(err) => {
+                    warn!(
+                        ?err,
+                        index,
+                        location = replica.announcement_location(),
+                        "Failed to fetch checkpoint from checkpoint syncer replica"
+                    );
+                    last_err = Some(err);
+                }
+            }
+        }
+        let Some(checkpoint) = checkpoint else {
+            // Only fail if none of the replicas could tell whether it has the checkpoint
+            return match (missing.is_empty(), last_err) {
+                (true, Some(err)) => Err(err),
+                _ => Ok(None),
+            };
+        };
+
+        let results = join_all(
+            missing
+                .iter()
+                .map(|replica| replica.write_checkpoint(&checkpoint)),
+        )
+        .await;
+        for (replica, result) in missing.iter().zip(results) {
+            match result {
+                Ok(()) => present += 1,
+                Err(err) => warn!(
+                    ?err,
+                    index,
+                    location = replica.announcement_location(),
+                    "Failed to copy checkpoint to checkpoint syncer replica missing it"
+                ),
+            }
+        }
+        // Let the checkpoint be signed and written again if too few replicas
+        // have it
+        Ok((present >= self.write_quorum).then_some(checkpoint))
+    }
+
+    async fn write_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        let results = join_all(
+            self.replicas
+                .iter()
+                .map(|replica| replica.write_checkpoint(signed_checkpoint)),
+        )
+        .await;
+        self.ensure_write_quorum(results, "checkpoint")
+    }
+
+    /// Writes the announcement to the replica whose location it announces
+    async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
+        let location = &signed_announcement.value.storage_location;
+        let replica = self
+            .replicas
+            .iter()
+            .find(|replica| replica.announcement_locations().contains(location))
+            .ok_or_else(|| eyre!("No checkpoint syncer replica at {location}"))?;
+        replica.write_announcement(signed_announcement).await
+    }
+
+    /// The location of the first replica
+    fn announcement_location(&self) -> String {
+        self.replicas
+            .first()
+            .map(|replica| replica.announcement_location())
+            .unwrap_or_default()
+    }
+
+    fn announcement_locations(&self) -> Vec<String> {
+        self.replicas
+            .iter()
+            .flat_map(|replica| replica.announcement_locations())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{Checkpoint, CheckpointWithMessageId, Signature, H256, U256};
+    use tempfile::TempDir;
+
+    use crate::LocalStorage;
+
+    use super::*;
+
+    fn local_replica(dir: &TempDir, name: &str) -> Arc<dyn CheckpointSyncer> {
+        Arc::new(LocalStorage::new(dir.path().join(name), None).unwrap())
+    }
+
+    #[tokio::test]
+    async fn writes_succeed_with_a_quorum_of_replicas() {
+        let dir = TempDir::new().unwrap();
+        let replicas = vec![local_replica(&dir, "a"), local_replica(&dir, "b")];
+        // Make the second replica unwritable
+        std::fs::remove_dir_all(dir.path().join("b")).unwrap();
+
+        let syncer = ReplicatedCheckpointSyncer::new(replicas.clone(), 1);
+        syncer.update_latest_index(5).await.unwrap();
+        assert_eq!(syncer.latest_index().await.unwrap(), Some(5));
+
+        let syncer = ReplicatedCheckpointSyncer::new(replicas, 2);
+        assert!(syncer.update_latest_index(6).await.is_err());
+        // The write still went to the healthy replica
+        assert_eq!(syncer.latest_index().await.unwrap(), Some(6));
+    }
+
+    #[tokio::test]
+    async fn fetching_a_checkpoint_copies_it_to_replicas_missing_it() {
+        let dir = TempDir::new().unwrap();
+        let a = local_replica(&dir, "a");
+        let b = local_replica(&dir, "b");
+        let checkpoint = SignedCheckpointWithMessageId {
+            value: CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: H256::from_low_u64_be(1),
+                    mailbox_domain: 10,
+                    root: H256::from_low_u64_be(2),
+                    index: 3,
+                },
+                message_id: H256::from_low_u64_be(4),
+            },
+            signature: Signature {
+                r: U256::from(5),
+                s: U256::from(6),
+                v: 27,
+            },
+        };
+        // Only the second replica got the checkpoint
+        b.write_checkpoint(&checkpoint).await.unwrap();
+
+        let syncer = ReplicatedCheckpointSyncer::new(vec![a.clone(), b], 2);
+        assert_eq!(
+            syncer.fetch_checkpoint(3).await.unwrap(),
+            Some(checkpoint.clone())
+        );
+        assert_eq!(a.fetch_checkpoint(3).await.unwrap(), Some(checkpoint));
+        assert_eq!(syncer.fetch_checkpoint(4).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn announces_every_replica() {
+        let dir = TempDir::new().unwrap();
+        let a = local_replica(&dir, "a");
+        let b = local_replica(&dir, "b");
+        let syncer = ReplicatedCheckpointSyncer::new(vec![a.clone(), b.clone()], 2);
+
+        assert_eq!(syncer.announcement_location(), a.announcement_location());
+        assert_eq!(
+            syncer.announcement_locations(),
+            vec![a.announcement_location(), b.announcement_location()]
+        );
+    }
+}
@@ -430,8 +430,8 @@ impl HyperlaneDomain {
         use HyperlaneDomainProtocol::*;
         let protocol = self.domain_protocol();
         many_to_one!(match protocol {
-            IndexMode::Block: [Ethereum, Cosmos],
-            IndexMode::Sequence : [Sealevel, Fuel],
+            IndexMode::Block: [Ethereum, Cosmos, Fuel],
+            IndexMode::Sequence : [Sealevel],
         })
     }
 }
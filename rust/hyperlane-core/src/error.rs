@@ -151,6 +151,9 @@ pub enum ChainCommunicationError {
     /// Hyperlane signer error
     #[error("{0}")]
     HyperlaneSignerError(#[from] HyperlaneSignerError),
+    /// The chain does not support processing messages in batches
+    #[error("Batching not supported")]
+    BatchingNotSupported,
 }
 
 impl ChainCommunicationError {
@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use auto_impl::auto_impl;
 use eyre::Result;
 
-use crate::LogMeta;
+use crate::{InFlightTransaction, LogMeta, H256};
 
 /// Interface for a HyperlaneLogStore that ingests logs.
 #[async_trait]
@@ -57,3 +57,15 @@ pub trait HyperlaneWatermarkedLogStore<T>: HyperlaneLogStore<T> {
     /// Stores the block number high watermark
     async fn store_high_watermark(&self, block_number: u32) -> Result<()>;
 }
+
+/// Interface for a store of transactions that were sent but haven't been
+/// included yet, so that a restarted agent can resume watching them instead
+/// of sending them again.
+#[auto_impl(&, Box, Arc)]
+pub trait HyperlaneInFlightTransactionStore: Send + Sync + Debug {
+    /// Gets the in-flight transaction identified by `key`
+    fn retrieve_in_flight_transaction(&self, key: &H256) -> Result<Option<InFlightTransaction>>;
+
+    /// Stores the in-flight transaction identified by `key`
+    fn store_in_flight_transaction(&self, key: &H256, tx: &InFlightTransaction) -> Result<()>;
+}
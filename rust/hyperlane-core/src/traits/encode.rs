@@ -1,6 +1,6 @@
 use std::io::{Error, ErrorKind};
 
-use crate::{GasPaymentKey, HyperlaneProtocolError, H160, H256, H512, U256};
+use crate::{GasPaymentKey, HyperlaneProtocolError, Signature, H160, H256, H512, U256};
 
 /// Simple trait for types with a canonical encoding
 pub trait Encode {
@@ -53,6 +53,31 @@ impl Decode for ethers_core::types::Signature {
     }
 }
 
+impl Encode for Signature {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        writer.write_all(&<[u8; 65]>::from(self))?;
+        Ok(65)
+    }
+}
+
+impl Decode for Signature {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+    {
+        let mut buf = [0u8; 65];
+        reader.read_exact(&mut buf)?;
+        Ok(Self {
+            r: U256::from_big_endian(&buf[0..32]),
+            s: U256::from_big_endian(&buf[32..64]),
+            v: buf[64] as u64,
+        })
+    }
+}
+
 macro_rules! impl_encode_for_primitive_hash {
     ($t:ty) => {
         impl Encode for $t {
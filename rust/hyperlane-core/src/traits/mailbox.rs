@@ -5,8 +5,8 @@ use async_trait::async_trait;
 use auto_impl::auto_impl;
 
 use crate::{
-    traits::TxOutcome, utils::domain_hash, ChainResult, HyperlaneContract, HyperlaneMessage,
-    TxCostEstimate, H256, U256,
+    traits::TxOutcome, utils::domain_hash, ChainCommunicationError, ChainResult, HyperlaneContract,
+    HyperlaneMessage, TxCostEstimate, H256, U256,
 };
 
 /// Interface for the Mailbox chain contract. Allows abstraction over different
@@ -52,4 +52,40 @@ pub trait Mailbox: HyperlaneContract + Send + Sync + Debug {
     /// Get the calldata for a transaction to process a message with a proof
     /// against the provided signed checkpoint
     fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8>;
+
+    /// Whether this mailbox can process several messages in a single
+    /// transaction with `process_batch`
+    fn supports_batching(&self) -> bool {
+        false
+    }
+
+    /// Process a batch of messages in a single transaction. Messages that
+    /// would fail are left out of the transaction and reported in the result
+    /// so they can be submitted on their own.
+    async fn process_batch(&self, _messages: &[BatchItem]) -> ChainResult<BatchResult> {
+        Err(ChainCommunicationError::BatchingNotSupported)
+    }
+}
+
+/// A message and the data needed to process it as part of a batch
+#[derive(Debug, Clone)]
+pub struct BatchItem {
+    /// The message to process
+    pub message: HyperlaneMessage,
+    /// ISM metadata for the message
+    pub metadata: Vec<u8>,
+    /// Gas limit estimated for processing the message on its own
+    pub gas_limit: U256,
+}
+
+/// The result of processing a batch of messages
+#[derive(Debug, Clone, Default)]
+pub struct BatchResult {
+    /// Outcome of the batch transaction, if one was submitted
+    pub outcome: Option<TxOutcome>,
+    /// Indexes into the batch of the messages that were not processed by it
+    pub failed_indexes: Vec<usize>,
+    /// Indexes into the batch of the messages whose call was included in the
+    /// batch transaction but reverted
+    pub reverted_indexes: Vec<usize>,
 }
@@ -7,7 +7,7 @@ use serde::{
 use std::fmt::{Debug, Formatter};
 
 use crate::utils::bytes_to_hex;
-use crate::{Signature, H160, H256};
+use crate::{Decode, Encode, HyperlaneProtocolError, Signature, H160, H256};
 
 /// An error incurred by a signer
 #[derive(thiserror::Error, Debug)]
@@ -27,6 +27,23 @@ pub trait HyperlaneSigner: Send + Sync + Debug {
     async fn sign_hash(&self, hash: &H256) -> Result<Signature, HyperlaneSignerError>;
 }
 
+/// A key held by a key management service such as AWS KMS, which signs
+/// without the private key ever leaving the service.
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait KmsKey: Send + Sync + Debug {
+    /// The public key: the SEC1 encoded point of a secp256k1 key, or the 32
+    /// bytes of an ed25519 key
+    async fn public_key(&self) -> Result<Vec<u8>, HyperlaneSignerError>;
+
+    /// Sign a 32 byte digest with a secp256k1 key, returning the 64 byte
+    /// `r || s` ECDSA signature
+    async fn sign_digest(&self, digest: &H256) -> Result<Vec<u8>, HyperlaneSignerError>;
+
+    /// Sign a message with an ed25519 key, returning the 64 byte signature
+    async fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>, HyperlaneSignerError>;
+}
+
 /// Auto-implemented extension trait for HyperlaneSigner.
 #[async_trait]
 pub trait HyperlaneSignerExt {
@@ -124,6 +141,27 @@ impl<T: Signable> SignedType<T> {
     }
 }
 
+impl<T: Signable + Encode> Encode for SignedType<T> {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        Ok(self.value.write_to(writer)? + self.signature.write_to(writer)?)
+    }
+}
+
+impl<T: Signable + Decode> Decode for SignedType<T> {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+    {
+        Ok(Self {
+            value: T::read_from(reader)?,
+            signature: Signature::read_from(reader)?,
+        })
+    }
+}
+
 impl<T: Signable + Debug> Debug for SignedType<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -16,6 +16,9 @@ pub trait ValidatorAnnounce: HyperlaneContract + Send + Sync + Debug {
         validators: &[H256],
     ) -> ChainResult<Vec<Vec<String>>>;
 
+    /// Returns every validator that has announced a storage location.
+    async fn get_announced_validators(&self) -> ChainResult<Vec<H256>>;
+
     /// Announce a storage location for a validator
     async fn announce(
         &self,
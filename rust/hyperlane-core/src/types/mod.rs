@@ -244,3 +244,72 @@ impl TxCostEstimate {
         self.l2_gas_limit.unwrap_or(self.gas_limit)
     }
 }
+
+/// A transaction that was sent but hasn't been included yet. Every time the
+/// transaction is replaced with a higher fee, the hash of the replacement is
+/// added, since any of them may end up being included.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InFlightTransaction {
+    /// The nonce all the replacements were sent with.
+    pub nonce: U256,
+    /// The max fee per gas of the latest replacement, or its gas price for
+    /// legacy transactions.
+    pub max_fee_per_gas: U256,
+    /// The max priority fee per gas of the latest replacement. Not set for
+    /// legacy transactions.
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// The hashes of all the replacements that were sent, oldest first. Empty
+    /// once the transaction no longer needs to be watched.
+    pub hashes: Vec<H256>,
+}
+
+impl InFlightTransaction {
+    /// Whether the transaction still needs to be watched.
+    pub fn is_pending(&self) -> bool {
+        !self.hashes.is_empty()
+    }
+}
+
+impl Encode for InFlightTransaction {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        let mut written = 0;
+        written += self.nonce.write_to(writer)?;
+        written += self.max_fee_per_gas.write_to(writer)?;
+        written += self.max_priority_fee_per_gas.is_some().write_to(writer)?;
+        written += self
+            .max_priority_fee_per_gas
+            .unwrap_or_default()
+            .write_to(writer)?;
+        written += (self.hashes.len() as u32).write_to(writer)?;
+        for hash in &self.hashes {
+            written += hash.write_to(writer)?;
+        }
+        Ok(written)
+    }
+}
+
+impl Decode for InFlightTransaction {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        let nonce = U256::read_from(reader)?;
+        let max_fee_per_gas = U256::read_from(reader)?;
+        let has_priority_fee = bool::read_from(reader)?;
+        let priority_fee = U256::read_from(reader)?;
+        let hash_count = u32::read_from(reader)?;
+        let hashes = (0..hash_count)
+            .map(|_| H256::read_from(reader))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            nonce,
+            max_fee_per_gas,
+            max_priority_fee_per_gas: has_priority_fee.then_some(priority_fee),
+            hashes,
+        })
+    }
+}
@@ -11,7 +11,7 @@ use fixed_hash::impl_fixed_hash_conversions;
 use num_traits::Zero;
 use uint::construct_uint;
 
-use crate::{types::serialize, ChainCommunicationError};
+use crate::{types::serialize, ChainCommunicationError, ChainResult};
 
 /// Error type for conversion.
 #[derive(Debug, PartialEq, Eq, thiserror::Error)]
@@ -364,6 +364,17 @@ impl FixedPointNumber {
                 .with_scale_round(fractional_digit_count, bigdecimal::RoundingMode::Ceiling),
         )
     }
+
+    /// Divide by `rhs`, failing if it's zero
+    pub fn checked_div(&self, rhs: impl Into<FixedPointNumber>) -> ChainResult<Self> {
+        let rhs = rhs.into();
+        if rhs.0.is_zero() {
+            return Err(ChainCommunicationError::from_other_str(
+                "attempt to divide by zero",
+            ));
+        }
+        Ok(Self(&self.0 / rhs.0))
+    }
 }
 
 impl Default for FixedPointNumber {
@@ -428,3 +439,27 @@ impl FromStr for FixedPointNumber {
         Ok(Self(BigDecimal::from_str(s)?))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_point_number_checked_div() {
+        assert_eq!(
+            FixedPointNumber::from(205_000)
+                .checked_div(200_000)
+                .unwrap(),
+            FixedPointNumber::from_str("1.025").unwrap()
+        );
+        assert_eq!(
+            FixedPointNumber::from(1).checked_div(3).unwrap(),
+            FixedPointNumber::from(BigDecimal::from(1) / BigDecimal::from(3))
+        );
+        assert_eq!(
+            FixedPointNumber::zero().checked_div(7).unwrap(),
+            FixedPointNumber::zero()
+        );
+        assert!(FixedPointNumber::from(1).checked_div(0).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+#![allow(non_snake_case)]
+use core::fmt::Debug;
+use mockall::*;
+
+use async_trait::async_trait;
+use hyperlane_core::*;
+
+mock! {
+    pub InterchainSecurityModule {
+        fn _domain(&self) -> &HyperlaneDomain;
+        fn _provider(&self) -> Box<dyn HyperlaneProvider>;
+        fn _address(&self) -> H256;
+        fn _module_type(&self) -> ChainResult<ModuleType>;
+        fn _dry_run_verify(
+            &self,
+            message: &HyperlaneMessage,
+            metadata: &[u8],
+        ) -> ChainResult<Option<U256>>;
+    }
+}
+
+mock! {
+    pub RoutingIsm {
+        fn _domain(&self) -> &HyperlaneDomain;
+        fn _provider(&self) -> Box<dyn HyperlaneProvider>;
+        fn _address(&self) -> H256;
+        fn _route(&self, message: &HyperlaneMessage) -> ChainResult<H256>;
+    }
+}
+
+mock! {
+    pub AggregationIsm {
+        fn _domain(&self) -> &HyperlaneDomain;
+        fn _provider(&self) -> Box<dyn HyperlaneProvider>;
+        fn _address(&self) -> H256;
+        fn _modules_and_threshold(
+            &self,
+            message: &HyperlaneMessage,
+        ) -> ChainResult<(Vec<H256>, u8)>;
+    }
+}
+
+mock! {
+    pub MultisigIsm {
+        fn _domain(&self) -> &HyperlaneDomain;
+        fn _provider(&self) -> Box<dyn HyperlaneProvider>;
+        fn _address(&self) -> H256;
+        fn _validators_and_threshold(
+            &self,
+            message: &HyperlaneMessage,
+        ) -> ChainResult<(Vec<H256>, u8)>;
+    }
+}
+
+/// The chain and contract traits are implemented the same way for each mock
+macro_rules! impl_contract {
+    ($mock:ty) => {
+        impl HyperlaneChain for $mock {
+            fn domain(&self) -> &HyperlaneDomain {
+                self._domain()
+            }
+
+            fn provider(&self) -> Box<dyn HyperlaneProvider> {
+                self._provider()
+            }
+        }
+
+        impl HyperlaneContract for $mock {
+            fn address(&self) -> H256 {
+                self._address()
+            }
+        }
+
+        impl Debug for $mock {
+            fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_contract!(MockInterchainSecurityModule);
+impl_contract!(MockRoutingIsm);
+impl_contract!(MockAggregationIsm);
+impl_contract!(MockMultisigIsm);
+
+#[async_trait]
+impl InterchainSecurityModule for MockInterchainSecurityModule {
+    async fn module_type(&self) -> ChainResult<ModuleType> {
+        self._module_type()
+    }
+
+    async fn dry_run_verify(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Option<U256>> {
+        self._dry_run_verify(message, metadata)
+    }
+}
+
+#[async_trait]
+impl RoutingIsm for MockRoutingIsm {
+    async fn route(&self, message: &HyperlaneMessage) -> ChainResult<H256> {
+        self._route(message)
+    }
+}
+
+#[async_trait]
+impl AggregationIsm for MockAggregationIsm {
+    async fn modules_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        self._modules_and_threshold(message)
+    }
+}
+
+#[async_trait]
+impl MultisigIsm for MockMultisigIsm {
+    async fn validators_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        self._validators_and_threshold(message)
+    }
+}
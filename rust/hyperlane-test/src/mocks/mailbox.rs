@@ -57,6 +57,8 @@ mock! {
             message: &HyperlaneMessage,
             metadata: &[u8],
         ) -> Vec<u8> {}
+
+        pub fn _process_batch(&self, messages: &[BatchItem]) -> ChainResult<BatchResult> {}
     }
 }
 
@@ -104,6 +106,10 @@ impl Mailbox for MockMailboxContract {
     fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
         self.process_calldata(message, metadata)
     }
+
+    async fn process_batch(&self, messages: &[BatchItem]) -> ChainResult<BatchResult> {
+        self._process_batch(messages)
+    }
 }
 
 impl HyperlaneChain for MockMailboxContract {
@@ -1,6 +1,9 @@
+/// Mock ISM contracts
+pub mod ism;
 /// Mock mailbox contract
 pub mod mailbox;
 pub mod validator_announce;
 
+pub use ism::{MockAggregationIsm, MockInterchainSecurityModule, MockMultisigIsm, MockRoutingIsm};
 pub use mailbox::MockMailboxContract;
 pub use validator_announce::MockValidatorAnnounceContract;
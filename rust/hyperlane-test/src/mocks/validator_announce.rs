@@ -14,6 +14,7 @@ mock! {
             &self,
             validators: &[H256],
         ) -> ChainResult<Vec<Vec<String>>>;
+        fn _get_announced_validators(&self) -> ChainResult<Vec<H256>>;
         fn _announce(
             &self,
             announcement: SignedType<Announcement>,
@@ -57,6 +58,10 @@ impl ValidatorAnnounce for MockValidatorAnnounceContract {
         self._get_announced_storage_locations(validators)
     }
 
+    async fn get_announced_validators(&self) -> ChainResult<Vec<H256>> {
+        self._get_announced_validators()
+    }
+
     async fn announce(
         &self,
         announcement: SignedType<Announcement>,
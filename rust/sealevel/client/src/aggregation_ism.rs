@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use crate::{
+    artifacts::{write_json, SingularProgramIdArtifact},
+    cmd_utils::{create_and_write_keypair, create_new_directory, deploy_program},
+    AggregationIsmCmd, AggregationIsmSubCmd, Context,
+};
+
+use hyperlane_sealevel_aggregation_ism::{
+    accounts::AggregationIsmStorageAccount,
+    aggregation_ism_storage_pda_seeds,
+    instruction::{
+        init_instruction, set_modules_and_threshold_instruction, transfer_ownership_instruction,
+        ModulesAndThreshold,
+    },
+};
+
+pub(crate) fn process_aggregation_ism_cmd(mut ctx: Context, cmd: AggregationIsmCmd) {
+    match cmd.cmd {
+        AggregationIsmSubCmd::Deploy(deploy) => {
+            let environments_dir = create_new_directory(
+                &deploy.env_args.environments_dir,
+                &deploy.env_args.environment,
+            );
+            let ism_dir = create_new_directory(&environments_dir, "aggregation-ism");
+            let chain_dir = create_new_directory(&ism_dir, &deploy.chain);
+            let context_dir = create_new_directory(&chain_dir, &deploy.context);
+            let key_dir = create_new_directory(&context_dir, "keys");
+
+            let ism_program_id =
+                deploy_aggregation_ism(&mut ctx, &deploy.built_so_dir, true, &key_dir);
+
+            write_json::<SingularProgramIdArtifact>(
+                &context_dir.join("program-ids.json"),
+                ism_program_id.into(),
+            );
+        }
+        AggregationIsmSubCmd::Init(init) => {
+            let init_instruction = init_instruction(init.program_id, ctx.payer_pubkey).unwrap();
+            ctx.new_txn().add(init_instruction).send_with_payer();
+        }
+        AggregationIsmSubCmd::SetModulesAndThreshold(set_config) => {
+            set_modules_and_threshold(
+                &mut ctx,
+                set_config.program_id,
+                ModulesAndThreshold {
+                    modules: set_config.modules,
+                    threshold: set_config.threshold,
+                },
+            );
+        }
+        AggregationIsmSubCmd::Query(query) => {
+            let (storage_pda_key, _storage_pda_bump) = Pubkey::find_program_address(
+                aggregation_ism_storage_pda_seeds!(),
+                &query.program_id,
+            );
+
+            let accounts = ctx
+                .client
+                .get_multiple_accounts_with_commitment(&[storage_pda_key], ctx.commitment)
+                .unwrap()
+                .value;
+            let storage =
+                AggregationIsmStorageAccount::fetch(&mut &accounts[0].as_ref().unwrap().data[..])
+                    .unwrap()
+                    .into_inner();
+            println!("Storage: {:#?}", storage);
+        }
+        AggregationIsmSubCmd::TransferOwnership(transfer_ownership) => {
+            let instruction = transfer_ownership_instruction(
+                transfer_ownership.program_id,
+                ctx.payer_pubkey,
+                Some(transfer_ownership.new_owner),
+            )
+            .unwrap();
+
+            ctx.new_txn()
+                .add_with_description(
+                    instruction,
+                    format!("Transfer ownership to {}", transfer_ownership.new_owner),
+                )
+                .send_with_payer();
+        }
+    }
+}
+
+pub(crate) fn deploy_aggregation_ism(
+    ctx: &mut Context,
+    built_so_dir: &Path,
+    use_existing_keys: bool,
+    key_dir: &Path,
+) -> Pubkey {
+    let (keypair, keypair_path) = create_and_write_keypair(
+        key_dir,
+        "hyperlane_sealevel_aggregation_ism-keypair.json",
+        use_existing_keys,
+    );
+    let program_id = keypair.pubkey();
+
+    deploy_program(
+        ctx.payer_keypair_path(),
+        keypair_path.to_str().unwrap(),
+        built_so_dir
+            .join("hyperlane_sealevel_aggregation_ism.so")
+            .to_str()
+            .unwrap(),
+        &ctx.client.url(),
+    );
+
+    println!("Deployed Aggregation ISM at program ID {}", program_id);
+
+    // Initialize
+    let instruction = init_instruction(program_id, ctx.payer_pubkey).unwrap();
+
+    ctx.new_txn()
+        .add_with_description(
+            instruction,
+            format!(
+                "Initializing Aggregation ISM with payer & owner {}",
+                ctx.payer_pubkey
+            ),
+        )
+        .send_with_payer();
+
+    program_id
+}
+
+pub(crate) fn set_modules_and_threshold(
+    ctx: &mut Context,
+    program_id: Pubkey,
+    modules_and_threshold: ModulesAndThreshold,
+) {
+    let description = format!("Set modules and threshold: {:?}", modules_and_threshold);
+    ctx.new_txn()
+        .add_with_description(
+            set_modules_and_threshold_instruction(
+                program_id,
+                ctx.payer_pubkey,
+                modules_and_threshold,
+            )
+            .unwrap(),
+            description,
+        )
+        .send_with_payer();
+}
@@ -0,0 +1,183 @@
+use std::path::Path;
+
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use crate::{
+    artifacts::{write_json, SingularProgramIdArtifact},
+    cmd_utils::{create_and_write_keypair, create_new_directory, deploy_program},
+    Context, DomainRoutingIsmCmd, DomainRoutingIsmSubCmd,
+};
+
+use hyperlane_sealevel_domain_routing_ism::{
+    access_control_pda_seeds,
+    accounts::{AccessControlAccount, DomainRouteAccount},
+    domain_route_pda_seeds,
+    instruction::{
+        init_instruction, remove_domain_ism_instruction, set_domain_ism_instruction,
+        transfer_ownership_instruction,
+    },
+};
+
+pub(crate) fn process_domain_routing_ism_cmd(mut ctx: Context, cmd: DomainRoutingIsmCmd) {
+    match cmd.cmd {
+        DomainRoutingIsmSubCmd::Deploy(deploy) => {
+            let environments_dir = create_new_directory(
+                &deploy.env_args.environments_dir,
+                &deploy.env_args.environment,
+            );
+            let ism_dir = create_new_directory(&environments_dir, "domain-routing-ism");
+            let chain_dir = create_new_directory(&ism_dir, &deploy.chain);
+            let context_dir = create_new_directory(&chain_dir, &deploy.context);
+            let key_dir = create_new_directory(&context_dir, "keys");
+
+            let ism_program_id =
+                deploy_domain_routing_ism(&mut ctx, &deploy.built_so_dir, true, &key_dir);
+
+            write_json::<SingularProgramIdArtifact>(
+                &context_dir.join("program-ids.json"),
+                ism_program_id.into(),
+            );
+        }
+        DomainRoutingIsmSubCmd::Init(init) => {
+            let init_instruction = init_instruction(init.program_id, ctx.payer_pubkey).unwrap();
+            ctx.new_txn().add(init_instruction).send_with_payer();
+        }
+        DomainRoutingIsmSubCmd::SetDomainIsm(set_domain_ism) => {
+            let instruction = set_domain_ism_instruction(
+                set_domain_ism.program_id,
+                ctx.payer_pubkey,
+                set_domain_ism.domain,
+                set_domain_ism.ism,
+            )
+            .unwrap();
+
+            ctx.new_txn()
+                .add_with_description(
+                    instruction,
+                    format!(
+                        "Set ISM for remote domain {} to {}",
+                        set_domain_ism.domain, set_domain_ism.ism
+                    ),
+                )
+                .send_with_payer();
+        }
+        DomainRoutingIsmSubCmd::RemoveDomainIsm(remove_domain_ism) => {
+            let instruction = remove_domain_ism_instruction(
+                remove_domain_ism.program_id,
+                ctx.payer_pubkey,
+                remove_domain_ism.domain,
+            )
+            .unwrap();
+
+            ctx.new_txn()
+                .add_with_description(
+                    instruction,
+                    format!("Remove ISM for remote domain {}", remove_domain_ism.domain),
+                )
+                .send_with_payer();
+        }
+        DomainRoutingIsmSubCmd::Query(query) => {
+            let (access_control_pda_key, _access_control_pda_bump) =
+                Pubkey::find_program_address(access_control_pda_seeds!(), &query.program_id);
+
+            let accounts = ctx
+                .client
+                .get_multiple_accounts_with_commitment(&[access_control_pda_key], ctx.commitment)
+                .unwrap()
+                .value;
+            let access_control =
+                AccessControlAccount::fetch(&mut &accounts[0].as_ref().unwrap().data[..])
+                    .unwrap()
+                    .into_inner();
+            println!("Access control: {:#?}", access_control);
+
+            if let Some(domains) = query.domains {
+                for domain in domains {
+                    let (domain_route_pda_key, _domain_route_pda_bump) =
+                        Pubkey::find_program_address(
+                            domain_route_pda_seeds!(domain),
+                            &query.program_id,
+                        );
+
+                    let accounts = ctx
+                        .client
+                        .get_multiple_accounts_with_commitment(
+                            &[domain_route_pda_key],
+                            ctx.commitment,
+                        )
+                        .unwrap()
+                        .value;
+
+                    let ism = accounts[0].as_ref().and_then(|account| {
+                        DomainRouteAccount::fetch(&mut &account.data[..])
+                            .unwrap()
+                            .into_inner()
+                            .ism
+                    });
+                    if let Some(ism) = ism {
+                        println!("ISM for domain {}: {}", domain, ism);
+                    } else {
+                        println!("No ISM for domain {}", domain);
+                    }
+                }
+            }
+        }
+        DomainRoutingIsmSubCmd::TransferOwnership(transfer_ownership) => {
+            let instruction = transfer_ownership_instruction(
+                transfer_ownership.program_id,
+                ctx.payer_pubkey,
+                Some(transfer_ownership.new_owner),
+            )
+            .unwrap();
+
+            ctx.new_txn()
+                .add_with_description(
+                    instruction,
+                    format!("Transfer ownership to {}", transfer_ownership.new_owner),
+                )
+                .send_with_payer();
+        }
+    }
+}
+
+pub(crate) fn deploy_domain_routing_ism(
+    ctx: &mut Context,
+    built_so_dir: &Path,
+    use_existing_keys: bool,
+    key_dir: &Path,
+) -> Pubkey {
+    let (keypair, keypair_path) = create_and_write_keypair(
+        key_dir,
+        "hyperlane_sealevel_domain_routing_ism-keypair.json",
+        use_existing_keys,
+    );
+    let program_id = keypair.pubkey();
+
+    deploy_program(
+        ctx.payer_keypair_path(),
+        keypair_path.to_str().unwrap(),
+        built_so_dir
+            .join("hyperlane_sealevel_domain_routing_ism.so")
+            .to_str()
+            .unwrap(),
+        &ctx.client.url(),
+    );
+
+    println!("Deployed Domain Routing ISM at program ID {}", program_id);
+
+    // Initialize
+    let instruction = init_instruction(program_id, ctx.payer_pubkey).unwrap();
+
+    ctx.new_txn()
+        .add_with_description(
+            instruction,
+            format!(
+                "Initializing Domain Routing ISM with payer & owner {}",
+                ctx.payer_pubkey
+            ),
+        )
+        .send_with_payer();
+
+    program_id
+}
@@ -58,10 +58,12 @@ use hyperlane_sealevel_validator_announce::{
 };
 use warp_route::parse_token_account_data;
 
+mod aggregation_ism;
 mod artifacts;
 mod cmd_utils;
 mod context;
 mod r#core;
+mod domain_routing_ism;
 mod helloworld;
 mod igp;
 mod multisig_ism;
@@ -69,6 +71,8 @@ mod router;
 mod serde;
 mod warp_route;
 
+use crate::aggregation_ism::process_aggregation_ism_cmd;
+use crate::domain_routing_ism::process_domain_routing_ism_cmd;
 use crate::helloworld::process_helloworld_cmd;
 use crate::igp::process_igp_cmd;
 use crate::multisig_ism::process_multisig_ism_message_id_cmd;
@@ -109,6 +113,8 @@ enum HyperlaneSealevelCmd {
     Igp(IgpCmd),
     ValidatorAnnounce(ValidatorAnnounceCmd),
     MultisigIsmMessageId(MultisigIsmMessageIdCmd),
+    AggregationIsm(AggregationIsmCmd),
+    DomainRoutingIsm(DomainRoutingIsmCmd),
     WarpRoute(WarpRouteCmd),
     HelloWorld(HelloWorldCmd),
 }
@@ -645,6 +651,115 @@ struct MultisigIsmMessageIdSetValidatorsAndThreshold {
     threshold: u8,
 }
 
+#[derive(Args)]
+struct AggregationIsmCmd {
+    #[command(subcommand)]
+    cmd: AggregationIsmSubCmd,
+}
+
+#[derive(Subcommand)]
+enum AggregationIsmSubCmd {
+    Deploy(AggregationIsmDeploy),
+    Init(AggregationIsmInit),
+    SetModulesAndThreshold(AggregationIsmSetModulesAndThreshold),
+    Query(AggregationIsmQuery),
+    TransferOwnership(TransferOwnership),
+}
+
+#[derive(Args)]
+struct AggregationIsmDeploy {
+    #[command(flatten)]
+    env_args: EnvironmentArgs,
+    #[arg(long)]
+    built_so_dir: PathBuf,
+    #[arg(long)]
+    chain: String,
+    #[arg(long)]
+    context: String,
+}
+
+#[derive(Args)]
+struct AggregationIsmInit {
+    #[arg(long, short)]
+    program_id: Pubkey,
+}
+
+#[derive(Args)]
+struct AggregationIsmQuery {
+    #[arg(long, short)]
+    program_id: Pubkey,
+}
+
+#[derive(Args)]
+struct AggregationIsmSetModulesAndThreshold {
+    #[arg(long, short)]
+    program_id: Pubkey,
+    #[arg(long, value_delimiter = ',')]
+    modules: Vec<Pubkey>,
+    #[arg(long)]
+    threshold: u8,
+}
+
+#[derive(Args)]
+struct DomainRoutingIsmCmd {
+    #[command(subcommand)]
+    cmd: DomainRoutingIsmSubCmd,
+}
+
+#[derive(Subcommand)]
+enum DomainRoutingIsmSubCmd {
+    Deploy(DomainRoutingIsmDeploy),
+    Init(DomainRoutingIsmInit),
+    SetDomainIsm(DomainRoutingIsmSetDomainIsm),
+    RemoveDomainIsm(DomainRoutingIsmRemoveDomainIsm),
+    Query(DomainRoutingIsmQuery),
+    TransferOwnership(TransferOwnership),
+}
+
+#[derive(Args)]
+struct DomainRoutingIsmDeploy {
+    #[command(flatten)]
+    env_args: EnvironmentArgs,
+    #[arg(long)]
+    built_so_dir: PathBuf,
+    #[arg(long)]
+    chain: String,
+    #[arg(long)]
+    context: String,
+}
+
+#[derive(Args)]
+struct DomainRoutingIsmInit {
+    #[arg(long, short)]
+    program_id: Pubkey,
+}
+
+#[derive(Args)]
+struct DomainRoutingIsmSetDomainIsm {
+    #[arg(long, short)]
+    program_id: Pubkey,
+    #[arg(long)]
+    domain: u32,
+    #[arg(long)]
+    ism: Pubkey,
+}
+
+#[derive(Args)]
+struct DomainRoutingIsmRemoveDomainIsm {
+    #[arg(long, short)]
+    program_id: Pubkey,
+    #[arg(long)]
+    domain: u32,
+}
+
+#[derive(Args)]
+struct DomainRoutingIsmQuery {
+    #[arg(long, short)]
+    program_id: Pubkey,
+    #[arg(long, value_delimiter = ',')]
+    domains: Option<Vec<u32>>,
+}
+
 #[derive(Args)]
 pub(crate) struct HelloWorldCmd {
     #[command(subcommand)]
@@ -748,6 +863,8 @@ fn main() {
         HyperlaneSealevelCmd::MultisigIsmMessageId(cmd) => {
             process_multisig_ism_message_id_cmd(ctx, cmd)
         }
+        HyperlaneSealevelCmd::AggregationIsm(cmd) => process_aggregation_ism_cmd(ctx, cmd),
+        HyperlaneSealevelCmd::DomainRoutingIsm(cmd) => process_domain_routing_ism_cmd(ctx, cmd),
         HyperlaneSealevelCmd::Core(cmd) => process_core_cmd(ctx, cmd),
         HyperlaneSealevelCmd::WarpRoute(cmd) => process_warp_route_cmd(ctx, cmd),
         HyperlaneSealevelCmd::HelloWorld(cmd) => process_helloworld_cmd(ctx, cmd),
@@ -0,0 +1,58 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use access_control::AccessControl;
+use account_utils::{AccountData, SizedData};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::instruction::ModulesAndThreshold;
+
+/// The data of the storage PDA account, which holds the owner of the
+/// program and the modules and threshold messages are verified with.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, PartialEq)]
+pub struct AggregationIsmStorage {
+    pub bump_seed: u8,
+    pub owner: Option<Pubkey>,
+    pub modules_and_threshold: ModulesAndThreshold,
+}
+
+impl SizedData for AggregationIsmStorage {
+    fn size(&self) -> usize {
+        // 1 byte bump seed
+        // + 1 byte Option variant + 32 byte owner pubkey
+        // + 4 byte modules length + 32 bytes per module pubkey
+        // + 1 byte threshold
+        1 + 1 + 32 + 4 + 32 * self.modules_and_threshold.modules.len() + 1
+    }
+}
+
+impl AccessControl for AggregationIsmStorage {
+    fn owner(&self) -> Option<&Pubkey> {
+        self.owner.as_ref()
+    }
+
+    fn set_owner(&mut self, new_owner: Option<Pubkey>) -> Result<(), ProgramError> {
+        self.owner = new_owner;
+        Ok(())
+    }
+}
+
+pub type AggregationIsmStorageAccount = AccountData<AggregationIsmStorage>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_aggregation_ism_storage_size() {
+        let data = AggregationIsmStorage {
+            bump_seed: 0,
+            owner: Some(Pubkey::new_unique()),
+            modules_and_threshold: ModulesAndThreshold {
+                modules: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+                threshold: 1,
+            },
+        };
+        let serialized = data.try_to_vec().unwrap();
+        assert_eq!(data.size(), serialized.len());
+    }
+}
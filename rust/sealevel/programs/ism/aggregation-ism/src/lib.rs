@@ -0,0 +1,11 @@
+//! An Interchain Security Module that requires a threshold of its
+//! configured modules to verify a message, each through a CPI.
+
+#![deny(warnings)]
+#![deny(unsafe_code)]
+
+pub mod accounts;
+pub mod error;
+pub mod instruction;
+pub mod metadata;
+pub mod processor;
@@ -0,0 +1,170 @@
+use crate::error::Error;
+
+/// Bytes used to store one member of the (start, end) range tuple
+const METADATA_RANGE_SIZE: usize = 4;
+
+/// The metadata of an aggregation ISM, holding the metadata of each of its
+/// modules that should verify the message.
+#[derive(Debug, PartialEq, Eq)]
+pub struct AggregationIsmMetadata {
+    pub module_metadatas: Vec<Option<Vec<u8>>>,
+    /// The number of accounts required by the `Verify` instruction of each
+    /// module with metadata, in order.
+    pub account_counts: Vec<u8>,
+}
+
+/// Format of metadata, matching `AggregationIsmMetadata.sol` followed by the
+/// account counts:
+/// [????:????] Metadata start/end uint32 ranges, packed as uint64, one per module
+/// [????:????] Module metadata, packed encoding
+/// [????:????] Account counts, one uint8 per module with metadata
+/// A module's metadata is absent if its range starts at 0.
+impl AggregationIsmMetadata {
+    pub fn new(bytes: &[u8], module_count: usize) -> Result<Self, Error> {
+        let ranges = Self::ranges(bytes, module_count)?;
+        let present = ranges.iter().flatten().count();
+        let account_counts_offset = bytes
+            .len()
+            .checked_sub(present)
+            .filter(|offset| *offset >= METADATA_RANGE_SIZE * 2 * module_count)
+            .ok_or(Error::InvalidMetadata)?;
+
+        let module_metadatas = ranges
+            .into_iter()
+            .map(|range| match range {
+                Some((_, end)) if end > account_counts_offset => Err(Error::InvalidMetadata),
+                Some((start, end)) => Ok(Some(bytes[start..end].to_vec())),
+                None => Ok(None),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            module_metadatas,
+            account_counts: bytes[account_counts_offset..].to_vec(),
+        })
+    }
+
+    /// Decodes the metadata of each module from `AggregationIsmMetadata.sol`
+    /// formatted bytes, which don't hold the account counts.
+    pub fn decode_module_metadatas(
+        bytes: &[u8],
+        module_count: usize,
+    ) -> Result<Vec<Option<Vec<u8>>>, Error> {
+        Ok(Self::ranges(bytes, module_count)?
+            .into_iter()
+            .map(|range| range.map(|(start, end)| bytes[start..end].to_vec()))
+            .collect())
+    }
+
+    /// The (start, end) range of each module's metadata, if present.
+    fn ranges(bytes: &[u8], module_count: usize) -> Result<Vec<Option<(usize, usize)>>, Error> {
+        if bytes.len() < METADATA_RANGE_SIZE * 2 * module_count {
+            return Err(Error::InvalidMetadata);
+        }
+
+        let range_member = |offset: usize| -> usize {
+            // This cannot panic since the ranges were checked to be in bounds above.
+            let member: [u8; METADATA_RANGE_SIZE] = bytes[offset..offset + METADATA_RANGE_SIZE]
+                .try_into()
+                .unwrap();
+            u32::from_be_bytes(member) as usize
+        };
+
+        (0..module_count)
+            .map(|index| {
+                let range_offset = METADATA_RANGE_SIZE * 2 * index;
+                let start = range_member(range_offset);
+                let end = range_member(range_offset + METADATA_RANGE_SIZE);
+                if start == 0 {
+                    return Ok(None);
+                }
+                if start > end || end > bytes.len() {
+                    return Err(Error::InvalidMetadata);
+                }
+                Ok(Some((start, end)))
+            })
+            .collect()
+    }
+
+    /// Encodes the metadata of each module, followed by the account counts.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![0; METADATA_RANGE_SIZE * 2 * self.module_metadatas.len()];
+        for (index, module_metadata) in self.module_metadatas.iter().enumerate() {
+            if let Some(module_metadata) = module_metadata {
+                let range_offset = METADATA_RANGE_SIZE * 2 * index;
+                let start = buffer.len() as u32;
+                buffer.extend_from_slice(module_metadata);
+                let end = buffer.len() as u32;
+                buffer[range_offset..range_offset + METADATA_RANGE_SIZE]
+                    .copy_from_slice(&start.to_be_bytes());
+                buffer[range_offset + METADATA_RANGE_SIZE..range_offset + METADATA_RANGE_SIZE * 2]
+                    .copy_from_slice(&end.to_be_bytes());
+            }
+        }
+        buffer.extend_from_slice(&self.account_counts);
+        buffer
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let metadata = AggregationIsmMetadata {
+            module_metadatas: vec![Some(vec![1, 2, 3]), None, Some(vec![]), Some(vec![4, 5])],
+            account_counts: vec![1, 0, 2],
+        };
+        let encoded = metadata.encode();
+        assert_eq!(
+            AggregationIsmMetadata::new(&encoded, metadata.module_metadatas.len()).unwrap(),
+            metadata,
+        );
+
+        // The module metadatas are also decoded without the account counts
+        let module_metadatas = AggregationIsmMetadata::decode_module_metadatas(
+            &encoded[..encoded.len() - metadata.account_counts.len()],
+            metadata.module_metadatas.len(),
+        )
+        .unwrap();
+        assert_eq!(module_metadatas, metadata.module_metadatas);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        // Too short to hold a range per module
+        assert_eq!(
+            AggregationIsmMetadata::new(&[0; 15], 2).unwrap_err(),
+            Error::InvalidMetadata,
+        );
+
+        // Range ends out of bounds
+        let mut bytes = vec![0; 8];
+        bytes[..4].copy_from_slice(&8u32.to_be_bytes());
+        bytes[4..].copy_from_slice(&9u32.to_be_bytes());
+        assert_eq!(
+            AggregationIsmMetadata::new(&bytes, 1).unwrap_err(),
+            Error::InvalidMetadata,
+        );
+
+        // Range starts after it ends
+        bytes[4..].copy_from_slice(&7u32.to_be_bytes());
+        assert_eq!(
+            AggregationIsmMetadata::new(&bytes, 1).unwrap_err(),
+            Error::InvalidMetadata,
+        );
+
+        // Missing the account count of a module with metadata
+        let mut bytes = AggregationIsmMetadata {
+            module_metadatas: vec![Some(vec![1, 2, 3])],
+            account_counts: vec![1],
+        }
+        .encode();
+        bytes.pop();
+        assert_eq!(
+            AggregationIsmMetadata::new(&bytes, 1).unwrap_err(),
+            Error::InvalidMetadata,
+        );
+    }
+}
@@ -0,0 +1,381 @@
+use hyperlane_core::ModuleType;
+
+use access_control::AccessControl;
+use account_utils::{create_pda_account, DiscriminatorDecode, SizedData};
+use serializable_account_meta::{SerializableAccountMeta, SimulationReturnData};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction as SolanaInstruction},
+    program::{invoke, set_return_data},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    accounts::{AggregationIsmStorage, AggregationIsmStorageAccount},
+    error::Error,
+    instruction::{Instruction, ModulesAndThreshold},
+    metadata::AggregationIsmMetadata,
+};
+
+use hyperlane_sealevel_interchain_security_module_interface::{
+    InterchainSecurityModuleInstruction, VerifyInstruction,
+};
+
+use borsh::BorshSerialize;
+
+const ISM_TYPE: ModuleType = ModuleType::Aggregation;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// PDA seeds relating to the storage PDA account.
+#[macro_export]
+macro_rules! aggregation_ism_storage_pda_seeds {
+    () => {{
+        &[b"aggregation_ism", b"-", b"storage"]
+    }};
+
+    ($bump_seed:expr) => {{
+        &[b"aggregation_ism", b"-", b"storage", &[$bump_seed]]
+    }};
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // First, try to decode the instruction as an interchain security module
+    // interface supported function based off the discriminator.
+    if let Ok(ism_instruction) = InterchainSecurityModuleInstruction::decode(instruction_data) {
+        return match ism_instruction {
+            InterchainSecurityModuleInstruction::Type => {
+                set_return_data(
+                    &SimulationReturnData::new(ISM_TYPE as u32)
+                        .try_to_vec()
+                        .map_err(|err| ProgramError::BorshIoError(err.to_string()))?[..],
+                );
+                return Ok(());
+            }
+            InterchainSecurityModuleInstruction::Verify(verify_data) => verify(
+                program_id,
+                accounts,
+                verify_data.metadata,
+                verify_data.message,
+            ),
+            InterchainSecurityModuleInstruction::VerifyAccountMetas(_) => {
+                let account_metas = verify_account_metas(program_id, accounts)?;
+                // Wrap it in the SimulationReturnData because serialized account_metas
+                // may end with zero byte(s), which are incorrectly truncated as
+                // simulated transaction return data.
+                // See `SimulationReturnData` for details.
+                let bytes = SimulationReturnData::new(account_metas)
+                    .try_to_vec()
+                    .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+                set_return_data(&bytes[..]);
+                Ok(())
+            }
+        };
+    }
+
+    match Instruction::decode(instruction_data)? {
+        // Initializes the program.
+        Instruction::Initialize => initialize(program_id, accounts),
+        // Sets the modules and threshold.
+        Instruction::SetModulesAndThreshold(config) => {
+            set_modules_and_threshold(program_id, accounts, config)
+        }
+        // Gets the owner of this program from the storage account.
+        Instruction::GetOwner => get_owner(program_id, accounts),
+        // Sets the owner of this program in the storage account.
+        Instruction::TransferOwnership(new_owner) => {
+            transfer_ownership(program_id, accounts, new_owner)
+        }
+    }
+}
+
+/// Initializes the program, creating the storage PDA account.
+///
+/// Accounts:
+/// 0. `[signer]` The new owner and payer of the storage PDA.
+/// 1. `[writable]` The storage PDA account.
+/// 2. `[executable]` The system program account.
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The new owner of this program and payer of the storage PDA.
+    let owner_account = next_account_info(accounts_iter)?;
+    if !owner_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Account 1: The storage PDA account.
+    let storage_pda_account = next_account_info(accounts_iter)?;
+    let (storage_pda_key, storage_pda_bump_seed) =
+        Pubkey::find_program_address(aggregation_ism_storage_pda_seeds!(), program_id);
+    if *storage_pda_account.key != storage_pda_key {
+        return Err(Error::AccountOutOfOrder.into());
+    }
+
+    // Ensure the storage PDA account isn't already initialized.
+    if let Ok(Some(_)) =
+        AggregationIsmStorageAccount::fetch_data(&mut &storage_pda_account.data.borrow()[..])
+    {
+        return Err(Error::AlreadyInitialized.into());
+    }
+
+    // Account 2: The system program account.
+    let system_program_account = next_account_info(accounts_iter)?;
+    if !solana_program::system_program::check_id(system_program_account.key) {
+        return Err(Error::AccountOutOfOrder.into());
+    }
+
+    // Create the storage PDA account. The modules and threshold are set
+    // separately, reallocating the account as needed.
+    let storage_account = AggregationIsmStorageAccount::from(AggregationIsmStorage {
+        bump_seed: storage_pda_bump_seed,
+        owner: Some(*owner_account.key),
+        modules_and_threshold: ModulesAndThreshold::default(),
+    });
+    let storage_account_data_size = storage_account.size();
+    create_pda_account(
+        owner_account,
+        &Rent::get()?,
+        storage_account_data_size,
+        program_id,
+        system_program_account,
+        storage_pda_account,
+        aggregation_ism_storage_pda_seeds!(storage_pda_bump_seed),
+    )?;
+
+    // Store the storage data.
+    storage_account.store(storage_pda_account, false)?;
+
+    Ok(())
+}
+
+/// Verifies a message with each module that metadata was provided for,
+/// requiring at least the threshold of modules to verify it.
+///
+/// Accounts:
+/// 0.    `[]` The storage PDA account.
+/// 1..N. For each module with metadata, in the configured order:
+///       - `[executable]` The module program.
+///       - `[??]` The accounts required by the module's `Verify` instruction,
+///         as many as the module's account count in the metadata.
+fn verify(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    metadata_bytes: Vec<u8>,
+    message_bytes: Vec<u8>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The storage PDA account.
+    let storage_pda_account = next_account_info(accounts_iter)?;
+    let storage = storage_data(program_id, storage_pda_account)?;
+    let modules_and_threshold = storage.modules_and_threshold;
+    // Errors if the modules and threshold haven't been set.
+    modules_and_threshold.validate()?;
+
+    let metadata =
+        AggregationIsmMetadata::new(&metadata_bytes, modules_and_threshold.modules.len())?;
+    let modules_to_verify: Vec<(Pubkey, Vec<u8>)> = modules_and_threshold
+        .modules
+        .into_iter()
+        .zip(metadata.module_metadatas)
+        .filter_map(|(module, module_metadata)| {
+            module_metadata.map(|module_metadata| (module, module_metadata))
+        })
+        .collect();
+    if modules_to_verify.len() < modules_and_threshold.threshold as usize {
+        return Err(Error::ThresholdNotMet.into());
+    }
+
+    // Each module that metadata was provided for must verify the message,
+    // so any failed CPI fails verification altogether. The metadata holds
+    // the account count of each of these modules.
+    for ((module, module_metadata), account_count) in
+        modules_to_verify.into_iter().zip(metadata.account_counts)
+    {
+        // Account 1..N: The module program.
+        let module_account = next_account_info(accounts_iter)?;
+        if *module_account.key != module || !module_account.executable {
+            return Err(Error::AccountOutOfOrder.into());
+        }
+
+        // Account 1..N: The accounts required by the module's `Verify` instruction.
+        let mut module_infos = vec![];
+        let mut module_account_metas = vec![];
+        for _ in 0..account_count {
+            let account_info = next_account_info(accounts_iter)?;
+            module_account_metas.push(AccountMeta {
+                pubkey: *account_info.key,
+                is_signer: account_info.is_signer,
+                is_writable: account_info.is_writable,
+            });
+            module_infos.push(account_info.clone());
+        }
+
+        let verify_instruction = InterchainSecurityModuleInstruction::Verify(
+            VerifyInstruction::new(module_metadata, message_bytes.clone()),
+        );
+        let verify = SolanaInstruction::new_with_bytes(
+            module,
+            &verify_instruction.encode()?,
+            module_account_metas,
+        );
+        invoke(&verify, &module_infos)?;
+    }
+
+    Ok(())
+}
+
+/// Gets the list of AccountMetas required by the `Verify` instruction.
+///
+/// The modules' programs and the accounts they require can't be known by
+/// this program, so they're expected to be appended by the caller as
+/// described in `verify`, along with the account counts in the metadata.
+///
+/// Accounts:
+/// 0. `[]` This program's PDA relating to the seeds VERIFY_ACCOUNT_METAS_PDA_SEEDS.
+///         Note this is not actually used / required in this implementation.
+fn verify_account_metas(
+    program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+) -> Result<Vec<SerializableAccountMeta>, ProgramError> {
+    let (storage_pda_key, _) =
+        Pubkey::find_program_address(aggregation_ism_storage_pda_seeds!(), program_id);
+
+    Ok(vec![
+        AccountMeta::new_readonly(storage_pda_key, false).into()
+    ])
+}
+
+/// Sets the modules and threshold.
+///
+/// Accounts:
+/// 0. `[signer]` The owner and payer of any storage PDA reallocation.
+/// 1. `[writable]` The storage PDA account.
+/// 2. `[executable]` The system program account.
+fn set_modules_and_threshold(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    config: ModulesAndThreshold,
+) -> ProgramResult {
+    // Validate the provided modules and threshold.
+    config.validate()?;
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The owner of this program.
+    // This is verified as correct further below.
+    let owner_account = next_account_info(accounts_iter)?;
+
+    // Account 1: The storage PDA account.
+    let storage_pda_account = next_account_info(accounts_iter)?;
+    let mut storage = storage_data(program_id, storage_pda_account)?;
+    // Ensure the owner account is the owner of this program.
+    storage.ensure_owner_signer(owner_account)?;
+
+    // Account 2: The system program account.
+    let system_program_account = next_account_info(accounts_iter)?;
+    if !solana_program::system_program::check_id(system_program_account.key) {
+        return Err(Error::AccountOutOfOrder.into());
+    }
+
+    // Store the new modules and threshold, reallocating if necessary.
+    storage.modules_and_threshold = config;
+    AggregationIsmStorageAccount::from(storage).store_with_rent_exempt_realloc(
+        storage_pda_account,
+        &Rent::get()?,
+        owner_account,
+        system_program_account,
+    )?;
+
+    Ok(())
+}
+
+/// Gets the owner of this program from the storage account, and returns it as return data.
+/// Intended to be used by instructions querying the owner.
+///
+/// Accounts:
+/// 0. `[]` The storage PDA account.
+fn get_owner(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The storage PDA account.
+    let storage_pda_account = next_account_info(accounts_iter)?;
+
+    let storage = storage_data(program_id, storage_pda_account)?;
+
+    // Wrap it in the SimulationReturnData because serialized `storage.owner`
+    // may end with zero byte(s), which are incorrectly truncated as
+    // simulated transaction return data.
+    // See `SimulationReturnData` for details.
+    let bytes = SimulationReturnData::new(storage.owner)
+        .try_to_vec()
+        .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+    set_return_data(&bytes[..]);
+    Ok(())
+}
+
+/// Gets the storage data of this program.
+/// Returns an Err if the provided account isn't the storage PDA.
+fn storage_data(
+    program_id: &Pubkey,
+    storage_pda_account: &AccountInfo,
+) -> Result<Box<AggregationIsmStorage>, ProgramError> {
+    let storage =
+        AggregationIsmStorageAccount::fetch_data(&mut &storage_pda_account.data.borrow()[..])?
+            .ok_or(Error::AccountNotInitialized)?;
+    // Confirm the key of the storage_pda_account is the correct PDA
+    // using the stored bump seed.
+    let storage_pda_key = Pubkey::create_program_address(
+        aggregation_ism_storage_pda_seeds!(storage.bump_seed),
+        program_id,
+    )?;
+    // This check validates that the provided storage_pda_account is valid
+    if *storage_pda_account.key != storage_pda_key {
+        return Err(Error::AccountOutOfOrder.into());
+    }
+    // Extra sanity check that the owner of the PDA account is this program
+    if storage_pda_account.owner != program_id {
+        return Err(Error::ProgramIdNotOwner.into());
+    }
+
+    Ok(storage)
+}
+
+/// Transfers ownership to a new owner.
+///
+/// Accounts:
+/// 0. `[signer]` The current owner.
+/// 1. `[writable]` The storage PDA account.
+fn transfer_ownership(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_owner: Option<Pubkey>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The current owner.
+    // This is verified as correct further below.
+    let owner_account = next_account_info(accounts_iter)?;
+
+    // Account 1: The storage PDA account.
+    let storage_pda_account = next_account_info(accounts_iter)?;
+    let mut storage = storage_data(program_id, storage_pda_account)?;
+
+    // Transfer ownership. This errors if `owner_account` is not a signer or the owner.
+    storage.transfer_ownership(owner_account, new_owner)?;
+
+    // Store the new owner.
+    AggregationIsmStorageAccount::from(storage).store(storage_pda_account, false)?;
+
+    Ok(())
+}
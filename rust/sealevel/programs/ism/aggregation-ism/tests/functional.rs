@@ -0,0 +1,562 @@
+//! Contains functional tests for things that cannot be done
+//! strictly in unit tests. This includes CPIs, like creating
+//! new PDA accounts and verifying with the modules.
+
+use borsh::BorshSerialize;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    system_program,
+};
+
+use hyperlane_core::{Encode, HyperlaneMessage, ModuleType};
+use hyperlane_sealevel_aggregation_ism::{
+    accounts::{AggregationIsmStorage, AggregationIsmStorageAccount},
+    aggregation_ism_storage_pda_seeds,
+    error::Error as AggregationIsmError,
+    instruction::{init_instruction, set_modules_and_threshold_instruction, ModulesAndThreshold},
+    metadata::AggregationIsmMetadata,
+    processor::process_instruction,
+};
+use hyperlane_sealevel_interchain_security_module_interface::{
+    InterchainSecurityModuleInstruction, VerifyInstruction, VERIFY_ACCOUNT_METAS_PDA_SEEDS,
+};
+use hyperlane_sealevel_test_ism::{
+    program::{
+        process_instruction as test_ism_process_instruction, TestIsmError, TestIsmInstruction,
+    },
+    test_ism_storage_pda_seeds,
+};
+use hyperlane_test_utils::{
+    assert_transaction_error, new_funded_keypair, process_instruction as process_ixn,
+    simulate_instruction,
+};
+use serializable_account_meta::{SerializableAccountMeta, SimulationReturnData};
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+    transaction::TransactionError,
+};
+
+pub fn aggregation_ism_id() -> Pubkey {
+    pubkey!("HYochb2hVYvY3zeDefAKyvRaPWBBvUuz5EP6ppAwkxdw")
+}
+
+/// The programs of the modules aggregated in tests, each a test ISM.
+fn module_ids() -> Vec<Pubkey> {
+    vec![
+        pubkey!("BEgK5Mas2SisjLZtRGWv28Cr8kyN9fGAxrGYMav8ScLL"),
+        pubkey!("9h4RJTrHLvRP1pLfj9xh2DFShQjBjMJb4y3JvqLAupyU"),
+        hyperlane_sealevel_test_ism::id(),
+    ]
+}
+
+fn storage_pda_key(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(aggregation_ism_storage_pda_seeds!(), program_id)
+}
+
+fn test_ism_storage_pda_key(module: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(test_ism_storage_pda_seeds!(), module).0
+}
+
+async fn setup_client() -> (BanksClient, Keypair) {
+    let mut program_test = ProgramTest::new(
+        "hyperlane_sealevel_aggregation_ism",
+        aggregation_ism_id(),
+        processor!(process_instruction),
+    );
+    for module in module_ids() {
+        program_test.add_program(
+            "hyperlane_sealevel_test_ism",
+            module,
+            processor!(test_ism_process_instruction),
+        );
+    }
+
+    let (mut banks_client, payer, _recent_blockhash) = program_test.start().await;
+
+    for module in module_ids() {
+        process_ixn(
+            &mut banks_client,
+            Instruction {
+                program_id: module,
+                data: TestIsmInstruction::Init.try_to_vec().unwrap(),
+                accounts: vec![
+                    AccountMeta::new_readonly(system_program::id(), false),
+                    AccountMeta::new(payer.pubkey(), true),
+                    AccountMeta::new(test_ism_storage_pda_key(&module), false),
+                ],
+            },
+            &payer,
+            &[&payer],
+        )
+        .await
+        .unwrap();
+    }
+
+    (banks_client, payer)
+}
+
+/// Initializes the aggregation ISM and sets all test modules with a threshold of 2.
+async fn initialize_and_configure(banks_client: &mut BanksClient, payer: &Keypair) {
+    let program_id = aggregation_ism_id();
+
+    process_ixn(
+        banks_client,
+        init_instruction(program_id, payer.pubkey()).unwrap(),
+        payer,
+        &[payer],
+    )
+    .await
+    .unwrap();
+
+    process_ixn(
+        banks_client,
+        set_modules_and_threshold_instruction(
+            program_id,
+            payer.pubkey(),
+            ModulesAndThreshold {
+                modules: module_ids(),
+                threshold: 2,
+            },
+        )
+        .unwrap(),
+        payer,
+        &[payer],
+    )
+    .await
+    .unwrap();
+}
+
+async fn get_storage(banks_client: &mut BanksClient) -> Box<AggregationIsmStorage> {
+    let storage_account_data = banks_client
+        .get_account(storage_pda_key(&aggregation_ism_id()).0)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    AggregationIsmStorageAccount::fetch_data(&mut &storage_account_data[..])
+        .unwrap()
+        .unwrap()
+}
+
+/// Builds a `Verify` instruction with metadata for the modules at `indexes`,
+/// followed by the program and storage PDA of each of those test ISMs.
+fn verify_instruction(indexes: &[usize]) -> Instruction {
+    verify_instruction_with_module_accounts(indexes, |module| {
+        vec![AccountMeta::new_readonly(
+            test_ism_storage_pda_key(module),
+            false,
+        )]
+    })
+}
+
+/// Builds a `Verify` instruction with metadata for the modules at `indexes`,
+/// followed by the program and `module_accounts` of each of those test ISMs.
+fn verify_instruction_with_module_accounts(
+    indexes: &[usize],
+    module_accounts: impl Fn(&Pubkey) -> Vec<AccountMeta>,
+) -> Instruction {
+    let program_id = aggregation_ism_id();
+    let modules = module_ids();
+
+    let mut accounts = vec![AccountMeta::new_readonly(
+        storage_pda_key(&program_id).0,
+        false,
+    )];
+    let mut account_counts = vec![];
+    for index in indexes {
+        let module_accounts = module_accounts(&modules[*index]);
+        account_counts.push(module_accounts.len() as u8);
+        accounts.push(AccountMeta::new_readonly(modules[*index], false));
+        accounts.extend(module_accounts);
+    }
+
+    let metadata = AggregationIsmMetadata {
+        module_metadatas: (0..modules.len())
+            .map(|index| indexes.contains(&index).then(|| vec![index as u8]))
+            .collect(),
+        account_counts,
+    };
+    let message = HyperlaneMessage::default();
+
+    Instruction::new_with_bytes(
+        program_id,
+        &InterchainSecurityModuleInstruction::Verify(VerifyInstruction {
+            metadata: metadata.encode(),
+            message: message.to_vec(),
+        })
+        .encode()
+        .unwrap(),
+        accounts,
+    )
+}
+
+#[tokio::test]
+async fn test_initialize() {
+    let program_id = aggregation_ism_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    process_ixn(
+        &mut banks_client,
+        init_instruction(program_id, payer.pubkey()).unwrap(),
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        get_storage(&mut banks_client).await,
+        Box::new(AggregationIsmStorage {
+            bump_seed: storage_pda_key(&program_id).1,
+            owner: Some(payer.pubkey()),
+            modules_and_threshold: ModulesAndThreshold::default(),
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_errors_if_called_twice() {
+    let program_id = aggregation_ism_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    process_ixn(
+        &mut banks_client,
+        init_instruction(program_id, payer.pubkey()).unwrap(),
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+
+    // Use a new payer to get a new tx ID, because the instruction
+    // data is the same.
+    let new_payer = new_funded_keypair(&mut banks_client, &payer, 1000000000).await;
+    let result = process_ixn(
+        &mut banks_client,
+        init_instruction(program_id, new_payer.pubkey()).unwrap(),
+        &new_payer,
+        &[&new_payer],
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(AggregationIsmError::AlreadyInitialized as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_set_modules_and_threshold() {
+    let program_id = aggregation_ism_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize_and_configure(&mut banks_client, &payer).await;
+
+    assert_eq!(
+        get_storage(&mut banks_client).await,
+        Box::new(AggregationIsmStorage {
+            bump_seed: storage_pda_key(&program_id).1,
+            owner: Some(payer.pubkey()),
+            modules_and_threshold: ModulesAndThreshold {
+                modules: module_ids(),
+                threshold: 2,
+            },
+        }),
+    );
+
+    // Shrinking the set of modules is fine too.
+    let modules_and_threshold = ModulesAndThreshold {
+        modules: module_ids()[..1].to_vec(),
+        threshold: 1,
+    };
+    process_ixn(
+        &mut banks_client,
+        set_modules_and_threshold_instruction(
+            program_id,
+            payer.pubkey(),
+            modules_and_threshold.clone(),
+        )
+        .unwrap(),
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_storage(&mut banks_client).await.modules_and_threshold,
+        modules_and_threshold,
+    );
+}
+
+#[tokio::test]
+async fn test_set_modules_and_threshold_errors_if_not_owner() {
+    let program_id = aggregation_ism_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize_and_configure(&mut banks_client, &payer).await;
+
+    let non_owner = new_funded_keypair(&mut banks_client, &payer, 1000000000).await;
+    let result = process_ixn(
+        &mut banks_client,
+        set_modules_and_threshold_instruction(
+            program_id,
+            non_owner.pubkey(),
+            ModulesAndThreshold {
+                modules: module_ids(),
+                threshold: 1,
+            },
+        )
+        .unwrap(),
+        &non_owner,
+        &[&non_owner],
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+}
+
+#[tokio::test]
+async fn test_set_modules_and_threshold_errors_if_invalid() {
+    let program_id = aggregation_ism_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize_and_configure(&mut banks_client, &payer).await;
+
+    let result = process_ixn(
+        &mut banks_client,
+        set_modules_and_threshold_instruction(
+            program_id,
+            payer.pubkey(),
+            ModulesAndThreshold {
+                modules: module_ids(),
+                threshold: 4,
+            },
+        )
+        .unwrap(),
+        &payer,
+        &[&payer],
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(AggregationIsmError::InvalidModulesAndThreshold as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_verify() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize_and_configure(&mut banks_client, &payer).await;
+
+    // Metadata for exactly the threshold of modules.
+    process_ixn(
+        &mut banks_client,
+        verify_instruction(&[0, 2]),
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+
+    // Metadata for all modules.
+    process_ixn(
+        &mut banks_client,
+        verify_instruction(&[0, 1, 2]),
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_verify_with_module_accounts_including_the_next_module() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize_and_configure(&mut banks_client, &payer).await;
+
+    // The accounts of each module end after its account count, even if they
+    // include the program of the next module.
+    let next_module = module_ids()[2];
+    process_ixn(
+        &mut banks_client,
+        verify_instruction_with_module_accounts(&[0, 2], |module| {
+            vec![
+                AccountMeta::new_readonly(test_ism_storage_pda_key(module), false),
+                AccountMeta::new_readonly(next_module, false),
+            ]
+        }),
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_verify_errors_if_threshold_not_met() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize_and_configure(&mut banks_client, &payer).await;
+
+    let result = process_ixn(
+        &mut banks_client,
+        verify_instruction(&[1]),
+        &payer,
+        &[&payer],
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(AggregationIsmError::ThresholdNotMet as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_verify_errors_if_module_rejects() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize_and_configure(&mut banks_client, &payer).await;
+
+    let rejecting_module = module_ids()[1];
+    process_ixn(
+        &mut banks_client,
+        Instruction {
+            program_id: rejecting_module,
+            data: TestIsmInstruction::SetAccept(false).try_to_vec().unwrap(),
+            accounts: vec![AccountMeta::new(
+                test_ism_storage_pda_key(&rejecting_module),
+                false,
+            )],
+        },
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+
+    // The modules that do accept meet the threshold, but any module
+    // provided with metadata must verify the message.
+    process_ixn(
+        &mut banks_client,
+        verify_instruction(&[0, 2]),
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+    let result = process_ixn(
+        &mut banks_client,
+        verify_instruction(&[0, 1, 2]),
+        &payer,
+        &[&payer],
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TestIsmError::VerifyNotAccepted as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_verify_errors_if_module_accounts_out_of_order() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize_and_configure(&mut banks_client, &payer).await;
+
+    // Swap the accounts of the two modules.
+    let mut instruction = verify_instruction(&[0, 2]);
+    instruction.accounts[1..].rotate_left(2);
+
+    let result = process_ixn(&mut banks_client, instruction, &payer, &[&payer]).await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(AggregationIsmError::AccountOutOfOrder as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_verify_account_metas() {
+    let program_id = aggregation_ism_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize_and_configure(&mut banks_client, &payer).await;
+
+    let (account_metas_pda_key, _) =
+        Pubkey::find_program_address(VERIFY_ACCOUNT_METAS_PDA_SEEDS, &program_id);
+    let account_metas = simulate_instruction::<SimulationReturnData<Vec<SerializableAccountMeta>>>(
+        &mut banks_client,
+        &payer,
+        Instruction::new_with_bytes(
+            program_id,
+            &InterchainSecurityModuleInstruction::VerifyAccountMetas(VerifyInstruction {
+                metadata: vec![],
+                message: HyperlaneMessage::default().to_vec(),
+            })
+            .encode()
+            .unwrap(),
+            vec![AccountMeta::new_readonly(account_metas_pda_key, false)],
+        ),
+    )
+    .await
+    .unwrap()
+    .unwrap()
+    .return_data;
+    let account_metas: Vec<AccountMeta> = account_metas.into_iter().map(Into::into).collect();
+
+    assert_eq!(
+        account_metas,
+        vec![AccountMeta::new_readonly(
+            storage_pda_key(&program_id).0,
+            false
+        )],
+    );
+}
+
+#[tokio::test]
+async fn test_ism_type() {
+    let program_id = aggregation_ism_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    let module_type = simulate_instruction::<SimulationReturnData<u32>>(
+        &mut banks_client,
+        &payer,
+        Instruction::new_with_bytes(
+            program_id,
+            &InterchainSecurityModuleInstruction::Type.encode().unwrap(),
+            vec![],
+        ),
+    )
+    .await
+    .unwrap()
+    .unwrap()
+    .return_data;
+
+    assert_eq!(module_type, ModuleType::Aggregation as u32);
+}
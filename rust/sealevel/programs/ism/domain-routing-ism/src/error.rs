@@ -0,0 +1,24 @@
+//! Hyperlane Sealevel domain routing ISM specific errors.
+
+use solana_program::program_error::ProgramError;
+
+#[derive(Copy, Clone, Debug, Eq, thiserror::Error, num_derive::FromPrimitive, PartialEq)]
+#[repr(u32)]
+pub enum Error {
+    #[error("Account not found in the correct order")]
+    AccountOutOfOrder = 1,
+    #[error("Program ID is not owner")]
+    ProgramIdNotOwner = 2,
+    #[error("Account not initialized")]
+    AccountNotInitialized = 3,
+    #[error("Already initialized")]
+    AlreadyInitialized = 4,
+    #[error("No ISM for the origin domain")]
+    NoIsmForDomain = 5,
+}
+
+impl From<Error> for ProgramError {
+    fn from(err: Error) -> Self {
+        ProgramError::Custom(err as u32)
+    }
+}
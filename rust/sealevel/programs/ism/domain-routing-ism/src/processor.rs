@@ -0,0 +1,504 @@
+use hyperlane_core::{Decode, HyperlaneMessage, ModuleType};
+
+use access_control::AccessControl;
+use account_utils::{create_pda_account, DiscriminatorDecode, SizedData};
+use serializable_account_meta::{SerializableAccountMeta, SimulationReturnData};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction as SolanaInstruction},
+    program::{invoke, set_return_data},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    accounts::{AccessControlAccount, AccessControlData, DomainRoute, DomainRouteAccount},
+    error::Error,
+    instruction::{Domained, Instruction},
+};
+
+use hyperlane_sealevel_interchain_security_module_interface::{
+    InterchainSecurityModuleInstruction, VerifyInstruction,
+};
+
+use borsh::BorshSerialize;
+
+const ISM_TYPE: ModuleType = ModuleType::Routing;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// PDA seeds relating to the access control PDA account.
+#[macro_export]
+macro_rules! access_control_pda_seeds {
+    () => {{
+        &[b"domain_routing_ism", b"-", b"access_control"]
+    }};
+
+    ($bump_seed:expr) => {{
+        &[
+            b"domain_routing_ism",
+            b"-",
+            b"access_control",
+            &[$bump_seed],
+        ]
+    }};
+}
+
+/// PDA seeds relating to the domain route PDA account of a domain.
+#[macro_export]
+macro_rules! domain_route_pda_seeds {
+    ($domain:expr) => {{
+        &[
+            b"domain_routing_ism",
+            b"-",
+            &$domain.to_le_bytes(),
+            b"-",
+            b"domain_route",
+        ]
+    }};
+
+    ($domain:expr, $bump_seed:expr) => {{
+        &[
+            b"domain_routing_ism",
+            b"-",
+            &$domain.to_le_bytes(),
+            b"-",
+            b"domain_route",
+            &[$bump_seed],
+        ]
+    }};
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // First, try to decode the instruction as an interchain security module
+    // interface supported function based off the discriminator.
+    if let Ok(ism_instruction) = InterchainSecurityModuleInstruction::decode(instruction_data) {
+        return match ism_instruction {
+            InterchainSecurityModuleInstruction::Type => {
+                set_return_data(
+                    &SimulationReturnData::new(ISM_TYPE as u32)
+                        .try_to_vec()
+                        .map_err(|err| ProgramError::BorshIoError(err.to_string()))?[..],
+                );
+                return Ok(());
+            }
+            InterchainSecurityModuleInstruction::Verify(verify_data) => verify(
+                program_id,
+                accounts,
+                verify_data.metadata,
+                verify_data.message,
+            ),
+            InterchainSecurityModuleInstruction::VerifyAccountMetas(verify_data) => {
+                let account_metas =
+                    verify_account_metas(program_id, accounts, verify_data.message)?;
+                // Wrap it in the SimulationReturnData because serialized account_metas
+                // may end with zero byte(s), which are incorrectly truncated as
+                // simulated transaction return data.
+                // See `SimulationReturnData` for details.
+                let bytes = SimulationReturnData::new(account_metas)
+                    .try_to_vec()
+                    .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+                set_return_data(&bytes[..]);
+                Ok(())
+            }
+        };
+    }
+
+    match Instruction::decode(instruction_data)? {
+        // Initializes the program.
+        Instruction::Initialize => initialize(program_id, accounts),
+        // Sets the ISM for a given domain.
+        Instruction::SetDomainIsm(config) => set_domain_ism(program_id, accounts, config),
+        // Removes the ISM for a given domain.
+        Instruction::RemoveDomainIsm(domain) => remove_domain_ism(program_id, accounts, domain),
+        // Gets the owner of this program from the access control account.
+        Instruction::GetOwner => get_owner(program_id, accounts),
+        // Sets the owner of this program in the access control account.
+        Instruction::TransferOwnership(new_owner) => {
+            transfer_ownership(program_id, accounts, new_owner)
+        }
+    }
+}
+
+/// Initializes the program, creating the access control PDA account.
+///
+/// Accounts:
+/// 0. `[signer]` The new owner and payer of the access control PDA.
+/// 1. `[writable]` The access control PDA account.
+/// 2. `[executable]` The system program account.
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The new owner of this program and payer of the access control PDA.
+    let owner_account = next_account_info(accounts_iter)?;
+    if !owner_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Account 1: The access control PDA account.
+    let access_control_pda_account = next_account_info(accounts_iter)?;
+    let (access_control_pda_key, access_control_pda_bump_seed) =
+        Pubkey::find_program_address(access_control_pda_seeds!(), program_id);
+    if *access_control_pda_account.key != access_control_pda_key {
+        return Err(Error::AccountOutOfOrder.into());
+    }
+
+    // Ensure the access control PDA account isn't already initialized.
+    if let Ok(Some(_)) =
+        AccessControlAccount::fetch_data(&mut &access_control_pda_account.data.borrow()[..])
+    {
+        return Err(Error::AlreadyInitialized.into());
+    }
+
+    // Account 2: The system program account.
+    let system_program_account = next_account_info(accounts_iter)?;
+    if !solana_program::system_program::check_id(system_program_account.key) {
+        return Err(Error::AccountOutOfOrder.into());
+    }
+
+    // Create the access control PDA account.
+    let access_control_account = AccessControlAccount::from(AccessControlData {
+        bump_seed: access_control_pda_bump_seed,
+        owner: Some(*owner_account.key),
+    });
+    let access_control_account_data_size = access_control_account.size();
+    create_pda_account(
+        owner_account,
+        &Rent::get()?,
+        access_control_account_data_size,
+        program_id,
+        system_program_account,
+        access_control_pda_account,
+        access_control_pda_seeds!(access_control_pda_bump_seed),
+    )?;
+
+    // Store the access control data.
+    access_control_account.store(access_control_pda_account, false)?;
+
+    Ok(())
+}
+
+/// Verifies a message with the ISM configured for the message's origin domain.
+///
+/// Accounts:
+/// 0.    `[]` The domain route PDA relating to the message's origin domain.
+/// 1.    `[executable]` The ISM program configured for the origin domain.
+/// 2..N. `[??]` The accounts required by the ISM's `Verify` instruction.
+fn verify(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    metadata_bytes: Vec<u8>,
+    message_bytes: Vec<u8>,
+) -> ProgramResult {
+    let message = HyperlaneMessage::read_from(&mut &message_bytes[..])
+        .map_err(|_| ProgramError::InvalidArgument)?;
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The domain route PDA relating to the message's origin domain.
+    let domain_route_pda_account = next_account_info(accounts_iter)?;
+    let ism = domain_ism(program_id, domain_route_pda_account, message.origin)?;
+
+    // Account 1: The ISM program configured for the origin domain.
+    let ism_account = next_account_info(accounts_iter)?;
+    if *ism_account.key != ism || !ism_account.executable {
+        return Err(Error::AccountOutOfOrder.into());
+    }
+
+    // Account 2..N: The accounts required by the ISM's `Verify` instruction.
+    let ism_infos: Vec<AccountInfo> = accounts_iter.cloned().collect();
+    let ism_account_metas = ism_infos
+        .iter()
+        .map(|account_info| AccountMeta {
+            pubkey: *account_info.key,
+            is_signer: account_info.is_signer,
+            is_writable: account_info.is_writable,
+        })
+        .collect();
+
+    let verify_instruction = InterchainSecurityModuleInstruction::Verify(VerifyInstruction::new(
+        metadata_bytes,
+        message_bytes,
+    ));
+    let verify =
+        SolanaInstruction::new_with_bytes(ism, &verify_instruction.encode()?, ism_account_metas);
+    invoke(&verify, &ism_infos)
+}
+
+/// Gets the list of AccountMetas required by the `Verify` instruction.
+///
+/// The ISM's program and the accounts it requires can't be known by this
+/// program, so they're expected to be appended by the caller as described
+/// in `verify`.
+///
+/// Accounts:
+/// 0. `[]` This program's PDA relating to the seeds VERIFY_ACCOUNT_METAS_PDA_SEEDS.
+///         Note this is not actually used / required in this implementation.
+fn verify_account_metas(
+    program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    message_bytes: Vec<u8>,
+) -> Result<Vec<SerializableAccountMeta>, ProgramError> {
+    let message = HyperlaneMessage::read_from(&mut &message_bytes[..])
+        .map_err(|_| ProgramError::InvalidArgument)?;
+    let (domain_route_pda_key, _) =
+        Pubkey::find_program_address(domain_route_pda_seeds!(message.origin), program_id);
+
+    Ok(vec![
+        AccountMeta::new_readonly(domain_route_pda_key, false).into()
+    ])
+}
+
+/// Gets the ISM configured for a given domain.
+/// Returns an Err if no ISM is configured for the domain.
+fn domain_ism(
+    program_id: &Pubkey,
+    domain_route_pda_account: &AccountInfo,
+    domain: u32,
+) -> Result<Pubkey, ProgramError> {
+    domain_route_data(program_id, domain_route_pda_account, domain)?
+        .ism
+        .ok_or_else(|| Error::NoIsmForDomain.into())
+}
+
+/// Gets the domain route data of a given domain.
+/// Returns an Err if the provided account isn't the domain's route PDA, or
+/// if the PDA hasn't been created.
+fn domain_route_data(
+    program_id: &Pubkey,
+    domain_route_pda_account: &AccountInfo,
+    domain: u32,
+) -> Result<Box<DomainRoute>, ProgramError> {
+    // The domain route PDA only exists once an ISM has been set for the domain.
+    if domain_route_pda_account.owner != program_id {
+        return Err(Error::NoIsmForDomain.into());
+    }
+
+    let domain_route =
+        DomainRouteAccount::fetch_data(&mut &domain_route_pda_account.data.borrow()[..])?
+            .ok_or(Error::NoIsmForDomain)?;
+
+    let domain_route_pda_key = Pubkey::create_program_address(
+        domain_route_pda_seeds!(domain, domain_route.bump_seed),
+        program_id,
+    )?;
+    // This check validates that the provided domain_route_pda_account is valid
+    if *domain_route_pda_account.key != domain_route_pda_key {
+        return Err(Error::AccountOutOfOrder.into());
+    }
+
+    Ok(domain_route)
+}
+
+/// Sets the ISM for a given domain.
+///
+/// Accounts:
+/// 0. `[signer]` The access control owner and payer of the domain route PDA.
+/// 1. `[]` The access control PDA account.
+/// 2. `[writable]` The domain route PDA relating to the provided domain.
+/// 3. `[executable]` OPTIONAL - The system program account. Required if creating the domain route PDA.
+fn set_domain_ism(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    config: Domained<Pubkey>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The owner of this program.
+    // This is verified as correct further below.
+    let owner_account = next_account_info(accounts_iter)?;
+
+    // Account 1: The access control PDA account.
+    let access_control_pda_account = next_account_info(accounts_iter)?;
+    let access_control_data = access_control_data(program_id, access_control_pda_account)?;
+    // Ensure the owner account is the owner of this program.
+    access_control_data.ensure_owner_signer(owner_account)?;
+
+    // Account 2: The domain route PDA relating to the provided domain.
+    let domain_route_pda_account = next_account_info(accounts_iter)?;
+
+    let domain_route =
+        DomainRouteAccount::fetch_data(&mut &domain_route_pda_account.data.borrow()[..]);
+
+    let bump_seed = match domain_route {
+        Ok(Some(domain_route)) => {
+            // The PDA account exists already, we need to confirm the key of the
+            // domain_route_pda_account is the PDA with the stored bump seed.
+            let domain_route_pda_key = Pubkey::create_program_address(
+                domain_route_pda_seeds!(config.domain, domain_route.bump_seed),
+                program_id,
+            )?;
+            // This check validates that the provided domain_route_pda_account is valid
+            if *domain_route_pda_account.key != domain_route_pda_key {
+                return Err(Error::AccountOutOfOrder.into());
+            }
+            // Extra sanity check that the owner of the PDA account is this program
+            if domain_route_pda_account.owner != program_id {
+                return Err(Error::ProgramIdNotOwner.into());
+            }
+
+            domain_route.bump_seed
+        }
+        Ok(None) | Err(_) => {
+            // Create the domain route PDA account if it doesn't exist.
+
+            // First find the key and bump seed for the domain route PDA, and ensure
+            // it matches the provided account.
+            let (domain_route_pda_key, domain_route_pda_bump) =
+                Pubkey::find_program_address(domain_route_pda_seeds!(config.domain), program_id);
+            if *domain_route_pda_account.key != domain_route_pda_key {
+                return Err(Error::AccountOutOfOrder.into());
+            }
+
+            // Account 3: The system program account.
+            let system_program_account = next_account_info(accounts_iter)?;
+            if !solana_program::system_program::check_id(system_program_account.key) {
+                return Err(Error::AccountOutOfOrder.into());
+            }
+
+            // Create the domain route PDA account.
+            create_pda_account(
+                owner_account,
+                &Rent::get()?,
+                DomainRouteAccount::from(DomainRoute::default()).size(),
+                program_id,
+                system_program_account,
+                domain_route_pda_account,
+                domain_route_pda_seeds!(config.domain, domain_route_pda_bump),
+            )?;
+
+            domain_route_pda_bump
+        }
+    };
+
+    // Now store the ISM for the domain.
+    DomainRouteAccount::from(DomainRoute {
+        bump_seed,
+        ism: Some(config.data),
+    })
+    .store(domain_route_pda_account, false)?;
+
+    Ok(())
+}
+
+/// Removes the ISM for a given domain.
+///
+/// Accounts:
+/// 0. `[signer]` The access control owner.
+/// 1. `[]` The access control PDA account.
+/// 2. `[writable]` The domain route PDA relating to the provided domain.
+fn remove_domain_ism(program_id: &Pubkey, accounts: &[AccountInfo], domain: u32) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The owner of this program.
+    // This is verified as correct further below.
+    let owner_account = next_account_info(accounts_iter)?;
+
+    // Account 1: The access control PDA account.
+    let access_control_pda_account = next_account_info(accounts_iter)?;
+    let access_control_data = access_control_data(program_id, access_control_pda_account)?;
+    // Ensure the owner account is the owner of this program.
+    access_control_data.ensure_owner_signer(owner_account)?;
+
+    // Account 2: The domain route PDA relating to the provided domain.
+    let domain_route_pda_account = next_account_info(accounts_iter)?;
+    let mut domain_route = domain_route_data(program_id, domain_route_pda_account, domain)?;
+    if domain_route.ism.is_none() {
+        return Err(Error::NoIsmForDomain.into());
+    }
+
+    // The domain route PDA account is kept, so that the ISM can be set again
+    // without having to recreate it.
+    domain_route.ism = None;
+    DomainRouteAccount::from(domain_route).store(domain_route_pda_account, false)?;
+
+    Ok(())
+}
+
+/// Gets the owner of this program from the access control account, and returns it as return data.
+/// Intended to be used by instructions querying the owner.
+///
+/// Accounts:
+/// 0. `[]` The access control PDA account.
+fn get_owner(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The access control PDA account.
+    let access_control_pda_account = next_account_info(accounts_iter)?;
+
+    let access_control_data = access_control_data(program_id, access_control_pda_account)?;
+
+    // Wrap it in the SimulationReturnData because serialized `access_control_data.owner`
+    // may end with zero byte(s), which are incorrectly truncated as
+    // simulated transaction return data.
+    // See `SimulationReturnData` for details.
+    let bytes = SimulationReturnData::new(access_control_data.owner)
+        .try_to_vec()
+        .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+    set_return_data(&bytes[..]);
+    Ok(())
+}
+
+/// Gets the access control data of this program.
+/// Returns an Err if the provided account isn't the access control PDA.
+fn access_control_data(
+    program_id: &Pubkey,
+    access_control_pda_account: &AccountInfo,
+) -> Result<AccessControlData, ProgramError> {
+    let access_control_data =
+        AccessControlAccount::fetch_data(&mut &access_control_pda_account.data.borrow()[..])?
+            .ok_or(Error::AccountNotInitialized)?;
+    // Confirm the key of the access_control_pda_account is the correct PDA
+    // using the stored bump seed.
+    let access_control_pda_key = Pubkey::create_program_address(
+        access_control_pda_seeds!(access_control_data.bump_seed),
+        program_id,
+    )?;
+    // This check validates that the provided access_control_pda_account is valid
+    if *access_control_pda_account.key != access_control_pda_key {
+        return Err(Error::AccountOutOfOrder.into());
+    }
+    // Extra sanity check that the owner of the PDA account is this program
+    if access_control_pda_account.owner != program_id {
+        return Err(Error::ProgramIdNotOwner.into());
+    }
+
+    Ok(*access_control_data)
+}
+
+/// Transfers ownership to a new access control owner.
+///
+/// Accounts:
+/// 0. `[signer]` The current access control owner.
+/// 1. `[writable]` The access control PDA account.
+fn transfer_ownership(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_owner: Option<Pubkey>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The current access control owner.
+    // This is verified as correct further below.
+    let owner_account = next_account_info(accounts_iter)?;
+
+    // Account 1: The access control PDA account.
+    let access_control_pda_account = next_account_info(accounts_iter)?;
+    let mut access_control_data = access_control_data(program_id, access_control_pda_account)?;
+
+    // Transfer ownership. This errors if `owner_account` is not a signer or the owner.
+    access_control_data.transfer_ownership(owner_account, new_owner)?;
+
+    // Store the new access control owner.
+    AccessControlAccount::from(access_control_data).store(access_control_pda_account, false)?;
+
+    Ok(())
+}
@@ -0,0 +1,541 @@
+//! Contains functional tests for things that cannot be done
+//! strictly in unit tests. This includes CPIs, like creating
+//! new PDA accounts and verifying with the routed ISMs.
+
+use borsh::BorshSerialize;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    system_program,
+};
+
+use hyperlane_core::{Encode, HyperlaneMessage, ModuleType};
+use hyperlane_sealevel_domain_routing_ism::{
+    access_control_pda_seeds,
+    accounts::{AccessControlAccount, AccessControlData, DomainRoute, DomainRouteAccount},
+    domain_route_pda_seeds,
+    error::Error as DomainRoutingIsmError,
+    instruction::{init_instruction, remove_domain_ism_instruction, set_domain_ism_instruction},
+    processor::process_instruction,
+};
+use hyperlane_sealevel_interchain_security_module_interface::{
+    InterchainSecurityModuleInstruction, VerifyInstruction, VERIFY_ACCOUNT_METAS_PDA_SEEDS,
+};
+use hyperlane_sealevel_test_ism::{
+    program::{
+        process_instruction as test_ism_process_instruction, TestIsmError, TestIsmInstruction,
+    },
+    test_ism_storage_pda_seeds,
+};
+use hyperlane_test_utils::{
+    assert_transaction_error, new_funded_keypair, process_instruction as process_ixn,
+    simulate_instruction,
+};
+use serializable_account_meta::{SerializableAccountMeta, SimulationReturnData};
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+    transaction::TransactionError,
+};
+
+const ORIGIN_DOMAIN: u32 = 1234u32;
+const OTHER_ORIGIN_DOMAIN: u32 = 4321u32;
+
+pub fn domain_routing_ism_id() -> Pubkey {
+    pubkey!("DA4xtVLjyByxb48zGTkqn7YZ1WxoTn1DdzifZV1Vq9Jk")
+}
+
+/// The programs of the ISMs routed to in tests, each a test ISM.
+fn ism_ids() -> Vec<Pubkey> {
+    vec![
+        pubkey!("A6HdZs85mAtmhnNuP8EgXSemBvkaJqA2T7vhJ7pyunK4"),
+        hyperlane_sealevel_test_ism::id(),
+    ]
+}
+
+fn access_control_pda_key(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(access_control_pda_seeds!(), program_id)
+}
+
+fn domain_route_pda_key(program_id: &Pubkey, domain: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(domain_route_pda_seeds!(domain), program_id)
+}
+
+fn test_ism_storage_pda_key(ism: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(test_ism_storage_pda_seeds!(), ism).0
+}
+
+async fn setup_client() -> (BanksClient, Keypair) {
+    let mut program_test = ProgramTest::new(
+        "hyperlane_sealevel_domain_routing_ism",
+        domain_routing_ism_id(),
+        processor!(process_instruction),
+    );
+    for ism in ism_ids() {
+        program_test.add_program(
+            "hyperlane_sealevel_test_ism",
+            ism,
+            processor!(test_ism_process_instruction),
+        );
+    }
+
+    let (mut banks_client, payer, _recent_blockhash) = program_test.start().await;
+
+    for ism in ism_ids() {
+        process_ixn(
+            &mut banks_client,
+            Instruction {
+                program_id: ism,
+                data: TestIsmInstruction::Init.try_to_vec().unwrap(),
+                accounts: vec![
+                    AccountMeta::new_readonly(system_program::id(), false),
+                    AccountMeta::new(payer.pubkey(), true),
+                    AccountMeta::new(test_ism_storage_pda_key(&ism), false),
+                ],
+            },
+            &payer,
+            &[&payer],
+        )
+        .await
+        .unwrap();
+    }
+
+    (banks_client, payer)
+}
+
+async fn initialize(banks_client: &mut BanksClient, payer: &Keypair) {
+    process_ixn(
+        banks_client,
+        init_instruction(domain_routing_ism_id(), payer.pubkey()).unwrap(),
+        payer,
+        &[payer],
+    )
+    .await
+    .unwrap();
+}
+
+async fn set_domain_ism(
+    banks_client: &mut BanksClient,
+    owner: &Keypair,
+    domain: u32,
+    ism: Pubkey,
+) -> Result<(), BanksClientError> {
+    process_ixn(
+        banks_client,
+        set_domain_ism_instruction(domain_routing_ism_id(), owner.pubkey(), domain, ism).unwrap(),
+        owner,
+        &[owner],
+    )
+    .await
+    .map(|_| ())
+}
+
+async fn get_domain_route(banks_client: &mut BanksClient, domain: u32) -> Box<DomainRoute> {
+    let domain_route_account_data = banks_client
+        .get_account(domain_route_pda_key(&domain_routing_ism_id(), domain).0)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    DomainRouteAccount::fetch_data(&mut &domain_route_account_data[..])
+        .unwrap()
+        .unwrap()
+}
+
+/// Builds a `Verify` instruction for a message from `domain`, routed to `ism`.
+fn verify_instruction(domain: u32, ism: Pubkey) -> Instruction {
+    let program_id = domain_routing_ism_id();
+    let message = HyperlaneMessage {
+        origin: domain,
+        ..HyperlaneMessage::default()
+    };
+
+    Instruction::new_with_bytes(
+        program_id,
+        &InterchainSecurityModuleInstruction::Verify(VerifyInstruction {
+            metadata: vec![],
+            message: message.to_vec(),
+        })
+        .encode()
+        .unwrap(),
+        vec![
+            AccountMeta::new_readonly(domain_route_pda_key(&program_id, domain).0, false),
+            AccountMeta::new_readonly(ism, false),
+            AccountMeta::new_readonly(test_ism_storage_pda_key(&ism), false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_initialize() {
+    let program_id = domain_routing_ism_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(&mut banks_client, &payer).await;
+
+    let (access_control_pda_key, access_control_pda_bump_seed) =
+        access_control_pda_key(&program_id);
+    let access_control_account_data = banks_client
+        .get_account(access_control_pda_key)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let access_control = AccessControlAccount::fetch_data(&mut &access_control_account_data[..])
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        access_control,
+        Box::new(AccessControlData {
+            bump_seed: access_control_pda_bump_seed,
+            owner: Some(payer.pubkey()),
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_errors_if_called_twice() {
+    let program_id = domain_routing_ism_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(&mut banks_client, &payer).await;
+
+    // Use a new payer to get a new tx ID, because the instruction
+    // data is the same.
+    let new_payer = new_funded_keypair(&mut banks_client, &payer, 1000000000).await;
+    let result = process_ixn(
+        &mut banks_client,
+        init_instruction(program_id, new_payer.pubkey()).unwrap(),
+        &new_payer,
+        &[&new_payer],
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DomainRoutingIsmError::AlreadyInitialized as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_set_domain_ism() {
+    let program_id = domain_routing_ism_id();
+    let (mut banks_client, payer) = setup_client().await;
+    let isms = ism_ids();
+
+    initialize(&mut banks_client, &payer).await;
+
+    // Creates the domain route PDA.
+    set_domain_ism(&mut banks_client, &payer, ORIGIN_DOMAIN, isms[0])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_domain_route(&mut banks_client, ORIGIN_DOMAIN).await,
+        Box::new(DomainRoute {
+            bump_seed: domain_route_pda_key(&program_id, ORIGIN_DOMAIN).1,
+            ism: Some(isms[0]),
+        }),
+    );
+
+    // Updates the existing domain route PDA.
+    set_domain_ism(&mut banks_client, &payer, ORIGIN_DOMAIN, isms[1])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_domain_route(&mut banks_client, ORIGIN_DOMAIN).await.ism,
+        Some(isms[1]),
+    );
+
+    // Other domains are unaffected.
+    assert!(banks_client
+        .get_account(domain_route_pda_key(&program_id, OTHER_ORIGIN_DOMAIN).0)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_set_domain_ism_errors_if_not_owner() {
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(&mut banks_client, &payer).await;
+
+    let non_owner = new_funded_keypair(&mut banks_client, &payer, 1000000000).await;
+    let result = set_domain_ism(&mut banks_client, &non_owner, ORIGIN_DOMAIN, ism_ids()[0]).await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+}
+
+#[tokio::test]
+async fn test_remove_domain_ism() {
+    let program_id = domain_routing_ism_id();
+    let (mut banks_client, payer) = setup_client().await;
+    let isms = ism_ids();
+
+    initialize(&mut banks_client, &payer).await;
+    set_domain_ism(&mut banks_client, &payer, ORIGIN_DOMAIN, isms[0])
+        .await
+        .unwrap();
+
+    process_ixn(
+        &mut banks_client,
+        remove_domain_ism_instruction(program_id, payer.pubkey(), ORIGIN_DOMAIN).unwrap(),
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_domain_route(&mut banks_client, ORIGIN_DOMAIN).await.ism,
+        None,
+    );
+
+    // Messages from the domain can no longer be verified.
+    let result = process_ixn(
+        &mut banks_client,
+        verify_instruction(ORIGIN_DOMAIN, isms[0]),
+        &payer,
+        &[&payer],
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DomainRoutingIsmError::NoIsmForDomain as u32),
+        ),
+    );
+
+    // The ISM can be set again.
+    set_domain_ism(&mut banks_client, &payer, ORIGIN_DOMAIN, isms[1])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_domain_route(&mut banks_client, ORIGIN_DOMAIN).await.ism,
+        Some(isms[1]),
+    );
+}
+
+#[tokio::test]
+async fn test_remove_domain_ism_errors_if_not_set() {
+    let program_id = domain_routing_ism_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(&mut banks_client, &payer).await;
+
+    let result = process_ixn(
+        &mut banks_client,
+        remove_domain_ism_instruction(program_id, payer.pubkey(), ORIGIN_DOMAIN).unwrap(),
+        &payer,
+        &[&payer],
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DomainRoutingIsmError::NoIsmForDomain as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_remove_domain_ism_errors_if_not_owner() {
+    let program_id = domain_routing_ism_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize(&mut banks_client, &payer).await;
+    set_domain_ism(&mut banks_client, &payer, ORIGIN_DOMAIN, ism_ids()[0])
+        .await
+        .unwrap();
+
+    let non_owner = new_funded_keypair(&mut banks_client, &payer, 1000000000).await;
+    let result = process_ixn(
+        &mut banks_client,
+        remove_domain_ism_instruction(program_id, non_owner.pubkey(), ORIGIN_DOMAIN).unwrap(),
+        &non_owner,
+        &[&non_owner],
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+}
+
+#[tokio::test]
+async fn test_verify() {
+    let (mut banks_client, payer) = setup_client().await;
+    let isms = ism_ids();
+
+    initialize(&mut banks_client, &payer).await;
+    set_domain_ism(&mut banks_client, &payer, ORIGIN_DOMAIN, isms[0])
+        .await
+        .unwrap();
+    set_domain_ism(&mut banks_client, &payer, OTHER_ORIGIN_DOMAIN, isms[1])
+        .await
+        .unwrap();
+
+    // Reject messages in the ISM of the other domain.
+    process_ixn(
+        &mut banks_client,
+        Instruction {
+            program_id: isms[1],
+            data: TestIsmInstruction::SetAccept(false).try_to_vec().unwrap(),
+            accounts: vec![AccountMeta::new(test_ism_storage_pda_key(&isms[1]), false)],
+        },
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+
+    process_ixn(
+        &mut banks_client,
+        verify_instruction(ORIGIN_DOMAIN, isms[0]),
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+
+    let result = process_ixn(
+        &mut banks_client,
+        verify_instruction(OTHER_ORIGIN_DOMAIN, isms[1]),
+        &payer,
+        &[&payer],
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TestIsmError::VerifyNotAccepted as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_verify_errors_if_no_ism_for_domain() {
+    let (mut banks_client, payer) = setup_client().await;
+    let isms = ism_ids();
+
+    initialize(&mut banks_client, &payer).await;
+    set_domain_ism(&mut banks_client, &payer, ORIGIN_DOMAIN, isms[0])
+        .await
+        .unwrap();
+
+    let result = process_ixn(
+        &mut banks_client,
+        verify_instruction(OTHER_ORIGIN_DOMAIN, isms[0]),
+        &payer,
+        &[&payer],
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DomainRoutingIsmError::NoIsmForDomain as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_verify_errors_if_wrong_ism() {
+    let (mut banks_client, payer) = setup_client().await;
+    let isms = ism_ids();
+
+    initialize(&mut banks_client, &payer).await;
+    set_domain_ism(&mut banks_client, &payer, ORIGIN_DOMAIN, isms[0])
+        .await
+        .unwrap();
+
+    let result = process_ixn(
+        &mut banks_client,
+        verify_instruction(ORIGIN_DOMAIN, isms[1]),
+        &payer,
+        &[&payer],
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DomainRoutingIsmError::AccountOutOfOrder as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_verify_account_metas() {
+    let program_id = domain_routing_ism_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    let (account_metas_pda_key, _) =
+        Pubkey::find_program_address(VERIFY_ACCOUNT_METAS_PDA_SEEDS, &program_id);
+    let message = HyperlaneMessage {
+        origin: ORIGIN_DOMAIN,
+        ..HyperlaneMessage::default()
+    };
+    let account_metas = simulate_instruction::<SimulationReturnData<Vec<SerializableAccountMeta>>>(
+        &mut banks_client,
+        &payer,
+        Instruction::new_with_bytes(
+            program_id,
+            &InterchainSecurityModuleInstruction::VerifyAccountMetas(VerifyInstruction {
+                metadata: vec![],
+                message: message.to_vec(),
+            })
+            .encode()
+            .unwrap(),
+            vec![AccountMeta::new_readonly(account_metas_pda_key, false)],
+        ),
+    )
+    .await
+    .unwrap()
+    .unwrap()
+    .return_data;
+    let account_metas: Vec<AccountMeta> = account_metas.into_iter().map(Into::into).collect();
+
+    assert_eq!(
+        account_metas,
+        vec![AccountMeta::new_readonly(
+            domain_route_pda_key(&program_id, ORIGIN_DOMAIN).0,
+            false
+        )],
+    );
+}
+
+#[tokio::test]
+async fn test_ism_type() {
+    let program_id = domain_routing_ism_id();
+    let (mut banks_client, payer) = setup_client().await;
+
+    let module_type = simulate_instruction::<SimulationReturnData<u32>>(
+        &mut banks_client,
+        &payer,
+        Instruction::new_with_bytes(
+            program_id,
+            &InterchainSecurityModuleInstruction::Type.encode().unwrap(),
+            vec![],
+        ),
+    )
+    .await
+    .unwrap()
+    .unwrap()
+    .return_data;
+
+    assert_eq!(module_type, ModuleType::Routing as u32);
+}
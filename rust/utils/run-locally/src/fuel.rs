@@ -0,0 +1,363 @@
+use std::env;
+use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use macro_rules_attribute::apply;
+use maplit::hashmap;
+use tempfile::tempdir;
+
+use crate::logging::log;
+use crate::metrics::agent_balance_sum;
+use crate::program::Program;
+use crate::utils::{as_task, concat_path, stop_child, AgentHandles, TaskHandle};
+use crate::{fetch_metric, AGENT_BIN_PATH};
+
+/// Path to a checkout of the hyperlane sway contracts. Defaults to cloning
+/// `FUEL_HYPERLANE_GIT` into a temp dir.
+const ENV_FUEL_HYPERLANE_PATH_KEY: &str = "E2E_FUEL_HYPERLANE_PATH";
+const FUEL_HYPERLANE_GIT: &str = "https://github.com/hyperlane-xyz/fuel-contracts";
+
+const FUEL_NODE_PORT: u32 = 4000;
+const FUEL_DOMAIN: u32 = 13374;
+const FUEL_CHAIN_NAME: &str = "fueltest1";
+
+const FUEL_RELAYER_METRICS_PORT: u32 = 9092;
+const FUEL_VALIDATOR_METRICS_PORT: u32 = 9094;
+
+const FUEL_MESSAGES_EXPECTED: u32 = 10;
+
+/// This is the well known private key of the genesis coin owner in
+/// fuel-core's `--chain local_testnet` configuration.
+const FUEL_DEPLOYER_KEY: &str =
+    "0xde97d8624a438121b86a1956544bd72ed68cd69f2c99555b08b1e8c51ffd511c";
+/// The validator enrolled in the default ISM, with a threshold of one.
+const FUEL_VALIDATOR_KEY: &str =
+    "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+/// The address of `FUEL_VALIDATOR_KEY`.
+const FUEL_VALIDATOR_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+const FUEL_ISM_THRESHOLD: u8 = 1;
+
+/// Sway contracts that are deployed, in order, and the agent setting each
+/// contract id is written to.
+const FUEL_HYPERLANE_CONTRACTS: &[(&str, &str)] = &[
+    ("contracts/mailbox", "MAILBOX"),
+    ("contracts/merkle-tree-hook", "MERKLETREEHOOK"),
+    ("contracts/igp", "INTERCHAINGASPAYMASTER"),
+    ("contracts/validator-announce", "VALIDATORANNOUNCE"),
+    (
+        "contracts/ism/multisig/message-id-multisig-ism",
+        "DEFAULTISM",
+    ),
+    ("contracts/test/msg-recipient-test", "TESTRECIPIENT"),
+];
+
+struct FuelDeployment {
+    /// (agent setting, contract id)
+    contracts: Vec<(&'static str, String)>,
+}
+
+impl FuelDeployment {
+    fn get(&self, key: &str) -> &str {
+        &self
+            .contracts
+            .iter()
+            .find(|(k, _)| *k == key)
+            .unwrap_or_else(|| panic!("{key} was not deployed"))
+            .1
+    }
+}
+
+fn fuel_node_url() -> String {
+    format!("http://127.0.0.1:{FUEL_NODE_PORT}")
+}
+
+#[apply(as_task)]
+fn launch_fuel_node() -> AgentHandles {
+    log!("Launching fuel-core...");
+    let node = Program::new("fuel-core")
+        .cmd("run")
+        .arg("db-type", "in-memory")
+        .arg("ip", "127.0.0.1")
+        .arg("port", FUEL_NODE_PORT.to_string())
+        .arg("chain", "local_testnet")
+        .flag("utxo-validation")
+        .flag("vm-backtrace")
+        .filter_logs(|_| false)
+        .spawn("FUEL");
+    sleep(Duration::from_secs(5));
+    node
+}
+
+/// Deploy every hyperlane sway contract with `forc deploy` and scrape the
+/// resulting contract ids from its output.
+fn deploy_fuel_hyperlane(contracts_path: &PathBuf) -> FuelDeployment {
+    log!("Building sway contracts...");
+    Program::new("forc")
+        .cmd("build")
+        .flag("release")
+        .working_dir(contracts_path)
+        .run()
+        .join();
+
+    let contracts = FUEL_HYPERLANE_CONTRACTS
+        .iter()
+        .map(|&(path, setting)| {
+            log!("Deploying {}...", path);
+            let output = Program::new("forc")
+                .cmd("deploy")
+                .arg("node-url", fuel_node_url())
+                .arg("gas-price", "1")
+                .flag("release")
+                .flag("default-signer")
+                .env("SIGNING_KEY", FUEL_DEPLOYER_KEY)
+                .working_dir(concat_path(contracts_path, path))
+                .run_with_output()
+                .join();
+            let id = output
+                .iter()
+                .find_map(|l| l.split("Contract id: ").nth(1))
+                .unwrap_or_else(|| panic!("Could not find contract id for {path}"))
+                .trim()
+                .to_owned();
+            (setting, id)
+        })
+        .collect();
+
+    FuelDeployment { contracts }
+}
+
+/// Enroll the validator in the multisig ISM and make it the mailbox's default
+/// ISM, using the `set-default-ism` script shipped with the sway contracts,
+/// so that deliveries are only processed with metadata the ISM verifies.
+fn configure_fuel_ism(contracts_path: &PathBuf, deployment: &FuelDeployment) {
+    log!("Configuring the default ISM...");
+    Program::new("forc")
+        .cmd("run")
+        .arg("node-url", fuel_node_url())
+        .arg("gas-price", "1")
+        .arg(
+            "contract",
+            format!(
+                "{},{}",
+                deployment.get("MAILBOX"),
+                deployment.get("DEFAULTISM")
+            ),
+        )
+        .flag("default-signer")
+        .env("SIGNING_KEY", FUEL_DEPLOYER_KEY)
+        .env("MAILBOX_ID", deployment.get("MAILBOX"))
+        .env("ISM_ID", deployment.get("DEFAULTISM"))
+        .env("VALIDATORS", FUEL_VALIDATOR_ADDRESS)
+        .env("THRESHOLD", FUEL_ISM_THRESHOLD.to_string())
+        .working_dir(concat_path(contracts_path, "scripts/set-default-ism"))
+        .run()
+        .join();
+}
+
+/// Dispatch a message to the test recipient using the `dispatch` script
+/// shipped with the sway contracts.
+fn dispatch_fuel_message(contracts_path: &PathBuf, deployment: &FuelDeployment) {
+    Program::new("forc")
+        .cmd("run")
+        .arg("node-url", fuel_node_url())
+        .arg("gas-price", "1")
+        .arg(
+            "contract",
+            format!(
+                "{},{}",
+                deployment.get("MAILBOX"),
+                deployment.get("MERKLETREEHOOK")
+            ),
+        )
+        .flag("default-signer")
+        .env("SIGNING_KEY", FUEL_DEPLOYER_KEY)
+        .env("MAILBOX_ID", deployment.get("MAILBOX"))
+        .env("RECIPIENT_ID", deployment.get("TESTRECIPIENT"))
+        .env("DESTINATION_DOMAIN", FUEL_DOMAIN.to_string())
+        .working_dir(concat_path(contracts_path, "scripts/dispatch"))
+        .run()
+        .join();
+}
+
+fn fuel_agent_env(deployment: &FuelDeployment) -> Program {
+    let chain = FUEL_CHAIN_NAME.to_uppercase();
+    let mut env = Program::default()
+        .env("RUST_BACKTRACE", "full")
+        .hyp_env("LOG_FORMAT", "compact")
+        .hyp_env("LOG_LEVEL", "debug")
+        .hyp_env(format!("CHAINS_{chain}_NAME"), FUEL_CHAIN_NAME)
+        .hyp_env(format!("CHAINS_{chain}_DOMAINID"), FUEL_DOMAIN.to_string())
+        .hyp_env(format!("CHAINS_{chain}_PROTOCOL"), "fuel")
+        .hyp_env(format!("CHAINS_{chain}_RPCURLS_0_HTTP"), fuel_node_url())
+        .hyp_env(format!("CHAINS_{chain}_BLOCKS_REORGPERIOD"), "0")
+        .hyp_env(format!("CHAINS_{chain}_INDEX_FROM"), "1")
+        .hyp_env(format!("CHAINS_{chain}_INDEX_CHUNK"), "10")
+        .hyp_env(format!("CHAINS_{chain}_SIGNER_TYPE"), "hexKey")
+        .hyp_env(format!("CHAINS_{chain}_SIGNER_KEY"), FUEL_DEPLOYER_KEY);
+    for &(_, setting) in FUEL_HYPERLANE_CONTRACTS {
+        if setting == "DEFAULTISM" || setting == "TESTRECIPIENT" {
+            continue;
+        }
+        env = env.hyp_env(format!("CHAINS_{chain}_{setting}"), deployment.get(setting));
+    }
+    env
+}
+
+#[allow(dead_code)]
+fn run_locally() {
+    const TIMEOUT_SECS: u64 = 60 * 10;
+
+    log!("Building rust...");
+    Program::new("cargo")
+        .cmd("build")
+        .working_dir("../../")
+        .arg("features", "test-utils")
+        .arg("bin", "relayer")
+        .arg("bin", "validator")
+        .filter_logs(|l| !l.contains("workspace-inheritance"))
+        .run()
+        .join();
+
+    let contracts_dir = tempdir().unwrap();
+    let contracts_path = match env::var(ENV_FUEL_HYPERLANE_PATH_KEY) {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => {
+            Program::new("git")
+                .cmd("clone")
+                .cmd(FUEL_HYPERLANE_GIT)
+                .cmd(contracts_dir.path().to_str().unwrap())
+                .run()
+                .join();
+            contracts_dir.path().to_path_buf()
+        }
+    };
+
+    let mut node = launch_fuel_node().join();
+    let deployment = deploy_fuel_hyperlane(&contracts_path);
+    configure_fuel_ism(&contracts_path, &deployment);
+
+    let checkpoints_dir = tempdir().unwrap();
+    let rocks_db_dir = tempdir().unwrap();
+    let agent_env = fuel_agent_env(&deployment);
+
+    let mut validator = agent_env
+        .clone()
+        .bin(concat_path(AGENT_BIN_PATH, "validator"))
+        .hyp_env("ORIGINCHAINNAME", FUEL_CHAIN_NAME)
+        .hyp_env("VALIDATOR_KEY", FUEL_VALIDATOR_KEY)
+        .hyp_env("CHECKPOINTSYNCER_TYPE", "localStorage")
+        .hyp_env(
+            "CHECKPOINTSYNCER_PATH",
+            checkpoints_dir.path().to_str().unwrap(),
+        )
+        .hyp_env("INTERVAL", "5")
+        .hyp_env("METRICSPORT", FUEL_VALIDATOR_METRICS_PORT.to_string())
+        .hyp_env(
+            "DB",
+            concat_path(&rocks_db_dir, "validator").to_str().unwrap(),
+        )
+        .spawn("VAL");
+
+    // dispatch the first message before starting the relayer so the
+    // backward cursor is exercised
+    dispatch_fuel_message(&contracts_path, &deployment);
+
+    let mut relayer = agent_env
+        .bin(concat_path(AGENT_BIN_PATH, "relayer"))
+        .hyp_env("RELAYCHAINS", FUEL_CHAIN_NAME)
+        .hyp_env("ALLOWLOCALCHECKPOINTSYNCERS", "true")
+        .hyp_env("GASPAYMENTENFORCEMENT", r#"[{"type": "none"}]"#)
+        .hyp_env("METRICSPORT", FUEL_RELAYER_METRICS_PORT.to_string())
+        .hyp_env(
+            "DB",
+            concat_path(&rocks_db_dir, "relayer").to_str().unwrap(),
+        )
+        .spawn("RLY");
+
+    for _ in 1..FUEL_MESSAGES_EXPECTED {
+        dispatch_fuel_message(&contracts_path, &deployment);
+    }
+
+    let starting_relayer_balance = agent_balance_sum(FUEL_RELAYER_METRICS_PORT).unwrap();
+    let loop_start = Instant::now();
+    let mut failure_occurred = false;
+    loop {
+        if termination_invariants_met(starting_relayer_balance).unwrap_or(false) {
+            break;
+        }
+        if (Instant::now() - loop_start).as_secs() > TIMEOUT_SECS {
+            log!("Timeout reached before all fuel messages were delivered");
+            failure_occurred = true;
+            break;
+        }
+        sleep(Duration::from_secs(5));
+    }
+
+    stop_child(&mut relayer.1);
+    stop_child(&mut validator.1);
+    stop_child(&mut node.1);
+
+    if failure_occurred {
+        panic!("E2E tests failed");
+    } else {
+        log!("E2E tests passed");
+    }
+}
+
+fn termination_invariants_met(starting_relayer_balance: f64) -> eyre::Result<bool> {
+    let dispatched_messages_scraped = fetch_metric(
+        &FUEL_RELAYER_METRICS_PORT.to_string(),
+        "hyperlane_contract_sync_stored_events",
+        &hashmap! {"data_type" => "message_dispatch"},
+    )?
+    .iter()
+    .sum::<u32>();
+    if dispatched_messages_scraped != FUEL_MESSAGES_EXPECTED {
+        log!(
+            "Relayer has indexed {} fuel dispatches, expected {}",
+            dispatched_messages_scraped,
+            FUEL_MESSAGES_EXPECTED
+        );
+        return Ok(false);
+    }
+
+    let delivered_messages = fetch_metric(
+        &FUEL_RELAYER_METRICS_PORT.to_string(),
+        "hyperlane_operations_processed_count",
+        &hashmap! {"phase" => "confirmed"},
+    )?
+    .iter()
+    .sum::<u32>();
+    if delivered_messages != FUEL_MESSAGES_EXPECTED {
+        log!(
+            "Relayer confirmed {} submitted fuel messages, expected {}",
+            delivered_messages,
+            FUEL_MESSAGES_EXPECTED
+        );
+        return Ok(false);
+    }
+
+    let ending_relayer_balance = agent_balance_sum(FUEL_RELAYER_METRICS_PORT).unwrap();
+    if starting_relayer_balance <= ending_relayer_balance {
+        log!(
+            "Expected starting relayer balance to be greater than ending relayer balance, but got {} <= {}",
+            starting_relayer_balance,
+            ending_relayer_balance
+        );
+        return Ok(false);
+    }
+
+    log!("Termination invariants have been meet");
+    Ok(true)
+}
+
+#[cfg(feature = "fuel")]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        run_locally()
+    }
+}
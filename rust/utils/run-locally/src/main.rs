@@ -38,6 +38,7 @@ use crate::{
 mod config;
 mod cosmos;
 mod ethereum;
+mod fuel;
 mod invariants;
 mod logging;
 mod metrics;
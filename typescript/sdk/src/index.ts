@@ -160,6 +160,7 @@ export {
   AgentChainMetadataSchema,
   AgentConfig,
   AgentConfigSchema,
+  AgentCosmosAccountAddressType,
   AgentLogFormat,
   AgentLogLevel,
   AgentSigner,
@@ -44,11 +44,18 @@ export enum AgentIndexMode {
   Sequence = 'sequence',
 }
 
+export enum AgentCosmosAccountAddressType {
+  Bitcoin = 'bitcoin',
+  Ethereum = 'ethereum',
+}
+
 export enum AgentSignerKeyType {
   Aws = 'aws',
   Hex = 'hexKey',
   Node = 'node',
   Cosmos = 'cosmosKey',
+  Remote = 'remote',
+  Keystore = 'keystore',
 }
 
 const AgentSignerHexKeySchema = z
@@ -62,6 +69,12 @@ const AgentSignerAwsKeySchema = z
     type: z.literal(AgentSignerKeyType.Aws).optional(),
     id: z.string().describe('The UUID identifying the AWS KMS key'),
     region: z.string().describe('The AWS region'),
+    prefix: z
+      .string()
+      .optional()
+      .describe(
+        'The bech32 prefix for the cosmos address, only needed for Cosmos',
+      ),
   })
   .describe(
     'An AWS signer. Note that AWS credentials must be inserted into the env separately.',
@@ -73,6 +86,59 @@ const AgentSignerCosmosKeySchema = z
     key: ZHash,
   })
   .describe('Cosmos key');
+const AgentSignerRemoteSchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Remote),
+    url: z.string().url().describe('The URL of the signing service'),
+    publicKey: z
+      .string()
+      .min(1)
+      .describe(
+        'The hex encoded public key identifying the key in the signing service',
+      ),
+    bearerToken: z
+      .string()
+      .optional()
+      .describe('A token to authenticate with the signing service'),
+    clientIdentity: z
+      .string()
+      .optional()
+      .describe(
+        'The path to a PEM file with the client certificate and private key to authenticate with the signing service over mTLS',
+      ),
+    caCertificate: z
+      .string()
+      .optional()
+      .describe(
+        'The path to a PEM file with a CA certificate to trust for the signing service',
+      ),
+    timeoutSecs: ZNzUint.optional().describe(
+      'How long to wait for a signature, 30 seconds by default',
+    ),
+  })
+  .describe('A key held by a remote signing service with a Web3Signer API');
+const AgentSignerKeystoreSchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Keystore),
+    path: z
+      .string()
+      .describe(
+        'The path to an encrypted Ethereum JSON keystore, or for Sealevel also a Solana keypair file',
+      ),
+    passwordFile: z
+      .string()
+      .optional()
+      .describe(
+        'The path to a file with the keystore password, not needed for Solana keypair files',
+      ),
+    prefix: z
+      .string()
+      .optional()
+      .describe(
+        'The bech32 prefix for the cosmos address, only needed for Cosmos',
+      ),
+  })
+  .describe('A key in a keystore file');
 const AgentSignerNodeSchema = z
   .object({
     type: z.literal(AgentSignerKeyType.Node),
@@ -83,12 +149,16 @@ const AgentSignerSchema = z.union([
   AgentSignerHexKeySchema,
   AgentSignerAwsKeySchema,
   AgentSignerCosmosKeySchema,
+  AgentSignerRemoteSchema,
+  AgentSignerKeystoreSchema,
   AgentSignerNodeSchema,
 ]);
 
 export type AgentSignerHexKey = z.infer<typeof AgentSignerHexKeySchema>;
 export type AgentSignerAwsKey = z.infer<typeof AgentSignerAwsKeySchema>;
 export type AgentSignerCosmosKey = z.infer<typeof AgentSignerNodeSchema>;
+export type AgentSignerRemote = z.infer<typeof AgentSignerRemoteSchema>;
+export type AgentSignerKeystore = z.infer<typeof AgentSignerKeystoreSchema>;
 export type AgentSignerNode = z.infer<typeof AgentSignerNodeSchema>;
 export type AgentSigner = z.infer<typeof AgentSignerSchema>;
 
@@ -114,6 +184,12 @@ const AgentCosmosChainMetadataSchema = z.object({
     .positive()
     .lte(32)
     .describe('The number of bytes used to represent a contract address.'),
+  accountAddressType: z
+    .nativeEnum(AgentCosmosAccountAddressType)
+    .optional()
+    .describe(
+      'How account addresses are derived from public keys. Defaults to bitcoin, the Cosmos SDK secp256k1 style; chains with eth-secp256k1 keys such as Injective use ethereum.',
+    ),
 });
 
 export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
@@ -133,6 +209,12 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
     signer: AgentSignerSchema.optional().describe(
       'The signer to use for this chain',
     ),
+    signerPool: z
+      .array(AgentSignerSchema)
+      .optional()
+      .describe(
+        'Signers the relayer submits transactions with in parallel instead of the signer, each with its own nonces.',
+      ),
     index: z
       .object({
         from: ZUint.optional().describe(
@@ -149,6 +231,43 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
           ),
       })
       .optional(),
+    gasEscalation: z
+      .object({
+        escalationIntervalSecs: ZNzUint.optional().describe(
+          'How long to wait for a transaction to be included before replacing it with one paying higher fees.',
+        ),
+        pollIntervalSecs: ZNzUint.optional().describe(
+          'How often to check whether a transaction has been included.',
+        ),
+        feeBumpPercent: ZUint.gte(10)
+          .optional()
+          .describe(
+            'Percentage by which fees are bumped for each replacement.',
+          ),
+        maxFeePerGas: ZUWei.optional().describe(
+          'The max fee per gas, or gas price for legacy transactions, a replacement may pay.',
+        ),
+        maxReplacements: ZUint.optional().describe(
+          'How many times a transaction is replaced before giving up on waiting for it.',
+        ),
+      })
+      .optional()
+      .describe(
+        'Replace transactions that are not included in time with ones paying higher fees. Only supported on EVM chains.',
+      ),
+    batch: z
+      .object({
+        maxBatchSize: ZNzUint.optional().describe(
+          'The max number of messages the relayer delivers in a single transaction. Defaults to 1, which disables batching.',
+        ),
+        batchContractAddress: ZHash.optional().describe(
+          'The Multicall3 contract used to batch deliveries on EVM chains. Defaults to the canonical deployment.',
+        ),
+      })
+      .optional()
+      .describe(
+        'Deliver multiple messages to this chain in a single transaction. Only supported on EVM and Sealevel chains.',
+      ),
   })
   .merge(AgentCosmosChainMetadataSchema.partial())
   .refine((metadata) => {
@@ -289,6 +408,27 @@ const MetricAppContextSchema = z.object({
   ),
 });
 
+const RateLimitSchema = z.object({
+  name: z
+    .string()
+    .min(1)
+    .optional()
+    .describe('The name of the rate limit in metrics. Defaults to its index.'),
+  matchingList: MatchingListSchema.optional().describe(
+    'The messages the rate limit applies to. Defaults to all messages. Cannot match on `recipientIsmType`.',
+  ),
+  messagesPerMinute: ZUint.optional().describe(
+    'The max number of messages delivered to a destination per minute.',
+  ),
+  gasPerHour: ZUWei.optional().describe(
+    'The max gas used by deliveries to a destination per hour.',
+  ),
+  nativeSpendPerDay: ZUWei.optional().describe(
+    'The max native tokens spent on deliveries to a destination per day, in the lowest denomination.',
+  ),
+});
+export type RateLimit = z.infer<typeof RateLimitSchema>;
+
 export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
   db: z
     .string()
@@ -316,6 +456,18 @@ export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
     .describe(
       'If no blacklist is provided ALL will be considered to not be on the blacklist.',
     ),
+  strictOrdering: z
+    .union([MatchingListSchema, z.string().min(1)])
+    .optional()
+    .describe(
+      'Messages that are delivered in nonce order per origin, sender and recipient. A message is held back until the messages before it are delivered. Cannot match on `recipientIsmType`.',
+    ),
+  rateLimits: z
+    .union([z.array(RateLimitSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'Rate limits on the messages delivered to each destination as JSON. A message is delivered once all the rate limits it matches allow it.',
+    ),
   transactionGasLimit: ZUWei.optional().describe(
     'This is optional. If not specified, any amount of gas will be valid, otherwise this is the max allowed gas in wei to relay a transaction.',
   ),
@@ -326,7 +478,13 @@ export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
     .boolean()
     .optional()
     .describe(
-      'If true, allows local storage based checkpoint syncers. Not intended for production use.',
+      'If true, allows local storage based checkpoint syncers, and ones served over HTTP from non-public hosts. Not intended for production use.',
+    ),
+  dryRun: z
+    .boolean()
+    .optional()
+    .describe(
+      'If true, messages are prepared but never submitted, and what would have been submitted is reported in metrics and the HTTP API.',
     ),
   metricAppContexts: z
     .union([z.array(MetricAppContextSchema), z.string().min(1)])
@@ -334,6 +492,12 @@ export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
     .describe(
       'A list of app contexts and their matching lists to use for metrics. A message will be classified as the first matching app context.',
     ),
+  watchtowerValidators: z
+    .record(z.union([z.array(ZHash), z.string().min(1)]))
+    .optional()
+    .describe(
+      'The origin chains whose validators have their checkpoints checked against the merkle tree, by chain name. Every validator announced on the chain is checked, as well as the listed validators, given as a list or a comma separated string of addresses. Fraudulent checkpoints are alerted on and persisted as evidence.',
+    ),
 });
 
 export type RelayerConfig = z.infer<typeof RelayerAgentConfigSchema>;
@@ -347,6 +511,80 @@ export const ScraperAgentConfigSchema = AgentConfigSchema.extend({
 
 export type ScraperConfig = z.infer<typeof ScraperAgentConfigSchema>;
 
+const LocalCheckpointSyncerSchema = z
+  .object({
+    type: z.literal('localStorage'),
+    path: z.string().min(1).describe('Path to the local storage location'),
+  })
+  .describe('A local checkpoint syncer');
+
+const S3CheckpointSyncerSchema = z
+  .object({
+    type: z.literal('s3'),
+    bucket: z.string().min(1),
+    region: z.string().min(1),
+    folder: z
+      .string()
+      .min(1)
+      .optional()
+      .describe(
+        'The folder/key-prefix to use, defaults to the root of the bucket',
+      ),
+  })
+  .describe('A checkpoint syncer that uses S3');
+
+const HttpCheckpointSyncerSchema = z
+  .object({
+    type: z.literal('http'),
+    url: z
+      .string()
+      .url()
+      .describe(
+        'The URL the checkpoints are served at, i.e. `<validator server URL>/checkpoints/<chain name>`',
+      ),
+    path: z
+      .string()
+      .min(1)
+      .describe('Path to the local storage location of the served checkpoints'),
+  })
+  .describe('A checkpoint syncer served over HTTP by the validator itself');
+
+const CheckpointSyncerSchema = z.discriminatedUnion('type', [
+  LocalCheckpointSyncerSchema,
+  S3CheckpointSyncerSchema,
+  HttpCheckpointSyncerSchema,
+  z
+    .object({
+      type: z.literal('replicated'),
+      syncers: z
+        .array(
+          z.discriminatedUnion('type', [
+            LocalCheckpointSyncerSchema,
+            S3CheckpointSyncerSchema,
+            HttpCheckpointSyncerSchema,
+          ]),
+        )
+        .min(1)
+        .describe(
+          'The replicas, in the order they are read from. Each of them is announced.',
+        ),
+      writeQuorum: ZNzUint.optional().describe(
+        'How many replicas a write must succeed on, defaults to all of them',
+      ),
+    })
+    .describe('A checkpoint syncer that replicates to several others'),
+]);
+
+const ValidatorOriginChainSchema = z.object({
+  checkpointSyncer: CheckpointSyncerSchema,
+  reorgPeriod: ZUint.optional().describe(
+    "The reorg period in blocks, defaults to the chain's `blocks.reorgPeriod`.",
+  ),
+  interval: ZUint.optional().describe(
+    'How long to wait between checking for new checkpoints in seconds, defaults to the top level `interval`.',
+  ),
+});
+
 export const ValidatorAgentConfigSchema = AgentConfigSchema.extend({
   db: z
     .string()
@@ -356,33 +594,33 @@ export const ValidatorAgentConfigSchema = AgentConfigSchema.extend({
   originChainName: z
     .string()
     .min(1)
-    .describe('Name of the chain to validate messages on'),
+    .optional()
+    .describe(
+      'Name of the chain to validate messages on. Mutually exclusive with `originChains`.',
+    ),
+  originChains: z
+    .record(ValidatorOriginChainSchema)
+    .optional()
+    .describe(
+      'The chains to validate messages on, keyed by chain name. Mutually exclusive with `originChainName`.',
+    ),
   validator: AgentSignerSchema.describe('The validator attestation signer'),
-  checkpointSyncer: z.discriminatedUnion('type', [
-    z
-      .object({
-        type: z.literal('localStorage'),
-        path: z.string().min(1).describe('Path to the local storage location'),
-      })
-      .describe('A local checkpoint syncer'),
-    z
-      .object({
-        type: z.literal('s3'),
-        bucket: z.string().min(1),
-        region: z.string().min(1),
-        folder: z
-          .string()
-          .min(1)
-          .optional()
-          .describe(
-            'The folder/key-prefix to use, defaults to the root of the bucket',
-          ),
-      })
-      .describe('A checkpoint syncer that uses S3'),
-  ]),
+  checkpointSyncer: CheckpointSyncerSchema.optional().describe(
+    'The checkpoint syncer of `originChainName`, required if it is set.',
+  ),
+  reorgPeriod: ZUint.optional().describe(
+    "The reorg period of `originChainName` in blocks, defaults to the chain's `blocks.reorgPeriod`.",
+  ),
   interval: ZUint.optional().describe(
     'How long to wait between checking for new checkpoints in seconds.',
   ),
+  slashingProtectionImport: z
+    .string()
+    .min(1)
+    .optional()
+    .describe(
+      'Path to a JSON export of signed checkpoints to import into the slashing protection database on startup.',
+    ),
 });
 
 export type ValidatorConfig = z.infer<typeof ValidatorAgentConfigSchema>;
@@ -4,7 +4,7 @@
  */
 import { z } from 'zod';
 
-import { ZHash, ZNzUint } from './customZodTypes';
+import { ZHash, ZNzUint, ZUWei, ZUint } from './customZodTypes';
 
 const DomainSchema = z.union([
   z.literal('*'),
@@ -14,12 +14,63 @@ const DomainSchema = z.union([
 
 const AddressSchema = z.union([z.literal('*'), ZHash, z.array(ZHash)]);
 
-const MatchingListElementSchema = z.object({
-  originDomain: DomainSchema.optional(),
-  senderAddress: AddressSchema.optional(),
-  destinationDomain: DomainSchema.optional(),
-  recipientAddress: AddressSchema.optional(),
-});
+const rangeSchema = <T extends z.ZodTypeAny>(value: T) =>
+  z.object({
+    min: value.optional().describe('Inclusive lower bound'),
+    max: value.optional().describe('Inclusive upper bound'),
+  });
+
+// A module type number or name, e.g. 5 or 'MESSAGE_ID_MULTISIG'
+const IsmTypeSchema = z.union([ZUint.lte(7), z.string().min(1)]);
+
+const MatchingListElementSchema = z
+  .object({
+    originDomain: DomainSchema.optional(),
+    senderAddress: AddressSchema.optional(),
+    destinationDomain: DomainSchema.optional(),
+    recipientAddress: AddressSchema.optional(),
+    bodyPrefix: z
+      .string()
+      .regex(/^(0x)?([0-9a-fA-F]{2})*$/)
+      .optional()
+      .describe('Hex prefix of the message body.'),
+    bodyRegex: z
+      .string()
+      .min(1)
+      .optional()
+      .describe(
+        'Regex matched against the lowercase hex encoding of the message body, without a 0x prefix.',
+      ),
+    bodyLength: rangeSchema(ZUint)
+      .optional()
+      .describe('Range of message body lengths in bytes.'),
+    tokenAmount: rangeSchema(ZUWei)
+      .optional()
+      .describe(
+        'Range of warp route token transfer amounts. Only applies to the warp route recipients listed in recipientAddress; messages that are not token transfers do not match.',
+      ),
+    recipientIsmType: z
+      .union([
+        z.literal('*'),
+        IsmTypeSchema,
+        z.array(IsmTypeSchema).nonempty(),
+      ])
+      .optional()
+      .describe(
+        "Module types of the recipient's ISM. Only matched once a message is prepared for delivery.",
+      ),
+  })
+  .refine(
+    (element) =>
+      !element.tokenAmount ||
+      (element.recipientAddress !== undefined &&
+        element.recipientAddress !== '*'),
+    {
+      message:
+        'tokenAmount rules must list the warp route recipients they apply to in recipientAddress',
+      path: ['recipientAddress'],
+    },
+  );
 
 export const MatchingListSchema = z.array(MatchingListElementSchema);
 